@@ -0,0 +1,105 @@
+//! Compares the decode throughput of `abio` against `postcard` and `rkyv` for an
+//! equivalent message header.
+//!
+//! Run with `cargo bench --features serde --bench interop`.
+
+use abio::interop::Embedded;
+use abio::{AsBytes, Decode, Endianness, NativeEndian, Result};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rkyv::Deserialize as _;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[repr(C)]
+struct Header {
+    magic: u32,
+    version: u16,
+    flags: u16,
+    length: u64,
+    checksum: u64,
+}
+
+// SAFETY: `Header` is `repr(C)` and its fields are laid out without any padding.
+unsafe impl AsBytes for Header {}
+
+impl<'data> Decode<'data> for Header {
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(Self, usize)> {
+        let (magic, mut offset) = u32::decode::<E>(bytes)?;
+        let (version, len) = u16::decode::<E>(&bytes[offset..])?;
+        offset += len;
+        let (flags, len) = u16::decode::<E>(&bytes[offset..])?;
+        offset += len;
+        let (length, len) = u64::decode::<E>(&bytes[offset..])?;
+        offset += len;
+        let (checksum, len) = u64::decode::<E>(&bytes[offset..])?;
+        offset += len;
+        Ok((Header { magic, version, flags, length, checksum }, offset))
+    }
+}
+
+/// A `postcard` message carrying a header described by `abio`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Message {
+    id: u32,
+    header: Embedded<Header>,
+}
+
+const HEADER: Header = Header {
+    magic: 0x4142_494f,
+    version: 3,
+    flags: 0x8001,
+    length: 0x0001_0000,
+    checksum: 0xdead_beef_cafe_f00d,
+};
+
+fn decode_header(c: &mut Criterion) {
+    let abio_bytes = HEADER.as_bytes().to_vec();
+    let postcard_bytes = postcard::to_allocvec(&HEADER).unwrap();
+    let rkyv_bytes = rkyv::to_bytes::<_, 64>(&HEADER).unwrap();
+
+    let mut group = c.benchmark_group("decode_header");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("abio", |b| {
+        b.iter(|| Header::decode::<NativeEndian>(black_box(&abio_bytes)).unwrap())
+    });
+    group.bench_function("postcard", |b| {
+        b.iter(|| postcard::from_bytes::<Header>(black_box(&postcard_bytes)).unwrap())
+    });
+    group.bench_function("rkyv", |b| {
+        b.iter(|| {
+            // SAFETY: The bytes were produced by `rkyv::to_bytes` for this exact type.
+            let archived = unsafe { rkyv::archived_root::<Header>(black_box(&rkyv_bytes)) };
+            let header: Header = archived
+                .deserialize(&mut rkyv::Infallible)
+                .unwrap();
+            header
+        })
+    });
+    group.finish();
+}
+
+fn decode_embedded(c: &mut Criterion) {
+    let message = Message { id: 7, header: Embedded::new(HEADER) };
+    let bytes = postcard::to_allocvec(&message).unwrap();
+    assert_eq!(postcard::from_bytes::<Message>(&bytes).unwrap(), message);
+
+    let mut group = c.benchmark_group("decode_embedded");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("postcard+abio", |b| {
+        b.iter(|| postcard::from_bytes::<Message>(black_box(&bytes)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, decode_header, decode_embedded);
+criterion_main!(benches);
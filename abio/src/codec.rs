@@ -1,6 +1,74 @@
 //! Encoding and Decoding
 
+#[cfg(feature = "arena")]
+pub mod arena;
+#[cfg(feature = "arena")]
+pub use arena::{decode_slice_in, decode_str_in, ArenaAlloc};
+pub mod bits;
+pub use bits::{BitField, BitOrder, BitReader};
+pub mod boolean;
+pub use boolean::{Bool32, Bool8};
+pub mod cancel;
+pub use cancel::CancelToken;
+pub mod chain;
+pub use chain::{Chain, EncodeSink};
+pub mod constant;
+pub use constant::ConstDecode;
+pub mod counted;
+pub use counted::{Counted, CountedIter, DecodeCounted};
+#[cfg(any(test, feature = "alloc"))]
+pub mod coverage;
+#[cfg(any(test, feature = "alloc"))]
+pub use coverage::{Coverage, CoverageReport};
+pub mod delta;
+pub use delta::DeltaOp;
 pub mod decoder;
-pub use decoder::{Decode, Decoder};
+pub use decoder::{Decode, DecodeWith, Decoder};
 pub mod encoder;
-pub use encoder::{Encode, Encoder};
+pub use encoder::{BufEncoder, Encode, Encoder};
+pub mod fixed;
+pub use fixed::{Fixed, FixedInt, Q16_16, Q8_8};
+pub mod float;
+pub use float::{TotalOrdF32, TotalOrdF64};
+#[cfg(any(test, feature = "alloc"))]
+pub mod hash;
+#[cfg(any(test, feature = "alloc"))]
+pub use hash::{hash_wire, wire_hash};
+pub mod incremental;
+pub use incremental::decode_incremental;
+#[cfg(any(test, feature = "alloc"))]
+pub use incremental::IncrementalDecoder;
+pub mod matrix;
+pub use matrix::{Matrix, MatrixRows};
+pub mod net;
+pub use net::SockAddr;
+pub mod packed;
+pub use packed::{PackedArray, PackedInt};
+pub mod padded;
+pub use padded::{MaybePadded, ZeroPadded};
+pub mod patch;
+pub use patch::Patcher;
+#[cfg(any(test, feature = "alloc"))]
+pub mod pool;
+#[cfg(any(test, feature = "alloc"))]
+pub use pool::{BufPool, PooledBuf};
+pub mod raw;
+pub use raw::RawBytes;
+pub mod reader;
+pub use reader::{Reader, SeekFrom};
+pub mod samples;
+pub use samples::{f32_to_i16, i16_to_f32, ChannelIter, Samples};
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "stats")]
+pub use stats::{measure, DecodeStats};
+pub mod stream;
+pub use stream::{decode_stream, DecodeStream, StreamEvent};
+pub mod tagged;
+pub use tagged::{DecodeTagged, EncodeTagged};
+pub mod text;
+pub use text::{encode_as_c_array, encode_as_hex_str, encode_as_rust_array};
+pub mod trace;
+pub use trace::{Observer, TraceEvent, TraceSink, Tracer};
+pub mod writer;
+pub use writer::Writer;
@@ -0,0 +1,202 @@
+//! Module containing the [`ArenaAlloc`] trait, used to decode variable-length
+//! containers into memory borrowed from a caller-provided arena.
+//!
+//! Decoding a large tree of messages onto the global heap produces many small,
+//! scattered allocations that must each be freed individually. Bump arenas instead
+//! place every allocation next to the previous one and release them all at once, so
+//! the decoded tree stays cache-friendly and is cheap to throw away.
+//!
+//! Types opt into arena decoding by overriding
+//! [`Decode::decode_in`][crate::Decode::decode_in]. Containers built from
+//! [`decode_slice_in`] and [`decode_str_in`] allocate their storage from the arena
+//! instead of the global heap.
+
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+use crate::{Decode, Endianness, Error, Result};
+
+/// An allocator handing out memory from a region that is released all at once,
+/// such as a bump arena.
+///
+/// Arenas allocate through a shared reference, so any number of values may borrow
+/// from the same arena while a message tree is being decoded. The values are never
+/// dropped individually; memory is reclaimed when the arena itself is reset or
+/// dropped, which requires that no borrows remain.
+///
+/// # Safety
+///
+/// Implementors must guarantee that a pointer returned by
+/// [`try_alloc_layout`][ArenaAlloc::try_alloc_layout]:
+///
+/// * is aligned to `layout.align()`, and valid for reads and writes of
+///   `layout.size()` bytes;
+/// * does not overlap any other allocation handed out by the arena;
+/// * remains valid for as long as the shared borrow of the arena it was allocated
+///   through.
+pub unsafe trait ArenaAlloc {
+    /// Allocates a block of memory fitting `layout`, returning `None` if the arena
+    /// is exhausted.
+    ///
+    /// The returned memory is uninitialized.
+    fn try_alloc_layout(&self, layout: Layout) -> Option<NonNull<u8>>;
+}
+
+// SAFETY: The allocation is forwarded to the referenced arena, which upholds the
+// contract for as long as it is borrowed.
+unsafe impl<A: ArenaAlloc + ?Sized> ArenaAlloc for &A {
+    #[inline]
+    fn try_alloc_layout(&self, layout: Layout) -> Option<NonNull<u8>> {
+        (**self).try_alloc_layout(layout)
+    }
+}
+
+/// Allocates uninitialized storage for `len` values of type `T` from `arena`,
+/// returning a pointer to the first value.
+///
+/// Zero-sized requests do not touch the arena and return a dangling pointer.
+fn alloc_array<T, A>(arena: &A, len: usize) -> Result<NonNull<T>>
+where
+    A: ArenaAlloc + ?Sized,
+{
+    let layout = Layout::array::<T>(len).map_err(|_| Error::arena_exhausted(usize::MAX, 1))?;
+    if layout.size() == 0 {
+        return Ok(NonNull::dangling());
+    }
+    arena
+        .try_alloc_layout(layout)
+        .map(NonNull::cast)
+        .ok_or_else(|| Error::arena_exhausted(layout.size(), layout.align()))
+}
+
+/// Decodes `count` consecutive values of type `T` from the start of `bytes` into a
+/// slice allocated from `arena`, returning the slice and the number of bytes
+/// consumed.
+///
+/// Each element is decoded with [`Decode::decode_in`], so nested containers are
+/// allocated from the same arena.
+///
+/// # Errors
+///
+/// Returns an error if any element fails to decode, or if the arena cannot hold
+/// `count` values of `T`. Elements decoded before the failure are not dropped, but
+/// their memory is reclaimed along with the rest of the arena.
+pub fn decode_slice_in<'data, 'arena, T, E, A>(
+    bytes: &'data [u8],
+    count: usize,
+    arena: &'arena A,
+) -> Result<(&'arena [T], usize)>
+where
+    'arena: 'data,
+    T: Decode<'data>,
+    E: Endianness,
+    A: ArenaAlloc + ?Sized,
+{
+    let ptr = alloc_array::<T, A>(arena, count)?;
+    let mut offset = 0;
+    for idx in 0..count {
+        let (value, len) = T::decode_in::<E, A>(
+            bytes
+                .get(offset..)
+                .unwrap_or_default(),
+            arena,
+        )?;
+        // SAFETY: The allocation holds `count` values, and `idx < count`.
+        unsafe {
+            ptr.as_ptr()
+                .add(idx)
+                .write(value)
+        };
+        offset += len;
+    }
+    // SAFETY: Every value was initialized by the loop above, and the arena keeps the
+    // allocation alive for `'arena`.
+    let values = unsafe { core::slice::from_raw_parts(ptr.as_ptr(), count) };
+    Ok((values, offset))
+}
+
+/// Copies `len` bytes of UTF-8 text from the start of `bytes` into a string
+/// allocated from `arena`, returning the string and the number of bytes consumed.
+///
+/// # Errors
+///
+/// Returns an error if `bytes` is shorter than `len`, if the text is not valid
+/// UTF-8, or if the arena cannot hold `len` bytes.
+pub fn decode_str_in<'arena, A>(
+    bytes: &[u8],
+    len: usize,
+    arena: &'arena A,
+) -> Result<(&'arena str, usize)>
+where
+    A: ArenaAlloc + ?Sized,
+{
+    let text = bytes
+        .get(..len)
+        .ok_or_else(|| Error::out_of_bounds(len, bytes.len()))?;
+    core::str::from_utf8(text)?;
+
+    let ptr = alloc_array::<u8, A>(arena, len)?;
+    // SAFETY: The allocation holds `len` bytes and cannot overlap the input, and the
+    // bytes were validated as UTF-8 before they were copied.
+    let text = unsafe {
+        core::ptr::copy_nonoverlapping(text.as_ptr(), ptr.as_ptr(), len);
+        core::str::from_utf8_unchecked(core::slice::from_raw_parts(ptr.as_ptr(), len))
+    };
+    Ok((text, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::{Cell, UnsafeCell};
+
+    use super::*;
+    use crate::LE;
+
+    /// Bump arena over a fixed-size buffer, used to exercise the arena routines
+    /// without an allocator.
+    struct FixedArena {
+        buf: UnsafeCell<[u64; 8]>,
+        used: Cell<usize>,
+    }
+
+    impl FixedArena {
+        fn new() -> Self {
+            Self { buf: UnsafeCell::new([0; 8]), used: Cell::new(0) }
+        }
+    }
+
+    unsafe impl ArenaAlloc for FixedArena {
+        fn try_alloc_layout(&self, layout: Layout) -> Option<NonNull<u8>> {
+            let base = self.buf.get().cast::<u8>();
+            let start = self
+                .used
+                .get()
+                .next_multiple_of(layout.align());
+            let end = start.checked_add(layout.size())?;
+            if end > core::mem::size_of::<[u64; 8]>() || layout.align() > 8 {
+                return None;
+            }
+            self.used.set(end);
+            NonNull::new(base.wrapping_add(start))
+        }
+    }
+
+    #[test]
+    fn decodes_slices_and_strings_into_arena() {
+        let arena = FixedArena::new();
+        let bytes = [0x01, 0x00, 0x02, 0x00, 0x03, 0x00, b'a', b'b', b'i', b'o'];
+
+        let (values, len) = decode_slice_in::<u16, LE, _>(&bytes, 3, &arena).unwrap();
+        assert_eq!(values, [1, 2, 3]);
+        assert_eq!(len, 6);
+
+        let (text, len) = decode_str_in(&bytes[6..], 4, &arena).unwrap();
+        assert_eq!(text, "abio");
+        assert_eq!(len, 4);
+        assert_eq!(arena.used.get(), 10);
+
+        assert!(decode_str_in(&[0xff], 1, &arena).is_err());
+        assert!(decode_slice_in::<u16, LE, _>(&bytes, 6, &arena).is_err());
+        assert!(decode_slice_in::<u64, LE, _>(&[0; 64], 8, &arena).is_err());
+    }
+}
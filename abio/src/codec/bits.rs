@@ -0,0 +1,228 @@
+//! Module containing the [`BitReader`] type, a cursor for reading values that are
+//! not aligned to byte boundaries, and the [`BitField`] trait for the bit fields of
+//! derived types.
+
+use crate::{Endian, Error, Result};
+
+/// Order in which the bits of each byte are consumed by a [`BitReader`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum BitOrder {
+    /// Bits are consumed starting from the most significant bit of each byte, and
+    /// values are assembled with their most significant bit first.
+    ///
+    /// This is the bit order used by most big endian codec bitstreams.
+    #[default]
+    MsbFirst,
+    /// Bits are consumed starting from the least significant bit of each byte, and
+    /// values are assembled with their least significant bit first.
+    ///
+    /// This is the bit order used by little endian formats, such as DEFLATE.
+    LsbFirst,
+}
+
+impl BitOrder {
+    /// Returns the conventional bit order for data serialized with the byte order
+    /// `endian`.
+    #[inline]
+    pub const fn from_endian(endian: Endian) -> BitOrder {
+        match endian {
+            Endian::Big => BitOrder::MsbFirst,
+            Endian::Little => BitOrder::LsbFirst,
+        }
+    }
+}
+
+/// A cursor over a slice of bytes that reads values with arbitrary bit widths.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BitReader<'data> {
+    /// Bytes the reader is reading from.
+    bytes: &'data [u8],
+    /// Offset of the cursor, in bits.
+    pos: usize,
+    /// Order in which the bits of each byte are consumed.
+    order: BitOrder,
+}
+
+impl<'data> BitReader<'data> {
+    /// Maximum number of bits that can be read with a single call to
+    /// [`read_bits`][BitReader::read_bits].
+    pub const MAX_BITS: u32 = u64::BITS;
+
+    /// Creates a new [`BitReader`] positioned at the first bit of `bytes`.
+    #[inline]
+    pub const fn new(bytes: &'data [u8], order: BitOrder) -> BitReader<'data> {
+        BitReader { bytes, pos: 0, order }
+    }
+
+    /// Returns the bit order used by this reader.
+    #[inline]
+    pub const fn bit_order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Returns the position of the cursor, in bits.
+    #[inline]
+    pub const fn bit_position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of bits remaining in the input.
+    #[inline]
+    pub const fn remaining_bits(&self) -> usize {
+        self.bytes.len() * 8 - self.pos
+    }
+
+    /// Returns the number of bytes touched by the cursor so far, including a
+    /// partially consumed trailing byte.
+    #[inline]
+    pub const fn bytes_consumed(&self) -> usize {
+        self.pos.div_ceil(8)
+    }
+
+    /// Advances the cursor to the start of the next byte, discarding any remaining
+    /// bits within the current byte.
+    #[inline]
+    pub fn align_to_byte(&mut self) {
+        self.pos = self.bytes_consumed() * 8;
+    }
+
+    /// Reads a single bit, returning `true` if it is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no bits remaining in the input.
+    #[inline]
+    pub fn read_bit(&mut self) -> Result<bool> {
+        self.read_bits(1)
+            .map(|bit| bit == 1)
+    }
+
+    /// Reads an unsigned value that is `count` bits wide.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `count` exceeds [`MAX_BITS`][BitReader::MAX_BITS], or if
+    /// fewer than `count` bits remain. The cursor is not advanced on error.
+    pub fn read_bits(&mut self, count: u32) -> Result<u64> {
+        if count > Self::MAX_BITS {
+            return Err(Error::verbose("Cannot read more than 64 bits into a single value"));
+        }
+        if (count as usize) > self.remaining_bits() {
+            let needed = (self.pos + count as usize).div_ceil(8);
+            return Err(Error::out_of_bounds(needed, self.bytes.len()));
+        }
+
+        let mut value = 0u64;
+        for idx in 0..count {
+            let byte = self.bytes[self.pos / 8];
+            let shift = self.pos % 8;
+            match self.order {
+                BitOrder::MsbFirst => {
+                    let bit = (byte >> (7 - shift)) & 1;
+                    value = (value << 1) | bit as u64;
+                }
+                BitOrder::LsbFirst => {
+                    let bit = (byte >> shift) & 1;
+                    value |= (bit as u64) << idx;
+                }
+            }
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+}
+
+/// Trait for values stored in a bit field of a type deriving
+/// [`Decode`][crate::Decode] or [`Encode`][crate::Encode].
+///
+/// Fields declared with `#[abio(bits = N)]` are packed together with the bit fields
+/// next to them, in the order chosen with `#[abio(bit_order = "msb" | "lsb")]` on
+/// the type. Each run of consecutive bit fields must fill whole bytes, and at most
+/// eight of them:
+///
+/// ```
+/// #[derive(abio::Decode, abio::Encode)]
+/// struct Ipv4Prefix {
+///     #[abio(bits = 4)]
+///     version: u8,
+///     #[abio(bits = 4)]
+///     ihl: u8,
+///     tos: u8,
+/// }
+/// ```
+pub trait BitField: Copy {
+    /// Creates a value from the bits of a field, returning `None` if they do not
+    /// hold a valid value.
+    fn from_bits(bits: u64) -> Option<Self>;
+
+    /// Returns the bits representing this value.
+    fn into_bits(self) -> u64;
+}
+
+impl BitField for bool {
+    #[inline]
+    fn from_bits(bits: u64) -> Option<Self> {
+        match bits {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn into_bits(self) -> u64 {
+        u64::from(self)
+    }
+}
+
+macro_rules! impl_bit_field {
+    ($($ty:ty),*) => {
+        $(
+            impl BitField for $ty {
+                #[inline]
+                fn from_bits(bits: u64) -> Option<Self> {
+                    <$ty>::try_from(bits).ok()
+                }
+
+                #[inline]
+                fn into_bits(self) -> u64 {
+                    u64::from(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_bit_field!(u8, u16, u32, u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_bits_in_both_orders() {
+        let bytes = [0b1011_0010, 0b0100_0001];
+
+        let mut msb = BitReader::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(msb.read_bits(3).unwrap(), 0b101);
+        assert_eq!(msb.read_bits(10).unwrap(), 0b10_0100_1000);
+        assert_eq!(msb.bytes_consumed(), 2);
+
+        let mut lsb = BitReader::new(&bytes, BitOrder::LsbFirst);
+        assert_eq!(lsb.read_bits(3).unwrap(), 0b010);
+        assert_eq!(lsb.read_bits(10).unwrap(), 0b00_0011_0110);
+
+        assert!(lsb.read_bits(4).is_err());
+        assert_eq!(lsb.remaining_bits(), 3);
+    }
+
+    #[test]
+    fn bit_fields_reject_out_of_range_values() {
+        assert_eq!(bool::from_bits(1), Some(true));
+        assert_eq!(bool::from_bits(2), None);
+        assert_eq!(u8::from_bits(0xff), Some(0xff));
+        assert_eq!(u8::from_bits(0x100), None);
+        assert_eq!(true.into_bits(), 1);
+        assert_eq!(0x1234u16.into_bits(), 0x1234);
+    }
+}
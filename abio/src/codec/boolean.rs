@@ -0,0 +1,264 @@
+//! Module containing the [`Bool8`] and [`Bool32`] types, for boolean fields stored
+//! as integers.
+//!
+//! A Rust `bool` cannot be read directly from untrusted bytes, since any byte other
+//! than `0` or `1` is not a valid `bool`. Formats also frequently store flags in
+//! wider integers, such as the 4-byte `BOOL` of the Windows API. These types hold
+//! the raw integer instead, so any bytes form a valid value, and they implement
+//! [`Abi`] and [`Zeroable`] to be used in zero-copy structs.
+//!
+//! Decoding is strict by default, rejecting any value other than `0` and `1`. A
+//! [`Codec`] built with [`lenient_bools`][lenient_bools] decodes any non-zero value
+//! as `true` instead, as does `decode_lenient`:
+//!
+//! ```
+//! use abio::codec::Bool32;
+//! use abio::config::Codec;
+//! use abio::{Decode, LE};
+//!
+//! let bytes = [0xff, 0xff, 0xff, 0xff];
+//! assert!(Bool32::decode::<LE>(&bytes).is_err());
+//!
+//! let codec = Codec::builder()
+//!     .little_endian()
+//!     .lenient_bools(true)
+//!     .build();
+//! let (flag, _) = codec.decode::<Bool32>(&bytes).unwrap();
+//! assert!(flag.get());
+//! ```
+//!
+//! [lenient_bools]: crate::config::CodecBuilder::lenient_bools
+
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use crate::config::Codec;
+use crate::{Abi, Decode, Encode, Endianness, Error, Result, Zeroable};
+
+macro_rules! impl_bool_types {
+    ($($(#[$attr:meta])* $name:ident($int:ty);)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Default)]
+            #[repr(transparent)]
+            pub struct $name {
+                bits: $int,
+            }
+
+            impl $name {
+                /// The `false` value, stored as `0`.
+                pub const FALSE: $name = $name { bits: 0 };
+
+                /// The `true` value, stored as `1`.
+                pub const TRUE: $name = $name { bits: 1 };
+
+                /// Creates a new value holding `value`.
+                #[inline]
+                #[must_use]
+                pub const fn new(value: bool) -> $name {
+                    $name { bits: value as $int }
+                }
+
+                /// Creates a new value from the integer it is stored as, treating any
+                /// non-zero integer as `true`.
+                #[inline]
+                #[must_use]
+                pub const fn from_bits(bits: $int) -> $name {
+                    $name { bits }
+                }
+
+                /// Returns the integer this value is stored as.
+                #[inline]
+                #[must_use]
+                pub const fn to_bits(self) -> $int {
+                    self.bits
+                }
+
+                /// Returns `true` if the stored integer is not zero.
+                #[inline]
+                #[must_use]
+                pub const fn get(self) -> bool {
+                    self.bits != 0
+                }
+
+                /// Decodes a value like [`decode`][Decode::decode], but treats any
+                /// non-zero integer as `true` instead of rejecting it.
+                ///
+                /// # Errors
+                ///
+                /// Returns an error if `bytes` is too short.
+                #[inline]
+                pub fn decode_lenient<E: Endianness>(bytes: &[u8]) -> Result<($name, usize)> {
+                    let (bits, len) = <$int>::decode::<E>(bytes)?;
+                    Ok(($name::from_bits(bits), len))
+                }
+            }
+
+            /// Only `0` and `1` are accepted, unless decoded with a [`Codec`] that
+            /// accepts lenient booleans.
+            impl<'data> Decode<'data> for $name {
+                #[inline]
+                fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<($name, usize)> {
+                    let (bits, len) = <$int>::decode::<E>(bytes)?;
+                    match bits {
+                        0 | 1 => Ok(($name::from_bits(bits), len)),
+                        _ => Err(Error::invalid_bit_field(
+                            stringify!($name),
+                            <$int>::BITS,
+                            u64::from(bits),
+                        )),
+                    }
+                }
+
+                #[inline]
+                fn decode_configured<E: Endianness>(
+                    bytes: &'data [u8],
+                    codec: &Codec,
+                ) -> Result<($name, usize)> {
+                    codec.check_cancelled()?;
+                    if codec.lenient_bools() {
+                        $name::decode_lenient::<E>(bytes)
+                    } else {
+                        $name::decode::<E>(bytes)
+                    }
+                }
+            }
+
+            /// Values are always encoded as `0` or `1`, even if decoded from another
+            /// non-zero integer.
+            impl Encode for $name {
+                #[inline]
+                fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+                    <$int>::from(self.get()).encode::<E>(buf)
+                }
+            }
+
+            // SAFETY: The type is a transparent wrapper around an integer primitive,
+            // so it has no padding and any bytes form a valid value.
+            unsafe impl Abi for $name {}
+            // SAFETY: The all-zero bit pattern is the `false` value.
+            unsafe impl Zeroable for $name {}
+
+            impl From<bool> for $name {
+                #[inline]
+                fn from(value: bool) -> $name {
+                    $name::new(value)
+                }
+            }
+
+            impl From<$name> for bool {
+                #[inline]
+                fn from(value: $name) -> bool {
+                    value.get()
+                }
+            }
+
+            /// Values are compared by the boolean they hold, so any two non-zero
+            /// integers are equal.
+            impl PartialEq for $name {
+                #[inline]
+                fn eq(&self, other: &$name) -> bool {
+                    self.get() == other.get()
+                }
+            }
+
+            impl Eq for $name {}
+
+            impl Hash for $name {
+                #[inline]
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.get().hash(state);
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.debug_tuple(stringify!($name))
+                        .field(&self.get())
+                        .finish()
+                }
+            }
+        )*
+    };
+}
+
+impl_bool_types! {
+    /// A boolean stored as a single byte.
+    Bool8(u8);
+    /// A boolean stored as a 4-byte integer, in the byte order of the input.
+    Bool32(u32);
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{BE, LE};
+
+    #[test]
+    fn decodes_strictly_unless_lenient() {
+        assert_eq!(Bool8::decode::<LE>(&[1]).unwrap(), (Bool8::TRUE, 1));
+        assert!(Bool8::decode::<LE>(&[2])
+            .unwrap_err()
+            .to_string()
+            .contains("Bool8"));
+        assert_eq!(Bool32::decode::<BE>(&[0, 0, 0, 1]).unwrap(), (Bool32::TRUE, 4));
+        assert!(Bool32::decode::<LE>(&[0, 0, 0, 1]).is_err());
+
+        let (flag, len) = Bool8::decode_lenient::<LE>(&[0x80]).unwrap();
+        assert_eq!((flag.get(), flag.to_bits(), len), (true, 0x80, 1));
+
+        let strict = Codec::new();
+        let lenient = Codec::builder()
+            .big_endian()
+            .lenient_bools(true)
+            .build();
+        assert!(strict
+            .decode::<Bool8>(&[2])
+            .is_err());
+        assert_eq!(
+            lenient
+                .decode::<Bool32>(&[0, 2, 0, 0])
+                .unwrap(),
+            (Bool32::TRUE, 4)
+        );
+        assert_eq!(
+            lenient
+                .decode::<Bool32>(&[0; 4])
+                .unwrap(),
+            (Bool32::FALSE, 4)
+        );
+
+        // Observed decodes honor the options of the codec as well.
+        struct Ignore;
+
+        impl crate::codec::Observer for Ignore {}
+
+        assert!(strict
+            .decode_observed::<Bool8>(&[2], &mut Ignore)
+            .is_err());
+        assert_eq!(
+            lenient
+                .decode_observed::<Bool8>(&[2], &mut Ignore)
+                .unwrap(),
+            (Bool8::TRUE, 1)
+        );
+
+        // Lenient values are written back as `0` or `1`.
+        let mut buf = [0xffu8; 4];
+        assert_eq!(
+            flag.encode::<LE>(&mut buf)
+                .unwrap(),
+            1
+        );
+        assert_eq!(buf[0], 1);
+        assert_eq!(
+            Bool32::from_bits(7)
+                .encode::<BE>(&mut buf)
+                .unwrap(),
+            4
+        );
+        assert_eq!(buf, [0, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,105 @@
+//! Module containing the [`CancelToken`] type, for stopping decodes of untrusted
+//! input that run for too long.
+//!
+//! A decode through a [`Codec`] built with a token checks the token before each
+//! field, and fails with an error for which [`Error::is_cancelled`] returns `true`
+//! once the token has been cancelled. The token may be cancelled from any thread,
+//! such as a watchdog enforcing a deadline:
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use abio::codec::CancelToken;
+//! use abio::config::Codec;
+//!
+//! # #[derive(abio::Decode)]
+//! # struct Message {
+//! #     id: u32,
+//! # }
+//! # let bytes = [0; 4];
+//! static TOKEN: CancelToken = CancelToken::new();
+//!
+//! let deadline = Duration::from_millis(10);
+//! let codec = Codec::builder()
+//!     .little_endian()
+//!     .cancel_token(&TOKEN)
+//!     .build();
+//! let decoded = std::thread::scope(|scope| {
+//!     scope.spawn(|| {
+//!         std::thread::sleep(deadline);
+//!         TOKEN.cancel();
+//!     });
+//!     codec.decode::<Message>(&bytes)
+//! });
+//! ```
+//!
+//! Only atomic loads and stores are used, which are available on targets without
+//! atomic read-modify-write instructions, such as `thumbv6m-none-eabi`.
+//!
+//! [`Codec`]: crate::config::Codec
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{Error, Result};
+
+/// Flag shared between a decode and the code that may cancel it.
+#[derive(Debug, Default)]
+pub struct CancelToken {
+    cancelled: AtomicBool,
+}
+
+impl CancelToken {
+    /// Creates a new token that has not been cancelled.
+    #[inline]
+    pub const fn new() -> CancelToken {
+        CancelToken { cancelled: AtomicBool::new(false) }
+    }
+
+    /// Cancels every decode checking this token. Decodes stop at the next field
+    /// boundary, rather than immediately.
+    #[inline]
+    pub fn cancel(&self) {
+        self.cancelled
+            .store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if this token has been cancelled.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .load(Ordering::Relaxed)
+    }
+
+    /// Returns an error if this token has been cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for which [`Error::is_cancelled`] returns `true` if this
+    /// token has been cancelled.
+    #[inline]
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Error::cancelled())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_cancellation_once_cancelled() {
+        let token = CancelToken::new();
+        assert!(token.check().is_ok());
+
+        token.cancel();
+        assert!(token.is_cancelled());
+        assert!(token
+            .check()
+            .unwrap_err()
+            .is_cancelled());
+    }
+}
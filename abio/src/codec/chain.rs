@@ -0,0 +1,210 @@
+//! Module containing the [`EncodeSink`] trait and the [`Chain`] type, encoding
+//! values across several fixed buffers.
+//!
+//! Ring buffers shared with kernels and devices are usually made of fixed-size
+//! pages allocated up front, and a message larger than the rest of a page
+//! continues on the next one. Chaining a [`BufEncoder`] per page writes such
+//! messages as if the pages were a single buffer, splitting values that straddle
+//! two pages:
+//!
+//! ```
+//! use abio::codec::{BufEncoder, EncodeSink};
+//! use abio::BE;
+//!
+//! let (mut first, mut second, mut third) = ([0u8; 4], [0u8; 4], [0u8; 4]);
+//! let mut encoder = BufEncoder::<BE>::new(&mut first)
+//!     .chain(BufEncoder::new(&mut second))
+//!     .chain(BufEncoder::new(&mut third));
+//! encoder.encode(&0x0102_0304_0506u64).unwrap();
+//! encoder.put_slice(b"abc").unwrap();
+//! assert_eq!(encoder.position(), 11);
+//! assert!(encoder.encode(&0u16).is_err());
+//!
+//! // The first two pages are full, and the third holds the last 3 bytes.
+//! assert_eq!(encoder.first().second().position(), 4);
+//! assert_eq!(encoder.second().written(), b"abc");
+//! ```
+
+use super::{BufEncoder, Encode, Encoder};
+use crate::{Error, Result};
+
+/// Maximum size of a value straddling two segments of a [`Chain`], which is
+/// encoded into a buffer of this size on the stack before being split.
+const MAX_STRADDLING_LEN: usize = 256;
+
+/// Trait for encoders writing sequentially into fixed storage, in the byte order
+/// `Endian`.
+///
+/// This trait is implemented by [`BufEncoder`] and by chains of encoders, so that
+/// a [`Chain`] can be chained again.
+pub trait EncodeSink {
+    /// Byte order the values are encoded in.
+    type Endian: Encoder;
+
+    /// Returns the number of bytes written.
+    fn position(&self) -> usize;
+
+    /// Returns the number of bytes that can still be written.
+    fn remaining(&self) -> usize;
+
+    /// Copies `bytes` as-is, advancing past them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `bytes.len()` bytes remain. Nothing is written
+    /// on error.
+    fn put_slice(&mut self, bytes: &[u8]) -> Result<()>;
+
+    /// Encodes `value`, advancing past it and returning the number of bytes
+    /// written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Encode::encode`]. Nothing is written on error,
+    /// although bytes past the position may have been overwritten.
+    fn encode<T: Encode + ?Sized>(&mut self, value: &T) -> Result<usize>;
+
+    /// Chains `next` after this encoder, returning an encoder that writes into
+    /// `next` once this one is full.
+    ///
+    /// Both encoders must use the same byte order.
+    #[inline]
+    fn chain<B>(self, next: B) -> Chain<Self, B>
+    where
+        Self: Sized,
+        B: EncodeSink<Endian = Self::Endian>,
+    {
+        Chain { first: self, second: next }
+    }
+}
+
+impl<E: Encoder> EncodeSink for BufEncoder<'_, E> {
+    type Endian = E;
+
+    #[inline]
+    fn position(&self) -> usize {
+        BufEncoder::position(self)
+    }
+
+    #[inline]
+    fn remaining(&self) -> usize {
+        BufEncoder::remaining(self)
+    }
+
+    #[inline]
+    fn put_slice(&mut self, bytes: &[u8]) -> Result<()> {
+        BufEncoder::put_slice(self, bytes)
+    }
+
+    #[inline]
+    fn encode<T: Encode + ?Sized>(&mut self, value: &T) -> Result<usize> {
+        BufEncoder::encode(self, value)
+    }
+}
+
+/// An encoder writing into `A` until it is full, and then into `B`, created with
+/// [`EncodeSink::chain`].
+///
+/// Positions are counted across both encoders, so [`position`][Chain::position]
+/// is the overall length of the bytes written. The boundary between both
+/// segments is the position of the first encoder, returned by
+/// [`first`][Chain::first].
+///
+/// Slices and values straddling the boundary are split across both segments.
+/// Values are encoded into a buffer on the stack before being split, so a value
+/// larger than 256 bytes must fit in the rest of a single segment.
+#[derive(Debug)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Chain<A, B> {
+    /// Returns the encoder written into first.
+    #[inline]
+    pub const fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Returns the encoder written into once the first one is full.
+    #[inline]
+    pub const fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Consumes the chain, returning both encoders.
+    #[inline]
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A, B> EncodeSink for Chain<A, B>
+where
+    A: EncodeSink,
+    B: EncodeSink<Endian = A::Endian>,
+{
+    type Endian = A::Endian;
+
+    #[inline]
+    fn position(&self) -> usize {
+        self.first.position() + self.second.position()
+    }
+
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.first.remaining() + self.second.remaining()
+    }
+
+    #[inline]
+    fn put_slice(&mut self, bytes: &[u8]) -> Result<()> {
+        let available = self.remaining();
+        if bytes.len() > available {
+            return Err(Error::out_of_bounds(bytes.len(), available));
+        }
+        let (head, tail) = bytes.split_at(bytes.len().min(self.first.remaining()));
+        self.first.put_slice(head)?;
+        self.second.put_slice(tail)
+    }
+
+    #[inline]
+    fn encode<T: Encode + ?Sized>(&mut self, value: &T) -> Result<usize> {
+        if self.first.remaining() == 0 {
+            return self.second.encode(value);
+        }
+        match self.first.encode(value) {
+            Err(err) if err.is_out_of_bounds() => {
+                let mut buf = [0u8; MAX_STRADDLING_LEN];
+                let len = value.encode::<A::Endian>(&mut buf)?;
+                self.put_slice(&buf[..len])?;
+                Ok(len)
+            }
+            written => written,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LE;
+
+    #[test]
+    fn splits_writes_across_segments() {
+        let (mut first, mut second) = ([0u8; 3], [0u8; 4]);
+        let mut encoder = BufEncoder::<LE>::new(&mut first).chain(BufEncoder::new(&mut second));
+        assert_eq!(encoder.encode(&0x0201u16), Ok(2));
+        assert_eq!(encoder.encode(&0x0605_0403u32), Ok(4));
+        assert_eq!((encoder.first().position(), encoder.second().position()), (3, 3));
+
+        assert_eq!(encoder.put_slice(b"ab"), Err(Error::out_of_bounds(2, 1)));
+        assert!(encoder.encode(&0u16).is_err());
+        assert_eq!(encoder.remaining(), 1);
+        encoder.put_slice(b"z").unwrap();
+        assert_eq!(encoder.position(), 7);
+
+        let (first, second) = encoder.into_inner();
+        assert_eq!(first.into_written(), [1, 2, 3]);
+        assert_eq!(second.into_written(), [4, 5, 6, b'z']);
+    }
+}
@@ -0,0 +1,205 @@
+//! Module containing the [`ConstDecode`] trait, for decoding fixed-layout types in
+//! `const` contexts.
+//!
+//! Types deriving [`Decode`][crate::Decode] with `#[abio(const_decode)]` implement
+//! this trait and gain an inherent `decode_const` method, allowing configuration
+//! blobs embedded with `include_bytes!` to be parsed entirely at compile time:
+//!
+//! ```
+//! #![feature(const_trait_impl)]
+//!
+//! #[derive(abio::Decode)]
+//! #[abio(const_decode, magic = b"CFG\0")]
+//! struct Config {
+//!     version: u16,
+//!     flags: u16,
+//! }
+//!
+//! # macro_rules! include_bytes {
+//! #     ($path:literal) => {
+//! #         b"CFG\0\x01\x00\x00\x00"
+//! #     };
+//! # }
+//! const CONFIG: Config = match Config::decode_const::<abio::LE>(include_bytes!("config.bin"), 0) {
+//!     Ok(config) => config,
+//!     Err(_) => panic!("invalid configuration"),
+//! };
+//! # assert_eq!(CONFIG.version, 1);
+//! ```
+//!
+//! Implementing the trait requires the nightly `const_trait_impl` feature in the
+//! crate deriving it.
+
+use core::mem::size_of;
+
+use crate::{Endian, Endianness, Error, Result};
+
+/// Trait for types with a fixed wire size that can be decoded by a `const fn`.
+///
+/// This trait is implemented for all integer primitives, `bool`, `char` and byte
+/// arrays, and is derived with `#[abio(const_decode)]` for structs composed solely of such
+/// types.
+#[const_trait]
+pub trait ConstDecode: Sized {
+    /// Number of bytes occupied by the type in the input.
+    const SIZE: usize;
+
+    /// Decodes a value from the `SIZE` bytes of `bytes` starting at `offset`, using
+    /// the byte order `E`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` ends before `offset + SIZE`, or if the bytes do
+    /// not hold a valid value.
+    fn decode_const<E: Endianness>(bytes: &[u8], offset: usize) -> Result<Self>;
+}
+
+macro_rules! impl_const_decode {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl const ConstDecode for $ty {
+                const SIZE: usize = size_of::<$ty>();
+
+                #[inline]
+                fn decode_const<E: Endianness>(bytes: &[u8], offset: usize) -> Result<Self> {
+                    match read_array::<{ size_of::<$ty>() }>(bytes, offset) {
+                        Ok(array) => Ok(match E::ENDIAN {
+                            Endian::Little => <$ty>::from_le_bytes(array),
+                            Endian::Big => <$ty>::from_be_bytes(array),
+                        }),
+                        Err(err) => Err(err),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_const_decode!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl const ConstDecode for bool {
+    const SIZE: usize = 1;
+
+    #[inline]
+    fn decode_const<E: Endianness>(bytes: &[u8], offset: usize) -> Result<Self> {
+        match read_array::<1>(bytes, offset) {
+            Ok([0]) => Ok(false),
+            Ok([1]) => Ok(true),
+            Ok([byte]) => Err(Error::invalid_value("bool", byte as u64)),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl const ConstDecode for char {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn decode_const<E: Endianness>(bytes: &[u8], offset: usize) -> Result<Self> {
+        match u32::decode_const::<E>(bytes, offset) {
+            Ok(scalar) => match char::from_u32(scalar) {
+                Some(value) => Ok(value),
+                None => Err(Error::invalid_value("char", scalar as u64)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl<const N: usize> const ConstDecode for [u8; N] {
+    const SIZE: usize = N;
+
+    #[inline]
+    fn decode_const<E: Endianness>(bytes: &[u8], offset: usize) -> Result<Self> {
+        read_array::<N>(bytes, offset)
+    }
+}
+
+/// Copies the `N` bytes of `bytes` starting at `offset` into an array.
+///
+/// # Errors
+///
+/// Returns an error if `bytes` ends before `offset + N`.
+#[inline]
+const fn read_array<const N: usize>(bytes: &[u8], offset: usize) -> Result<[u8; N]> {
+    if bytes
+        .len()
+        .saturating_sub(offset)
+        < N
+    {
+        return Err(Error::out_of_bounds(
+            N,
+            bytes
+                .len()
+                .saturating_sub(offset),
+        ));
+    }
+    let mut array = [0u8; N];
+    let mut idx = 0;
+    while idx < N {
+        array[idx] = bytes[offset + idx];
+        idx += 1;
+    }
+    Ok(array)
+}
+
+/// Compares the bytes of `bytes` starting at `offset` against `magic`.
+///
+/// This is the `const` counterpart of the signature check emitted for types
+/// declaring `#[abio(magic = ...)]`, and is used by derived `decode_const`
+/// methods.
+///
+/// # Errors
+///
+/// Returns an error if the bytes differ from `magic`, or if `bytes` ends before
+/// `offset + magic.len()`.
+#[doc(hidden)]
+#[inline]
+pub const fn match_magic(bytes: &[u8], offset: usize, magic: &'static [u8]) -> Result<()> {
+    let available = bytes
+        .len()
+        .saturating_sub(offset);
+    if available < magic.len() {
+        return Err(Error::out_of_bounds(magic.len(), available));
+    }
+    let (_, rest) = bytes.split_at(offset);
+    let (actual, _) = rest.split_at(magic.len());
+    let mut idx = 0;
+    while idx < magic.len() {
+        if actual[idx] != magic[idx] {
+            return Err(Error::bad_magic(magic, actual, offset));
+        }
+        idx += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BE, LE};
+
+    const BYTES: &[u8] = &[0x43, 0x46, 0x47, 0x00, 0x01, 0x02, 0x03, 0x04];
+    const VALUE: u32 = match u32::decode_const::<BE>(BYTES, 4) {
+        Ok(value) => value,
+        Err(_) => panic!("the input holds a `u32` at offset 4"),
+    };
+
+    #[test]
+    fn decodes_primitives_at_compile_time() {
+        assert_eq!(VALUE, 0x0102_0304);
+        assert_eq!(u16::decode_const::<LE>(BYTES, 6).unwrap(), 0x0403);
+        assert_eq!(<[u8; 3]>::decode_const::<LE>(BYTES, 0).unwrap(), *b"CFG");
+        assert!(u32::decode_const::<LE>(BYTES, 5).is_err());
+        assert!(u8::decode_const::<LE>(BYTES, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn matches_magic_signatures() {
+        assert!(match_magic(BYTES, 0, b"CFG\0").is_ok());
+        assert!(match_magic(BYTES, 1, b"CFG")
+            .unwrap_err()
+            .is_bad_magic());
+        assert!(match_magic(BYTES, 6, b"CFG").is_err());
+    }
+}
@@ -0,0 +1,257 @@
+//! Module containing the [`Counted`] type and the [`DecodeCounted`] trait, for
+//! decoding fields whose number of elements is given by an earlier field.
+//!
+//! Many formats store an array as a count followed by that many elements. Types
+//! deriving [`Decode`] declare such arrays with `#[abio(len = "count")]`, where
+//! `count` is an expression over previously decoded fields:
+//!
+//! ```
+//! #[derive(abio::Decode)]
+//! struct Table<'data> {
+//!     count: u16,
+//!     #[abio(len = "count")]
+//!     entries: abio::codec::Counted<'data, u32>,
+//! }
+//! ```
+//!
+//! Byte strings may also be decoded into a [`Bytes`], or, with the `alloc` feature,
+//! into a `Cow<'data, [u8]>` that borrows from the input until it is modified.
+
+#[cfg(any(test, feature = "alloc"))]
+use alloc::borrow::Cow;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::size_of;
+
+use crate::{
+    Abi, BigEndian, Bytes, Decode, Endian, Endianness, Error, LittleEndian, Result, Source,
+};
+
+/// Trait for types decoded from a number of elements known ahead of time, rather
+/// than from the input alone.
+pub trait DecodeCounted<'data>: Sized {
+    /// Decodes `count` elements from the start of `bytes`, returning the decoded
+    /// value and the number of bytes consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the size of `count` elements overflows a `usize`, or if
+    /// `bytes` ends before the last element.
+    fn decode_counted<E: Endianness>(bytes: &'data [u8], count: usize) -> Result<(Self, usize)>;
+}
+
+/// The raw bytes of a field are captured as-is, one byte per element.
+impl<'data> DecodeCounted<'data> for Bytes<'data> {
+    #[inline]
+    fn decode_counted<E: Endianness>(bytes: &'data [u8], count: usize) -> Result<(Self, usize)> {
+        let region = bytes.read_slice(0, count)?;
+        Ok((Bytes::new_allow_empty(region), count))
+    }
+}
+
+/// The raw bytes of a field are borrowed as-is, one byte per element, and are only
+/// copied once the field is modified.
+#[cfg(any(test, feature = "alloc"))]
+impl<'data> DecodeCounted<'data> for Cow<'data, [u8]> {
+    #[inline]
+    fn decode_counted<E: Endianness>(bytes: &'data [u8], count: usize) -> Result<(Self, usize)> {
+        let region = bytes.read_slice(0, count)?;
+        Ok((Cow::Borrowed(region), count))
+    }
+}
+
+/// A borrowed array of `T` whose length was read from the input, decoding each
+/// element only when it is accessed.
+///
+/// The bytes of all elements are bounds-checked when the array is decoded, so
+/// accessing an element can only fail if its bytes do not hold a valid `T`.
+pub struct Counted<'data, T> {
+    /// Bytes of all elements, in order.
+    bytes: Bytes<'data>,
+    /// Number of elements.
+    count: usize,
+    /// Byte order the array was decoded with.
+    endian: Endian,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Counted<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Counted<'_, T> {}
+
+impl<'data, T> Counted<'data, T> {
+    /// Creates an array of `count` elements over `bytes`, which must hold exactly
+    /// `count * size_of::<T>()` bytes.
+    #[inline]
+    pub(crate) const fn from_parts(bytes: Bytes<'data>, count: usize, endian: Endian) -> Self {
+        Counted { bytes, count, endian, _marker: PhantomData }
+    }
+}
+
+impl<'data, T: Abi + Decode<'data>> Counted<'data, T> {
+    /// Returns the number of elements in the array.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the array does not contain any elements.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the bytes of all elements of the array.
+    #[inline]
+    pub const fn as_bytes(&self) -> Bytes<'data> {
+        self.bytes
+    }
+
+    /// Decodes the element at `idx`, returning `None` if it is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes of the element do not hold a valid `T`.
+    #[inline]
+    pub fn get(&self, idx: usize) -> Option<Result<T>> {
+        if idx >= self.count {
+            return None;
+        }
+        let bytes = self
+            .bytes
+            .into_slice()
+            .get(idx * size_of::<T>()..)
+            .unwrap_or_default();
+        let decoded = match self.endian {
+            Endian::Little => T::decode::<LittleEndian>(bytes),
+            Endian::Big => T::decode::<BigEndian>(bytes),
+        };
+        Some(decoded.map(|(value, _)| value))
+    }
+
+    /// Returns an iterator decoding each element of the array in order.
+    #[inline]
+    pub const fn iter(&self) -> CountedIter<'data, T> {
+        CountedIter { array: *self, idx: 0 }
+    }
+}
+
+impl<'data, T: Abi + Decode<'data>> DecodeCounted<'data> for Counted<'data, T> {
+    #[inline]
+    fn decode_counted<E: Endianness>(bytes: &'data [u8], count: usize) -> Result<(Self, usize)> {
+        let len = count
+            .checked_mul(size_of::<T>())
+            .ok_or_else(|| Error::out_of_bounds(usize::MAX, bytes.len()))?;
+        let region = bytes.read_slice(0, len)?;
+        let array = Counted::from_parts(Bytes::new_allow_empty(region), count, E::ENDIAN);
+        Ok((array, len))
+    }
+}
+
+impl<'data, T: Abi + Decode<'data> + fmt::Debug> fmt::Debug for Counted<'data, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.iter())
+            .finish()
+    }
+}
+
+impl<'data, T: Abi + Decode<'data>> IntoIterator for Counted<'data, T> {
+    type IntoIter = CountedIter<'data, T>;
+    type Item = Result<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of a [`Counted`] array, created by
+/// [`Counted::iter`].
+#[derive(Clone, Copy)]
+pub struct CountedIter<'data, T> {
+    array: Counted<'data, T>,
+    idx: usize,
+}
+
+impl<'data, T: Abi + Decode<'data>> Iterator for CountedIter<'data, T> {
+    type Item = Result<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.array.get(self.idx)?;
+        self.idx += 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.array.len() - self.idx;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'data, T: Abi + Decode<'data>> ExactSizeIterator for CountedIter<'data, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Encode, BE, LE};
+
+    #[test]
+    fn decodes_elements_lazily() {
+        let bytes = [0, 1, 0, 2, 0, 3, 0xff];
+        let (array, len) = Counted::<u16>::decode_counted::<BE>(&bytes, 3).unwrap();
+        assert_eq!(len, 6);
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.as_bytes().len(), 6);
+        assert_eq!(array.get(2).unwrap().unwrap(), 3);
+        assert!(array.get(3).is_none());
+
+        let values = array
+            .iter()
+            .collect::<Result<alloc::vec::Vec<_>>>()
+            .unwrap();
+        assert_eq!(values, [1, 2, 3]);
+
+        let (empty, len) = Counted::<u16>::decode_counted::<LE>(&[], 0).unwrap();
+        assert!(empty.is_empty() && len == 0);
+    }
+
+    #[test]
+    fn rejects_truncated_and_overflowing_counts() {
+        let bytes = [0u8; 7];
+        assert!(Counted::<u16>::decode_counted::<LE>(&bytes, 4).is_err());
+        assert!(Counted::<u32>::decode_counted::<LE>(&bytes, usize::MAX / 2).is_err());
+        assert_eq!(
+            Bytes::decode_counted::<LE>(&bytes, 7)
+                .unwrap()
+                .1,
+            7
+        );
+        assert!(Bytes::decode_counted::<LE>(&bytes, 8).is_err());
+    }
+
+    #[test]
+    fn decodes_byte_strings_without_copying() {
+        let bytes = [1, 2, 3];
+        let (mut cow, len) = Cow::<[u8]>::decode_counted::<LE>(&bytes, 2).unwrap();
+        assert_eq!(len, 2);
+        assert!(matches!(cow, Cow::Borrowed(&[1, 2])));
+
+        cow.to_mut().push(4);
+        let mut buf = [0u8; 3];
+        assert_eq!(
+            cow.encode::<LE>(&mut buf)
+                .unwrap(),
+            3
+        );
+        assert_eq!(buf, [1, 2, 4]);
+        assert!(cow
+            .encode::<LE>(&mut buf[..2])
+            .is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Module containing the [`Coverage`] type, for recording which bytes of an input
+//! were consumed while decoding it.
+//!
+//! Wrapping a source in a [`Coverage`] before handing it to a [`Reader`] records
+//! every region that is read. The [report][Coverage::report] lists the regions that
+//! were never read, which is useful when reverse engineering a format to find the
+//! fields that existing type definitions do not cover yet:
+//!
+//! ```
+//! use abio::codec::Coverage;
+//! use abio::Reader;
+//!
+//! let input: &[u8] = b"\x01\x00\x00\x00????payload";
+//! let coverage = Coverage::new(input);
+//! let mut reader = Reader::new(&coverage);
+//! reader.read_bytes(4).unwrap();
+//! reader.set_position(8).unwrap();
+//! reader.read_bytes(7).unwrap();
+//!
+//! let report = coverage.report();
+//! assert_eq!(report.consumed, 11);
+//! assert_eq!(report.gaps, [abio::Span::new(4, 4)]);
+//! ```
+//!
+//! [`Reader`]: crate::Reader
+
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+
+use crate::{Result, Source, Span};
+
+/// A [`Source`] that records the regions read from the source it wraps.
+///
+/// Both [`read_slice`][Source::read_slice] and
+/// [`copy_to_slice`][Source::copy_to_slice] count as consuming the region they
+/// read. Peeking with [`peek_slice`][Source::peek_slice] and scanning for a
+/// signature with [`rscan_for`][Source::rscan_for] do not, so values decoded with
+/// [`Reader::read`][crate::Reader::read] consume only the bytes they were decoded
+/// from.
+#[derive(Debug)]
+pub struct Coverage<'data, S: Source + ?Sized = [u8]> {
+    /// Source the regions are read from.
+    source: &'data S,
+    /// Sorted, non-overlapping and non-adjacent regions that have been read.
+    consumed: RefCell<Vec<Span>>,
+}
+
+impl<'data, S: Source + ?Sized> Coverage<'data, S> {
+    /// Creates a new [`Coverage`] wrapping `source`, with no bytes consumed.
+    #[inline]
+    pub const fn new(source: &'data S) -> Coverage<'data, S> {
+        Coverage { source, consumed: RefCell::new(Vec::new()) }
+    }
+
+    /// Returns the wrapped source.
+    #[inline]
+    pub const fn source(&self) -> &'data S {
+        self.source
+    }
+
+    /// Returns the regions that have been read so far, in ascending order.
+    ///
+    /// Overlapping and adjacent reads are merged into a single region.
+    pub fn consumed(&self) -> Vec<Span> {
+        self.consumed.borrow().clone()
+    }
+
+    /// Returns a report of the bytes that have been read so far, and of the regions
+    /// that have not been read.
+    pub fn report(&self) -> CoverageReport {
+        let consumed = self.consumed.borrow();
+        let mut gaps = Vec::new();
+        let mut offset = 0;
+        for span in consumed.iter() {
+            if span.start() > offset {
+                gaps.push(Span::from_range(offset..span.start()));
+            }
+            offset = span.end();
+        }
+        if self.source.len() > offset {
+            gaps.push(Span::from_range(offset..self.source.len()));
+        }
+        CoverageReport {
+            len: self.source.len(),
+            consumed: consumed
+                .iter()
+                .map(Span::size)
+                .sum(),
+            gaps,
+        }
+    }
+
+    /// Marks the `len` bytes starting at `offset` as consumed.
+    fn record(&self, offset: usize, len: usize) {
+        if len == 0 {
+            return;
+        }
+        let (mut start, mut end) = (offset, offset + len);
+        let mut consumed = self.consumed.borrow_mut();
+        // Regions ending before `start` are unaffected, and the ones that follow up to
+        // the first region starting after `end` are merged with the new region.
+        let first = consumed.partition_point(|span| span.end() < start);
+        let last = consumed.partition_point(|span| span.start() <= end);
+        if first < last {
+            start = start.min(consumed[first].start());
+            end = end.max(consumed[last - 1].end());
+        }
+        consumed.splice(first..last, [Span::from_range(start..end)]);
+    }
+}
+
+impl<S: Source + ?Sized> Source for Coverage<'_, S> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.source.len()
+    }
+
+    #[inline]
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        let bytes = self
+            .source
+            .read_slice(offset, len)?;
+        self.record(offset, len);
+        Ok(bytes)
+    }
+
+    #[inline]
+    fn peek_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.source
+            .peek_slice(offset, len)
+    }
+
+    #[inline]
+    fn contiguous_len(&self, offset: usize) -> usize {
+        self.source
+            .contiguous_len(offset)
+    }
+
+    #[inline]
+    fn copy_to_slice(&self, offset: usize, dst: &mut [u8]) -> Result<()> {
+        self.source
+            .copy_to_slice(offset, dst)?;
+        self.record(offset, dst.len());
+        Ok(())
+    }
+
+    #[inline]
+    fn rscan_for(&self, needle: &[u8], window: usize) -> Result<Option<usize>> {
+        self.source
+            .rscan_for(needle, window)
+    }
+}
+
+/// Summary of the bytes of an input that were read, returned by
+/// [`Coverage::report`].
+///
+/// The [`Display`][fmt::Display] implementation lists each unread region on its
+/// own line.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CoverageReport {
+    /// Total number of bytes in the input.
+    pub len: usize,
+    /// Number of distinct bytes that were read.
+    pub consumed: usize,
+    /// Regions that were not read, in ascending order.
+    pub gaps: Vec<Span>,
+}
+
+impl CoverageReport {
+    /// Returns `true` if every byte of the input was read.
+    #[inline]
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.gaps.is_empty()
+    }
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "consumed {} of {} bytes", self.consumed, self.len)?;
+        for gap in &self.gaps {
+            writeln!(f, "unread: {:#x}..{:#x} ({} bytes)", gap.start(), gap.end(), gap.size())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{Reader, LE};
+
+    #[test]
+    fn merges_reads_and_reports_gaps() {
+        let input = [0u8; 32];
+        let coverage = Coverage::new(&input[..]);
+        let mut reader = Reader::new(&coverage);
+        reader.read_bytes(4).unwrap();
+        reader.set_position(10).unwrap();
+        reader
+            .copy_to_slice(&mut [0; 2])
+            .unwrap();
+        reader.read_bytes(0).unwrap();
+        reader.set_position(20).unwrap();
+        reader.read_bytes(4).unwrap();
+        assert_eq!(coverage.consumed(), [Span::new(0, 4), Span::new(10, 2), Span::new(20, 4)]);
+
+        // Reads bridging, touching and overlapping existing regions are merged.
+        reader.set_position(3).unwrap();
+        reader.read_bytes(8).unwrap();
+        reader.read_bytes(1).unwrap();
+        reader.set_position(18).unwrap();
+        reader.read_bytes(8).unwrap();
+        assert_eq!(coverage.consumed(), [Span::new(0, 12), Span::new(18, 8)]);
+        assert_eq!(
+            coverage
+                .rscan_for(b"\x01", 32)
+                .unwrap(),
+            None
+        );
+
+        let report = coverage.report();
+        assert_eq!(report.consumed, 20);
+        assert_eq!(report.gaps, [Span::new(12, 6), Span::new(26, 6)]);
+        assert!(!report.is_complete());
+        assert_eq!(
+            report.to_string(),
+            "consumed 20 of 32 bytes\nunread: 0xc..0x12 (6 bytes)\nunread: 0x1a..0x20 (6 bytes)\n"
+        );
+
+        reader.set_position(0).unwrap();
+        reader.read_bytes(32).unwrap();
+        assert!(coverage.report().is_complete());
+    }
+
+    #[test]
+    fn decoded_values_consume_only_their_bytes() {
+        let input = [0u8; 16];
+        let coverage = Coverage::new(&input[..]);
+        let mut reader = Reader::new(&coverage);
+        reader
+            .read::<u16, LE>()
+            .unwrap();
+        reader.skip(4).unwrap();
+        reader
+            .read::<u32, LE>()
+            .unwrap();
+        assert!(reader
+            .read::<u64, LE>()
+            .is_err());
+        assert_eq!(coverage.consumed(), [Span::new(0, 2), Span::new(6, 4)]);
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{Abi, Endianness, Result};
+#[cfg(feature = "arena")]
+use super::arena::ArenaAlloc;
+use super::trace::Tracer;
+use crate::config::Codec;
+use crate::{Endian, Endianness, Error, Result, BE, LE};
+
+pub mod combinator;
 
 /// A trait to define the endianness, or byte order, of some contiguous region of
 /// memory represented as a byte slice.
@@ -108,52 +114,235 @@ pub trait Decoder: Endianness {
     fn read_i128(bytes: &[u8]) -> Result<i128>;
 }
 
-/// The [`Encode`] trait defines how a type is decoded or decoded from a
-/// slice or chunk of bytes after being validated. It provides a way to translate raw
-/// byte sequences back into meaningful data in a structured manner.
+/// The [`Decode`] trait defines how a type is decoded from a slice of bytes after
+/// being validated. It provides a way to translate raw byte sequences back into
+/// meaningful data in a structured manner.
 ///
-/// # Performance
+/// # Ownership
 ///
-/// Although this trait prioritizes safety and correctness over speed, it offers two
-/// variants of the provided reader routines. One of them returns a
-/// reference to the underlying data while the second performs a [`core::ptr::read`]
-/// operation on the pointer, performing a bitwise copy. This method is not quite as
-/// performant as taking a reference to a dereferenced raw pointer, so this trait
-/// provides the ability to implement both.
+/// Decoding returns an owned value rather than a reference into the input. Values
+/// that must be byte-swapped, or that are assembled from several fields, do not
+/// exist anywhere in the input, so there is nothing a reference could point to.
+/// For zero-copy access to types that are already laid out correctly in memory,
+/// use the [`Abi`][crate::Abi] and [`AsBytes`][crate::AsBytes] traits instead.
 ///
 /// # Size and Alignment
 ///
-/// Correctly handling alignment is an important part of preventing
-/// undefined behavior, which is a key goal of the abio crate.
+/// Decoding copies bytes out of the input, so the input does not need to meet the
+/// alignment requirements of `Self`.
 ///
 /// # Endian-Aware Decoding
 ///
-/// [`Encode`] leverages the [`Endianness`] trait to provide ergonmic access to
+/// [`Decode`] leverages the [`Endianness`] trait to provide ergonmic access to
 /// endian-aware read and write primitives. These primitives operate on raw byte
 /// slices only, since file and network I/O is outside the scope of [`abio`][crate].
 /// It ensures the proper interpretation of data according to the specific byte order
 /// serialization type.
 ///
-/// # Implementing Encode
+/// # Implementing Decode
 ///
-/// Blanket-like implementations are provided out of the box for all simple
-/// primitives and common types, so you don't have to repeat this work. If you would
-/// prefer to implement [`Encode`] yourself, you may disable the
-/// __`default-codec`__ feature, which is enabled by default. ability to disable them
-/// behind a feature-gate.
+/// Implementations are provided out of the box for all integer primitives, `bool`,
+/// `char` and the aligned integer types in the [`integer`][crate::integer] module,
+/// so you don't have to repeat this work. For your own types, prefer deriving this trait.
+pub trait Decode<'data>: Sized {
+    /// Decodes a concrete type from the start of a slice of bytes, returning the
+    /// decoded value and the number of bytes consumed.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `bytes` does not contain enough bytes to
+    /// decode `Self`, or if the bytes do not represent a valid value of `Self`.
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(Self, usize)>;
+
+    /// Decodes a concrete type with the byte order and options of `codec`, chosen
+    /// at runtime.
+    ///
+    /// [`Codec::decode`] calls this method after applying the byte limit of the
+    /// codec. The default implementation decodes with the byte order of `codec`,
+    /// through [`decode_configured`][Decode::decode_configured].
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`decode_configured`][Decode::decode_configured].
+    #[inline]
+    fn decode_with(bytes: &'data [u8], codec: &Codec) -> Result<(Self, usize)> {
+        match codec.endian() {
+            Endian::Little => Self::decode_configured::<LE>(bytes, codec),
+            Endian::Big => Self::decode_configured::<BE>(bytes, codec),
+        }
+    }
+
+    /// Decodes a concrete type exactly like [`decode`][Decode::decode], enforcing
+    /// the element count and nesting depth of the limit of `codec`, and stopping
+    /// once its cancel token has been cancelled.
+    ///
+    /// The default implementation checks the cancel token once, and ignores the
+    /// limit, which is correct for types without fields. Derived implementations
+    /// [enter][Codec::enter] one level of nesting, check the cancel token before
+    /// each field, check the count of each length-prefixed field against the limit
+    /// and forward the entered codec to each field, so hostile inputs fail as soon
+    /// as they exceed the limit, and deeply nested inputs stop soon after the token
+    /// is cancelled. Types whose decoding depends on the other options of the codec
+    /// override it, such as [`Bool8`][crate::codec::Bool8] when the codec accepts
+    /// lenient booleans.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`decode`][Decode::decode], and also
+    /// returns an error for which
+    /// [`Error::is_limit_exceeded`][crate::Error::is_limit_exceeded] returns `true`
+    /// if the value exceeds the limit of `codec`, or for which
+    /// [`Error::is_cancelled`][crate::Error::is_cancelled] returns `true` if its
+    /// cancel token has been cancelled.
+    #[inline]
+    fn decode_configured<E: Endianness>(
+        bytes: &'data [u8],
+        codec: &Codec,
+    ) -> Result<(Self, usize)> {
+        codec.check_cancelled()?;
+        Self::decode::<E>(bytes)
+    }
+
+    /// Decodes a concrete type exactly like [`decode`][Decode::decode], recording
+    /// each decoded field into `tracer`.
+    ///
+    /// The default implementation does not record any events, and decodes through
+    /// [`decode_configured`][Decode::decode_configured] if `tracer` carries a
+    /// [`Codec`]. Types deriving this trait override it to record one event per
+    /// field, which makes it possible to explain why a parse produced a given
+    /// value, and enforce the codec of `tracer` like `decode_configured` does.
+    /// Events only hold the decoded value of each field for types annotated with
+    /// `#[abio(trace)]`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`decode`][Decode::decode], and the
+    /// errors of [`decode_configured`][Decode::decode_configured] if `tracer`
+    /// carries a codec.
+    #[inline]
+    fn decode_traced<E: Endianness>(
+        bytes: &'data [u8],
+        tracer: &mut Tracer<'_>,
+    ) -> Result<(Self, usize)> {
+        match tracer.codec() {
+            Some(codec) => Self::decode_configured::<E>(bytes, codec),
+            None => Self::decode::<E>(bytes),
+        }
+    }
+
+    /// Decodes a concrete type exactly like [`decode`][Decode::decode], allocating
+    /// the storage of any variable-length containers from `arena` instead of the
+    /// global heap.
+    ///
+    /// The default implementation does not allocate, and is correct for any type
+    /// that does not contain such containers. Derived implementations forward the
+    /// arena to each field when the `arena` feature is enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`decode`][Decode::decode], and also
+    /// returns an error if `arena` is exhausted.
+    #[cfg(feature = "arena")]
+    #[inline]
+    fn decode_in<'arena, E: Endianness, A: ArenaAlloc + ?Sized>(
+        bytes: &'data [u8],
+        arena: &'arena A,
+    ) -> Result<(Self, usize)>
+    where
+        'arena: 'data,
+    {
+        let _ = arena;
+        Self::decode::<E>(bytes)
+    }
+}
+
+/// Trait for types that can only be decoded with outside information, such as the
+/// version of the format, the base of a string table or the architecture of the
+/// machine that produced the input, given as a context of type `Ctx`.
+///
+/// Every type implementing [`Decode`] also implements this trait for any context,
+/// which it ignores, so types requiring a context can hold fields that do not.
+///
+/// # Deriving
+///
+/// Deriving [`Decode`] on a type declaring `#[abio(ctx = "Ctx")]` implements
+/// `DecodeWith<'data, Ctx>` instead. The context is passed on to every field, so it
+/// is threaded through nested decodes, and attribute expressions such as `if`,
+/// `len` and `version` refer to it as `ctx`.
+///
+/// # Examples
 ///
-/// This trait can be implemented for any type `T` where `T` implements the [`Abi`]
-/// trait, and is said to be "ABI-compatible" with respect to this crate.
-pub trait Decode<'data>: Abi {
-    /// Decodes a concrete type from a slice of bytes, returning a reference to
-    /// `Self` and the number of bytes consumed.
+/// ```
+/// use abio::{Decode, DecodeWith, Endianness, Result, BE};
+///
+/// /// A length stored in 16 bits before version 2 of the format, and in 32 bits
+/// /// since.
+/// #[derive(Debug, PartialEq)]
+/// struct Length(u32);
+///
+/// impl<'data> DecodeWith<'data, u8> for Length {
+///     fn decode_ctx<E: Endianness>(
+///         bytes: &'data [u8],
+///         version: &u8,
+///     ) -> Result<(Length, usize)> {
+///         if *version < 2 {
+///             let (len, size) = u16::decode::<E>(bytes)?;
+///             Ok((Length(len.into()), size))
+///         } else {
+///             let (len, size) = u32::decode::<E>(bytes)?;
+///             Ok((Length(len), size))
+///         }
+///     }
+/// }
+///
+/// let bytes = [0, 1, 0, 0];
+/// assert_eq!(Length::decode_ctx::<BE>(&bytes, &1).unwrap(), (Length(1), 2));
+/// assert_eq!(Length::decode_ctx::<BE>(&bytes, &2).unwrap(), (Length(0x0001_0000), 4));
+/// ```
+pub trait DecodeWith<'data, Ctx: ?Sized>: Sized {
+    /// Decodes a concrete type from the start of a slice of bytes with the context
+    /// `ctx`, returning the decoded value and the number of bytes consumed.
     ///
     /// # Errors
     ///
-    /// This method returns an error if of the following conditions are true:
-    ///   * `bytes.len() < Self::SIZE`
-    ///   * The pointer represented by `bytes` does not meet the alignment
-    ///     requirements of `Self`.
-    /// [`Abi`].
-    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(&'data Self, usize)>;
+    /// This method returns an error if `bytes` does not contain enough bytes to
+    /// decode `Self`, or if the bytes do not represent a valid value of `Self` in
+    /// the context `ctx`.
+    fn decode_ctx<E: Endianness>(bytes: &'data [u8], ctx: &Ctx) -> Result<(Self, usize)>;
+}
+
+impl<'data, T: Decode<'data>, Ctx: ?Sized> DecodeWith<'data, Ctx> for T {
+    #[inline]
+    fn decode_ctx<E: Endianness>(bytes: &'data [u8], ctx: &Ctx) -> Result<(T, usize)> {
+        let _ = ctx;
+        T::decode::<E>(bytes)
+    }
+}
+
+/// A `bool` occupies a single byte, which must be `0` or `1`. Any other byte is
+/// rejected rather than reinterpreted, since it is not a valid `bool`.
+impl<'data> Decode<'data> for bool {
+    #[inline]
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(bool, usize)> {
+        let (byte, len) = u8::decode::<E>(bytes)?;
+        match byte {
+            0 => Ok((false, len)),
+            1 => Ok((true, len)),
+            _ => Err(Error::invalid_value("bool", u64::from(byte))),
+        }
+    }
+}
+
+/// A `char` occupies four bytes holding its Unicode scalar value in the byte order
+/// `E`. Surrogates and values past [`char::MAX`] are rejected.
+impl<'data> Decode<'data> for char {
+    #[inline]
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(char, usize)> {
+        let (scalar, len) = u32::decode::<E>(bytes)?;
+        match char::from_u32(scalar) {
+            Some(value) => Ok((value, len)),
+            None => Err(Error::invalid_value("char", u64::from(scalar))),
+        }
+    }
 }
@@ -0,0 +1,338 @@
+//! Module containing the [`Parser`] trait and its combinators, for composing
+//! ad-hoc parsers over a [`Reader`] without declaring a type for every layout.
+//!
+//! Parsers are built from [`Decode::decode`][crate::Decode::decode] functions,
+//! such as `u16::decode::<BE>`, the primitives [`bytes`] and [`tag`], or closures,
+//! and combined with the methods of [`Parser`]. Combinators do not allocate, and a
+//! parser that fails leaves the reader where it started, so the
+//! [position][Reader::position] of the reader is the offset of the value that
+//! could not be parsed:
+//!
+//! ```
+//! use abio::decoder::combinator::{bytes, tag, Parser};
+//! use abio::{Decode, Reader, BE};
+//!
+//! // A length-prefixed name following a signature.
+//! let name = tag(b"NM").and_then(|_| u8::decode::<BE>.and_then(|len| bytes(len.into())));
+//!
+//! let mut input = Reader::new(&b"NM\x03abcNM\x09de"[..]);
+//! assert_eq!(name.parse(&mut input).unwrap(), b"abc");
+//! assert!(name.parse(&mut input).is_err());
+//! assert_eq!(input.position(), 6);
+//! ```
+
+use crate::codec::Reader;
+use crate::{Error, Result, Source, Span};
+
+/// Trait for parsers reading a value of type [`Output`][Parser::Output] from a
+/// [`Reader`].
+///
+/// This trait is implemented for functions and closures with the signature of
+/// [`Decode::decode`][crate::Decode::decode], taking the bytes following the
+/// position of the reader and returning the parsed value along with the number of
+/// bytes consumed. This includes `T::decode::<E>` itself for every type `T`
+/// implementing [`Decode`][crate::Decode], so any decodable type can be parsed
+/// without a dedicated primitive.
+pub trait Parser<'data> {
+    /// Type of the parsed values.
+    type Output;
+
+    /// Parses a value from `input`, advancing it past the bytes consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes following the position of `input` do not
+    /// hold a valid value. Implementations must leave `input` unchanged on error.
+    fn parse<S: Source + ?Sized>(&self, input: &mut Reader<'data, S>) -> Result<Self::Output>;
+
+    /// Returns a parser transforming the values parsed by this parser with `f`.
+    #[inline]
+    fn map<F, U>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+        F: Fn(Self::Output) -> U,
+    {
+        Map { parser: self, f }
+    }
+
+    /// Returns a parser running the parser returned by `f` after this parser, with
+    /// the value parsed by this parser.
+    ///
+    /// This is how the layout of later values is made to depend on earlier ones,
+    /// such as a payload following its length.
+    #[inline]
+    fn and_then<F, P>(self, f: F) -> AndThen<Self, F>
+    where
+        Self: Sized,
+        F: Fn(Self::Output) -> P,
+        P: Parser<'data>,
+    {
+        AndThen { parser: self, f }
+    }
+
+    /// Returns a parser running this parser `N` times in a row, and collecting the
+    /// values parsed into an array.
+    #[inline]
+    fn repeated<const N: usize>(self) -> Repeated<Self, N>
+    where
+        Self: Sized,
+    {
+        Repeated { parser: self }
+    }
+
+    /// Returns a parser running this parser without advancing the reader, to look
+    /// ahead at the input before deciding how to parse it.
+    #[inline]
+    fn peek(self) -> Peek<Self>
+    where
+        Self: Sized,
+    {
+        Peek { parser: self }
+    }
+
+    /// Returns a parser pairing the values parsed by this parser with the span of
+    /// the bytes they were parsed from, relative to the start of the reader.
+    #[inline]
+    fn spanned(self) -> Spanned<Self>
+    where
+        Self: Sized,
+    {
+        Spanned { parser: self }
+    }
+}
+
+/// The function is given the bytes following the position of the reader, which
+/// must be contiguous in its source.
+impl<'data, F, T> Parser<'data> for F
+where
+    F: Fn(&'data [u8]) -> Result<(T, usize)>,
+{
+    type Output = T;
+
+    #[inline]
+    fn parse<S: Source + ?Sized>(&self, input: &mut Reader<'data, S>) -> Result<T> {
+        let mut cursor = *input;
+        let rest = cursor.read_bytes(cursor.remaining())?;
+        let (value, len) = self(rest)?;
+        input.read_bytes(len)?;
+        Ok(value)
+    }
+}
+
+/// Parser reading a fixed number of bytes, created by [`bytes`].
+#[derive(Clone, Copy, Debug)]
+pub struct BytesParser {
+    len: usize,
+}
+
+/// Returns a parser reading the next `len` bytes as a borrowed slice.
+#[inline]
+pub const fn bytes(len: usize) -> BytesParser {
+    BytesParser { len }
+}
+
+impl<'data> Parser<'data> for BytesParser {
+    type Output = &'data [u8];
+
+    #[inline]
+    fn parse<S: Source + ?Sized>(&self, input: &mut Reader<'data, S>) -> Result<&'data [u8]> {
+        input.read_bytes(self.len)
+    }
+}
+
+/// Parser matching a signature, created by [`tag`].
+#[derive(Clone, Copy, Debug)]
+pub struct TagParser {
+    expected: &'static [u8],
+}
+
+/// Returns a parser consuming the bytes of `expected`, and failing with an error
+/// reporting the offset of the mismatch if the input holds other bytes.
+#[inline]
+pub const fn tag(expected: &'static [u8]) -> TagParser {
+    TagParser { expected }
+}
+
+impl<'data> Parser<'data> for TagParser {
+    type Output = &'data [u8];
+
+    #[inline]
+    fn parse<S: Source + ?Sized>(&self, input: &mut Reader<'data, S>) -> Result<&'data [u8]> {
+        let offset = input.position();
+        let mut cursor = *input;
+        let actual = cursor.read_bytes(
+            self.expected
+                .len()
+                .min(cursor.remaining()),
+        )?;
+        if actual != self.expected {
+            return Err(Error::bad_magic(self.expected, actual, offset));
+        }
+        *input = cursor;
+        Ok(actual)
+    }
+}
+
+/// Parser transforming the values of another parser, created by [`Parser::map`].
+#[derive(Clone, Copy, Debug)]
+pub struct Map<P, F> {
+    parser: P,
+    f: F,
+}
+
+impl<'data, P, F, U> Parser<'data> for Map<P, F>
+where
+    P: Parser<'data>,
+    F: Fn(P::Output) -> U,
+{
+    type Output = U;
+
+    #[inline]
+    fn parse<S: Source + ?Sized>(&self, input: &mut Reader<'data, S>) -> Result<U> {
+        self.parser
+            .parse(input)
+            .map(&self.f)
+    }
+}
+
+/// Parser running a parser chosen from the values of another parser, created by
+/// [`Parser::and_then`].
+#[derive(Clone, Copy, Debug)]
+pub struct AndThen<P, F> {
+    parser: P,
+    f: F,
+}
+
+impl<'data, P, F, Q> Parser<'data> for AndThen<P, F>
+where
+    P: Parser<'data>,
+    F: Fn(P::Output) -> Q,
+    Q: Parser<'data>,
+{
+    type Output = Q::Output;
+
+    #[inline]
+    fn parse<S: Source + ?Sized>(&self, input: &mut Reader<'data, S>) -> Result<Q::Output> {
+        let mut cursor = *input;
+        let first = self.parser.parse(&mut cursor)?;
+        let value = (self.f)(first).parse(&mut cursor)?;
+        *input = cursor;
+        Ok(value)
+    }
+}
+
+/// Parser running another parser a fixed number of times, created by
+/// [`Parser::repeated`].
+#[derive(Clone, Copy, Debug)]
+pub struct Repeated<P, const N: usize> {
+    parser: P,
+}
+
+impl<'data, P, const N: usize> Parser<'data> for Repeated<P, N>
+where
+    P: Parser<'data>,
+{
+    type Output = [P::Output; N];
+
+    #[inline]
+    fn parse<S: Source + ?Sized>(&self, input: &mut Reader<'data, S>) -> Result<[P::Output; N]> {
+        let mut cursor = *input;
+        let mut failure = None;
+        let values = core::array::from_fn::<_, N, _>(|_| match failure {
+            Some(_) => None,
+            None => self
+                .parser
+                .parse(&mut cursor)
+                .map_err(|error| failure = Some(error))
+                .ok(),
+        });
+        if let Some(error) = failure {
+            return Err(error);
+        }
+        *input = cursor;
+        Ok(values.map(|value| value.unwrap_or_else(|| unreachable!())))
+    }
+}
+
+/// Parser running another parser without advancing the reader, created by
+/// [`Parser::peek`].
+#[derive(Clone, Copy, Debug)]
+pub struct Peek<P> {
+    parser: P,
+}
+
+impl<'data, P> Parser<'data> for Peek<P>
+where
+    P: Parser<'data>,
+{
+    type Output = P::Output;
+
+    #[inline]
+    fn parse<S: Source + ?Sized>(&self, input: &mut Reader<'data, S>) -> Result<P::Output> {
+        let mut cursor = *input;
+        self.parser.parse(&mut cursor)
+    }
+}
+
+/// Parser pairing the values of another parser with the span they were parsed
+/// from, created by [`Parser::spanned`].
+#[derive(Clone, Copy, Debug)]
+pub struct Spanned<P> {
+    parser: P,
+}
+
+impl<'data, P> Parser<'data> for Spanned<P>
+where
+    P: Parser<'data>,
+{
+    type Output = (P::Output, Span);
+
+    #[inline]
+    fn parse<S: Source + ?Sized>(&self, input: &mut Reader<'data, S>) -> Result<(P::Output, Span)> {
+        let start = input.position();
+        let value = self.parser.parse(input)?;
+        Ok((value, Span::new(start, input.position() - start)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Decode, BE, LE};
+
+    #[test]
+    fn composes_parsers_without_advancing_on_error() {
+        let input = [0x02, 0x00, 0x01, 0x00, 0x02, 0x00, 0xff];
+        let mut reader = Reader::new(&input[..]);
+
+        let count = u8::decode::<LE>.peek();
+        assert_eq!(count.parse(&mut reader), Ok(2));
+        assert_eq!(reader.position(), 0);
+
+        let pairs = u8::decode::<LE>
+            .and_then(|_| {
+                u16::decode::<BE>
+                    .map(u32::from)
+                    .repeated::<2>()
+            })
+            .spanned();
+        assert_eq!(pairs.parse(&mut reader), Ok(([1, 2], Span::new(0, 5))));
+
+        // The third element is cut short, so nothing is consumed.
+        let triple = u8::decode::<LE>.repeated::<3>();
+        assert!(triple
+            .parse(&mut reader)
+            .unwrap_err()
+            .is_out_of_bounds());
+        assert_eq!(reader.position(), 5);
+
+        let error = tag(b"\x00\xfe")
+            .parse(&mut reader)
+            .unwrap_err();
+        assert_eq!(error, Error::bad_magic(b"\x00\xfe", &[0x00, 0xff], 5));
+
+        let rest = |bytes: &[u8]| Ok((bytes.len(), bytes.len()));
+        assert_eq!(rest.parse(&mut reader), Ok(2));
+        assert!(reader.is_exhausted());
+    }
+}
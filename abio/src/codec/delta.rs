@@ -0,0 +1,353 @@
+//! Module containing byte-level deltas between two buffers, for tooling that ships
+//! updates as a list of changes rather than as a full image, such as firmware
+//! updaters.
+//!
+//! A delta is a stream of [`DeltaOp`]s, each producing a run of bytes of the new
+//! buffer by copying it from the old buffer, filling it with a single byte, or
+//! inserting it literally. Each op starts with a tag byte, followed by its fields:
+//!
+//! | Tag | Op       | Fields                                      |
+//! |-----|----------|---------------------------------------------|
+//! | `0` | `Copy`   | `offset: u32`, `len: u32`                   |
+//! | `1` | `Fill`   | `byte: u8`, `len: u32`                      |
+//! | `2` | `Insert` | `len: u32`, followed by `len` literal bytes |
+//!
+//! Integers are encoded in the byte order chosen by the caller, which must be the
+//! same when computing and applying the delta.
+
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+use crate::{Bytes, Decode, Encode, Endianness, Error, Result, Source};
+
+const TAG_COPY: u8 = 0;
+const TAG_FILL: u8 = 1;
+const TAG_INSERT: u8 = 2;
+
+/// Shortest run worth emitting as a `Copy` or `Fill` op, rather than inserting the
+/// bytes literally. Shorter runs cost about as much to describe as to insert.
+#[cfg(any(test, feature = "alloc"))]
+const MIN_RUN: usize = 8;
+
+/// A single step of a delta, producing a run of bytes of the new buffer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DeltaOp<'data> {
+    /// Copies `len` bytes of the old buffer, starting at `offset`.
+    Copy {
+        /// Offset of the first copied byte within the old buffer.
+        offset: u32,
+        /// Number of bytes to copy.
+        len: u32,
+    },
+    /// Repeats `byte` `len` times.
+    Fill {
+        /// Value of every byte of the run.
+        byte: u8,
+        /// Number of bytes in the run.
+        len: u32,
+    },
+    /// Inserts the given bytes.
+    Insert(Bytes<'data>),
+}
+
+impl DeltaOp<'_> {
+    /// Returns the number of bytes this op produces in the new buffer.
+    #[inline]
+    pub const fn output_len(&self) -> usize {
+        match self {
+            DeltaOp::Copy { len, .. } | DeltaOp::Fill { len, .. } => *len as usize,
+            DeltaOp::Insert(bytes) => bytes.len(),
+        }
+    }
+
+    /// Returns the number of bytes this op occupies in the delta.
+    #[inline]
+    pub const fn encoded_len(&self) -> usize {
+        match self {
+            DeltaOp::Copy { .. } => 1 + 4 + 4,
+            DeltaOp::Fill { .. } => 1 + 1 + 4,
+            DeltaOp::Insert(bytes) => 1 + 4 + bytes.len(),
+        }
+    }
+}
+
+impl<'data> Decode<'data> for DeltaOp<'data> {
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(Self, usize)> {
+        let tag = bytes.read_slice(0, 1)?[0];
+        match tag {
+            TAG_COPY => {
+                let (offset, _) = u32::decode::<E>(&bytes[1..])?;
+                let (len, _) = u32::decode::<E>(&bytes[5..])?;
+                Ok((DeltaOp::Copy { offset, len }, 9))
+            }
+            TAG_FILL => {
+                let byte = bytes.read_slice(1, 1)?[0];
+                let (len, _) = u32::decode::<E>(&bytes[2..])?;
+                Ok((DeltaOp::Fill { byte, len }, 6))
+            }
+            TAG_INSERT => {
+                let (len, _) = u32::decode::<E>(&bytes[1..])?;
+                let literal = bytes.read_slice(5, len as usize)?;
+                Ok((DeltaOp::Insert(Bytes::new_allow_empty(literal)), 5 + literal.len()))
+            }
+            _ => Err(Error::unknown_discriminant("DeltaOp", tag as i128)),
+        }
+    }
+}
+
+impl Encode for DeltaOp<'_> {
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        let (len, available) = (self.encoded_len(), buf.len());
+        let buf = buf
+            .get_mut(..len)
+            .ok_or_else(|| Error::out_of_bounds(len, available))?;
+        match self {
+            DeltaOp::Copy { offset, len } => {
+                buf[0] = TAG_COPY;
+                offset.encode::<E>(&mut buf[1..5])?;
+                len.encode::<E>(&mut buf[5..])?;
+            }
+            DeltaOp::Fill { byte, len } => {
+                buf[0] = TAG_FILL;
+                buf[1] = *byte;
+                len.encode::<E>(&mut buf[2..])?;
+            }
+            DeltaOp::Insert(bytes) => {
+                let literal_len = u32::try_from(bytes.len())
+                    .map_err(|_| Error::verbose("inserted run is longer than `u32::MAX` bytes"))?;
+                buf[0] = TAG_INSERT;
+                literal_len.encode::<E>(&mut buf[1..5])?;
+                buf[5..].copy_from_slice(bytes.into_slice());
+            }
+        }
+        Ok(len)
+    }
+}
+
+/// Iterator decoding the ops of a delta, created by [`ops`].
+///
+/// If an op cannot be decoded, the iterator yields a single error and then ends.
+#[derive(Clone, Debug)]
+pub struct DeltaOps<'data, E> {
+    bytes: &'data [u8],
+    _marker: PhantomData<fn() -> E>,
+}
+
+impl<'data, E: Endianness> Iterator for DeltaOps<'data, E> {
+    type Item = Result<DeltaOp<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        match DeltaOp::decode::<E>(self.bytes) {
+            Ok((op, len)) => {
+                self.bytes = &self.bytes[len..];
+                Some(Ok(op))
+            }
+            Err(err) => {
+                self.bytes = &[];
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<E: Endianness> FusedIterator for DeltaOps<'_, E> {}
+
+/// Returns an iterator decoding the ops of `delta`, using the byte order `E`.
+#[inline]
+pub const fn ops<E: Endianness>(delta: &[u8]) -> DeltaOps<'_, E> {
+    DeltaOps { bytes: delta, _marker: PhantomData }
+}
+
+/// Returns the length of the buffer produced by applying `delta`.
+///
+/// # Errors
+///
+/// Returns an error if `delta` is malformed.
+pub fn patched_len<E: Endianness>(delta: &[u8]) -> Result<usize> {
+    ops::<E>(delta).try_fold(0usize, |total, op| {
+        total
+            .checked_add(op?.output_len())
+            .ok_or_else(|| Error::out_of_bounds(usize::MAX, total))
+    })
+}
+
+/// Applies `delta` to `old`, writing the new buffer into the start of `out` and
+/// returning its length.
+///
+/// # Errors
+///
+/// Returns an error if `delta` is malformed, if it copies bytes from outside of
+/// `old`, or if `out` is too small to hold the new buffer.
+pub fn apply<E: Endianness>(old: &[u8], delta: &[u8], out: &mut [u8]) -> Result<usize> {
+    let mut written = 0;
+    for op in ops::<E>(delta) {
+        let op = op?;
+        let len = op.output_len();
+        let available = out.len() - written;
+        let dst = out
+            .get_mut(written..written + len.min(available))
+            .filter(|dst| dst.len() == len)
+            .ok_or_else(|| Error::out_of_bounds(len, available))?;
+        match op {
+            DeltaOp::Copy { offset, .. } => {
+                dst.copy_from_slice(old.read_slice(offset as usize, len)?);
+            }
+            DeltaOp::Fill { byte, .. } => dst.fill(byte),
+            DeltaOp::Insert(bytes) => dst.copy_from_slice(bytes.into_slice()),
+        }
+        written += len;
+    }
+    Ok(written)
+}
+
+/// Computes a delta turning `old` into `new`, encoded using the byte order `E`.
+///
+/// Runs of `new` are copied from the same offset of `old`, or from right after the
+/// previous copy, which finds changes made in place and shifted regions following
+/// an insertion. Runs of a single repeated byte, such as erased flash, are filled.
+/// Everything else is inserted literally. This keeps the computation linear in the
+/// length of the buffers, at the cost of missing matches moved elsewhere.
+#[cfg(any(test, feature = "alloc"))]
+pub fn diff<E: Endianness>(old: &[u8], new: &[u8]) -> alloc::vec::Vec<u8> {
+    let mut delta = alloc::vec::Vec::new();
+    let mut push = |op: DeltaOp<'_>| {
+        let start = delta.len();
+        delta.resize(start + op.encoded_len(), 0);
+        op.encode::<E>(&mut delta[start..])
+            .expect("ops are encoded into buffers of their exact length");
+    };
+
+    let mut literal_start = 0;
+    let mut cursor = 0;
+    let mut idx = 0;
+    while idx < new.len() {
+        let rest = &new[idx..];
+        let fill = rest
+            .iter()
+            .take_while(|byte| **byte == rest[0])
+            .count();
+        let (copy_offset, copy) = [idx, cursor]
+            .into_iter()
+            .map(|offset| {
+                (
+                    offset,
+                    common_prefix(
+                        old.get(offset..)
+                            .unwrap_or_default(),
+                        rest,
+                    ),
+                )
+            })
+            .max_by_key(|(_, len)| *len)
+            .unwrap_or_default();
+
+        if fill.max(copy) < MIN_RUN {
+            idx += 1;
+            continue;
+        }
+        for literal in new[literal_start..idx].chunks(u32::MAX as usize) {
+            push(DeltaOp::Insert(Bytes::new_allow_empty(literal)));
+        }
+        let clamp = |len: usize| len.min(u32::MAX as usize);
+        // Offsets past `u32::MAX` cannot be described, so such runs are filled or
+        // inserted instead.
+        let len = match u32::try_from(copy_offset) {
+            Ok(offset) if copy >= fill => {
+                let len = clamp(copy);
+                push(DeltaOp::Copy { offset, len: len as u32 });
+                cursor = copy_offset + len;
+                len
+            }
+            _ if fill >= MIN_RUN => {
+                let len = clamp(fill);
+                push(DeltaOp::Fill { byte: rest[0], len: len as u32 });
+                len
+            }
+            _ => {
+                let len = clamp(copy);
+                push(DeltaOp::Insert(Bytes::new_allow_empty(&rest[..len])));
+                len
+            }
+        };
+        idx += len;
+        literal_start = idx;
+    }
+    for literal in new[literal_start..].chunks(u32::MAX as usize) {
+        push(DeltaOp::Insert(Bytes::new_allow_empty(literal)));
+    }
+    delta
+}
+
+/// Returns the number of leading bytes `a` and `b` have in common.
+#[cfg(any(test, feature = "alloc"))]
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter()
+        .zip(b)
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{BE, LE};
+
+    fn roundtrip<E: Endianness>(old: &[u8], new: &[u8]) -> Vec<u8> {
+        let delta = diff::<E>(old, new);
+        let mut out = vec![0; patched_len::<E>(&delta).unwrap()];
+        assert_eq!(apply::<E>(old, &delta, &mut out).unwrap(), new.len());
+        assert_eq!(out, new);
+        delta
+    }
+
+    #[test]
+    fn diffs_and_applies_changes() {
+        let old = (0..=255u8)
+            .cycle()
+            .take(1024)
+            .collect::<Vec<_>>();
+        let mut new = old.clone();
+        new[100..104].copy_from_slice(b"abio");
+        new[600..700].fill(0xff);
+        new.splice(800..800, *b"inserted");
+
+        let delta = roundtrip::<LE>(&old, &new);
+        assert!(delta.len() < new.len() / 8);
+        let ops = ops::<LE>(&delta)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(ops[0], DeltaOp::Copy { offset: 0, len: 100 });
+        assert_eq!(ops[1], DeltaOp::Insert(Bytes::new_allow_empty(b"abio")));
+        assert!(ops.contains(&DeltaOp::Fill { byte: 0xff, len: 100 }));
+        assert_eq!(format!("{:?}", ops[1]), "Insert(Bytes([97, 98, 105, 111]))");
+
+        roundtrip::<BE>(&old, &new);
+        roundtrip::<LE>(&[], b"short");
+        roundtrip::<LE>(b"anything", &[]);
+    }
+
+    #[test]
+    fn rejects_malformed_deltas() {
+        let mut out = [0u8; 4];
+        let copy = [TAG_COPY, 2, 0, 0, 0, 4, 0, 0, 0];
+        assert!(apply::<LE>(b"abcd", &copy, &mut out).is_err());
+        assert_eq!(apply::<LE>(b"abcdef", &copy, &mut out).unwrap(), 4);
+        assert_eq!(&out, b"cdef");
+
+        let fill = [TAG_FILL, 0xaa, 5, 0, 0, 0];
+        assert!(apply::<LE>(&[], &fill, &mut out).is_err());
+        assert!(apply::<LE>(&[], &[9], &mut out).is_err());
+        assert!(patched_len::<LE>(&[TAG_INSERT, 3, 0, 0, 0, 1]).is_err());
+
+        let mut ops = ops::<LE>(&[TAG_FILL, 0]);
+        assert!(ops.next().unwrap().is_err());
+        assert!(ops.next().is_none());
+    }
+}
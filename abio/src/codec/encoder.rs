@@ -1,118 +1,330 @@
-use crate::{Abi, Endianness, Result};
+#[cfg(any(test, feature = "alloc"))]
+use alloc::borrow::Cow;
+use core::marker::PhantomData;
 
-/// Trait to define types that can write values
-pub trait Encode<T: Abi> {
-    /// Encode a value into a mutable slice of bytes, returning the number of
-    /// bytes written.
-    ///
-    /// This crate uses the [`ptr::write`][`core::ptr::write`] method whenever
-    /// possible because [`abio`][crate] favors soundness and correctness over
-    /// performance. This helps us avoid **undefined behaviour**, and
-    /// strikes a healthy balance between safety and efficiency.
-    ///
-    /// # Performance
-    ///
-    /// This operation provides a balance between performance and safety. It favors
-    /// safety whenever it can only have one of the two. In most cases, it can be
-    /// safe without sacrificing on speed.
+use crate::{Endianness, Error, Result};
+
+/// The [`Encode`] trait defines how a value is written into a slice of bytes. It is
+/// the counterpart of the [`Decode`][crate::Decode] trait, so encoding a value and
+/// decoding the result with the same byte order produces the original value.
+///
+/// # Implementing Encode
+///
+/// Implementations are provided out of the box for all integer primitives and the
+/// aligned integer types in the [`integer`][crate::integer] module. For your own
+/// types, prefer deriving this trait alongside [`Decode`][crate::Decode].
+pub trait Encode {
+    /// Encodes this value into the start of `buf` using the byte order `E`,
+    /// returning the number of bytes written.
     ///
     /// # Errors
     ///
-    /// Returns an error if the operation fails due to a size mismatch, misaligned
-    /// memory access, or if the result represents a type with an invalid byte
-    /// pattern.
-    fn encode<E: Endianness>(buf: &mut [u8], value: T) -> Result<()>;
+    /// Returns an error if `buf` is too small to hold the encoded value. Bytes may
+    /// have been written into `buf` before the error was detected.
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize>;
+}
+
+/// The bytes are written as-is, whether they are still borrowed from the input
+/// they were decoded from or have since been modified.
+#[cfg(any(test, feature = "alloc"))]
+impl Encode for Cow<'_, [u8]> {
+    #[inline]
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        let (len, available) = (self.len(), buf.len());
+        buf.get_mut(..len)
+            .ok_or_else(|| Error::out_of_bounds(len, available))?
+            .copy_from_slice(self);
+        Ok(len)
+    }
+}
+
+/// A `bool` is written as a single byte, `0` or `1`.
+impl Encode for bool {
+    #[inline]
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        u8::from(*self).encode::<E>(buf)
+    }
+}
+
+/// A `char` is written as its Unicode scalar value, in four bytes.
+impl Encode for char {
+    #[inline]
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        u32::from(*self).encode::<E>(buf)
+    }
 }
 
 /// Trait to define types that can encode values into buffers of bytes.
 pub trait Encoder: Endianness {
-    /// Write an aligned [`u8`] value into a mutable byte slice.
+    /// Write an aligned [`u8`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_u8(buf: &mut [u8], value: u8) -> Result<()>;
+    fn write_u8(buf: &mut [u8], value: u8) -> Result<usize>;
 
-    /// Write an aligned [`u16`] value into a mutable byte slice.
+    /// Write an aligned [`u16`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_u16(buf: &mut [u8], value: u16) -> Result<()>;
+    fn write_u16(buf: &mut [u8], value: u16) -> Result<usize>;
 
-    /// Write an aligned [`u32`] value into a mutable byte slice.
+    /// Write an aligned [`u32`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_u32(buf: &mut [u8], value: u32) -> Result<()>;
+    fn write_u32(buf: &mut [u8], value: u32) -> Result<usize>;
 
-    /// Write an aligned [`u64`] value into a mutable byte slice.
+    /// Write an aligned [`u64`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_u64(buf: &mut [u8], value: u64) -> Result<()>;
+    fn write_u64(buf: &mut [u8], value: u64) -> Result<usize>;
 
-    /// Write an aligned [`u128`] value into a mutable byte slice.
+    /// Write an aligned [`u128`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_u128(buf: &mut [u8], value: u128) -> Result<()>;
+    fn write_u128(buf: &mut [u8], value: u128) -> Result<usize>;
 
-    /// Write an aligned [`i8`] value into a mutable byte slice.
+    /// Write an aligned [`i8`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_i8(buf: &mut [u8], value: i8) -> Result<()>;
+    fn write_i8(buf: &mut [u8], value: i8) -> Result<usize>;
 
-    /// Write an aligned [`i16`] value into a mutable byte slice.
+    /// Write an aligned [`i16`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_i16(buf: &mut [u8], value: i16) -> Result<()>;
+    fn write_i16(buf: &mut [u8], value: i16) -> Result<usize>;
 
-    /// Write an aligned [`i32`] value into a mutable byte slice.
+    /// Write an aligned [`i32`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_i32(buf: &mut [u8], value: i32) -> Result<()>;
+    fn write_i32(buf: &mut [u8], value: i32) -> Result<usize>;
 
-    /// Write an aligned [`i64`] value into a mutable byte slice.
+    /// Write an aligned [`i64`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_i64(buf: &mut [u8], value: i64) -> Result<()>;
+    fn write_i64(buf: &mut [u8], value: i64) -> Result<usize>;
 
-    /// Write an aligned [`i128`] value into a mutable byte slice.
+    /// Write an aligned [`i128`] value into a mutable byte slice, returning
+    /// the number of bytes written.
     ///
     /// # Errors
     ///
     /// Returns an error if the write operation fails. The main source of error is
     /// when `buf` does not contain enough bytes to construct the type represented by
     /// `value`.
-    fn write_i128(buf: &mut [u8], value: i128) -> Result<()>;
+    fn write_i128(buf: &mut [u8], value: i128) -> Result<usize>;
+}
+
+/// Macro to generate the `put_*` methods of [`BufEncoder`], forwarding to the
+/// matching methods of the [`Encoder`] trait.
+macro_rules! impl_put_methods {
+    ($($ty:ty, $put:ident, $write:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Writes a [`", stringify!($ty), "`] in the byte order `E`, advancing the")]
+            /// cursor past it.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if fewer bytes than the size of the value remain. The
+            /// cursor is not advanced on error.
+            #[inline]
+            pub fn $put(&mut self, value: $ty) -> Result<()> {
+                let len = E::$write(&mut self.buf[self.pos..], value)?;
+                self.pos += len;
+                Ok(())
+            }
+        )*
+    };
+}
+
+/// A position-tracking encoder writing values into a fixed buffer, in the byte
+/// order `E`.
+///
+/// Each write starts at the current position and advances it past the bytes
+/// written, so consecutive fields can be encoded without threading offsets
+/// through every call. Writes that do not fit in the rest of the buffer fail with
+/// an out-of-bounds error giving the number of bytes needed and available, and
+/// leave the position unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use abio::codec::BufEncoder;
+/// use abio::BE;
+///
+/// let mut buf = [0u8; 7];
+/// let mut encoder = BufEncoder::<BE>::new(&mut buf);
+/// encoder.put_u16(0x0102).unwrap();
+/// encoder.encode(&0x0304_0506u32).unwrap();
+/// assert_eq!(encoder.remaining(), 1);
+/// assert!(encoder.put_u16(0).is_err());
+/// assert_eq!(encoder.written(), [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+/// ```
+#[derive(Debug)]
+pub struct BufEncoder<'buf, E: Encoder> {
+    /// Buffer the encoder is writing into.
+    buf: &'buf mut [u8],
+    /// Offset of the cursor within `buf`.
+    pos: usize,
+    _endian: PhantomData<E>,
+}
+
+impl<'buf, E: Encoder> BufEncoder<'buf, E> {
+    /// Creates a new [`BufEncoder`] writing into `buf`, positioned at its first
+    /// byte.
+    #[inline]
+    pub const fn new(buf: &'buf mut [u8]) -> BufEncoder<'buf, E> {
+        BufEncoder { buf, pos: 0, _endian: PhantomData }
+    }
+
+    /// Returns the position of the cursor, which is the number of bytes written.
+    #[inline]
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of bytes left in the buffer after the cursor.
+    #[inline]
+    pub const fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Returns the bytes written so far.
+    #[inline]
+    pub fn written(&self) -> &[u8] {
+        &self.buf[..self.pos]
+    }
+
+    /// Consumes the encoder, returning the bytes written.
+    #[inline]
+    pub fn into_written(self) -> &'buf mut [u8] {
+        &mut self.buf[..self.pos]
+    }
+
+    /// Consumes the encoder, returning the number of bytes written.
+    ///
+    /// This is how owners of the buffer, such as a
+    /// [`PooledBuf`][crate::codec::PooledBuf], learn the length of the message
+    /// encoded into it once the encoder no longer borrows it.
+    #[inline]
+    pub const fn finish(self) -> usize {
+        self.pos
+    }
+
+    /// Encodes `value` in the byte order `E`, advancing the cursor past it and
+    /// returning the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Encode::encode`]. The cursor is not advanced on
+    /// error, although bytes after it may have been overwritten.
+    #[inline]
+    pub fn encode<T: Encode + ?Sized>(&mut self, value: &T) -> Result<usize> {
+        let len = value.encode::<E>(&mut self.buf[self.pos..])?;
+        self.pos += len;
+        Ok(len)
+    }
+
+    /// Copies `bytes` as-is, advancing the cursor past them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `bytes.len()` bytes remain. The cursor is not
+    /// advanced on error.
+    #[inline]
+    pub fn put_slice(&mut self, bytes: &[u8]) -> Result<()> {
+        let (len, available) = (bytes.len(), self.remaining());
+        self.buf[self.pos..]
+            .get_mut(..len)
+            .ok_or_else(|| Error::out_of_bounds(len, available))?
+            .copy_from_slice(bytes);
+        self.pos += len;
+        Ok(())
+    }
+
+    impl_put_methods! {
+        u8,     put_u8,     write_u8,
+        u16,    put_u16,    write_u16,
+        u32,    put_u32,    write_u32,
+        u64,    put_u64,    write_u64,
+        u128,   put_u128,   write_u128,
+        i8,     put_i8,     write_i8,
+        i16,    put_i16,    write_i16,
+        i32,    put_i32,    write_i32,
+        i64,    put_i64,    write_i64,
+        i128,   put_i128,   write_i128,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BE, LE};
+
+    #[test]
+    fn writes_sequentially_and_reports_overflow() {
+        let mut buf = [0u8; 8];
+        let mut encoder = BufEncoder::<LE>::new(&mut buf);
+        encoder.put_u8(0xff).unwrap();
+        encoder.put_i16(-2).unwrap();
+        assert_eq!(encoder.encode(&true), Ok(1));
+        encoder
+            .put_slice(&[0xaa, 0xbb])
+            .unwrap();
+        assert_eq!(encoder.position(), 6);
+
+        assert_eq!(encoder.put_u32(0), Err(Error::out_of_bounds(4, 2)));
+        assert_eq!(encoder.put_slice(&[0; 3]), Err(Error::out_of_bounds(3, 2)));
+        assert_eq!(encoder.remaining(), 2);
+        assert_eq!(encoder.into_written(), [0xff, 0xfe, 0xff, 0x01, 0xaa, 0xbb]);
+
+        let mut encoder = BufEncoder::<BE>::new(&mut buf);
+        encoder
+            .put_u64(0x0102_0304_0506_0708)
+            .unwrap();
+        assert_eq!(encoder.remaining(), 0);
+        assert_eq!(buf, [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
 }
@@ -0,0 +1,281 @@
+//! Module containing the [`Fixed`] type, for decoding fixed-point numbers.
+//!
+//! Font and media formats frequently store fractional values as scaled integers.
+//! For example, TrueType stores version numbers and transform components as 16.16
+//! fixed-point values, which are decoded as [`Q16_16`].
+
+use core::fmt;
+
+use crate::{sealed, Decode, Encode, Endianness, Result};
+
+/// Trait for native integer primitives that can store the bits of a [`Fixed`]
+/// value.
+///
+/// This trait is sealed and implemented for all integer primitives up to 32 bits
+/// wide, so that products and quotients of two values can be computed without
+/// overflowing an [`i128`].
+pub trait FixedInt: Copy + sealed::Sealed {
+    /// Number of bits in the native representation of this type.
+    const BITS: u32;
+
+    /// Converts this value into an [`i128`], which holds every value of this type.
+    fn to_i128(self) -> i128;
+
+    /// Converts `value` into this type, or returns `None` if it is out of range.
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! impl_fixed_int {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl FixedInt for $ty {
+                const BITS: u32 = <$ty>::BITS;
+
+                #[inline(always)]
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                #[inline(always)]
+                fn from_i128(value: i128) -> Option<Self> {
+                    <$ty>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_int!(u8, u16, u32, i8, i16, i32);
+
+/// A fixed-point number stored as an integer of type `I`, of which the low
+/// `FRAC_BITS` bits hold the fractional part.
+///
+/// The value of a [`Fixed`] is its integer representation divided by
+/// `2^FRAC_BITS`. It is decoded and encoded exactly as its integer representation,
+/// so it has the same size and byte order on the wire.
+///
+/// # Examples
+///
+/// ```
+/// use abio::codec::Q16_16;
+/// use abio::{Decode, BE};
+///
+/// let (scale, _) = Q16_16::decode::<BE>(&[0x00, 0x01, 0x80, 0x00]).unwrap();
+/// assert_eq!(scale.to_f32(), 1.5);
+/// assert_eq!(scale.checked_mul(scale).unwrap().to_f32(), 2.25);
+/// ```
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct Fixed<I: FixedInt, const FRAC_BITS: u32> {
+    bits: I,
+}
+
+/// A signed 16.16 fixed-point number, such as the `Fixed` type of TrueType.
+pub type Q16_16 = Fixed<i32, 16>;
+
+/// A signed 8.8 fixed-point number.
+pub type Q8_8 = Fixed<i16, 8>;
+
+impl<I: FixedInt, const FRAC_BITS: u32> Fixed<I, FRAC_BITS> {
+    /// Compile-time validation of the number of fractional bits.
+    const VALID_BITS: () = assert!(
+        FRAC_BITS <= I::BITS,
+        "The number of fractional bits must be no wider than the integer type"
+    );
+
+    /// Scale between the integer representation and the value of this type.
+    const SCALE: f64 = (1u64 << FRAC_BITS) as f64;
+
+    /// Creates a new [`Fixed`] from its integer representation.
+    #[inline]
+    pub const fn from_bits(bits: I) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_BITS;
+        Self { bits }
+    }
+
+    /// Returns the integer representation of this value.
+    #[inline]
+    pub const fn to_bits(self) -> I {
+        self.bits
+    }
+
+    /// Converts `value` into the nearest [`Fixed`] value, or returns `None` if
+    /// `value` is not finite or is out of range.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        let scaled = value * Self::SCALE;
+        // `f64::round` is not available without `std`, so round half away from zero
+        // before truncating.
+        let rounded = if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 };
+        I::from_i128(rounded as i128).map(Self::from_bits)
+    }
+
+    /// Converts `value` into the nearest [`Fixed`] value, or returns `None` if
+    /// `value` is not finite or is out of range.
+    #[inline]
+    pub fn from_f32(value: f32) -> Option<Self> {
+        Self::from_f64(f64::from(value))
+    }
+
+    /// Converts this value into an [`f64`], which represents it exactly.
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        self.bits.to_i128() as f64 / Self::SCALE
+    }
+
+    /// Converts this value into the nearest [`f32`].
+    #[inline]
+    pub fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    /// Adds two values, returning `None` on overflow.
+    #[inline]
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::from_i128(self.bits.to_i128() + rhs.bits.to_i128())
+    }
+
+    /// Subtracts `rhs` from this value, returning `None` on overflow.
+    #[inline]
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::from_i128(self.bits.to_i128() - rhs.bits.to_i128())
+    }
+
+    /// Multiplies two values, returning `None` on overflow. The product is rounded
+    /// towards negative infinity.
+    #[inline]
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::from_i128((self.bits.to_i128() * rhs.bits.to_i128()) >> FRAC_BITS)
+    }
+
+    /// Divides this value by `rhs`, returning `None` if `rhs` is zero or on
+    /// overflow. The quotient is rounded towards zero.
+    #[inline]
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        let dividend = self.bits.to_i128() << FRAC_BITS;
+        dividend
+            .checked_div(rhs.bits.to_i128())
+            .and_then(Self::from_i128)
+    }
+
+    /// Converts an intermediate integer representation into a [`Fixed`] value.
+    #[inline]
+    fn from_i128(bits: i128) -> Option<Self> {
+        I::from_i128(bits).map(Self::from_bits)
+    }
+}
+
+impl<'data, I, const FRAC_BITS: u32> Decode<'data> for Fixed<I, FRAC_BITS>
+where
+    I: FixedInt + Decode<'data>,
+{
+    #[inline]
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(Self, usize)> {
+        let (bits, size) = I::decode::<E>(bytes)?;
+        Ok((Self::from_bits(bits), size))
+    }
+}
+
+impl<I, const FRAC_BITS: u32> Encode for Fixed<I, FRAC_BITS>
+where
+    I: FixedInt + Encode,
+{
+    #[inline]
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        self.bits.encode::<E>(buf)
+    }
+}
+
+impl<I: FixedInt, const FRAC_BITS: u32> fmt::Debug for Fixed<I, FRAC_BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Fixed")
+            .field(&self.to_f64())
+            .finish()
+    }
+}
+
+impl<I: FixedInt, const FRAC_BITS: u32> fmt::Display for Fixed<I, FRAC_BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f64(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{BE, LE};
+
+    #[test]
+    fn decodes_and_scales_fixed_point_values() {
+        let (value, size) = Q16_16::decode::<BE>(&[0xff, 0xfe, 0x40, 0x00]).unwrap();
+        assert_eq!(size, 4);
+        assert_eq!(value.to_f64(), -1.75);
+        assert_eq!(value.to_bits(), -0x0001_c000);
+
+        let (value, _) = Q8_8::decode::<LE>(&[0x80, 0x02]).unwrap();
+        assert_eq!(value.to_f32(), 2.5);
+        assert!(Q8_8::decode::<LE>(&[0x80]).is_err());
+
+        let mut buf = [0u8; 2];
+        assert_eq!(
+            value
+                .encode::<BE>(&mut buf)
+                .unwrap(),
+            2
+        );
+        assert_eq!(buf, [0x02, 0x80]);
+    }
+
+    #[test]
+    fn converts_from_floats_and_checks_arithmetic() {
+        let half = Q8_8::from_f32(0.5).unwrap();
+        let three = Q8_8::from_f64(3.0).unwrap();
+        assert_eq!(half.to_bits(), 0x80);
+        assert_eq!(Q8_8::from_f64(-0.001953125), Some(Q8_8::from_bits(-1)));
+        assert_eq!(Q8_8::from_f64(128.0), None);
+        assert_eq!(Q8_8::from_f64(f64::NAN), None);
+
+        assert_eq!(
+            three
+                .checked_add(half)
+                .unwrap()
+                .to_f64(),
+            3.5
+        );
+        assert_eq!(
+            half.checked_sub(three)
+                .unwrap()
+                .to_f64(),
+            -2.5
+        );
+        assert_eq!(
+            three
+                .checked_mul(half)
+                .unwrap()
+                .to_f64(),
+            1.5
+        );
+        assert_eq!(
+            half.checked_div(three)
+                .unwrap()
+                .to_bits(),
+            0x2a
+        );
+        assert_eq!(three.checked_div(Q8_8::default()), None);
+        let hundred = Q8_8::from_f64(100.0).unwrap();
+        assert_eq!(hundred.checked_add(hundred), None);
+        assert_eq!(hundred.checked_mul(three), None);
+
+        let unsigned = Fixed::<u16, 12>::from_f64(15.999).unwrap();
+        assert_eq!(unsigned.to_bits(), 0xfffc);
+        assert_eq!(Fixed::<u16, 12>::from_f64(-1.0), None);
+        assert_eq!(half.to_string(), "0.5");
+        assert_eq!(format!("{three:?}"), "Fixed(3.0)");
+    }
+}
@@ -0,0 +1,239 @@
+//! Module containing the implementations of [`Decode`] and [`Encode`] for `f32` and
+//! `f64`, and the [`TotalOrdF32`] and [`TotalOrdF64`] types, for formats that sort
+//! float keys.
+//!
+//! # Bit-Exact Round Trips
+//!
+//! Floats are decoded and encoded through their bit patterns, with
+//! [`f32::from_bits`] and [`f32::to_bits`], and never through float arithmetic or
+//! conversions. Encoding a decoded float therefore reproduces its bytes exactly,
+//! including the sign of zeroes and the payload of NaNs, whether quiet or
+//! signaling. Formats that store tagged values or checksums in NaN payloads can be
+//! rewritten without corrupting them:
+//!
+//! ```
+//! use abio::{Decode, Encode, BE};
+//!
+//! // A signaling NaN with a payload of 1.
+//! let bytes = [0x7f, 0x80, 0x00, 0x01];
+//! let (value, _) = f32::decode::<BE>(&bytes).unwrap();
+//! assert!(value.is_nan());
+//!
+//! let mut buf = [0u8; 4];
+//! value.encode::<BE>(&mut buf).unwrap();
+//! assert_eq!(buf, bytes);
+//! ```
+//!
+//! # Ordering
+//!
+//! The comparison operators of floats do not form a total order, since NaNs
+//! compare unequal to everything, so floats cannot be used as the keys of sorted
+//! containers. The [`TotalOrdF32`] and [`TotalOrdF64`] wrappers order floats with
+//! [`f32::total_cmp`] instead, and are decoded and encoded exactly like the floats
+//! they wrap.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use crate::{Abi, Decode, Encode, Endianness, Result, Zeroable};
+
+macro_rules! impl_float_codec {
+    ($($float:ty, $bits:ty;)*) => {
+        $(
+            #[doc = concat!("A `", stringify!($float), "` is stored as its bit pattern, so")]
+            /// decoding preserves every bit, including the payload of NaNs.
+            impl<'data> Decode<'data> for $float {
+                #[inline]
+                fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<($float, usize)> {
+                    let (bits, len) = <$bits>::decode::<E>(bytes)?;
+                    Ok((<$float>::from_bits(bits), len))
+                }
+            }
+
+            #[doc = concat!("A `", stringify!($float), "` is stored as its bit pattern, so")]
+            /// encoding preserves every bit, including the payload of NaNs.
+            impl Encode for $float {
+                #[inline]
+                fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+                    self.to_bits().encode::<E>(buf)
+                }
+            }
+        )*
+    };
+}
+
+impl_float_codec! {
+    f32, u32;
+    f64, u64;
+}
+
+macro_rules! impl_total_ord_types {
+    ($($(#[$attr:meta])* $name:ident($float:ty);)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Default)]
+            #[repr(transparent)]
+            pub struct $name(pub $float);
+
+            impl $name {
+                /// Wraps `value`.
+                #[inline]
+                #[must_use]
+                pub const fn new(value: $float) -> $name {
+                    $name(value)
+                }
+
+                /// Returns the wrapped value.
+                #[inline]
+                #[must_use]
+                pub const fn get(self) -> $float {
+                    self.0
+                }
+            }
+
+            impl<'data> Decode<'data> for $name {
+                #[inline]
+                fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<($name, usize)> {
+                    let (value, len) = <$float>::decode::<E>(bytes)?;
+                    Ok(($name(value), len))
+                }
+            }
+
+            impl Encode for $name {
+                #[inline]
+                fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+                    self.0.encode::<E>(buf)
+                }
+            }
+
+            // SAFETY: The type is a transparent wrapper around a float primitive, so it
+            // has no padding and any bytes form a valid value.
+            unsafe impl Abi for $name {}
+            // SAFETY: The all-zero bit pattern is positive zero.
+            unsafe impl Zeroable for $name {}
+
+            /// Values are equal if they have the same bit pattern, which is
+            /// consistent with their ordering.
+            impl PartialEq for $name {
+                #[inline]
+                fn eq(&self, other: &$name) -> bool {
+                    self.0.to_bits() == other.0.to_bits()
+                }
+            }
+
+            impl Eq for $name {}
+
+            impl PartialOrd for $name {
+                #[inline]
+                fn partial_cmp(&self, other: &$name) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for $name {
+                #[inline]
+                fn cmp(&self, other: &$name) -> Ordering {
+                    self.0.total_cmp(&other.0)
+                }
+            }
+
+            impl Hash for $name {
+                #[inline]
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.0.to_bits().hash(state);
+                }
+            }
+
+            impl fmt::Debug for $name {
+                #[inline]
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Debug::fmt(&self.0, f)
+                }
+            }
+
+            impl fmt::Display for $name {
+                #[inline]
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Display::fmt(&self.0, f)
+                }
+            }
+
+            impl From<$float> for $name {
+                #[inline]
+                fn from(value: $float) -> $name {
+                    $name(value)
+                }
+            }
+
+            impl From<$name> for $float {
+                #[inline]
+                fn from(value: $name) -> $float {
+                    value.0
+                }
+            }
+        )*
+    };
+}
+
+impl_total_ord_types! {
+    /// An `f32` ordered by [`f32::total_cmp`], so that it can be sorted and used as
+    /// the key of ordered containers.
+    ///
+    /// Negative NaNs sort before every other value, and positive NaNs after, with
+    /// `-0.0` sorting before `+0.0`.
+    TotalOrdF32(f32);
+    /// An `f64` ordered by [`f64::total_cmp`], so that it can be sorted and used as
+    /// the key of ordered containers.
+    ///
+    /// Negative NaNs sort before every other value, and positive NaNs after, with
+    /// `-0.0` sorting before `+0.0`.
+    TotalOrdF64(f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BE, LE};
+
+    #[test]
+    fn round_trips_nan_payloads() {
+        let signaling = f32::from_bits(0x7fa0_0001);
+        let mut buf = [0u8; 8];
+        assert_eq!(signaling.encode::<LE>(&mut buf), Ok(4));
+        assert_eq!(buf[..4], [0x01, 0x00, 0xa0, 0x7f]);
+        let (value, len) = f32::decode::<LE>(&buf).unwrap();
+        assert_eq!((value.to_bits(), len), (0x7fa0_0001, 4));
+
+        for bits in [0x7ff0_0000_0000_0001, 0xfff4_0000_dead_beef, 0x8000_0000_0000_0000] {
+            let value = f64::from_bits(bits);
+            assert_eq!(value.encode::<BE>(&mut buf), Ok(8));
+            assert_eq!(buf, bits.to_be_bytes());
+            let (decoded, _) = f64::decode::<BE>(&buf).unwrap();
+            assert_eq!(decoded.to_bits(), bits);
+        }
+        assert!(f64::decode::<BE>(&buf[..7]).is_err());
+    }
+
+    #[test]
+    fn orders_floats_totally() {
+        let mut keys = [
+            TotalOrdF64(f64::NAN),
+            TotalOrdF64(1.0),
+            TotalOrdF64(-f64::NAN),
+            TotalOrdF64(0.0),
+            TotalOrdF64(-0.0),
+        ];
+        keys.sort();
+        let bits = keys.map(|key| key.get().to_bits());
+        assert_eq!(
+            bits,
+            [(-f64::NAN).to_bits(), (-0.0f64).to_bits(), 0, 1.0f64.to_bits(), f64::NAN.to_bits()]
+        );
+        assert_eq!(TotalOrdF32(f32::NAN), TotalOrdF32(f32::NAN));
+        assert_ne!(TotalOrdF32(0.0), TotalOrdF32(-0.0));
+
+        let (key, _) = TotalOrdF32::decode::<BE>(&[0xff, 0xc0, 0, 0]).unwrap();
+        assert!(key < TotalOrdF32(f32::NEG_INFINITY));
+    }
+}
@@ -0,0 +1,123 @@
+//! Module containing [`wire_hash`] and [`hash_wire`], for hashing values by their
+//! encoded bytes.
+//!
+//! Deriving [`Hash`][core::hash::Hash] hashes the in-memory representation of a
+//! value, which differs between hosts of different byte orders and pointer widths.
+//! Hashing the bytes produced by [`Encode`] instead gives every host the same
+//! hash for the same message, which makes it suitable for content addressing and
+//! for deduplicating messages received from other machines:
+//!
+//! ```
+//! use std::collections::hash_map::DefaultHasher;
+//!
+//! use abio::codec::wire_hash;
+//! use abio::{BE, LE};
+//!
+//! let le = wire_hash::<DefaultHasher, LE>(&0x0102_0304u32).unwrap();
+//! let be = wire_hash::<DefaultHasher, BE>(&0x0403_0201u32).unwrap();
+//! assert_eq!(le, be);
+//! ```
+
+use alloc::vec;
+use core::hash::Hasher;
+
+use crate::{Encode, Endianness, Result};
+
+/// Size of the buffer a value is first encoded into. The buffer is doubled each
+/// time it is too small for the encoded value.
+const INITIAL_BUF_LEN: usize = 256;
+
+/// Feeds the bytes of `value`, encoded with the byte order `E`, into `hasher`.
+///
+/// Exactly the encoded bytes are written, without a length prefix, so hashing a
+/// value is equivalent to hashing its encoding with [`Hasher::write`].
+///
+/// # Errors
+///
+/// Returns an error if `value` cannot be encoded, or if no buffer large enough to
+/// hold its encoding can be allocated. Nothing is written into `hasher` on error.
+pub fn hash_wire<E, H>(value: &(impl Encode + ?Sized), hasher: &mut H) -> Result<()>
+where
+    E: Endianness,
+    H: Hasher + ?Sized,
+{
+    let mut buf = vec![0u8; INITIAL_BUF_LEN];
+    loop {
+        match value.encode::<E>(&mut buf) {
+            Ok(len) => {
+                hasher.write(&buf[..len]);
+                return Ok(());
+            }
+            Err(err) if err.is_out_of_bounds() => {
+                if buf
+                    .try_reserve_exact(buf.len())
+                    .is_err()
+                {
+                    return Err(err);
+                }
+                buf.resize(buf.len() * 2, 0);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Returns the hash computed by a new `H` over the bytes of `value`, encoded with
+/// the byte order `E`.
+///
+/// See [`hash_wire`] to feed the bytes into an existing hasher instead.
+///
+/// # Errors
+///
+/// Returns an error if `value` cannot be encoded, or if no buffer large enough to
+/// hold its encoding can be allocated.
+#[inline]
+pub fn wire_hash<H, E>(value: &(impl Encode + ?Sized)) -> Result<u64>
+where
+    H: Hasher + Default,
+    E: Endianness,
+{
+    let mut hasher = H::default();
+    hash_wire::<E, H>(value, &mut hasher)?;
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{BE, LE};
+
+    /// Hasher recording the bytes written into it.
+    #[derive(Default)]
+    struct Recorder(Vec<u8>);
+
+    impl Hasher for Recorder {
+        fn finish(&self) -> u64 {
+            self.0.len() as u64
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    #[test]
+    fn hashes_the_encoded_bytes() {
+        let mut hasher = Recorder::default();
+        hash_wire::<LE, _>(&0x0102_0304u32, &mut hasher).unwrap();
+        hash_wire::<BE, _>(&0x0506u16, &mut hasher).unwrap();
+        assert_eq!(hasher.0, [0x04, 0x03, 0x02, 0x01, 0x05, 0x06]);
+
+        // Values larger than the initial buffer are encoded into a larger one.
+        let payload = (0..1000)
+            .map(|idx| idx as u8)
+            .collect::<Vec<_>>();
+        let mut hasher = Recorder::default();
+        hash_wire::<LE, _>(&Cow::Borrowed(&payload[..]), &mut hasher).unwrap();
+        assert_eq!(hasher.0, payload);
+        assert_eq!(wire_hash::<Recorder, BE>(&Cow::Borrowed(&payload[..])).unwrap(), 1000);
+    }
+}
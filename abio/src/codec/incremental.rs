@@ -0,0 +1,227 @@
+//! Module containing [`decode_incremental`] and [`IncrementalDecoder`], for
+//! decoding values from input that arrives in pieces.
+//!
+//! When reading from a socket or a chunked reader, the whole message is rarely
+//! available up front. Decoding incrementally reports an input that ends too early
+//! as an error for which [`Error::is_incomplete`] returns `true`, along with the
+//! minimum number of bytes needed to make progress, so the caller can wait for
+//! more input and try again:
+//!
+//! ```
+//! use abio::codec::IncrementalDecoder;
+//! use abio::BE;
+//!
+//! let mut decoder = IncrementalDecoder::<BE>::new();
+//! decoder.feed(&[0x01, 0x02]);
+//!
+//! let err = decoder.decode::<u32>().unwrap_err();
+//! assert_eq!(err.needed(), Some(2));
+//!
+//! decoder.feed(&[0x03, 0x04, 0x05]);
+//! assert_eq!(decoder.decode::<u32>().unwrap(), 0x0102_0304);
+//! assert_eq!(decoder.buffered(), 1);
+//! ```
+
+#[cfg(any(test, feature = "alloc"))]
+use alloc::vec::Vec;
+#[cfg(any(test, feature = "alloc"))]
+use core::marker::PhantomData;
+
+use crate::{Decode, Endianness, Error, Result};
+
+/// Decodes a `T` from the start of `bytes` like [`Decode::decode`], but reports an
+/// input that ends before the value as [incomplete][Error::is_incomplete].
+///
+/// This function does not keep any state, and is suited to callers managing their
+/// own buffer. Decoding starts over from the start of `bytes` on every call.
+///
+/// # Errors
+///
+/// Returns an error for which [`Error::needed`] returns the minimum number of
+/// additional bytes if `bytes` ends before the value, and the errors of
+/// [`Decode::decode`] otherwise.
+#[inline]
+pub fn decode_incremental<'data, T, E>(bytes: &'data [u8]) -> Result<(T, usize)>
+where
+    T: Decode<'data>,
+    E: Endianness,
+{
+    T::decode::<E>(bytes).map_err(Error::into_incomplete)
+}
+
+/// A decoder buffering input that arrives in pieces, decoding values once enough
+/// of it is available.
+///
+/// Bytes are appended with [`feed`][Self::feed], and values are decoded from the
+/// start of the buffered bytes with [`decode`][Self::decode]. The bytes of a
+/// decoded value are removed from the buffer, while the bytes of an incomplete
+/// value are kept, so decoding resumes with the same value once more bytes arrive.
+///
+/// The decoder remembers how many bytes the last incomplete value needed, and
+/// reports a value of the same type as incomplete again without decoding it until
+/// enough bytes have been fed. This keeps feeding a large value one byte at a time
+/// from decoding it over and over.
+#[cfg(any(test, feature = "alloc"))]
+#[derive(Clone, Debug)]
+pub struct IncrementalDecoder<E: Endianness> {
+    /// Bytes fed into the decoder that have not been decoded yet.
+    buf: Vec<u8>,
+    /// Number of buffered bytes needed before decoding is attempted again.
+    wanted: usize,
+    /// Name of the type whose incomplete value set `wanted`.
+    wanted_by: &'static str,
+    _endian: PhantomData<E>,
+}
+
+#[cfg(any(test, feature = "alloc"))]
+impl<E: Endianness> IncrementalDecoder<E> {
+    /// Creates a decoder with an empty buffer.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            wanted: 0,
+            wanted_by: "",
+            _endian: PhantomData,
+        }
+    }
+
+    /// Appends `bytes` to the buffered input.
+    #[inline]
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buf
+            .extend_from_slice(bytes);
+    }
+
+    /// Returns the number of bytes fed into the decoder that have not been decoded
+    /// yet.
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the bytes fed into the decoder that have not been decoded yet.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Decodes a `T` from the start of the buffered input, removing its bytes from
+    /// the buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for which [`Error::needed`] returns the minimum number of
+    /// bytes to [`feed`][Self::feed] before calling this method again if the
+    /// buffered input ends before the value, and the errors of [`Decode::decode`]
+    /// otherwise. The buffered input is left unchanged on error.
+    pub fn decode<T>(&mut self) -> Result<T>
+    where
+        T: for<'data> Decode<'data>,
+    {
+        let type_name = core::any::type_name::<T>();
+        if self.buf.len() < self.wanted && self.wanted_by == type_name {
+            return Err(Error::incomplete(self.wanted - self.buf.len()));
+        }
+        match decode_incremental::<T, E>(&self.buf) {
+            Ok((value, len)) => {
+                self.buf.drain(..len);
+                self.wanted = 0;
+                Ok(value)
+            }
+            Err(err) => {
+                if let Some(needed) = err.needed() {
+                    self.wanted = self
+                        .buf
+                        .len()
+                        .saturating_add(needed);
+                    self.wanted_by = type_name;
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(any(test, feature = "alloc"))]
+impl<E: Endianness> Default for IncrementalDecoder<E> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LE;
+
+    #[test]
+    fn reports_missing_bytes_until_the_value_is_complete() {
+        let err = decode_incremental::<u64, LE>(&[0; 5]).unwrap_err();
+        assert!(err.is_incomplete());
+        assert_eq!(err.needed(), Some(3));
+        assert_eq!(decode_incremental::<u16, LE>(&[0x01, 0x02, 0x03]).unwrap(), (0x0201, 2));
+        // Errors other than a short input are left as they are.
+        assert_eq!(
+            decode_incremental::<bool, LE>(&[2])
+                .unwrap_err()
+                .needed(),
+            None
+        );
+
+        let mut decoder = IncrementalDecoder::<LE>::new();
+        decoder.feed(&[0x01]);
+        assert_eq!(
+            decoder
+                .decode::<u32>()
+                .unwrap_err()
+                .needed(),
+            Some(3)
+        );
+        decoder.feed(&[0x02]);
+        assert_eq!(
+            decoder
+                .decode::<u32>()
+                .unwrap_err()
+                .needed(),
+            Some(2)
+        );
+        assert_eq!(decoder.as_bytes(), [0x01, 0x02]);
+
+        decoder.feed(&[0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(decoder.decode::<u32>().unwrap(), 0x0403_0201);
+        assert_eq!(decoder.decode::<u16>().unwrap(), 0x0605);
+        assert_eq!(decoder.buffered(), 0);
+        assert_eq!(
+            decoder
+                .decode::<u8>()
+                .unwrap_err()
+                .needed(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn decodes_other_types_while_a_value_is_incomplete() {
+        let mut decoder = IncrementalDecoder::<LE>::new();
+        decoder.feed(&[0x01, 0x02]);
+        assert_eq!(
+            decoder
+                .decode::<u32>()
+                .unwrap_err()
+                .needed(),
+            Some(2)
+        );
+        assert_eq!(decoder.decode::<u8>().unwrap(), 0x01);
+        assert_eq!(
+            decoder
+                .decode::<u32>()
+                .unwrap_err()
+                .needed(),
+            Some(3)
+        );
+        assert_eq!(decoder.decode::<u8>().unwrap(), 0x02);
+        assert_eq!(decoder.buffered(), 0);
+    }
+}
@@ -0,0 +1,268 @@
+//! Module containing the [`Matrix`] type, a two-dimensional view over a grid of
+//! values stored row by row.
+//!
+//! Bitmaps, heightmaps and register dumps store a grid of fixed-size values, one
+//! row after the other. Rows are frequently padded to a multiple of some alignment,
+//! so the distance between the start of two rows, called the stride, may be larger
+//! than the bytes of the values in a row:
+//!
+//! ```
+//! use abio::codec::Matrix;
+//! use abio::{Bytes, BE};
+//!
+//! // Two rows of three `u16` values, each padded to 8 bytes.
+//! let bytes = [
+//!     0, 1, 0, 2, 0, 3, 0xff, 0xff, //
+//!     0, 4, 0, 5, 0, 6,
+//! ];
+//! let matrix = Matrix::<u16>::new::<BE>(Bytes::new(&bytes), 3, 2, 8).unwrap();
+//! assert_eq!(matrix.get(1, 2).unwrap().unwrap(), 6);
+//! assert!(matrix.get(0, 3).is_none());
+//!
+//! let sums = matrix
+//!     .rows()
+//!     .map(|row| row.iter().map(Result::unwrap).sum::<u16>());
+//! assert!(sums.eq([6, 15]));
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::size_of;
+
+use super::Counted;
+use crate::{Abi, BigEndian, Bytes, Decode, Endian, Endianness, Error, LittleEndian, Result};
+
+/// A borrowed grid of `T` with `height` rows of `width` values, decoding each value
+/// only when it is accessed.
+///
+/// Consecutive rows start `stride` bytes apart. The padding at the end of a row is
+/// never read, and the padding of the last row may be missing from the input.
+pub struct Matrix<'data, T> {
+    /// Bytes of all rows, in order, including the padding between them.
+    bytes: Bytes<'data>,
+    /// Number of values in a row.
+    width: usize,
+    /// Number of rows.
+    height: usize,
+    /// Number of bytes between the start of two consecutive rows.
+    stride: usize,
+    /// Byte order the values are decoded with.
+    endian: Endian,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Matrix<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Matrix<'_, T> {}
+
+impl<'data, T: Abi + Decode<'data>> Matrix<'data, T> {
+    /// Creates a view over `height` rows of `width` values, each row starting
+    /// `stride` bytes after the previous one, and the values being decoded with the
+    /// byte order `E`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `stride` is smaller than the size of `width` values, or
+    /// if `bytes` ends before the last value of the last row.
+    pub fn new<E: Endianness>(
+        bytes: Bytes<'data>,
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Matrix<'data, T>> {
+        let len = Self::region_len(width, height, stride)
+            .ok_or_else(|| Error::out_of_bounds(usize::MAX, bytes.len()))?;
+        if width * size_of::<T>() > stride {
+            return Err(Error::validation_failed(
+                "The stride of a matrix is smaller than the size of its rows",
+            ));
+        }
+        let region = bytes
+            .into_slice()
+            .get(..len)
+            .ok_or_else(|| Error::out_of_bounds(len, bytes.len()))?;
+        Ok(Matrix {
+            bytes: Bytes::new_allow_empty(region),
+            width,
+            height,
+            stride,
+            endian: E::ENDIAN,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of bytes from the start of the first row to the end of
+    /// the last value of the last row, or `None` if it overflows a `usize`.
+    fn region_len(width: usize, height: usize, stride: usize) -> Option<usize> {
+        let row_len = width.checked_mul(size_of::<T>())?;
+        match height.checked_sub(1) {
+            Some(last) => last
+                .checked_mul(stride)?
+                .checked_add(row_len),
+            None => Some(0),
+        }
+    }
+
+    /// Returns the number of values in a row.
+    #[inline]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    #[inline]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the number of bytes between the start of two consecutive rows.
+    #[inline]
+    pub const fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the bytes of all rows, including the padding between them.
+    #[inline]
+    pub const fn as_bytes(&self) -> Bytes<'data> {
+        self.bytes
+    }
+
+    /// Decodes the value at `col` within the row `row`, returning `None` if either
+    /// is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes of the value do not hold a valid `T`.
+    #[inline]
+    pub fn get(&self, row: usize, col: usize) -> Option<Result<T>> {
+        if row >= self.height || col >= self.width {
+            return None;
+        }
+        let bytes = self
+            .bytes
+            .into_slice()
+            .get(row * self.stride + col * size_of::<T>()..)
+            .unwrap_or_default();
+        let decoded = match self.endian {
+            Endian::Little => T::decode::<LittleEndian>(bytes),
+            Endian::Big => T::decode::<BigEndian>(bytes),
+        };
+        Some(decoded.map(|(value, _)| value))
+    }
+
+    /// Returns the values of the row `row`, without its padding, or `None` if it is
+    /// out of bounds.
+    #[inline]
+    pub fn row(&self, row: usize) -> Option<Counted<'data, T>> {
+        if row >= self.height {
+            return None;
+        }
+        let start = row * self.stride;
+        let bytes = self
+            .bytes
+            .into_slice()
+            .get(start..start + self.width * size_of::<T>())
+            .unwrap_or_default();
+        Some(Counted::from_parts(Bytes::new_allow_empty(bytes), self.width, self.endian))
+    }
+
+    /// Returns an iterator over the rows of the matrix, from top to bottom.
+    #[inline]
+    pub const fn rows(&self) -> MatrixRows<'data, T> {
+        MatrixRows { matrix: *self, row: 0 }
+    }
+}
+
+impl<'data, T: Abi + Decode<'data> + fmt::Debug> fmt::Debug for Matrix<'data, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.rows())
+            .finish()
+    }
+}
+
+/// Iterator over the rows of a [`Matrix`], created by [`Matrix::rows`].
+#[derive(Clone, Copy)]
+pub struct MatrixRows<'data, T> {
+    matrix: Matrix<'data, T>,
+    row: usize,
+}
+
+impl<'data, T: Abi + Decode<'data>> Iterator for MatrixRows<'data, T> {
+    type Item = Counted<'data, T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.matrix.row(self.row)?;
+        self.row += 1;
+        Some(row)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.matrix.height() - self.row;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'data, T: Abi + Decode<'data>> ExactSizeIterator for MatrixRows<'data, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LE;
+
+    #[test]
+    fn accesses_values_by_row_and_column() {
+        let bytes = [1, 2, 3, 0xee, 4, 5, 6, 0xee, 7, 8, 9];
+        let matrix = Matrix::<u8>::new::<LE>(Bytes::new(&bytes), 3, 3, 4).unwrap();
+        assert_eq!((matrix.width(), matrix.height(), matrix.stride()), (3, 3, 4));
+        assert_eq!(
+            matrix
+                .get(2, 0)
+                .unwrap()
+                .unwrap(),
+            7
+        );
+        assert!(matrix.get(3, 0).is_none());
+        assert!(matrix.row(3).is_none());
+
+        let rows = matrix
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .collect::<Result<alloc::vec::Vec<_>>>()
+                    .unwrap()
+            })
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(rows, [[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+
+        let words = Matrix::<u16>::new::<LE>(Bytes::new(&bytes[..6]), 1, 2, 4).unwrap();
+        assert_eq!(
+            words
+                .get(1, 0)
+                .unwrap()
+                .unwrap(),
+            0x0504
+        );
+        assert_eq!(words.as_bytes().len(), 6);
+    }
+
+    #[test]
+    fn rejects_short_inputs_and_strides() {
+        let bytes = [0u8; 8];
+        assert!(Matrix::<u8>::new::<LE>(Bytes::new(&bytes), 3, 3, 3).is_err());
+        assert!(Matrix::<u16>::new::<LE>(Bytes::new(&bytes), 2, 2, 3).is_err());
+        assert!(Matrix::<u8>::new::<LE>(Bytes::new(&bytes), 1, usize::MAX, 2).is_err());
+        assert_eq!(
+            Matrix::<u32>::new::<LE>(Bytes::new(&bytes), 0, 0, 0)
+                .unwrap()
+                .rows()
+                .len(),
+            0
+        );
+    }
+}
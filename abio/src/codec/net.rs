@@ -0,0 +1,299 @@
+//! Module containing the wire formats of the [`core::net`] address types, and the
+//! [`SockAddr`] type for socket addresses stored in the layout of `sockaddr_in` and
+//! `sockaddr_in6`.
+//!
+//! Addresses are stored as their octets, in the order they are written in, and
+//! never byte-swapped. Ports are stored as 16-bit integers following the address,
+//! in the byte order used to decode or encode them, which is big endian in most
+//! network protocols, such as the PROXY protocol:
+//!
+//! ```
+//! use core::net::{Ipv4Addr, SocketAddrV4};
+//!
+//! use abio::{Decode, BE};
+//!
+//! let bytes = [192, 168, 0, 1, 0x1f, 0x90];
+//! let (addr, len) = SocketAddrV4::decode::<BE>(&bytes).unwrap();
+//! assert_eq!(addr, SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 1), 8080));
+//! assert_eq!(len, 6);
+//! ```
+//!
+//! Addresses also convert to and from a [`Chunk`] of their octets.
+
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::{Chunk, Decode, Encode, Endianness, Error, Result, BE};
+
+/// Returns the first `N` bytes of `bytes`, or an error if it is shorter.
+#[inline]
+fn read_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N]> {
+    bytes
+        .first_chunk::<N>()
+        .copied()
+        .ok_or_else(|| Error::out_of_bounds(N, bytes.len()))
+}
+
+/// Copies `array` into the start of `buf`, or returns an error if it is shorter.
+#[inline]
+fn write_array<const N: usize>(buf: &mut [u8], array: [u8; N]) -> Result<usize> {
+    let available = buf.len();
+    *buf.first_chunk_mut::<N>()
+        .ok_or_else(|| Error::out_of_bounds(N, available))? = array;
+    Ok(N)
+}
+
+/// An IPv4 address is stored as its 4 octets.
+impl<'data> Decode<'data> for Ipv4Addr {
+    #[inline]
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(Ipv4Addr, usize)> {
+        Ok((Ipv4Addr::from(read_array::<4>(bytes)?), 4))
+    }
+}
+
+impl Encode for Ipv4Addr {
+    #[inline]
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        write_array(buf, self.octets())
+    }
+}
+
+/// An IPv6 address is stored as its 16 octets.
+impl<'data> Decode<'data> for Ipv6Addr {
+    #[inline]
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(Ipv6Addr, usize)> {
+        Ok((Ipv6Addr::from(read_array::<16>(bytes)?), 16))
+    }
+}
+
+impl Encode for Ipv6Addr {
+    #[inline]
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        write_array(buf, self.octets())
+    }
+}
+
+/// An IPv4 socket address is stored as its address followed by its port.
+impl<'data> Decode<'data> for SocketAddrV4 {
+    #[inline]
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(SocketAddrV4, usize)> {
+        let (ip, len) = Ipv4Addr::decode::<E>(bytes)?;
+        let (port, _) = u16::decode::<E>(&bytes[len..])?;
+        Ok((SocketAddrV4::new(ip, port), len + 2))
+    }
+}
+
+impl Encode for SocketAddrV4 {
+    #[inline]
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.ip().encode::<E>(buf)?;
+        Ok(len
+            + self
+                .port()
+                .encode::<E>(&mut buf[len..])?)
+    }
+}
+
+/// An IPv6 socket address is stored as its address followed by its port. The flow
+/// information and scope identifier are not stored, and decode as `0`.
+impl<'data> Decode<'data> for SocketAddrV6 {
+    #[inline]
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(SocketAddrV6, usize)> {
+        let (ip, len) = Ipv6Addr::decode::<E>(bytes)?;
+        let (port, _) = u16::decode::<E>(&bytes[len..])?;
+        Ok((SocketAddrV6::new(ip, port, 0, 0), len + 2))
+    }
+}
+
+impl Encode for SocketAddrV6 {
+    #[inline]
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.ip().encode::<E>(buf)?;
+        Ok(len
+            + self
+                .port()
+                .encode::<E>(&mut buf[len..])?)
+    }
+}
+
+impl From<Ipv4Addr> for Chunk<4> {
+    #[inline]
+    fn from(addr: Ipv4Addr) -> Chunk<4> {
+        Chunk::from(addr.octets())
+    }
+}
+
+impl From<Chunk<4>> for Ipv4Addr {
+    #[inline]
+    fn from(chunk: Chunk<4>) -> Ipv4Addr {
+        Ipv4Addr::from(chunk.into_array())
+    }
+}
+
+impl From<Ipv6Addr> for Chunk<16> {
+    #[inline]
+    fn from(addr: Ipv6Addr) -> Chunk<16> {
+        Chunk::from(addr.octets())
+    }
+}
+
+impl From<Chunk<16>> for Ipv6Addr {
+    #[inline]
+    fn from(chunk: Chunk<16>) -> Ipv6Addr {
+        Ipv6Addr::from(chunk.into_array())
+    }
+}
+
+/// A socket address stored in the layout of the Linux `sockaddr_in` or
+/// `sockaddr_in6` structs, as found in netlink messages and captures of the Linux
+/// cooked capture format.
+///
+/// The address family is stored in the byte order used to decode or encode the
+/// address, which is the byte order of the machine that produced it. The port and
+/// flow information are always stored in network byte order, as in the structs
+/// themselves.
+///
+/// | Layout         | Size | Fields                                            |
+/// |----------------|------|---------------------------------------------------|
+/// | `sockaddr_in`  | 16   | family, port, address, 8 bytes of zeroes          |
+/// | `sockaddr_in6` | 28   | family, port, flow information, address, scope id |
+///
+/// # Examples
+///
+/// ```
+/// use core::net::{Ipv4Addr, SocketAddr};
+///
+/// use abio::codec::SockAddr;
+/// use abio::{Decode, LE};
+///
+/// let bytes = [2, 0, 0x00, 0x50, 10, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+/// let (addr, len) = SockAddr::decode::<LE>(&bytes).unwrap();
+/// assert_eq!(addr.0, SocketAddr::from((Ipv4Addr::new(10, 0, 0, 1), 80)));
+/// assert_eq!(len, 16);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SockAddr(pub SocketAddr);
+
+impl SockAddr {
+    /// Address family of `sockaddr_in`.
+    pub const AF_INET: u16 = 2;
+
+    /// Address family of `sockaddr_in6` on Linux.
+    pub const AF_INET6: u16 = 10;
+
+    /// Size of `sockaddr_in`.
+    pub const IN_SIZE: usize = 16;
+
+    /// Size of `sockaddr_in6`.
+    pub const IN6_SIZE: usize = 28;
+}
+
+impl<'data> Decode<'data> for SockAddr {
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(SockAddr, usize)> {
+        let (family, _) = u16::decode::<E>(bytes)?;
+        match family {
+            SockAddr::AF_INET => {
+                let raw = read_array::<{ SockAddr::IN_SIZE }>(bytes)?;
+                let (port, _) = u16::decode::<BE>(&raw[2..])?;
+                let (ip, _) = Ipv4Addr::decode::<E>(&raw[4..])?;
+                Ok((SockAddr(SocketAddrV4::new(ip, port).into()), SockAddr::IN_SIZE))
+            }
+            SockAddr::AF_INET6 => {
+                let raw = read_array::<{ SockAddr::IN6_SIZE }>(bytes)?;
+                let (port, _) = u16::decode::<BE>(&raw[2..])?;
+                let (flowinfo, _) = u32::decode::<BE>(&raw[4..])?;
+                let (ip, _) = Ipv6Addr::decode::<E>(&raw[8..])?;
+                let (scope_id, _) = u32::decode::<E>(&raw[24..])?;
+                let addr = SocketAddrV6::new(ip, port, flowinfo, scope_id);
+                Ok((SockAddr(addr.into()), SockAddr::IN6_SIZE))
+            }
+            _ => Err(Error::unknown_discriminant("SockAddr", i128::from(family))),
+        }
+    }
+}
+
+impl Encode for SockAddr {
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        match self.0 {
+            SocketAddr::V4(addr) => {
+                let mut raw = [0u8; SockAddr::IN_SIZE];
+                SockAddr::AF_INET.encode::<E>(&mut raw)?;
+                addr.port()
+                    .encode::<BE>(&mut raw[2..])?;
+                addr.ip()
+                    .encode::<E>(&mut raw[4..])?;
+                write_array(buf, raw)
+            }
+            SocketAddr::V6(addr) => {
+                let mut raw = [0u8; SockAddr::IN6_SIZE];
+                SockAddr::AF_INET6.encode::<E>(&mut raw)?;
+                addr.port()
+                    .encode::<BE>(&mut raw[2..])?;
+                addr.flowinfo()
+                    .encode::<BE>(&mut raw[4..])?;
+                addr.ip()
+                    .encode::<E>(&mut raw[8..])?;
+                addr.scope_id()
+                    .encode::<E>(&mut raw[24..])?;
+                write_array(buf, raw)
+            }
+        }
+    }
+}
+
+impl From<SocketAddr> for SockAddr {
+    #[inline]
+    fn from(addr: SocketAddr) -> SockAddr {
+        SockAddr(addr)
+    }
+}
+
+impl From<SockAddr> for SocketAddr {
+    #[inline]
+    fn from(addr: SockAddr) -> SocketAddr {
+        addr.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LE;
+
+    #[test]
+    fn round_trips_address_tuples() {
+        let v6 = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0);
+        let mut buf = [0u8; 18];
+        assert_eq!(v6.encode::<BE>(&mut buf), Ok(18));
+        assert_eq!(buf[15..], [1, 0x01, 0xbb]);
+        assert_eq!(SocketAddrV6::decode::<BE>(&buf), Ok((v6, 18)));
+        assert!(SocketAddrV4::decode::<BE>(&buf[..5]).is_err());
+
+        let chunk = Chunk::from(Ipv4Addr::BROADCAST);
+        assert_eq!(chunk.into_array(), [0xff; 4]);
+        assert_eq!(Ipv4Addr::from(chunk), Ipv4Addr::BROADCAST);
+        assert_eq!(Ipv6Addr::from(Chunk::from(Ipv6Addr::LOCALHOST)), Ipv6Addr::LOCALHOST);
+    }
+
+    #[test]
+    fn round_trips_sockaddr_layouts() {
+        let v6 = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 7, 3);
+        let mut buf = [0u8; 32];
+        assert_eq!(SockAddr(v6.into()).encode::<LE>(&mut buf), Ok(28));
+        assert_eq!(buf[..8], [10, 0, 0x1f, 0x90, 0, 0, 0, 7]);
+        assert_eq!(buf[24..28], [3, 0, 0, 0]);
+        assert_eq!(SockAddr::decode::<LE>(&buf), Ok((SockAddr(v6.into()), 28)));
+
+        let v4 = SocketAddr::from((Ipv4Addr::LOCALHOST, 53));
+        assert_eq!(SockAddr(v4).encode::<BE>(&mut buf), Ok(16));
+        assert_eq!(buf[..8], [0, 2, 0, 53, 127, 0, 0, 1]);
+        assert_eq!(SockAddr::decode::<BE>(&buf), Ok((SockAddr(v4), 16)));
+        assert!(SockAddr(v4)
+            .encode::<BE>(&mut buf[..15])
+            .is_err());
+
+        assert!(SockAddr::decode::<LE>(&[2, 0, 0, 53]).is_err());
+        assert!(SockAddr::decode::<LE>(&[1, 0])
+            .unwrap_err()
+            .is_unknown_discriminant());
+    }
+}
@@ -0,0 +1,192 @@
+//! Module containing the [`PackedArray`] type, for decoding arrays of values that
+//! are packed contiguously with a bit width that is not a multiple of 8.
+//!
+//! Sensor and codec formats frequently pack samples this way. For example, a
+//! 10-bit image sensor may store four samples in five bytes.
+
+use core::ops::{Deref, Index};
+
+use super::bits::{BitOrder, BitReader};
+use crate::{sealed, Decode, Endianness, Error, Result};
+
+/// Trait for native integer primitives that can be unpacked from a
+/// [`PackedArray`].
+///
+/// This trait is sealed and implemented for all integer primitives up to 64 bits
+/// wide. Signed values are sign-extended from their packed width.
+pub trait PackedInt: Copy + Default + sealed::Sealed {
+    /// Number of bits in the native representation of this type.
+    const BITS: u32;
+
+    /// Converts the low `bits` bits of `raw` into a value of this type.
+    fn from_raw_bits(raw: u64, bits: u32) -> Self;
+}
+
+macro_rules! impl_packed_int {
+    (@unsigned $($ty:ty),* $(,)?) => {
+        $(
+            impl PackedInt for $ty {
+                const BITS: u32 = <$ty>::BITS;
+
+                #[inline(always)]
+                fn from_raw_bits(raw: u64, _bits: u32) -> Self {
+                    raw as $ty
+                }
+            }
+        )*
+    };
+    (@signed $($ty:ty),* $(,)?) => {
+        $(
+            impl PackedInt for $ty {
+                const BITS: u32 = <$ty>::BITS;
+
+                #[inline(always)]
+                fn from_raw_bits(raw: u64, bits: u32) -> Self {
+                    // Shift the sign bit of the packed value into the sign bit of an `i64`,
+                    // then shift back to sign-extend it.
+                    let shift = u64::BITS - bits;
+                    (((raw << shift) as i64) >> shift) as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_packed_int!(@unsigned u8, u16, u32, u64);
+impl_packed_int!(@signed i8, i16, i32, i64);
+
+/// An array of `N` integers, each packed into exactly `BITS` bits on the wire.
+///
+/// Values are stored contiguously without any padding between them. The final
+/// byte is zero-padded if `BITS * N` is not a multiple of 8.
+///
+/// # Bit Order
+///
+/// When decoded through the [`Decode`] trait, the bit order is derived from the
+/// byte order: big endian data is read [most significant bit first][msb], and
+/// little endian data is read [least significant bit first][lsb]. Use
+/// [`decode_with_order`][PackedArray::decode_with_order] to choose the bit order
+/// explicitly.
+///
+/// [msb]: BitOrder::MsbFirst
+/// [lsb]: BitOrder::LsbFirst
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PackedArray<T: PackedInt, const BITS: u32, const N: usize> {
+    values: [T; N],
+}
+
+impl<T: PackedInt, const BITS: u32, const N: usize> PackedArray<T, BITS, N> {
+    /// Exact number of bytes occupied by the array on the wire.
+    pub const WIRE_SIZE: usize = (BITS as usize * N).div_ceil(8);
+
+    /// Compile-time validation of the bit width.
+    const VALID_BITS: () = assert!(
+        BITS > 0 && BITS <= T::BITS,
+        "The packed bit width must be non-zero and no wider than the element type"
+    );
+
+    /// Creates a new [`PackedArray`] from an array of values.
+    #[inline]
+    pub const fn new(values: [T; N]) -> Self {
+        Self { values }
+    }
+
+    /// Returns the exact number of bytes occupied by the array on the wire.
+    #[inline]
+    pub const fn wire_size() -> usize {
+        Self::WIRE_SIZE
+    }
+
+    /// Returns a reference to the unpacked values.
+    #[inline]
+    pub const fn as_array(&self) -> &[T; N] {
+        &self.values
+    }
+
+    /// Consumes the [`PackedArray`], returning the unpacked values.
+    #[inline]
+    pub const fn into_array(self) -> [T; N] {
+        self.values
+    }
+
+    /// Decodes a [`PackedArray`] from the start of `bytes` using an explicit bit
+    /// order, returning the array and the number of bytes consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` contains fewer than
+    /// [`WIRE_SIZE`][PackedArray::WIRE_SIZE] bytes.
+    pub fn decode_with_order(bytes: &[u8], order: BitOrder) -> Result<(Self, usize)> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_BITS;
+
+        if bytes.len() < Self::WIRE_SIZE {
+            return Err(Error::out_of_bounds(Self::WIRE_SIZE, bytes.len()));
+        }
+
+        let mut reader = BitReader::new(&bytes[..Self::WIRE_SIZE], order);
+        let mut values = [T::default(); N];
+        for value in values.iter_mut() {
+            *value = T::from_raw_bits(reader.read_bits(BITS)?, BITS);
+        }
+        Ok((Self { values }, Self::WIRE_SIZE))
+    }
+}
+
+impl<'data, T: PackedInt, const BITS: u32, const N: usize> Decode<'data>
+    for PackedArray<T, BITS, N>
+{
+    #[inline]
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(Self, usize)> {
+        Self::decode_with_order(bytes, BitOrder::from_endian(E::ENDIAN))
+    }
+}
+
+impl<T: PackedInt, const BITS: u32, const N: usize> Deref for PackedArray<T, BITS, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl<T: PackedInt, const BITS: u32, const N: usize> Index<usize> for PackedArray<T, BITS, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.values[idx]
+    }
+}
+
+impl<T: PackedInt, const BITS: u32, const N: usize> From<[T; N]> for PackedArray<T, BITS, N> {
+    #[inline]
+    fn from(values: [T; N]) -> Self {
+        Self::new(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpack_ten_bit_samples() {
+        // Four 10-bit samples (0x3ff, 0x001, 0x200, 0x155) packed MSB-first into 5 bytes.
+        let bytes = [0xff, 0xc0, 0x18, 0x01, 0x55];
+        let (samples, consumed) =
+            PackedArray::<u16, 10, 4>::decode_with_order(&bytes, BitOrder::MsbFirst).unwrap();
+        assert_eq!(consumed, 5);
+        assert_eq!(PackedArray::<u16, 10, 4>::wire_size(), 5);
+        assert_eq!(samples.into_array(), [0x3ff, 0x001, 0x200, 0x155]);
+
+        let (signed, _) =
+            PackedArray::<i16, 10, 4>::decode_with_order(&bytes, BitOrder::MsbFirst).unwrap();
+        assert_eq!(signed.into_array(), [-1, 1, -512, 0x155]);
+
+        assert!(
+            PackedArray::<u16, 10, 4>::decode_with_order(&bytes[..4], BitOrder::MsbFirst).is_err()
+        );
+    }
+}
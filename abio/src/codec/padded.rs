@@ -0,0 +1,141 @@
+//! Module containing the [`MaybePadded`] type, for values padded to a fixed size
+//! with bytes of unspecified content.
+//!
+//! Some producers write records into slots of a fixed size, leaving whatever bytes
+//! happened to be in memory after the end of the record. Decoding such a slot as
+//! the record itself leaves the junk to the next field, while decoding it with a
+//! strict [`Codec`][crate::config::Codec] fails with a size mismatch. Wrapping the
+//! field in [`MaybePadded`] consumes the whole slot instead, ignoring the bytes
+//! after the value, or requiring them to be zero with [`ZeroPadded`]:
+//!
+//! ```
+//! use abio::codec::{MaybePadded, ZeroPadded};
+//! use abio::{Decode, LE};
+//!
+//! let bytes = [0x01, 0x02, 0xde, 0xad];
+//! let (value, len) = MaybePadded::<u16, 4>::decode::<LE>(&bytes).unwrap();
+//! assert_eq!((value.into_inner(), len), (0x0201, 4));
+//!
+//! assert!(ZeroPadded::<u16, 4>::decode::<LE>(&bytes).is_err());
+//! assert!(ZeroPadded::<u16, 4>::decode::<LE>(&[0x01, 0x02, 0, 0]).is_ok());
+//! ```
+
+use core::ops::{Deref, DerefMut};
+
+use crate::{Decode, Encode, Endianness, Error, Result};
+
+/// A `T` stored at the start of a slot of `SIZE` bytes, followed by padding up to
+/// the end of the slot.
+///
+/// The padding is ignored when decoding, unless `ZEROED` is `true`, in which case
+/// any non-zero byte of padding is rejected. It is always written as zeroes when
+/// encoding.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MaybePadded<T, const SIZE: usize, const ZEROED: bool = false>(pub T);
+
+/// A `T` stored at the start of a slot of `SIZE` bytes, followed by zeroes up to
+/// the end of the slot.
+pub type ZeroPadded<T, const SIZE: usize> = MaybePadded<T, SIZE, true>;
+
+impl<T, const SIZE: usize, const ZEROED: bool> MaybePadded<T, SIZE, ZEROED> {
+    /// Number of bytes of the slot holding the value and its padding.
+    pub const SIZE: usize = SIZE;
+
+    /// Wraps `value`, to be stored in a slot of `SIZE` bytes.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        MaybePadded(value)
+    }
+
+    /// Returns the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, const SIZE: usize, const ZEROED: bool> Deref for MaybePadded<T, SIZE, ZEROED> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, const SIZE: usize, const ZEROED: bool> DerefMut for MaybePadded<T, SIZE, ZEROED> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T, const SIZE: usize, const ZEROED: bool> From<T> for MaybePadded<T, SIZE, ZEROED> {
+    #[inline]
+    fn from(value: T) -> Self {
+        MaybePadded(value)
+    }
+}
+
+/// The value is decoded from the first `SIZE` bytes of the input, and the whole
+/// slot is consumed.
+impl<'data, T, const SIZE: usize, const ZEROED: bool> Decode<'data> for MaybePadded<T, SIZE, ZEROED>
+where
+    T: Decode<'data>,
+{
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(Self, usize)> {
+        let slot = bytes
+            .get(..SIZE)
+            .ok_or_else(|| Error::out_of_bounds(SIZE, bytes.len()))?;
+        let (value, len) = T::decode::<E>(slot)?;
+        if ZEROED
+            && slot[len..]
+                .iter()
+                .any(|&byte| byte != 0)
+        {
+            return Err(Error::validation_failed("The padding after a value is not zeroed"));
+        }
+        Ok((MaybePadded(value), SIZE))
+    }
+}
+
+impl<T: Encode, const SIZE: usize, const ZEROED: bool> Encode for MaybePadded<T, SIZE, ZEROED> {
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        let available = buf.len();
+        let slot = buf
+            .get_mut(..SIZE)
+            .ok_or_else(|| Error::out_of_bounds(SIZE, available))?;
+        let len = self.0.encode::<E>(slot)?;
+        slot[len..].fill(0);
+        Ok(SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BE, LE};
+
+    #[test]
+    fn consumes_the_whole_slot() {
+        let bytes = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xff];
+        let (value, len) = MaybePadded::<u32, 6>::decode::<BE>(&bytes).unwrap();
+        assert_eq!((*value, len), (0x1234_5678, 6));
+        assert!(MaybePadded::<u32, 6>::decode::<BE>(&bytes[..5]).is_err());
+        // The value may not extend past its slot.
+        assert!(MaybePadded::<u32, 2>::decode::<BE>(&bytes).is_err());
+
+        assert!(ZeroPadded::<u16, 4>::decode::<LE>(&bytes)
+            .unwrap_err()
+            .is_validation_failed());
+        let (value, _) = ZeroPadded::<u16, 4>::decode::<LE>(&[1, 0, 0, 0]).unwrap();
+        assert_eq!(value.into_inner(), 1);
+
+        let mut buf = [0xaa; 5];
+        assert_eq!(ZeroPadded::<u16, 4>::new(0x0102).encode::<BE>(&mut buf), Ok(4));
+        assert_eq!(buf, [0x01, 0x02, 0, 0, 0xaa]);
+        assert!(MaybePadded::<u16, 8>::new(1)
+            .encode::<BE>(&mut buf)
+            .is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Module containing [`Patcher`], for editing the fields of an encoded value in
+//! place, addressing them by path.
+//!
+//! Paths are dot-separated field names, resolved against the [`Descriptor`] of the
+//! outermost type. Fields holding another described type are traversed when they
+//! are marked with `#[abio(nested)]`:
+//!
+//! ```
+//! use abio::codec::Patcher;
+//! use abio::{BytesMut, LE};
+//!
+//! # #[derive(abio::Abi)]
+//! # #[repr(C)]
+//! # struct FileHeader {
+//! #     machine: u16,
+//! #     sections: u16,
+//! #     timestamp: u32,
+//! # }
+//! # #[derive(abio::Abi)]
+//! # #[repr(C)]
+//! # struct OptionalHeader {
+//! #     magic: u16,
+//! #     linker: u16,
+//! #     checksum: u32,
+//! # }
+//! # #[derive(abio::Abi)]
+//! # #[repr(C)]
+//! # struct NtHeaders {
+//! #     signature: u32,
+//! #     #[abio(nested)]
+//! #     file: FileHeader,
+//! #     #[abio(nested)]
+//! #     optional: OptionalHeader,
+//! # }
+//! # fn main() -> abio::Result<()> {
+//! # let mut image = [0xff; 20];
+//! let mut bytes = BytesMut::new(&mut image);
+//! Patcher::<NtHeaders, LE>::new(&mut bytes)
+//!     .set("optional.checksum", 0u32)?
+//!     .set("file.timestamp", 0u32)?;
+//! # assert_eq!(image[8..12], [0; 4]);
+//! # assert_eq!(image[16..20], [0; 4]);
+//! # Ok(())
+//! # }
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::layout::{Describe, Descriptor, FieldDescriptor};
+use crate::{BytesMut, Encode, Endianness, Error, Result, Span};
+
+/// Separator between the field names of a path.
+const SEPARATOR: char = '.';
+
+/// Editor re-encoding the fields of a value of type `T` in place, using the byte
+/// order `E`.
+pub struct Patcher<'a, 'data, T, E> {
+    bytes: &'a mut BytesMut<'data>,
+    _marker: PhantomData<fn() -> (T, E)>,
+}
+
+impl<'a, 'data, T: Describe, E: Endianness> Patcher<'a, 'data, T, E> {
+    /// Creates a patcher editing the value of type `T` encoded at the start of
+    /// `bytes`.
+    #[inline]
+    pub const fn new(bytes: &'a mut BytesMut<'data>) -> Self {
+        Self { bytes, _marker: PhantomData }
+    }
+
+    /// Returns the span of the field at `path`, relative to the start of the value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` does not name a field of `T`, or if it continues
+    /// past a field that is not marked as nested.
+    pub fn locate(&self, path: &str) -> Result<Span> {
+        let (field, offset) = resolve(const { &T::DESCRIPTOR }, path)?;
+        Ok(Span::new(offset, field.size))
+    }
+
+    /// Encodes `value` into the field at `path`, replacing its previous contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` cannot be resolved, if the field lies outside of
+    /// the underlying bytes, or if `value` is not encoded in exactly as many bytes
+    /// as the field occupies.
+    pub fn set<V: Encode>(&mut self, path: &str, value: V) -> Result<&mut Self> {
+        let span = self.locate(path)?;
+        let field = self
+            .bytes
+            .subslice_mut(span.range())?;
+        let written = value.encode::<E>(field)?;
+        if written != field.len() {
+            return Err(Error::size_mismatch(field.len(), written));
+        }
+        Ok(self)
+    }
+
+    /// Overwrites the field at `path` with `bytes`, which must be exactly as long as
+    /// the field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` cannot be resolved, if the field lies outside of
+    /// the underlying bytes, or if its length differs from `bytes.len()`.
+    pub fn set_bytes(&mut self, path: &str, bytes: &[u8]) -> Result<&mut Self> {
+        let span = self.locate(path)?;
+        let field = self
+            .bytes
+            .subslice_mut(span.range())?;
+        if bytes.len() != field.len() {
+            return Err(Error::size_mismatch(field.len(), bytes.len()));
+        }
+        field.copy_from_slice(bytes);
+        Ok(self)
+    }
+}
+
+/// Resolves `path` against `descriptor`, returning the named field along with its
+/// offset from the start of the outermost type.
+fn resolve(
+    mut descriptor: &'static Descriptor,
+    path: &str,
+) -> Result<(&'static FieldDescriptor, usize)> {
+    let mut offset = 0;
+    let mut segments = path.split(SEPARATOR).peekable();
+    while let Some(name) = segments.next() {
+        let field = descriptor
+            .field(name)
+            .ok_or_else(|| Error::verbose("patch path names a field that does not exist"))?;
+        offset += field.offset;
+        if segments.peek().is_none() {
+            return Ok((field, offset));
+        }
+        descriptor = field.nested.ok_or_else(|| {
+            Error::verbose("patch path continues past a field that is not nested")
+        })?;
+    }
+    unreachable!("`split` always yields at least one segment")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BE, LE};
+
+    const FILE: Descriptor = Descriptor {
+        name: "FileHeader",
+        size: 6,
+        align: 1,
+        repr: "C",
+        fields: &[
+            FieldDescriptor {
+                name: "machine",
+                offset: 0,
+                size: 2,
+                nested: None,
+                endian: None,
+            },
+            FieldDescriptor {
+                name: "timestamp",
+                offset: 2,
+                size: 4,
+                nested: None,
+                endian: None,
+            },
+        ],
+    };
+
+    struct Headers;
+
+    impl Describe for Headers {
+        const DESCRIPTOR: Descriptor = Descriptor {
+            name: "Headers",
+            size: 10,
+            align: 1,
+            repr: "C",
+            fields: &[
+                FieldDescriptor {
+                    name: "signature",
+                    offset: 0,
+                    size: 4,
+                    nested: None,
+                    endian: None,
+                },
+                FieldDescriptor {
+                    name: "file",
+                    offset: 4,
+                    size: 6,
+                    nested: Some(&FILE),
+                    endian: None,
+                },
+            ],
+        };
+        const PADDING: &'static [Span] = &[];
+    }
+
+    #[test]
+    fn patches_fields_by_path() {
+        let mut buf = [0xaa; 10];
+        let mut bytes = BytesMut::new(&mut buf);
+        let mut patcher = Patcher::<Headers, LE>::new(&mut bytes);
+        assert_eq!(
+            patcher
+                .locate("file.timestamp")
+                .unwrap()
+                .range(),
+            6..10
+        );
+        patcher
+            .set("file.timestamp", 0x0102_0304u32)
+            .unwrap()
+            .set_bytes("signature", b"PE\0\0")
+            .unwrap();
+        assert_eq!(buf, [b'P', b'E', 0, 0, 0xaa, 0xaa, 0x04, 0x03, 0x02, 0x01]);
+
+        let mut bytes = BytesMut::new(&mut buf);
+        Patcher::<Headers, BE>::new(&mut bytes)
+            .set("file.machine", 0x8664u16)
+            .unwrap();
+        assert_eq!(buf[4..6], [0x86, 0x64]);
+    }
+
+    #[test]
+    fn rejects_invalid_paths_and_values() {
+        let mut buf = [0; 8];
+        let mut bytes = BytesMut::new(&mut buf);
+        let mut patcher = Patcher::<Headers, LE>::new(&mut bytes);
+        assert!(patcher
+            .locate("file.missing")
+            .is_err());
+        assert!(patcher
+            .locate("signature.inner")
+            .is_err());
+        assert!(patcher.locate("").is_err());
+        assert!(patcher
+            .set("file.machine", 0u32)
+            .is_err());
+        assert!(patcher
+            .set_bytes("signature", b"PE")
+            .is_err());
+        // The timestamp lies past the end of the buffer.
+        assert!(patcher
+            .set("file.timestamp", 0u32)
+            .is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! Module containing the [`BufPool`] type, recycling the buffers values are encoded
+//! into.
+//!
+//! Producers encoding many small messages per second spend a noticeable share of
+//! their time allocating and freeing the buffers holding each message. A
+//! [`BufPool`] keeps the buffers handed back to it, sorted by size, and hands them
+//! out again instead of allocating new ones:
+//!
+//! ```
+//! use abio::codec::BufPool;
+//! use abio::BE;
+//!
+//! let mut pool = BufPool::new();
+//! for seq in 0..3u32 {
+//!     let mut buf = pool.get(16);
+//!     let message = buf
+//!         .encode_with::<BE>(|encoder| {
+//!             encoder.put_u16(0xcafe)?;
+//!             encoder.put_u32(seq)
+//!         })
+//!         .unwrap();
+//!     assert_eq!(message, [0xca, 0xfe, 0, 0, 0, seq as u8]);
+//!     pool.put(buf);
+//! }
+//! assert_eq!(pool.len(), 1);
+//! ```
+//!
+//! This module is only available when the __`alloc`__ feature is enabled.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{BufEncoder, Encoder};
+use crate::{BytesMut, Result};
+
+/// Binary logarithm of the size of the smallest buffers handed out by a pool.
+const MIN_CLASS_SHIFT: u32 = 6;
+
+/// Number of size classes kept by a pool, from 64 bytes to 64 KiB. Larger buffers
+/// are allocated on demand and freed when handed back.
+const CLASS_COUNT: usize = 11;
+
+/// Default number of buffers kept per size class.
+const DEFAULT_MAX_PER_CLASS: usize = 16;
+
+/// A pool of buffers of common sizes, handed out by [`get`][BufPool::get] and
+/// recycled by [`put`][BufPool::put].
+///
+/// Buffers are sized to the next power of two of the capacity requested, starting
+/// at 64 bytes, so that a buffer handed back for one message can be reused for any
+/// message of a similar size. The pool keeps a bounded number of buffers per size,
+/// and frees the others.
+#[derive(Debug)]
+pub struct BufPool {
+    classes: [Vec<Vec<u8>>; CLASS_COUNT],
+    max_per_class: usize,
+}
+
+impl BufPool {
+    /// Creates an empty pool, keeping up to 16 buffers of each size.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> BufPool {
+        BufPool::with_max_per_class(DEFAULT_MAX_PER_CLASS)
+    }
+
+    /// Creates an empty pool, keeping up to `max` buffers of each size.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_per_class(max: usize) -> BufPool {
+        BufPool {
+            classes: [const { Vec::new() }; CLASS_COUNT],
+            max_per_class: max,
+        }
+    }
+
+    /// Returns the number of buffers held by the pool.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.classes
+            .iter()
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Returns `true` if the pool holds no buffers.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a buffer of at least `capacity` bytes, reusing one handed back to
+    /// the pool if possible.
+    ///
+    /// A reused buffer still holds the bytes of its previous use.
+    #[must_use]
+    pub fn get(&mut self, capacity: usize) -> PooledBuf {
+        let buf = match class_of(capacity) {
+            Some(class) => self.classes[class]
+                .pop()
+                .unwrap_or_else(|| vec![0; class_size(class)]),
+            None => vec![0; capacity],
+        };
+        PooledBuf { buf, written: 0 }
+    }
+
+    /// Hands `buf` back to the pool, to be reused by a later call to
+    /// [`get`][BufPool::get].
+    ///
+    /// The buffer is freed instead if the pool already holds as many buffers of its
+    /// size as it keeps, or if it is larger than the largest size kept.
+    pub fn put(&mut self, buf: PooledBuf) {
+        let PooledBuf { buf, .. } = buf;
+        if let Some(class) = class_of(buf.len()).filter(|&class| class_size(class) == buf.len()) {
+            let pooled = &mut self.classes[class];
+            if pooled.len() < self.max_per_class {
+                pooled.push(buf);
+            }
+        }
+    }
+}
+
+impl Default for BufPool {
+    #[inline]
+    fn default() -> BufPool {
+        BufPool::new()
+    }
+}
+
+/// Returns the size class of buffers holding at least `capacity` bytes, if the
+/// pool keeps buffers that large.
+#[inline]
+fn class_of(capacity: usize) -> Option<usize> {
+    let size = capacity
+        .max(1 << MIN_CLASS_SHIFT)
+        .checked_next_power_of_two()?;
+    let class = (size.trailing_zeros() - MIN_CLASS_SHIFT) as usize;
+    (class < CLASS_COUNT).then_some(class)
+}
+
+/// Returns the size of the buffers of `class`.
+#[inline]
+const fn class_size(class: usize) -> usize {
+    1 << (class as u32 + MIN_CLASS_SHIFT)
+}
+
+/// A buffer handed out by a [`BufPool`], to be handed back with
+/// [`BufPool::put`] once the bytes encoded into it have been sent.
+///
+/// Dropping the buffer instead frees it, like any other allocation.
+#[derive(Debug)]
+pub struct PooledBuf {
+    buf: Vec<u8>,
+    /// Number of bytes written by the last call to `encode_with`.
+    written: usize,
+}
+
+impl PooledBuf {
+    /// Returns the size of the buffer, which may exceed the capacity requested.
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns a view of the whole buffer, for writing into it directly.
+    #[inline]
+    pub fn as_bytes_mut(&mut self) -> BytesMut<'_> {
+        BytesMut::new(&mut self.buf)
+    }
+
+    /// Encodes values into the start of the buffer with `f`, in the byte order `E`,
+    /// and returns the bytes written once the encoder is
+    /// [finished][BufEncoder::finish].
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors returned by `f`. The bytes written before the error are
+    /// discarded.
+    pub fn encode_with<E: Encoder>(
+        &mut self,
+        f: impl FnOnce(&mut BufEncoder<'_, E>) -> Result<()>,
+    ) -> Result<&[u8]> {
+        self.written = 0;
+        let mut encoder = BufEncoder::<E>::new(&mut self.buf);
+        f(&mut encoder)?;
+        self.written = encoder.finish();
+        Ok(self.written())
+    }
+
+    /// Returns the bytes written by the last successful call to
+    /// [`encode_with`][PooledBuf::encode_with].
+    #[inline]
+    #[must_use]
+    pub fn written(&self) -> &[u8] {
+        &self.buf[..self.written]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LE;
+
+    #[test]
+    fn recycles_buffers_by_size_class() {
+        assert_eq!(class_of(0), Some(0));
+        assert_eq!(class_of(64), Some(0));
+        assert_eq!(class_of(65), Some(1));
+        assert_eq!(class_of(1 << 16), Some(CLASS_COUNT - 1));
+        assert_eq!(class_of((1 << 16) + 1), None);
+        assert_eq!(class_of(usize::MAX), None);
+
+        let mut pool = BufPool::with_max_per_class(1);
+        let mut first = pool.get(100);
+        assert_eq!(first.capacity(), 128);
+        assert_eq!(first.encode_with::<LE>(|encoder| encoder.put_u16(0x0102)), Ok(&[2, 1][..]));
+        assert!(first
+            .encode_with::<LE>(|encoder| encoder.put_slice(&[0; 129]))
+            .is_err());
+        assert!(first.written().is_empty());
+        let ptr = first.as_bytes_mut().as_ptr();
+
+        let second = pool.get(128);
+        pool.put(first);
+        pool.put(second);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(
+            pool.get(80)
+                .as_bytes_mut()
+                .as_ptr(),
+            ptr
+        );
+        assert!(pool.is_empty());
+
+        let large = pool.get(1 << 20);
+        assert_eq!(large.capacity(), 1 << 20);
+        pool.put(large);
+        assert!(pool.is_empty());
+    }
+}
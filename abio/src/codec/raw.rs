@@ -0,0 +1,55 @@
+//! Module containing the [`RawBytes`] trait, for fields holding a fixed number of
+//! bytes that are copied without being interpreted.
+//!
+//! Opaque blobs, reserved regions and checksums verified after decoding are best
+//! kept as the exact bytes found in the input. A field declaring
+//! `#[abio(bytes = N)]` is read and written as `N` raw bytes, regardless of the
+//! byte order used for the rest of the type:
+//!
+//! ```
+//! #[derive(abio::Decode, abio::Encode)]
+//! struct Block {
+//!     length: u32,
+//!     #[abio(bytes = 16)]
+//!     digest: abio::Chunk<16>,
+//!     #[abio(bytes = 4)]
+//!     reserved: [u8; 4],
+//! }
+//! ```
+
+use crate::Chunk;
+
+/// Trait for types holding exactly `N` bytes, which are decoded and encoded as-is.
+///
+/// This trait is implemented for `[u8; N]` and [`Chunk<N>`].
+pub trait RawBytes<const N: usize>: Sized {
+    /// Creates a value holding `bytes`.
+    fn from_raw(bytes: [u8; N]) -> Self;
+
+    /// Returns the bytes held by this value.
+    fn as_raw(&self) -> &[u8; N];
+}
+
+impl<const N: usize> RawBytes<N> for [u8; N] {
+    #[inline(always)]
+    fn from_raw(bytes: [u8; N]) -> Self {
+        bytes
+    }
+
+    #[inline(always)]
+    fn as_raw(&self) -> &[u8; N] {
+        self
+    }
+}
+
+impl<const N: usize> RawBytes<N> for Chunk<N> {
+    #[inline(always)]
+    fn from_raw(bytes: [u8; N]) -> Self {
+        Chunk::from_ne_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn as_raw(&self) -> &[u8; N] {
+        self.as_byte_array()
+    }
+}
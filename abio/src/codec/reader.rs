@@ -0,0 +1,667 @@
+//! Module containing the [`Reader`] type, a position-tracking cursor for parsing
+//! data sequentially from a [`Source`].
+
+use crate::source::traits::checked_end;
+use crate::{Chunk, Decode, Endianness, Error, Result, Source, Word};
+
+/// Enumeration of possible methods to seek within the region of a [`Reader`].
+///
+/// This mirrors `std::io::SeekFrom`, which is unavailable in `no_std` builds.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SeekFrom {
+    /// Sets the position to the given number of bytes from the start of the region.
+    Start(u64),
+    /// Sets the position to the end of the region plus the given number of bytes.
+    End(i64),
+    /// Sets the position to the current position plus the given number of bytes.
+    Current(i64),
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::SeekFrom> for SeekFrom {
+    #[inline]
+    fn from(pos: std::io::SeekFrom) -> SeekFrom {
+        match pos {
+            std::io::SeekFrom::Start(offset) => SeekFrom::Start(offset),
+            std::io::SeekFrom::End(offset) => SeekFrom::End(offset),
+            std::io::SeekFrom::Current(offset) => SeekFrom::Current(offset),
+        }
+    }
+}
+
+/// A cursor over a bounded region of a [`Source`].
+///
+/// The reader tracks the current position within its region, so sequential
+/// parsing does not require manually threading offsets through every call. All
+/// positions reported by the reader are relative to the start of its region,
+/// including the [offsets][Error::offset] recorded by the errors it returns.
+///
+/// # Bounded Regions
+///
+/// A reader never accesses bytes outside of its region, even if the underlying
+/// source is larger. This makes it possible to hand a sub-reader created with
+/// [`take`][Reader::take] to an inner decoder, guaranteeing that the decoder cannot
+/// read past the end of a nested, length-delimited container.
+#[derive(Debug)]
+pub struct Reader<'data, S: Source + ?Sized = [u8]> {
+    /// Source the reader is reading from.
+    source: &'data S,
+    /// Absolute offset of the first byte within the region of this reader.
+    start: usize,
+    /// Absolute offset one past the last byte within the region of this reader.
+    end: usize,
+    /// Absolute offset of the cursor.
+    pos: usize,
+}
+
+impl<S: Source + ?Sized> Clone for Reader<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<S: Source + ?Sized> Copy for Reader<'_, S> {}
+
+impl<'data, S: Source + ?Sized> Reader<'data, S> {
+    /// Creates a new [`Reader`] spanning the entire `source`, positioned at its
+    /// first byte.
+    #[inline]
+    pub fn new(source: &'data S) -> Reader<'data, S> {
+        Reader { source, start: 0, end: source.len(), pos: 0 }
+    }
+
+    /// Creates a new [`Reader`] spanning the entire `source`, positioned one past its
+    /// last byte.
+    ///
+    /// The reader starts out exhausted, and is meant to be moved backwards with
+    /// [`seek`][Reader::seek] to read structures stored at the end of the input.
+    #[inline]
+    pub fn from_end(source: &'data S) -> Reader<'data, S> {
+        Reader { source, start: 0, end: source.len(), pos: source.len() }
+    }
+
+    /// Returns the source this reader is reading from.
+    #[inline]
+    pub const fn source(&self) -> &'data S {
+        self.source
+    }
+
+    /// Returns the current position of the cursor, relative to the start of the
+    /// region of this reader.
+    #[inline]
+    pub const fn position(&self) -> usize {
+        self.pos - self.start
+    }
+
+    /// Returns the total number of bytes within the region of this reader.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the region of this reader does not contain any bytes.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the number of bytes between the cursor and the end of the region of
+    /// this reader.
+    #[inline]
+    pub const fn remaining(&self) -> usize {
+        self.end - self.pos
+    }
+
+    /// Returns `true` if all of the bytes within the region have been consumed.
+    #[inline]
+    pub const fn is_exhausted(&self) -> bool {
+        self.pos == self.end
+    }
+
+    /// Moves the cursor to `pos`, relative to the start of the region of this reader,
+    /// returning the new position.
+    ///
+    /// Seeking to the end of the region is allowed, which leaves the reader
+    /// exhausted. This makes it possible to navigate formats that store references
+    /// to earlier or later parts of the input, such as the central directory at the
+    /// end of a ZIP archive, without manually creating new readers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new position would be negative or past the end of the
+    /// region. The cursor is not moved on error.
+    #[inline]
+    pub fn seek(&mut self, pos: SeekFrom) -> Result<usize> {
+        let target = match pos {
+            SeekFrom::Start(offset) => usize::try_from(offset).ok(),
+            SeekFrom::End(offset) => checked_offset(self.len(), offset),
+            SeekFrom::Current(offset) => checked_offset(self.position(), offset),
+        }
+        .ok_or_else(|| Error::verbose("Cannot seek to a negative or overflowing position"))?;
+        self.set_position(target)?;
+        Ok(target)
+    }
+
+    /// Moves the cursor back to the start of the region of this reader.
+    #[inline]
+    pub fn rewind(&mut self) {
+        self.pos = self.start;
+    }
+
+    /// Moves the cursor to `pos`, relative to the start of the region of this
+    /// reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pos` is past the end of the region. The cursor is not
+    /// moved on error.
+    #[inline]
+    pub fn set_position(&mut self, pos: usize) -> Result<()> {
+        if pos > self.len() {
+            return Err(Error::out_of_bounds(pos, self.len()));
+        }
+        self.pos = self.start + pos;
+        Ok(())
+    }
+
+    /// Advances the cursor by `len` bytes, returning the absolute offset of the
+    /// cursor before it was advanced.
+    #[inline]
+    fn advance(&mut self, len: usize) -> Result<usize> {
+        #[cfg(feature = "stats")]
+        crate::codec::stats::record_bounds_check();
+        checked_end(self.pos, len, self.end)
+            .map_err(|_| Error::out_of_bounds(len, self.remaining()))?;
+        let offset = self.pos;
+        self.pos += len;
+        Ok(offset)
+    }
+
+    /// Runs `read` on a copy of the cursor, which replaces the cursor only if `read`
+    /// succeeds. Errors record the position of the cursor they occurred at.
+    #[inline]
+    fn transact<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let mut cursor = *self;
+        match read(&mut cursor) {
+            Ok(value) => {
+                *self = cursor;
+                Ok(value)
+            }
+            Err(err) => Err(err.at_offset(self.position())),
+        }
+    }
+
+    /// Decodes a value of type `T` in the byte order `E`, advancing the cursor past
+    /// the bytes it was decoded from.
+    ///
+    /// The value is decoded from the bytes the source can lend out contiguously from
+    /// the cursor, so values within a single segment of a
+    /// [`SegmentedSource`][crate::SegmentedSource] are read without copying. Use
+    /// [`read_word`][Reader::read_word] or [`read_chunk`][Reader::read_chunk] for
+    /// fixed-size values that may straddle segments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abio::{Reader, BE};
+    ///
+    /// let mut reader = Reader::new(&b"\x00\x2aok\x01"[..]);
+    /// assert_eq!(reader.read::<u16, BE>().unwrap(), 42);
+    /// reader.skip(2).unwrap();
+    /// assert_eq!(reader.read_chunk::<1>().unwrap().into_array(), [1]);
+    ///
+    /// let err = reader.read::<u32, BE>().unwrap_err();
+    /// assert_eq!(err.offset(), Some(5));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the remaining bytes do not hold a valid `T`, or if the
+    /// value extends past the bytes the source can lend out contiguously. The
+    /// cursor is not advanced on error.
+    #[inline]
+    pub fn read<T: Decode<'data>, E: Endianness>(&mut self) -> Result<T> {
+        self.transact(|cursor| {
+            let remaining = cursor.remaining();
+            let rest = cursor.contiguous()?;
+            let (value, len) = match T::decode::<E>(rest) {
+                Err(err) if err.is_out_of_bounds() && rest.len() < remaining => {
+                    let missing = err
+                        .into_incomplete()
+                        .needed()
+                        .unwrap_or_default();
+                    return Err(Error::non_contiguous(cursor.position(), rest.len() + missing));
+                }
+                decoded => decoded?,
+            };
+            // Reading the bytes the value was decoded from lets sources that track
+            // their reads record only those bytes.
+            let offset = cursor.advance(len)?;
+            cursor
+                .source
+                .read_slice(offset, len)?;
+            Ok(value)
+        })
+    }
+
+    /// Reads a [`Word`] in the byte order `E`, advancing the cursor past it.
+    ///
+    /// Like [`read_chunk`][Reader::read_chunk], the bytes of the word are copied, so
+    /// this method also works for sources that are not contiguous in memory, such
+    /// as words straddling two segments of a
+    /// [`SegmentedSource`][crate::SegmentedSource].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `size_of::<W>()` bytes remain. The cursor is
+    /// not advanced on error.
+    #[inline]
+    pub fn read_word<W: Word, E: Endianness>(&mut self) -> Result<W> {
+        let mut buf = [0u8; 8];
+        let buf = &mut buf[..W::SIZE];
+        self.copy_to_slice(buf)?;
+        W::read_prefix(buf, E::ENDIAN).ok_or_else(|| Error::out_of_bounds(W::SIZE, buf.len()))
+    }
+
+    /// Reads a [`Chunk`] of `N` bytes, advancing the cursor past them.
+    ///
+    /// The bytes are copied as-is, without any endianness conversions, so this
+    /// method also works for sources that are not contiguous in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `N` bytes remain. The cursor is not advanced on
+    /// error.
+    #[inline]
+    pub fn read_chunk<const N: usize>(&mut self) -> Result<Chunk<N>> {
+        let mut buf = [0u8; N];
+        self.copy_to_slice(&mut buf)?;
+        Ok(Chunk::from_ne_bytes(buf))
+    }
+
+    /// Advances the cursor past the next `len` bytes without reading them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `len` bytes remain. The cursor is not advanced
+    /// on error.
+    #[inline]
+    pub fn skip(&mut self, len: usize) -> Result<()> {
+        self.transact(|cursor| cursor.advance(len))?;
+        Ok(())
+    }
+
+    /// Reads `len` bytes as a borrowed slice, advancing the cursor past them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `len` bytes remain, or if the source cannot
+    /// lend out a contiguous view of the bytes. The cursor is not advanced on error.
+    #[inline]
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'data [u8]> {
+        self.transact(|cursor| {
+            let offset = cursor.advance(len)?;
+            cursor
+                .source
+                .read_slice(offset, len)
+        })
+    }
+
+    /// Copies `dst.len()` bytes into `dst`, advancing the cursor past them.
+    ///
+    /// Unlike [`read_bytes`][Reader::read_bytes], this method also works for sources
+    /// that are not contiguous in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `dst.len()` bytes remain. The cursor is not
+    /// advanced on error.
+    #[inline]
+    pub fn copy_to_slice(&mut self, dst: &mut [u8]) -> Result<()> {
+        self.transact(|cursor| {
+            let offset = cursor.advance(dst.len())?;
+            cursor
+                .source
+                .copy_to_slice(offset, dst)
+        })
+    }
+
+    /// Peeks at the bytes the source can lend out contiguously from the cursor, up to
+    /// the end of the region of this reader.
+    #[inline]
+    fn contiguous(&self) -> Result<&'data [u8]> {
+        let len = self
+            .source
+            .contiguous_len(self.pos)
+            .min(self.remaining());
+        self.source
+            .peek_slice(self.pos, len)
+    }
+
+    /// Splits off a child reader covering exactly the next `len` bytes, advancing
+    /// this reader past them.
+    ///
+    /// The child reader has an independent cursor and cannot read beyond its own
+    /// region, making it suitable for decoding nested, length-delimited containers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `len` bytes remain. The cursor is not advanced
+    /// on error.
+    #[inline]
+    pub fn take(&mut self, len: usize) -> Result<Reader<'data, S>> {
+        self.transact(|cursor| {
+            let start = cursor.advance(len)?;
+            Ok(Reader { source: cursor.source, start, end: start + len, pos: start })
+        })
+    }
+}
+
+/// Applies a signed `offset` to `base`, returning `None` if the result is negative
+/// or does not fit in a `usize`.
+#[inline]
+fn checked_offset(base: usize, offset: i64) -> Option<usize> {
+    let magnitude = usize::try_from(offset.unsigned_abs()).ok()?;
+    if offset < 0 {
+        base.checked_sub(magnitude)
+    } else {
+        base.checked_add(magnitude)
+    }
+}
+
+/// Reading advances the cursor of the [`Reader`], so its position always reflects the
+/// number of bytes consumed through the [`std::io::Read`] interface.
+#[cfg(feature = "std")]
+impl<S: Source + ?Sized> std::io::Read for Reader<'_, S> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.remaining());
+        self.copy_to_slice(&mut buf[..len])?;
+        Ok(len)
+    }
+}
+
+/// Buffered reads lend out the bytes of the region the source can borrow
+/// contiguously from the cursor, so sources made of several segments, such as a
+/// [`SegmentedSource`][crate::SegmentedSource], are read one segment at a time.
+#[cfg(feature = "std")]
+impl<S: Source + ?Sized> std::io::BufRead for Reader<'_, S> {
+    #[inline]
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.contiguous()?)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(self.remaining());
+        // The bytes were only peeked at by `fill_buf`, so they are read here to let
+        // sources that track their reads record them.
+        let _ = self
+            .source
+            .read_slice(self.pos, amt);
+        self.pos += amt;
+    }
+}
+
+/// Seeking is bounded by the region of the [`Reader`], so positions reported through
+/// the [`std::io::Seek`] interface are relative to the start of the region.
+#[cfg(feature = "std")]
+impl<S: Source + ?Sized> std::io::Seek for Reader<'_, S> {
+    #[inline]
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        let pos = Reader::seek(self, pos.into())?;
+        Ok(pos as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::Coverage;
+    use crate::{SegmentedSource, Words, BE};
+
+    #[test]
+    fn take_splits_off_bounded_child_reader() {
+        let bytes: &[u8] = b"\x03\x00abcdef";
+        let mut reader = Reader::new(bytes);
+        assert_eq!(reader.read_bytes(2).unwrap(), b"\x03\x00");
+
+        let mut child = reader.take(3).unwrap();
+        assert_eq!(reader.position(), 5);
+        assert_eq!(child.len(), 3);
+        assert_eq!(child.read_bytes(2).unwrap(), b"ab");
+        assert!(child.read_bytes(2).is_err());
+        assert_eq!(child.read_bytes(1).unwrap(), b"c");
+        assert!(child.is_exhausted());
+
+        assert!(reader.take(4).is_err());
+        assert_eq!(reader.position(), 5);
+        assert_eq!(reader.read_bytes(3).unwrap(), b"def");
+    }
+
+    #[test]
+    fn seek_moves_within_region() {
+        let bytes: &[u8] = b"\x00\x01headerbodyTAIL";
+        let mut reader = Reader::new(bytes);
+        let mut region = reader.take(16).unwrap();
+
+        assert_eq!(
+            region
+                .seek(SeekFrom::End(-4))
+                .unwrap(),
+            12
+        );
+        assert_eq!(region.read_bytes(4).unwrap(), b"TAIL");
+        assert!(region.is_exhausted());
+
+        assert_eq!(
+            region
+                .seek(SeekFrom::Start(2))
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            region
+                .seek(SeekFrom::Current(6))
+                .unwrap(),
+            8
+        );
+        assert_eq!(region.read_bytes(4).unwrap(), b"body");
+
+        assert!(region
+            .seek(SeekFrom::Current(-13))
+            .is_err());
+        assert!(region
+            .seek(SeekFrom::End(1))
+            .is_err());
+        assert!(region
+            .seek(SeekFrom::Start(u64::MAX))
+            .is_err());
+        assert!(region.set_position(17).is_err());
+        assert_eq!(region.position(), 12);
+
+        let mut tail = Reader::from_end(bytes);
+        assert!(tail.is_exhausted());
+        assert_eq!(
+            tail.seek(SeekFrom::Current(-4))
+                .unwrap(),
+            12
+        );
+        assert_eq!(tail.read_bytes(4).unwrap(), b"TAIL");
+
+        region.rewind();
+        assert_eq!(region.read_bytes(2).unwrap(), b"\x00\x01");
+    }
+
+    #[test]
+    fn errors_record_position_of_cursor() {
+        let bytes: &[u8] = b"\x00\x10\x00\x00\x00\x07body";
+        let mut reader = Reader::new(bytes);
+        assert_eq!(
+            reader
+                .read::<u16, BE>()
+                .unwrap(),
+            16
+        );
+        assert_eq!(
+            reader
+                .read::<u32, BE>()
+                .unwrap(),
+            7
+        );
+        reader.skip(1).unwrap();
+        assert_eq!(
+            reader
+                .read_chunk::<2>()
+                .unwrap(),
+            Chunk::from_ne_bytes(*b"od")
+        );
+
+        let err = reader
+            .read::<u32, BE>()
+            .unwrap_err();
+        assert_eq!(err.offset(), Some(9));
+        assert_eq!(reader.remaining(), 1);
+        assert_eq!(
+            reader
+                .skip(2)
+                .unwrap_err()
+                .offset(),
+            Some(9)
+        );
+        assert_eq!(
+            reader
+                .read_chunk::<2>()
+                .unwrap_err()
+                .offset(),
+            Some(9)
+        );
+
+        reader.set_position(6).unwrap();
+        let mut body = reader.take(4).unwrap();
+        body.skip(3).unwrap();
+        assert_eq!(
+            body.read_bytes(2)
+                .unwrap_err()
+                .offset(),
+            Some(3)
+        );
+        assert_eq!(body.position(), 3);
+    }
+
+    #[test]
+    fn reads_values_from_segmented_sources() {
+        let segments: [&[u8]; 3] = [b"\x00\x2a\x00", b"\x00\x00\x07", b"ok"];
+        let source = SegmentedSource::new(&segments);
+        let mut reader = Reader::new(&source);
+        assert_eq!(
+            reader
+                .read::<u16, BE>()
+                .unwrap(),
+            42
+        );
+
+        // The next value straddles the first two segments, so it cannot be borrowed
+        // and decoding it fails without advancing the cursor. Reading it as a word
+        // copies its bytes instead.
+        let err = reader
+            .read::<u32, BE>()
+            .unwrap_err();
+        assert_eq!(err, Error::non_contiguous(2, 4).at_offset(2));
+        assert_eq!(reader.position(), 2);
+        assert_eq!(
+            reader
+                .read_word::<u32, BE>()
+                .unwrap(),
+            7
+        );
+        assert_eq!(reader.read_bytes(2).unwrap(), b"ok");
+        assert!(reader.is_exhausted());
+        assert!(reader
+            .read_word::<u16, BE>()
+            .is_err());
+
+        // Errors of a child reader report positions relative to its own region.
+        let mut reader = Reader::new(&source);
+        reader.skip(1).unwrap();
+        let mut child = reader.take(4).unwrap();
+        assert_eq!(
+            child
+                .read::<u32, BE>()
+                .unwrap_err(),
+            Error::non_contiguous(0, 4).at_offset(0)
+        );
+
+        #[cfg(feature = "std")]
+        {
+            use std::io::BufRead;
+
+            let mut reader = Reader::new(&source);
+            reader.consume(1);
+            assert_eq!(reader.fill_buf().unwrap(), b"\x2a\x00");
+            reader.consume(2);
+            assert_eq!(reader.fill_buf().unwrap(), b"\x00\x00\x07");
+            reader.consume(3);
+            assert_eq!(reader.fill_buf().unwrap(), b"ok");
+            reader.consume(2);
+            assert!(reader
+                .fill_buf()
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn reads_values_through_wrapping_sources() {
+        let segments: [&[u8]; 2] = [b"\x00\x2a", b"\x00\x07"];
+        let source = SegmentedSource::new(&segments);
+
+        let by_ref = &source;
+        let mut reader = Reader::new(&by_ref);
+        assert_eq!(
+            reader
+                .read::<u16, BE>()
+                .unwrap(),
+            42
+        );
+        assert_eq!(
+            reader
+                .read::<u16, BE>()
+                .unwrap(),
+            7
+        );
+
+        let coverage = Coverage::new(&source);
+        let mut reader = Reader::new(&coverage);
+        assert_eq!(
+            reader
+                .read::<u16, BE>()
+                .unwrap(),
+            42
+        );
+        assert_eq!(
+            reader
+                .read::<u16, BE>()
+                .unwrap(),
+            7
+        );
+
+        // Words in a foreign byte order can only be copied out of the buffer.
+        let words = [0x2au16, 7];
+        let words = Words::<u16, BE>::new(&words);
+        let by_ref = &words;
+        let mut reader = Reader::new(&by_ref);
+        assert_eq!(
+            reader
+                .read_word::<u16, BE>()
+                .unwrap(),
+            42
+        );
+        match reader.read::<u16, BE>() {
+            Ok(value) => assert!(words.is_zero_copy() && value == 7),
+            Err(err) => {
+                assert!(!words.is_zero_copy());
+                assert_eq!(err, Error::non_contiguous(2, 2).at_offset(2));
+            }
+        }
+    }
+}
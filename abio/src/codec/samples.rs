@@ -0,0 +1,277 @@
+//! Module containing the [`Samples`] type, a view over interleaved audio samples,
+//! and conversions between integer and floating point samples.
+//!
+//! Raw PCM data, such as the `data` chunk of a WAV file, stores one frame after the
+//! other, each frame holding one sample per channel. [`Samples`] decodes these
+//! samples in place, by frame or by channel:
+//!
+//! ```
+//! use abio::codec::{i16_to_f32, Samples};
+//! use abio::{Bytes, LE};
+//!
+//! // Two stereo frames of 16-bit samples.
+//! let bytes = [0x00, 0x40, 0x00, 0xc0, 0xff, 0x7f, 0x00, 0x80];
+//! let samples = Samples::<i16, 2>::new::<LE>(Bytes::new(&bytes));
+//! assert_eq!(samples.frames(), 2);
+//!
+//! let right = samples
+//!     .channel(1)
+//!     .unwrap()
+//!     .map(|sample| i16_to_f32(sample.unwrap()));
+//! assert!(right.eq([-0.5, -1.0]));
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::size_of;
+
+use super::Counted;
+use crate::{Abi, BigEndian, Bytes, Decode, Endian, Endianness, LittleEndian, Result};
+
+/// Scale between an `i16` sample and its `f32` equivalent.
+const I16_SCALE: f32 = 32768.0;
+
+/// Converts a 16-bit integer sample into a floating point sample in `-1.0..1.0`.
+#[inline]
+#[must_use]
+pub fn i16_to_f32(sample: i16) -> f32 {
+    f32::from(sample) / I16_SCALE
+}
+
+/// Converts a floating point sample into the nearest 16-bit integer sample.
+///
+/// Samples outside of `-1.0..=1.0` are clipped, and `NaN` is converted into
+/// silence.
+#[inline]
+#[must_use]
+pub fn f32_to_i16(sample: f32) -> i16 {
+    if sample.is_nan() {
+        return 0;
+    }
+    let scaled = sample * I16_SCALE;
+    // `f32::round` is not available without `std`, so round half away from zero
+    // before truncating. The conversion saturates at the bounds of an `i16`.
+    let rounded = if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 };
+    rounded as i16
+}
+
+/// A borrowed sequence of interleaved frames of `CHANNELS` samples of type `T`,
+/// decoding each sample only when it is accessed.
+///
+/// Bytes after the last complete frame, such as the padding byte of an odd-sized
+/// WAV chunk, are not part of the view.
+pub struct Samples<'data, T, const CHANNELS: usize> {
+    /// Bytes of all complete frames, in order.
+    bytes: Bytes<'data>,
+    /// Number of complete frames.
+    frames: usize,
+    /// Byte order the samples are decoded with.
+    endian: Endian,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, const CHANNELS: usize> Clone for Samples<'_, T, CHANNELS> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T, const CHANNELS: usize> Copy for Samples<'_, T, CHANNELS> {}
+
+impl<'data, T: Abi + Decode<'data>, const CHANNELS: usize> Samples<'data, T, CHANNELS> {
+    /// Number of bytes in a frame.
+    pub const FRAME_SIZE: usize = CHANNELS * size_of::<T>();
+
+    /// Compile-time validation of the number of channels.
+    const VALID_CHANNELS: () = assert!(CHANNELS > 0, "Samples must have at least one channel");
+
+    /// Creates a view over the frames in `bytes`, whose samples are decoded with
+    /// the byte order `E`.
+    pub fn new<E: Endianness>(bytes: Bytes<'data>) -> Samples<'data, T, CHANNELS> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_CHANNELS;
+
+        let frames = bytes
+            .len()
+            .checked_div(Self::FRAME_SIZE)
+            .unwrap_or_default();
+        let region = &bytes.into_slice()[..frames * Self::FRAME_SIZE];
+        Samples {
+            bytes: Bytes::new_allow_empty(region),
+            frames,
+            endian: E::ENDIAN,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of channels in a frame.
+    #[inline]
+    pub const fn channels(&self) -> usize {
+        CHANNELS
+    }
+
+    /// Returns the number of complete frames.
+    #[inline]
+    pub const fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Returns the byte order the samples are decoded with.
+    #[inline]
+    pub const fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Returns the bytes of all complete frames.
+    #[inline]
+    pub const fn as_bytes(&self) -> Bytes<'data> {
+        self.bytes
+    }
+
+    /// Decodes the sample of `channel` within the frame `frame`, returning `None`
+    /// if either is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes of the sample do not hold a valid `T`.
+    #[inline]
+    pub fn get(&self, frame: usize, channel: usize) -> Option<Result<T>> {
+        if frame >= self.frames || channel >= CHANNELS {
+            return None;
+        }
+        let bytes = self
+            .bytes
+            .into_slice()
+            .get((frame * CHANNELS + channel) * size_of::<T>()..)
+            .unwrap_or_default();
+        let decoded = match self.endian {
+            Endian::Little => T::decode::<LittleEndian>(bytes),
+            Endian::Big => T::decode::<BigEndian>(bytes),
+        };
+        Some(decoded.map(|(value, _)| value))
+    }
+
+    /// Returns the samples of the frame `frame`, one per channel, or `None` if it is
+    /// out of bounds.
+    #[inline]
+    pub fn frame(&self, frame: usize) -> Option<Counted<'data, T>> {
+        if frame >= self.frames {
+            return None;
+        }
+        let start = frame * Self::FRAME_SIZE;
+        let bytes = self
+            .bytes
+            .into_slice()
+            .get(start..start + Self::FRAME_SIZE)
+            .unwrap_or_default();
+        Some(Counted::from_parts(Bytes::new_allow_empty(bytes), CHANNELS, self.endian))
+    }
+
+    /// Returns an iterator decoding the samples of `channel` from every frame, or
+    /// `None` if `channel` is out of bounds.
+    #[inline]
+    pub const fn channel(&self, channel: usize) -> Option<ChannelIter<'data, T, CHANNELS>> {
+        if channel >= CHANNELS {
+            return None;
+        }
+        Some(ChannelIter { samples: *self, channel, frame: 0 })
+    }
+}
+
+impl<'data, T, const CHANNELS: usize> fmt::Debug for Samples<'data, T, CHANNELS>
+where
+    T: Abi + Decode<'data> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.frames).filter_map(|frame| self.frame(frame)))
+            .finish()
+    }
+}
+
+/// Iterator over the samples of one channel of [`Samples`], created by
+/// [`Samples::channel`].
+#[derive(Clone, Copy)]
+pub struct ChannelIter<'data, T, const CHANNELS: usize> {
+    samples: Samples<'data, T, CHANNELS>,
+    channel: usize,
+    frame: usize,
+}
+
+impl<'data, T: Abi + Decode<'data>, const CHANNELS: usize> Iterator
+    for ChannelIter<'data, T, CHANNELS>
+{
+    type Item = Result<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self
+            .samples
+            .get(self.frame, self.channel)?;
+        self.frame += 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.samples.frames() - self.frame;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'data, T: Abi + Decode<'data>, const CHANNELS: usize> ExactSizeIterator
+    for ChannelIter<'data, T, CHANNELS>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BE, LE};
+
+    #[test]
+    fn splits_interleaved_frames_by_channel() {
+        let bytes = [0, 1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0xff];
+        let samples = Samples::<u16, 3>::new::<BE>(Bytes::new(&bytes));
+        assert_eq!((samples.channels(), samples.frames()), (3, 2));
+        assert_eq!(samples.as_bytes().len(), 12);
+        assert_eq!(
+            samples
+                .get(1, 0)
+                .unwrap()
+                .unwrap(),
+            4
+        );
+        assert!(samples.get(2, 0).is_none());
+        assert!(samples.channel(3).is_none());
+
+        let middle = samples
+            .channel(1)
+            .unwrap()
+            .collect::<Result<alloc::vec::Vec<_>>>()
+            .unwrap();
+        assert_eq!(middle, [2, 5]);
+        let frame = samples
+            .frame(1)
+            .unwrap()
+            .iter()
+            .collect::<Result<alloc::vec::Vec<_>>>()
+            .unwrap();
+        assert_eq!(frame, [4, 5, 6]);
+
+        let mono = Samples::<i16, 1>::new::<LE>(Bytes::new(&bytes[..1]));
+        assert_eq!(mono.frames(), 0);
+    }
+
+    #[test]
+    fn converts_between_integer_and_float_samples() {
+        assert_eq!(i16_to_f32(i16::MIN), -1.0);
+        assert_eq!(i16_to_f32(0x4000), 0.5);
+        assert_eq!(f32_to_i16(-1.0), i16::MIN);
+        assert_eq!(f32_to_i16(1.0), i16::MAX);
+        assert_eq!(f32_to_i16(2.5), i16::MAX);
+        assert_eq!(f32_to_i16(f32::NAN), 0);
+        for sample in [i16::MIN, -1, 0, 1, 0x1234, i16::MAX] {
+            assert_eq!(f32_to_i16(i16_to_f32(sample)), sample);
+        }
+    }
+}
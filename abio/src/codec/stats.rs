@@ -0,0 +1,113 @@
+//! Module containing opt-in counters of the reads and bounds checks performed while
+//! decoding, enabled with the `stats` feature.
+//!
+//! The counters make it possible to verify that a decoder coalesces its reads, for
+//! example that a fixed-layout header is read with a single bounds check rather
+//! than one per field, and to spot regressions when a type's layout changes.
+//!
+//! Counters are kept per thread, so concurrent decodes on other threads do not
+//! affect the statistics returned by [`measure`].
+
+use std::cell::Cell;
+
+/// Statistics collected while running the closure passed to [`measure`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DecodeStats {
+    /// Number of reads from a source or primitive decoder.
+    pub reads: usize,
+    /// Total number of bytes returned by those reads.
+    pub bytes_read: usize,
+    /// Number of bounds checks performed before reading.
+    pub bounds_checks: usize,
+}
+
+impl DecodeStats {
+    /// Returns the average number of bytes returned per read, or `0` if nothing
+    /// was read.
+    ///
+    /// Higher values indicate that adjacent reads were coalesced.
+    #[inline]
+    #[must_use]
+    pub const fn bytes_per_read(&self) -> usize {
+        match self
+            .bytes_read
+            .checked_div(self.reads)
+        {
+            Some(avg) => avg,
+            None => 0,
+        }
+    }
+}
+
+std::thread_local! {
+    static STATS: Cell<DecodeStats> = const {
+        Cell::new(DecodeStats { reads: 0, bytes_read: 0, bounds_checks: 0 })
+    };
+}
+
+/// Runs `f`, returning its result along with the statistics of the reads it
+/// performed on the current thread.
+///
+/// Calls may be nested, in which case the statistics of the inner call are also
+/// included in those of the outer call.
+#[inline]
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, DecodeStats) {
+    let before = STATS.get();
+    let result = f();
+    let after = STATS.get();
+    let stats = DecodeStats {
+        reads: after.reads - before.reads,
+        bytes_read: after.bytes_read - before.bytes_read,
+        bounds_checks: after.bounds_checks - before.bounds_checks,
+    };
+    (result, stats)
+}
+
+/// Records a read of `len` bytes.
+#[inline]
+pub(crate) fn record_read(len: usize) {
+    STATS.with(|stats| {
+        let mut current = stats.get();
+        current.reads += 1;
+        current.bytes_read += len;
+        stats.set(current);
+    });
+}
+
+/// Records a bounds check.
+#[inline]
+pub(crate) fn record_bounds_check() {
+    STATS.with(|stats| {
+        let mut current = stats.get();
+        current.bounds_checks += 1;
+        stats.set(current);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Decode, Reader, LE};
+
+    #[test]
+    fn counts_reads_and_bounds_checks() {
+        let bytes: &[u8] = &[1, 0, 2, 0, 0, 0];
+        let ((a, b), stats) = measure(|| {
+            let (a, _) = u16::decode::<LE>(bytes).unwrap();
+            let (b, _) = u32::decode::<LE>(&bytes[2..]).unwrap();
+            (a, b)
+        });
+        assert_eq!((a, b), (1, 2));
+        assert_eq!(stats, DecodeStats { reads: 2, bytes_read: 6, bounds_checks: 2 });
+        assert_eq!(stats.bytes_per_read(), 3);
+
+        let (_, stats) = measure(|| {
+            let mut reader = Reader::new(bytes);
+            reader.read_bytes(6).unwrap();
+            assert!(reader.read_bytes(1).is_err());
+        });
+        assert_eq!(stats.reads, 1);
+        assert_eq!(stats.bounds_checks, 3);
+        assert_eq!(DecodeStats::default().bytes_per_read(), 0);
+    }
+}
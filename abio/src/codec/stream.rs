@@ -0,0 +1,307 @@
+//! Module containing [`DecodeStream`], an event-based pull parser walking the
+//! fields of an input as described by a [`Descriptor`].
+//!
+//! Consumers that transform binary data without needing typed values, such as
+//! format converters or redactors, can use the stream to visit every field of an
+//! input along with its raw bytes:
+//!
+//! ```
+//! use abio::codec::{decode_stream, StreamEvent};
+//!
+//! # #[derive(abio::Abi)]
+//! # #[repr(C)]
+//! # struct Header {
+//! #     magic: u32,
+//! #     len: u32,
+//! # }
+//! # fn main() -> abio::Result<()> {
+//! # let bytes = &[0; 8];
+//! for event in decode_stream::<Header>(bytes) {
+//!     match event? {
+//!         StreamEvent::Field { field, .. } => print!("{} = ", field.name),
+//!         StreamEvent::Bytes(bytes) => println!("{bytes:02x?}"),
+//!         _ => {}
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use core::iter::FusedIterator;
+
+use crate::layout::{Describe, Descriptor, FieldDescriptor};
+use crate::{Error, Result};
+
+/// Maximum number of descriptors a [`DecodeStream`] can be nested within at once.
+pub const MAX_DEPTH: usize = 16;
+
+/// An event produced by a [`DecodeStream`].
+///
+/// A described type produces a [`StructStart`][Self::StructStart] event, then a
+/// [`Field`][Self::Field] event for each of its fields, and finally an
+/// [`End`][Self::End] event. Each field event is directly followed by either the
+/// [`Bytes`][Self::Bytes] of the field, or by the events of its nested layout.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StreamEvent<'data> {
+    /// The start of a described type.
+    StructStart {
+        /// Layout of the type.
+        descriptor: &'static Descriptor,
+        /// Offset of the type, relative to the start of the stream.
+        offset: usize,
+    },
+    /// The start of a field of the innermost type.
+    Field {
+        /// Layout of the field.
+        field: &'static FieldDescriptor,
+        /// Offset of the field, relative to the start of the stream.
+        offset: usize,
+    },
+    /// The raw bytes of the preceding field.
+    Bytes(&'data [u8]),
+    /// The end of the innermost type.
+    End,
+}
+
+/// Type currently being walked by a [`DecodeStream`].
+#[derive(Clone, Copy, Debug)]
+struct Frame {
+    descriptor: &'static Descriptor,
+    /// Offset of the type, relative to the start of the stream.
+    base: usize,
+    /// Index of the next field to visit.
+    next: usize,
+}
+
+/// Event a [`DecodeStream`] will produce next.
+#[derive(Clone, Copy, Debug)]
+enum Pending {
+    Start(&'static Descriptor, usize),
+    Field(&'static FieldDescriptor, usize),
+    Next,
+    Done,
+}
+
+/// An event-based pull parser walking the fields of an input, created by
+/// [`decode_stream`] or [`DecodeStream::new`].
+///
+/// The stream yields [`StreamEvent`]s until the outermost type ends. If the input
+/// is too short, or if descriptors are nested more than [`MAX_DEPTH`] levels deep,
+/// the stream yields a single error and then ends.
+#[derive(Clone, Debug)]
+pub struct DecodeStream<'data> {
+    bytes: &'data [u8],
+    frames: [Option<Frame>; MAX_DEPTH],
+    depth: usize,
+    pending: Pending,
+}
+
+impl<'data> DecodeStream<'data> {
+    /// Creates a stream walking `bytes` according to `descriptor`.
+    #[inline]
+    pub const fn new(bytes: &'data [u8], descriptor: &'static Descriptor) -> Self {
+        Self {
+            bytes,
+            frames: [None; MAX_DEPTH],
+            depth: 0,
+            pending: Pending::Start(descriptor, 0),
+        }
+    }
+
+    /// Returns the number of types the stream is currently nested within.
+    #[inline]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn advance(&mut self) -> Result<Option<StreamEvent<'data>>> {
+        match self.pending {
+            Pending::Start(descriptor, offset) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(Error::verbose("descriptors are nested too deeply"));
+                }
+                self.region(offset, descriptor.size)?;
+                self.frames[self.depth] = Some(Frame { descriptor, base: offset, next: 0 });
+                self.depth += 1;
+                self.pending = Pending::Next;
+                Ok(Some(StreamEvent::StructStart { descriptor, offset }))
+            }
+            Pending::Field(field, offset) => match field.nested {
+                Some(descriptor) => {
+                    self.pending = Pending::Start(descriptor, offset);
+                    self.advance()
+                }
+                None => {
+                    let bytes = self.region(offset, field.size)?;
+                    self.pending = Pending::Next;
+                    Ok(Some(StreamEvent::Bytes(bytes)))
+                }
+            },
+            Pending::Next => {
+                let frame = self.frames[self.depth - 1]
+                    .as_mut()
+                    .expect("frames below `depth` are always set");
+                match frame
+                    .descriptor
+                    .fields
+                    .get(frame.next)
+                {
+                    Some(field) => {
+                        frame.next += 1;
+                        let offset = frame.base + field.offset;
+                        self.pending = Pending::Field(field, offset);
+                        Ok(Some(StreamEvent::Field { field, offset }))
+                    }
+                    None => {
+                        self.depth -= 1;
+                        self.frames[self.depth] = None;
+                        self.pending = if self.depth == 0 { Pending::Done } else { Pending::Next };
+                        Ok(Some(StreamEvent::End))
+                    }
+                }
+            }
+            Pending::Done => Ok(None),
+        }
+    }
+
+    /// Returns the `size` bytes of the input starting at `offset`.
+    fn region(&self, offset: usize, size: usize) -> Result<&'data [u8]> {
+        offset
+            .checked_add(size)
+            .and_then(|end| self.bytes.get(offset..end))
+            .ok_or_else(|| Error::out_of_bounds(offset.saturating_add(size), self.bytes.len()))
+    }
+}
+
+impl<'data> Iterator for DecodeStream<'data> {
+    type Item = Result<StreamEvent<'data>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.advance() {
+            Ok(event) => event.map(Ok),
+            Err(err) => {
+                self.pending = Pending::Done;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl FusedIterator for DecodeStream<'_> {}
+
+/// Creates a [`DecodeStream`] walking `bytes` according to the layout of `T`.
+#[inline]
+pub const fn decode_stream<T: Describe>(bytes: &[u8]) -> DecodeStream<'_> {
+    DecodeStream::new(bytes, const { &T::DESCRIPTOR })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    const POINT: Descriptor = Descriptor {
+        name: "Point",
+        size: 4,
+        align: 1,
+        repr: "C",
+        fields: &[
+            FieldDescriptor { name: "x", offset: 0, size: 2, nested: None, endian: None },
+            FieldDescriptor { name: "y", offset: 2, size: 2, nested: None, endian: None },
+        ],
+    };
+
+    const LINE: Descriptor = Descriptor {
+        name: "Line",
+        size: 9,
+        align: 1,
+        repr: "C",
+        fields: &[
+            FieldDescriptor {
+                name: "color",
+                offset: 0,
+                size: 1,
+                nested: None,
+                endian: None,
+            },
+            FieldDescriptor {
+                name: "start",
+                offset: 1,
+                size: 4,
+                nested: Some(&POINT),
+                endian: None,
+            },
+            FieldDescriptor {
+                name: "end",
+                offset: 5,
+                size: 4,
+                nested: Some(&POINT),
+                endian: None,
+            },
+        ],
+    };
+
+    struct Line;
+
+    impl Describe for Line {
+        const DESCRIPTOR: Descriptor = LINE;
+        const PADDING: &'static [crate::Span] = &[];
+    }
+
+    #[test]
+    fn walks_nested_descriptors() {
+        let bytes = [0xff, 1, 0, 2, 0, 3, 0, 4, 0];
+        let events = decode_stream::<Line>(&bytes)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(events.len(), 1 + 2 + (2 + 2 * 2 + 1) * 2 + 1);
+        assert!(
+            matches!(events[0], StreamEvent::StructStart { descriptor, offset: 0 } if descriptor.name == "Line")
+        );
+        assert_eq!(events[2], StreamEvent::Bytes(&[0xff]));
+        assert!(
+            matches!(events[3], StreamEvent::Field { field, offset: 1 } if field.name == "start")
+        );
+        assert!(matches!(events[4], StreamEvent::StructStart { offset: 1, .. }));
+
+        let fields = events
+            .iter()
+            .filter_map(|event| match event {
+                StreamEvent::Field { field, offset } => Some((field.name, *offset)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [("color", 0), ("start", 1), ("x", 1), ("y", 3), ("end", 5), ("x", 5), ("y", 7)]
+        );
+        assert_eq!(events.last(), Some(&StreamEvent::End));
+    }
+
+    #[test]
+    fn stops_after_an_error() {
+        let mut stream = decode_stream::<Line>(&[0; 8]);
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+
+        static DEEP: Descriptor = Descriptor {
+            name: "Deep",
+            size: 0,
+            align: 1,
+            repr: "C",
+            fields: &[FieldDescriptor {
+                name: "inner",
+                offset: 0,
+                size: 0,
+                nested: Some(&DEEP),
+                endian: None,
+            }],
+        };
+        let mut stream = DecodeStream::new(&[], &DEEP);
+        let err = stream.find_map(Result::err);
+        assert!(err.is_some());
+        assert_eq!(stream.depth(), MAX_DEPTH);
+    }
+}
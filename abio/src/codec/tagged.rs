@@ -0,0 +1,67 @@
+//! Module containing the [`DecodeTagged`] and [`EncodeTagged`] traits, for enums
+//! whose discriminant is stored apart from their payload.
+//!
+//! Many formats store the kind of a record in a header field, with the payload
+//! selected by it following later in the input. Enums deriving [`Decode`] and
+//! [`Encode`] implement these traits, so that a containing type can decode such a
+//! payload with `#[abio(tag_field = "kind")]`, where `kind` is an expression over
+//! previously decoded fields:
+//!
+//! ```
+//! #[derive(abio::Decode, abio::Encode)]
+//! #[repr(u8)]
+//! enum Body {
+//!     Ping = 1,
+//!     Data(u32) = 2,
+//! }
+//!
+//! #[derive(abio::Decode, abio::Encode)]
+//! struct Record {
+//!     kind: u8,
+//!     length: u16,
+//!     #[abio(tag_field = "kind")]
+//!     body: Body,
+//! }
+//! ```
+//!
+//! The tag is not written when encoding the payload, so the field it is read from
+//! must be kept in agreement with the variant of the enum.
+//!
+//! [`Decode`]: crate::Decode
+//! [`Encode`]: crate::Encode
+
+use crate::{Endianness, Result};
+
+/// Trait for types decoded from a tag known ahead of time, rather than from a tag
+/// preceding them in the input.
+pub trait DecodeTagged<'data>: Sized {
+    /// Type of the tag selecting the variant to decode.
+    type Tag;
+
+    /// Decodes the variant selected by `tag` from the start of `bytes`, returning
+    /// the decoded value and the number of bytes consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `tag` does not select any variant, or if the payload of
+    /// the variant cannot be decoded from `bytes`.
+    fn decode_tagged<E: Endianness>(bytes: &'data [u8], tag: Self::Tag) -> Result<(Self, usize)>;
+}
+
+/// Trait for types encoded without the tag selecting their variant, which is
+/// stored elsewhere.
+pub trait EncodeTagged {
+    /// Type of the tag selecting the variant of this value.
+    type Tag;
+
+    /// Returns the tag selecting the variant of this value.
+    fn tag(&self) -> Self::Tag;
+
+    /// Encodes the payload of this value into the start of `buf` using the byte
+    /// order `E`, without its tag, returning the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too small to hold the encoded payload.
+    fn encode_untagged<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize>;
+}
@@ -0,0 +1,147 @@
+//! Textual encoders for rendering the wire representation of values.
+//!
+//! The routines in this module write the raw bytes of any [`AsBytes`] value into a
+//! [`fmt::Write`] sink as human-readable text. They are primarily intended for
+//! debugging, generating test fixtures, and embedding binary blobs directly in
+//! source code.
+//!
+//! # Byte Order
+//!
+//! The bytes are rendered exactly as they exist in memory. No endianness
+//! conversions are performed, so the output reflects the wire representation of
+//! the value on the current target.
+
+use core::fmt;
+
+use crate::AsBytes;
+
+/// Number of bytes rendered on each line of the array-style encoders.
+const BYTES_PER_LINE: usize = 12;
+
+/// Encodes the bytes of `value` as a contiguous string of lowercase hexadecimal
+/// digits, writing the result into `out`.
+///
+/// Each byte is rendered using exactly two digits, so the output always contains
+/// `2 * size_of_val(value)` characters.
+///
+/// # Errors
+///
+/// Returns an error if writing into the `out` sink fails.
+pub fn encode_as_hex_str<T, W>(value: &T, out: &mut W) -> fmt::Result
+where
+    T: AsBytes + ?Sized,
+    W: fmt::Write,
+{
+    value
+        .as_bytes()
+        .iter()
+        .try_for_each(|byte| write!(out, "{byte:02x}"))
+}
+
+/// Encodes the bytes of `value` as a Rust constant declaration named `name`,
+/// writing the result into `out`.
+///
+/// The generated source has the form `const NAME: [u8; N] = [ .. ];`, with the
+/// bytes rendered as hexadecimal literals.
+///
+/// # Errors
+///
+/// Returns an error if writing into the `out` sink fails.
+pub fn encode_as_rust_array<T, W>(value: &T, name: &str, out: &mut W) -> fmt::Result
+where
+    T: AsBytes + ?Sized,
+    W: fmt::Write,
+{
+    let bytes = value.as_bytes();
+    writeln!(out, "const {name}: [u8; {}] = [", bytes.len())?;
+    write_array_body(bytes, out)?;
+    writeln!(out, "];")
+}
+
+/// Encodes the bytes of `value` as a C array declaration named `name`, writing the
+/// result into `out`.
+///
+/// The generated source has the form `static const unsigned char name[N] = { .. };`,
+/// with the bytes rendered as hexadecimal literals.
+///
+/// # Errors
+///
+/// Returns an error if writing into the `out` sink fails.
+pub fn encode_as_c_array<T, W>(value: &T, name: &str, out: &mut W) -> fmt::Result
+where
+    T: AsBytes + ?Sized,
+    W: fmt::Write,
+{
+    let bytes = value.as_bytes();
+    writeln!(out, "static const unsigned char {name}[{}] = {{", bytes.len())?;
+    write_array_body(bytes, out)?;
+    writeln!(out, "}};")
+}
+
+/// Writes the comma-separated, indented body shared by the array encoders.
+fn write_array_body<W: fmt::Write>(bytes: &[u8], out: &mut W) -> fmt::Result {
+    for line in bytes.chunks(BYTES_PER_LINE) {
+        out.write_str("    ")?;
+        for (idx, byte) in line.iter().enumerate() {
+            if idx > 0 {
+                out.write_char(' ')?;
+            }
+            write!(out, "{byte:#04x},")?;
+        }
+        out.write_char('\n')?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fixed-capacity sink used to capture the output without an allocator.
+    struct StackWriter {
+        buf: [u8; 256],
+        len: usize,
+    }
+
+    impl StackWriter {
+        fn new() -> Self {
+            Self { buf: [0u8; 256], len: 0 }
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.buf[..self.len]).unwrap()
+        }
+    }
+
+    impl fmt::Write for StackWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            if end > self.buf.len() {
+                return Err(fmt::Error);
+            }
+            self.buf[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn text_encoders_render_wire_bytes() {
+        let value = [0x4du8, 0x5a, 0x90, 0x00];
+
+        let mut out = StackWriter::new();
+        encode_as_hex_str(&value, &mut out).unwrap();
+        assert_eq!(out.as_str(), "4d5a9000");
+
+        let mut out = StackWriter::new();
+        encode_as_rust_array(&value, "DOS_MAGIC", &mut out).unwrap();
+        assert_eq!(out.as_str(), "const DOS_MAGIC: [u8; 4] = [\n    0x4d, 0x5a, 0x90, 0x00,\n];\n");
+
+        let mut out = StackWriter::new();
+        encode_as_c_array(&value, "dos_magic", &mut out).unwrap();
+        assert_eq!(
+            out.as_str(),
+            "static const unsigned char dos_magic[4] = {\n    0x4d, 0x5a, 0x90, 0x00,\n};\n"
+        );
+    }
+}
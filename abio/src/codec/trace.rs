@@ -0,0 +1,333 @@
+//! Module containing the types used to record decode traces.
+//!
+//! A decode trace is a sequence of [`TraceEvent`]s, one per decoded field, that
+//! explains how a value was assembled from its input. Every type deriving
+//! [`Decode`][crate::Decode] records events when decoded through
+//! [`Decode::decode_traced`][crate::Decode::decode_traced]. The decoded value of
+//! each field is only recorded for types annotated with `#[abio(trace)]`, since
+//! recording it requires the type of every field to implement `Debug`.
+//!
+//! An [`Observer`] is additionally told when each field starts decoding, and why
+//! a decode failed, which is enough to build annotated hex views, report progress
+//! or log failures. Observers are passed to [`Codec::decode_observed`] rather than
+//! stored in the [`Codec`]: codecs are `Copy` and shared between decodes, while an
+//! observer is borrowed mutably for the duration of a single decode.
+//!
+//! [`Codec::decode_observed`]: crate::config::Codec::decode_observed
+
+use core::fmt;
+
+use crate::config::Codec;
+use crate::{Error, Result};
+
+/// A single field recorded while decoding a value.
+#[derive(Clone, Copy)]
+pub struct TraceEvent<'a> {
+    /// Name of the type containing the field.
+    pub type_name: &'static str,
+    /// Name of the field, or its index for tuple structs.
+    pub field: &'static str,
+    /// Offset of the first byte of the field, relative to the start of the input
+    /// passed to the outermost call to [`decode_traced`][crate::Decode::decode_traced].
+    pub offset: usize,
+    /// Raw bytes the field was decoded from.
+    pub bytes: &'a [u8],
+    /// The decoded value of the field, if the type containing it is annotated with
+    /// `#[abio(trace)]`.
+    pub value: Option<&'a dyn fmt::Debug>,
+}
+
+impl fmt::Debug for TraceEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceEvent")
+            .field("type_name", &self.type_name)
+            .field("field", &self.field)
+            .field("offset", &self.offset)
+            .field("bytes", &self.bytes)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl fmt::Display for TraceEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#010x} {}.{}", self.offset, self.type_name, self.field)?;
+        if let Some(value) = self.value {
+            write!(f, " = {value:?}")?;
+        }
+        f.write_str(" [")?;
+        for (idx, byte) in self.bytes.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        f.write_str("]")
+    }
+}
+
+/// A user-supplied destination for [`TraceEvent`]s.
+///
+/// Events are recorded in the order their fields finish decoding, so the fields of
+/// a nested value are recorded before the field that contains it.
+///
+/// This trait is implemented for closures accepting a `&TraceEvent`, so a sink can
+/// be created inline without declaring a new type.
+pub trait TraceSink {
+    /// Records a single event.
+    fn record(&mut self, event: &TraceEvent<'_>);
+}
+
+impl<F> TraceSink for F
+where
+    F: FnMut(&TraceEvent<'_>),
+{
+    #[inline]
+    fn record(&mut self, event: &TraceEvent<'_>) {
+        self(event)
+    }
+}
+
+/// A user-supplied observer of the progress of a decode.
+///
+/// Every method does nothing by default, so implementations only override the
+/// callbacks they need. Fields are reported by every type deriving
+/// [`Decode`][crate::Decode], and the field a decode failed in is the last one
+/// started without having ended.
+pub trait Observer {
+    /// Called before the field `field` of the type `type_name` is decoded, starting
+    /// `offset` bytes into the input.
+    #[inline]
+    fn on_field_start(&mut self, type_name: &'static str, field: &'static str, offset: usize) {
+        let _ = (type_name, field, offset);
+    }
+
+    /// Called once a field was decoded successfully, with the same event a
+    /// [`TraceSink`] would record.
+    #[inline]
+    fn on_field_end(&mut self, event: &TraceEvent<'_>) {
+        let _ = event;
+    }
+
+    /// Called once with the error a decode failed with.
+    #[inline]
+    fn on_error(&mut self, error: &Error) {
+        let _ = error;
+    }
+}
+
+/// Destination of the events of a [`Tracer`].
+enum Sink<'s> {
+    Trace(&'s mut dyn TraceSink),
+    Observe(&'s mut dyn Observer),
+}
+
+impl Sink<'_> {
+    #[inline]
+    fn reborrow(&mut self) -> Sink<'_> {
+        match self {
+            Sink::Trace(sink) => Sink::Trace(&mut **sink),
+            Sink::Observe(observer) => Sink::Observe(&mut **observer),
+        }
+    }
+}
+
+/// Handle passed through a traced decode, tracking the absolute offset of the value
+/// currently being decoded.
+///
+/// A tracer may also carry the [`Codec`] the value is decoded with, in which case
+/// the traced decode enforces the limit and options of the codec exactly like
+/// [`Codec::decode`] does.
+pub struct Tracer<'s> {
+    sink: Sink<'s>,
+    base: usize,
+    codec: Option<Codec>,
+}
+
+impl<'s> Tracer<'s> {
+    /// Creates a new [`Tracer`] that records events into `sink`.
+    #[inline]
+    pub fn new(sink: &'s mut dyn TraceSink) -> Tracer<'s> {
+        Tracer { sink: Sink::Trace(sink), base: 0, codec: None }
+    }
+
+    /// Creates a new [`Tracer`] that reports the start and end of every field to
+    /// `observer`.
+    #[inline]
+    pub fn observed(observer: &'s mut dyn Observer) -> Tracer<'s> {
+        Tracer { sink: Sink::Observe(observer), base: 0, codec: None }
+    }
+
+    /// Returns this tracer, decoding values with the limit and options of `codec`.
+    #[inline]
+    #[must_use]
+    pub fn with_codec(mut self, codec: Codec) -> Tracer<'s> {
+        self.codec = Some(codec);
+        self
+    }
+
+    /// Returns the codec values are decoded with, if any.
+    #[inline]
+    pub const fn codec(&self) -> Option<&Codec> {
+        self.codec.as_ref()
+    }
+
+    /// Returns the absolute offset of the value currently being decoded.
+    #[inline]
+    pub const fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Returns a child tracer for a value beginning `offset` bytes into the value
+    /// currently being decoded.
+    #[inline]
+    pub fn nested(&mut self, offset: usize) -> Tracer<'_> {
+        Tracer {
+            sink: self.sink.reborrow(),
+            base: self.base + offset,
+            codec: self.codec,
+        }
+    }
+
+    /// Returns a tracer for the fields of the value currently being decoded, one
+    /// level deeper into the [`Limit`] of the codec.
+    ///
+    /// Implementations of [`Decode::decode_traced`] call this method before
+    /// decoding a value with fields, like [`Codec::enter`] for
+    /// [`Decode::decode_configured`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the limit of the codec is already at its maximum depth.
+    ///
+    /// [`Limit`]: crate::config::Limit
+    /// [`Decode::decode_traced`]: crate::Decode::decode_traced
+    /// [`Decode::decode_configured`]: crate::Decode::decode_configured
+    #[inline]
+    pub fn enter(&mut self) -> Result<Tracer<'_>> {
+        let codec = match &self.codec {
+            Some(codec) => Some(codec.enter()?),
+            None => None,
+        };
+        Ok(Tracer { sink: self.sink.reborrow(), base: self.base, codec })
+    }
+
+    /// Returns an error if the [`CancelToken`][crate::codec::CancelToken] of the
+    /// codec, if any, has been cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for which [`Error::is_cancelled`] returns `true` if the
+    /// token of the codec has been cancelled.
+    #[inline]
+    pub fn check_cancelled(&self) -> Result<()> {
+        match &self.codec {
+            Some(codec) => codec.check_cancelled(),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that a length-prefixed field may hold `count` elements under the
+    /// [`Limit`][crate::config::Limit] of the codec, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `count` is larger than the maximum number of elements.
+    #[inline]
+    pub fn check_elements(&self, count: usize) -> Result<()> {
+        match &self.codec {
+            Some(codec) => codec
+                .limit()
+                .check_elements(count),
+            None => Ok(()),
+        }
+    }
+
+    /// Reports that a field beginning `offset` bytes into the value currently being
+    /// decoded is about to be decoded.
+    #[inline]
+    pub fn start(&mut self, type_name: &'static str, field: &'static str, offset: usize) {
+        if let Sink::Observe(observer) = &mut self.sink {
+            observer.on_field_start(type_name, field, self.base + offset);
+        }
+    }
+
+    /// Records a field beginning `offset` bytes into the value currently being
+    /// decoded.
+    #[inline]
+    pub fn record(
+        &mut self,
+        type_name: &'static str,
+        field: &'static str,
+        offset: usize,
+        bytes: &[u8],
+        value: Option<&dyn fmt::Debug>,
+    ) {
+        let event = TraceEvent { type_name, field, offset: self.base + offset, bytes, value };
+        match &mut self.sink {
+            Sink::Trace(sink) => sink.record(&event),
+            Sink::Observe(observer) => observer.on_field_end(&event),
+        }
+    }
+}
+
+impl fmt::Debug for Tracer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracer")
+            .field("base", &self.base)
+            .field("codec", &self.codec)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_tracers_report_absolute_offsets() {
+        let mut offsets = [0usize; 2];
+        let mut count = 0;
+        let mut sink = |event: &TraceEvent<'_>| {
+            offsets[count] = event.offset;
+            count += 1;
+        };
+
+        let mut tracer = Tracer::new(&mut sink);
+        tracer.record("Outer", "magic", 0, &[0x4d, 0x5a], Some(&0x5a4du16));
+        tracer
+            .nested(8)
+            .record("Inner", "len", 4, &[1, 0, 0, 0], None);
+        drop(tracer);
+
+        assert_eq!(count, 2);
+        assert_eq!(offsets, [0, 12]);
+    }
+
+    #[test]
+    fn observers_see_the_start_and_end_of_fields() {
+        #[derive(Default)]
+        struct Offsets {
+            started: usize,
+            ended: usize,
+        }
+
+        impl Observer for Offsets {
+            fn on_field_start(&mut self, _: &'static str, _: &'static str, offset: usize) {
+                self.started += offset;
+            }
+
+            fn on_field_end(&mut self, event: &TraceEvent<'_>) {
+                self.ended += event.offset;
+            }
+        }
+
+        let mut observer = Offsets::default();
+        let mut tracer = Tracer::observed(&mut observer);
+        let mut nested = tracer.nested(4);
+        nested.start("Inner", "len", 2);
+        nested.record("Inner", "len", 2, &[1], Some(&1u8));
+        tracer.start("Outer", "tail", 8);
+        assert_eq!((observer.started, observer.ended), (14, 6));
+    }
+}
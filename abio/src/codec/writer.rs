@@ -0,0 +1,219 @@
+//! Module containing the [`Writer`] type, a position-tracking cursor for writing
+//! data sequentially into a fixed buffer.
+
+use crate::source::traits::checked_end;
+use crate::{AsBytes, Chunk, Encode, Endianness, Error, Result};
+
+/// A cursor writing into a fixed buffer, the counterpart of a
+/// [`Reader`][crate::Reader].
+///
+/// The writer tracks the current position within the buffer, so sequential
+/// encoding does not require manually threading offsets through every call. Each
+/// write gives its own byte order, so fields of mixed byte order can be written
+/// in turn. Formats using a single byte order throughout can use a
+/// [`BufEncoder`][crate::codec::BufEncoder] instead, which also provides `put_*`
+/// methods for every integer type.
+///
+/// Writes that do not fit in the rest of the buffer fail with an out-of-bounds
+/// error giving the number of bytes needed and available, instead of panicking.
+/// Like the errors of a reader, they record the [offset][Error::offset] of the
+/// failing write, and leave the cursor unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use abio::{Writer, BE, LE};
+///
+/// let mut buf = [0u8; 8];
+/// let mut writer = Writer::new(&mut buf);
+/// writer.write_bytes(b"ID").unwrap();
+/// writer.write::<u16, BE>(&0x0102).unwrap();
+/// writer.write::<u16, LE>(&0x0304).unwrap();
+/// assert_eq!(writer.remaining(), 2);
+///
+/// let err = writer.write::<u32, BE>(&0).unwrap_err();
+/// assert_eq!(err.offset(), Some(6));
+/// assert_eq!(writer.written(), b"ID\x01\x02\x04\x03");
+/// ```
+#[derive(Debug)]
+pub struct Writer<'buf> {
+    /// Buffer the writer is writing into.
+    buf: &'buf mut [u8],
+    /// Offset of the cursor within `buf`.
+    pos: usize,
+}
+
+impl<'buf> Writer<'buf> {
+    /// Creates a new [`Writer`] writing into `buf`, positioned at its first byte.
+    #[inline]
+    pub const fn new(buf: &'buf mut [u8]) -> Writer<'buf> {
+        Writer { buf, pos: 0 }
+    }
+
+    /// Returns the position of the cursor, which is the number of bytes written.
+    #[inline]
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the total number of bytes of the buffer.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if the buffer does not contain any bytes.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the number of bytes left in the buffer after the cursor.
+    #[inline]
+    pub const fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Returns the bytes written so far.
+    #[inline]
+    pub fn written(&self) -> &[u8] {
+        &self.buf[..self.pos]
+    }
+
+    /// Consumes the writer, returning the bytes written.
+    #[inline]
+    pub fn into_written(self) -> &'buf mut [u8] {
+        &mut self.buf[..self.pos]
+    }
+
+    /// Records the position of the cursor in `err`.
+    #[inline]
+    const fn locate(&self, err: Error) -> Error {
+        err.at_offset(self.pos)
+    }
+
+    /// Encodes `value` in the byte order `E`, advancing the cursor past it and
+    /// returning the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Encode::encode`]. The cursor is not advanced on
+    /// error, although bytes after it may have been overwritten.
+    #[inline]
+    pub fn write<T: Encode + ?Sized, E: Endianness>(&mut self, value: &T) -> Result<usize> {
+        let len = value
+            .encode::<E>(&mut self.buf[self.pos..])
+            .map_err(|err| self.locate(err))?;
+        self.pos += len;
+        Ok(len)
+    }
+
+    /// Copies `bytes` as-is, advancing the cursor past them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `bytes.len()` bytes remain. The cursor is not
+    /// advanced on error.
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        let end = checked_end(self.pos, bytes.len(), self.buf.len())
+            .map_err(|_| self.locate(Error::out_of_bounds(bytes.len(), self.remaining())))?;
+        self.buf[self.pos..end].copy_from_slice(bytes);
+        self.pos = end;
+        Ok(())
+    }
+
+    /// Copies the bytes of `chunk` as-is, advancing the cursor past them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `N` bytes remain. The cursor is not advanced on
+    /// error.
+    #[inline]
+    pub fn write_chunk<const N: usize>(&mut self, chunk: Chunk<N>) -> Result<()> {
+        self.write_bytes(chunk.as_slice())
+    }
+
+    /// Copies the bytes of `value` as they are laid out in memory, advancing the
+    /// cursor past them.
+    ///
+    /// This is the counterpart of [`read_struct`][crate::SourceExt::read_struct].
+    /// No byte order conversion takes place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `size_of_val(value)` bytes remain. The cursor
+    /// is not advanced on error.
+    #[inline]
+    pub fn write_struct<T: AsBytes + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.write_bytes(value.as_bytes())
+    }
+
+    /// Advances the cursor past the next `len` bytes, leaving them as they are.
+    ///
+    /// This reserves space for fields written later, such as a length or checksum
+    /// only known once the rest of the message has been written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than `len` bytes remain. The cursor is not advanced
+    /// on error.
+    #[inline]
+    pub fn skip(&mut self, len: usize) -> Result<()> {
+        self.pos = checked_end(self.pos, len, self.buf.len())
+            .map_err(|_| self.locate(Error::out_of_bounds(len, self.remaining())))?;
+        Ok(())
+    }
+}
+
+/// Writing advances the cursor of the [`Writer`], and writes as many bytes as fit
+/// in the rest of its buffer.
+#[cfg(feature = "std")]
+impl std::io::Write for Writer<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.remaining());
+        self.write_bytes(&buf[..len])?;
+        Ok(len)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BE, LE};
+
+    #[test]
+    fn writes_sequentially_and_reports_overflow() {
+        let mut buf = [0u8; 10];
+        let mut writer = Writer::new(&mut buf);
+        writer.skip(2).unwrap();
+        assert_eq!(writer.write::<u16, BE>(&0x0102), Ok(2));
+        assert_eq!(writer.write::<i16, LE>(&-2), Ok(2));
+        writer
+            .write_struct(&[0xaau8, 0xbb])
+            .unwrap();
+        writer
+            .write_chunk(Chunk::from_ne_bytes([0xcc]))
+            .unwrap();
+        assert_eq!(writer.position(), 9);
+
+        let err = writer.write_bytes(&[0; 2]).unwrap_err();
+        assert_eq!(err, Error::out_of_bounds(2, 1).at_offset(9));
+        assert_eq!(
+            writer
+                .write::<u32, LE>(&0)
+                .unwrap_err()
+                .offset(),
+            Some(9)
+        );
+        assert!(writer.skip(2).is_err());
+        assert_eq!(writer.remaining(), 1);
+        assert_eq!(writer.into_written(), [0, 0, 1, 2, 0xfe, 0xff, 0xaa, 0xbb, 0xcc]);
+    }
+}
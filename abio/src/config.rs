@@ -0,0 +1,747 @@
+//! Runtime configuration for decoding and encoding.
+//!
+//! Decoding and encoding normally select the byte order at compile time, through
+//! the `E` type parameter of [`Decode::decode`] and [`Encode::encode`]. A [`Codec`]
+//! instead carries the byte order, along with the other options of a format, as a
+//! runtime value. Build one when the options are only known at runtime, such as
+//! after reading a header, and use it to decode and encode any number of types:
+//!
+//! ```
+//! use abio::config::{Codec, Limit};
+//!
+//! let codec = Codec::builder()
+//!     .little_endian()
+//!     .limit(Limit::bytes(1 << 20))
+//!     .build();
+//!
+//! let (magic, len) = codec.decode::<u32>(&[0x7f, b'E', b'L', b'F']).unwrap();
+//! assert_eq!((magic, len), (0x464c_457f, 4));
+//! ```
+//!
+//! # Trailing bytes
+//!
+//! Bytes left after a decoded value are ignored by default. [`Trailing`] selects
+//! whether they are rejected instead, as strict protocol validation requires, or
+//! returned to the caller by [`Codec::decode_with_remainder`], as best-effort
+//! parsers of concatenated or damaged inputs require.
+//!
+//! # Validation
+//!
+//! A [`Validator`] registered with [`Codec::with_validator`] checks every value
+//! decoded through the codec, layering policies such as maximum sizes or allowed
+//! versions on top of existing decoders.
+//!
+//! # Cancellation
+//!
+//! A [`CancelToken`] registered with [`CodecBuilder::cancel_token`] is checked
+//! before each field of the values decoded through the codec, so decodes of
+//! untrusted input can be stopped from another thread, such as a watchdog
+//! enforcing a deadline.
+
+use core::hash::{Hash, Hasher};
+use core::{fmt, ptr};
+
+use crate::codec::{CancelToken, Observer, TraceSink, Tracer};
+use crate::{Bytes, Decode, Encode, Endian, Error, Result, BE, LE};
+
+mod validator;
+pub use validator::{ValidatingCodec, Validator};
+
+/// Resource limits a [`Codec`] enforces on a single value.
+///
+/// Limits protect against hostile inputs announcing huge lengths or deeply nested
+/// values, by failing the decode as soon as a limit is reached instead of consuming
+/// the rest of the input:
+///
+/// - the maximum number of bytes bounds the input read for, and the output written
+///   by, a single value,
+/// - the maximum number of elements bounds the count of each length-prefixed
+///   field, declared with `#[abio(len = "..")]`, and
+/// - the maximum depth bounds how deeply derived types are nested within each
+///   other, counting the decoded value itself as the first level.
+///
+/// Element counts and nesting are checked by [`Decode::decode_configured`], which
+/// [`Codec::decode`] uses and derived implementations forward to every field, and
+/// by [`Decode::decode_traced`] for [`Codec::decode_observed`].
+/// Errors caused by any limit are reported by [`Error::is_limit_exceeded`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Limit {
+    max_bytes: Option<usize>,
+    max_elements: Option<usize>,
+    max_depth: Option<usize>,
+    /// Number of levels entered so far with [`Limit::enter`].
+    depth: usize,
+}
+
+impl Limit {
+    /// No limits, allowing values to span the whole input or output, with any
+    /// number of elements and levels of nesting.
+    pub const NONE: Limit = Limit {
+        max_bytes: None,
+        max_elements: None,
+        max_depth: None,
+        depth: 0,
+    };
+
+    /// Creates a limit of `max_bytes` bytes.
+    #[inline]
+    #[must_use]
+    pub const fn bytes(max_bytes: usize) -> Limit {
+        Limit { max_bytes: Some(max_bytes), ..Limit::NONE }
+    }
+
+    /// Returns this limit, additionally allowing at most `max_elements` elements in
+    /// each length-prefixed field.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_elements(mut self, max_elements: usize) -> Limit {
+        self.max_elements = Some(max_elements);
+        self
+    }
+
+    /// Returns this limit, additionally allowing values to be nested at most
+    /// `max_depth` levels deep.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Limit {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Returns the maximum number of bytes, or `None` if there is no limit.
+    #[inline]
+    #[must_use]
+    pub const fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// Returns the maximum number of elements in a length-prefixed field, or `None`
+    /// if there is no limit.
+    #[inline]
+    #[must_use]
+    pub const fn max_elements(&self) -> Option<usize> {
+        self.max_elements
+    }
+
+    /// Returns the maximum depth of nested values, or `None` if there is no limit.
+    #[inline]
+    #[must_use]
+    pub const fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Checks that a length-prefixed field may hold `count` elements.
+    ///
+    /// Implementations of [`Decode::decode_configured`] call this method with the
+    /// count of each length-prefixed field, before decoding its elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `count` is larger than the maximum number of elements.
+    #[inline]
+    pub const fn check_elements(&self, count: usize) -> Result<()> {
+        match self.max_elements {
+            Some(limit) if count > limit => Err(Error::element_limit_exceeded(count, limit)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the limit to decode the fields of a value with, one level deeper than
+    /// this one.
+    ///
+    /// [`Codec::enter`] calls this method before a value with fields is decoded,
+    /// and the returned limit applies to each field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this limit is already at the maximum depth.
+    #[inline]
+    pub const fn enter(&self) -> Result<Limit> {
+        match self.max_depth {
+            Some(limit) if self.depth >= limit => Err(Error::depth_limit_exceeded(limit)),
+            _ => Ok(Limit { depth: self.depth + 1, ..*self }),
+        }
+    }
+
+    /// Returns the number of bytes out of `len` that may be used.
+    const fn clamp(&self, len: usize) -> usize {
+        match self.max_bytes {
+            Some(max_bytes) if max_bytes < len => max_bytes,
+            _ => len,
+        }
+    }
+}
+
+/// Policy for the bytes left in the input after a value was decoded by a [`Codec`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Trailing {
+    /// Trailing bytes are silently ignored.
+    #[default]
+    Ignore,
+    /// Trailing bytes fail the decode.
+    Reject,
+    /// Trailing bytes are returned to the caller by
+    /// [`Codec::decode_with_remainder`].
+    Return,
+}
+
+/// Byte order, strictness and limits used to decode and encode values, chosen at
+/// runtime.
+///
+/// Use [`Codec::builder`] to create a codec with options other than the defaults,
+/// which are the native byte order, no strictness, strict booleans, no [`Limit`]
+/// and no [`CancelToken`].
+/// The presets [`Codec::network`], [`Codec::native`] and [`Codec::le_packed`] cover
+/// the most common formats.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Codec {
+    endian: Endian,
+    trailing: Trailing,
+    lenient_bools: bool,
+    limit: Limit,
+    cancel: Option<Cancel>,
+}
+
+impl Codec {
+    /// Creates a codec with the default options.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Codec {
+        Codec {
+            endian: Endian::NATIVE,
+            trailing: Trailing::Ignore,
+            lenient_bools: false,
+            limit: Limit::NONE,
+            cancel: None,
+        }
+    }
+
+    /// Creates a codec for network protocols, decoding and encoding values in big
+    /// endian byte order and rejecting inputs with bytes left after the value.
+    #[inline]
+    #[must_use]
+    pub const fn network() -> Codec {
+        Codec::builder()
+            .big_endian()
+            .strict(true)
+            .build()
+    }
+
+    /// Creates a codec for data produced by the current machine, decoding and
+    /// encoding values in its native byte order.
+    ///
+    /// This is the same as [`Codec::new`], spelled out for call sites that depend on
+    /// the byte order of the host.
+    #[inline]
+    #[must_use]
+    pub const fn native() -> Codec {
+        Codec::builder()
+            .endian(Endian::NATIVE)
+            .build()
+    }
+
+    /// Creates a codec for packed little endian formats, such as most file formats
+    /// and the packed structs of C compilers.
+    ///
+    /// Values are never padded implicitly, so fields are read back to back unless
+    /// their type asks for padding or alignment, such as through the `pad_before`
+    /// and `align_to` attributes of the derive macros.
+    #[inline]
+    #[must_use]
+    pub const fn le_packed() -> Codec {
+        Codec::builder()
+            .little_endian()
+            .build()
+    }
+
+    /// Returns a [`CodecBuilder`] starting from the default options.
+    #[inline]
+    #[must_use]
+    pub const fn builder() -> CodecBuilder {
+        CodecBuilder { codec: Codec::new() }
+    }
+
+    /// Returns the byte order values are decoded and encoded with.
+    #[inline]
+    #[must_use]
+    pub const fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Returns `true` if decoding rejects inputs with bytes left after the value.
+    #[inline]
+    #[must_use]
+    pub const fn is_strict(&self) -> bool {
+        matches!(self.trailing, Trailing::Reject)
+    }
+
+    /// Returns the policy for bytes left after a decoded value.
+    #[inline]
+    #[must_use]
+    pub const fn trailing(&self) -> Trailing {
+        self.trailing
+    }
+
+    /// Returns `true` if boolean wire types, such as [`Bool8`], decode any non-zero
+    /// value as `true` instead of rejecting values other than `0` and `1`.
+    ///
+    /// [`Bool8`]: crate::codec::Bool8
+    #[inline]
+    #[must_use]
+    pub const fn lenient_bools(&self) -> bool {
+        self.lenient_bools
+    }
+
+    /// Returns the maximum number of bytes read or written for a single value.
+    #[inline]
+    #[must_use]
+    pub const fn limit(&self) -> Limit {
+        self.limit
+    }
+
+    /// Returns the token cancelling the decodes of this codec, if any.
+    #[inline]
+    #[must_use]
+    pub const fn cancel_token(&self) -> Option<&'static CancelToken> {
+        match self.cancel {
+            Some(Cancel(token)) => Some(token),
+            None => None,
+        }
+    }
+
+    /// Returns an error if the [`CancelToken`] of this codec has been cancelled.
+    ///
+    /// Implementations of [`Decode::decode_configured`] call this method before
+    /// decoding each field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for which [`Error::is_cancelled`] returns `true` if the
+    /// token of this codec has been cancelled.
+    #[inline]
+    pub fn check_cancelled(&self) -> Result<()> {
+        match self.cancel {
+            Some(Cancel(token)) => token.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the codec to decode the fields of a value with, one level deeper
+    /// into the [`Limit`] of this codec.
+    ///
+    /// Implementations of [`Decode::decode_configured`] call this method before
+    /// decoding a value with fields, and pass the returned codec to each field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the limit of this codec is already at its maximum depth.
+    #[inline]
+    pub const fn enter(&self) -> Result<Codec> {
+        match self.limit.enter() {
+            Ok(limit) => Ok(Codec { limit, ..*self }),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Decodes a `T` from the start of `bytes`, returning the decoded value and the
+    /// number of bytes consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Decode::decode`], and an error if:
+    ///
+    /// - the value extends past the maximum number of bytes of the [`Limit`] of this
+    ///   codec, or declares more elements or nests deeper than the limit allows, or
+    /// - this codec is strict and bytes are left after the value.
+    pub fn decode<'data, T: Decode<'data>>(&self, bytes: &'data [u8]) -> Result<(T, usize)> {
+        self.decode_by(bytes, |input| T::decode_with(input, self))
+    }
+
+    /// Decodes a `T` from the start of `bytes` like [`Codec::decode`], recording
+    /// each decoded field into `sink`.
+    ///
+    /// The value is decoded with [`Decode::decode_traced`], so every type deriving
+    /// [`Decode`] records the name, offset and raw bytes of each of its fields, and
+    /// types annotated with `#[abio(trace)]` also record the decoded value. The
+    /// limit and options of this codec apply to the traced decode as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Codec::decode`].
+    pub fn decode_traced<'data, T: Decode<'data>>(
+        &self,
+        bytes: &'data [u8],
+        sink: &mut dyn TraceSink,
+    ) -> Result<(T, usize)> {
+        self.decode_with_tracer(bytes, Tracer::new(sink))
+    }
+
+    /// Decodes a `T` from the start of `bytes` like [`Codec::decode`], reporting the
+    /// progress of the decode to `observer`.
+    ///
+    /// The value is decoded with [`Decode::decode_traced`], so the fields of types
+    /// deriving [`Decode`] are reported to [`Observer::on_field_start`] and
+    /// [`Observer::on_field_end`]. If the decode fails, the error is passed to
+    /// [`Observer::on_error`] before it is returned.
+    ///
+    /// The observer is passed to each decode rather than stored in the codec, since
+    /// codecs are `Copy` and may be shared between decodes, while the observer is
+    /// borrowed mutably until the decode returns.
+    ///
+    /// The tracer carries this codec, so the element count and nesting depth of its
+    /// [`Limit`], its lenient booleans and its [`CancelToken`] apply to the traced
+    /// decode as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Codec::decode`].
+    pub fn decode_observed<'data, T: Decode<'data>>(
+        &self,
+        bytes: &'data [u8],
+        observer: &mut dyn Observer,
+    ) -> Result<(T, usize)> {
+        let decoded = self.decode_with_tracer(bytes, Tracer::observed(observer));
+        if let Err(err) = &decoded {
+            observer.on_error(err);
+        }
+        decoded
+    }
+
+    /// Decodes a `T` from the start of `bytes` with [`Decode::decode_traced`],
+    /// handing this codec to `tracer`.
+    fn decode_with_tracer<'data, T: Decode<'data>>(
+        &self,
+        bytes: &'data [u8],
+        tracer: Tracer<'_>,
+    ) -> Result<(T, usize)> {
+        let mut tracer = tracer.with_codec(*self);
+        self.decode_by(bytes, |input| match self.endian {
+            Endian::Little => T::decode_traced::<LE>(input, &mut tracer),
+            Endian::Big => T::decode_traced::<BE>(input, &mut tracer),
+        })
+    }
+
+    /// Decodes a value from the start of `bytes` with `decode`, applying the limit
+    /// and the trailing bytes policy of this codec.
+    fn decode_by<'data, T>(
+        &self,
+        bytes: &'data [u8],
+        decode: impl FnOnce(&'data [u8]) -> Result<(T, usize)>,
+    ) -> Result<(T, usize)> {
+        let input = &bytes[..self.limit.clamp(bytes.len())];
+        let (value, len) = match decode(input) {
+            Err(err) if err.is_out_of_bounds() && input.len() < bytes.len() => {
+                return Err(Error::limit_exceeded(input.len()));
+            }
+            decoded => decoded?,
+        };
+        if self.is_strict() && len != bytes.len() {
+            return Err(Error::size_mismatch(len, bytes.len()));
+        }
+        Ok((value, len))
+    }
+
+    /// Decodes a `T` from the start of `bytes` like [`Codec::decode`], returning the
+    /// decoded value and the bytes left after it.
+    ///
+    /// The remainder holds the trailing bytes only if the [`Trailing`] policy of
+    /// this codec is [`Trailing::Return`], and is empty otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Codec::decode`].
+    pub fn decode_with_remainder<'data, T: Decode<'data>>(
+        &self,
+        bytes: &'data [u8],
+    ) -> Result<(T, Bytes<'data>)> {
+        let (value, len) = self.decode::<T>(bytes)?;
+        let remainder = match self.trailing {
+            Trailing::Return => &bytes[len..],
+            Trailing::Ignore | Trailing::Reject => &[],
+        };
+        Ok((value, Bytes::new_allow_empty(remainder)))
+    }
+
+    /// Encodes `value` at the start of `buf`, returning the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Encode::encode`], and an error if the encoded value
+    /// extends past the [`Limit`] of this codec.
+    pub fn encode<T: Encode + ?Sized>(&self, value: &T, buf: &mut [u8]) -> Result<usize> {
+        let len = buf.len();
+        let limited = self.limit.clamp(len);
+        let output = &mut buf[..limited];
+        let encoded = match self.endian {
+            Endian::Little => value.encode::<LE>(output),
+            Endian::Big => value.encode::<BE>(output),
+        };
+        match encoded {
+            Err(err) if err.is_out_of_bounds() && limited < len => {
+                Err(Error::limit_exceeded(limited))
+            }
+            encoded => encoded,
+        }
+    }
+}
+
+impl Default for Codec {
+    #[inline]
+    fn default() -> Codec {
+        Codec::new()
+    }
+}
+
+/// Builder for a [`Codec`], returned by [`Codec::builder`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CodecBuilder {
+    codec: Codec,
+}
+
+impl CodecBuilder {
+    /// Decodes and encodes values in little endian byte order.
+    #[inline]
+    #[must_use]
+    pub const fn little_endian(self) -> CodecBuilder {
+        self.endian(Endian::Little)
+    }
+
+    /// Decodes and encodes values in big endian byte order.
+    #[inline]
+    #[must_use]
+    pub const fn big_endian(self) -> CodecBuilder {
+        self.endian(Endian::Big)
+    }
+
+    /// Decodes and encodes values in the byte order `endian`.
+    #[inline]
+    #[must_use]
+    pub const fn endian(mut self, endian: Endian) -> CodecBuilder {
+        self.codec.endian = endian;
+        self
+    }
+
+    /// Rejects inputs with bytes left after the decoded value if `strict` is `true`,
+    /// and ignores them otherwise.
+    ///
+    /// This is a shorthand for [`trailing`][Self::trailing] with
+    /// [`Trailing::Reject`] or [`Trailing::Ignore`].
+    #[inline]
+    #[must_use]
+    pub const fn strict(self, strict: bool) -> CodecBuilder {
+        self.trailing(if strict { Trailing::Reject } else { Trailing::Ignore })
+    }
+
+    /// Handles bytes left after the decoded value according to `trailing`.
+    #[inline]
+    #[must_use]
+    pub const fn trailing(mut self, trailing: Trailing) -> CodecBuilder {
+        self.codec.trailing = trailing;
+        self
+    }
+
+    /// Decodes any non-zero value of a boolean wire type, such as [`Bool8`], as
+    /// `true` if `lenient` is `true`.
+    ///
+    /// [`Bool8`]: crate::codec::Bool8
+    #[inline]
+    #[must_use]
+    pub const fn lenient_bools(mut self, lenient: bool) -> CodecBuilder {
+        self.codec.lenient_bools = lenient;
+        self
+    }
+
+    /// Limits the number of bytes read or written for a single value.
+    #[inline]
+    #[must_use]
+    pub const fn limit(mut self, limit: Limit) -> CodecBuilder {
+        self.codec.limit = limit;
+        self
+    }
+
+    /// Stops decodes once `token` has been cancelled, at the next field boundary.
+    ///
+    /// The token is borrowed for `'static` so that codecs remain `Copy`, which is
+    /// met by tokens in a `static`, or leaked with `Box::leak`. Two codecs are only
+    /// equal if they share the same token.
+    #[inline]
+    #[must_use]
+    pub const fn cancel_token(mut self, token: &'static CancelToken) -> CodecBuilder {
+        self.codec.cancel = Some(Cancel(token));
+        self
+    }
+
+    /// Returns the configured [`Codec`].
+    #[inline]
+    #[must_use]
+    pub const fn build(self) -> Codec {
+        self.codec
+    }
+}
+
+/// [`CancelToken`] of a [`Codec`], compared and hashed by address.
+#[derive(Clone, Copy)]
+struct Cancel(&'static CancelToken);
+
+impl PartialEq for Cancel {
+    #[inline]
+    fn eq(&self, other: &Cancel) -> bool {
+        ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Cancel {}
+
+impl Hash for Cancel {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(self.0, state);
+    }
+}
+
+impl fmt::Debug for Cancel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_and_encodes_with_runtime_options() {
+        let bytes = [0x01, 0x02, 0x03];
+        let be = Codec::builder()
+            .big_endian()
+            .build();
+        assert_eq!(
+            be.decode::<u16>(&bytes)
+                .unwrap(),
+            (0x0102, 2)
+        );
+
+        let strict = Codec::builder()
+            .little_endian()
+            .strict(true)
+            .build();
+        assert_eq!(
+            strict
+                .decode::<u16>(&bytes[..2])
+                .unwrap(),
+            (0x0201, 2)
+        );
+        assert_eq!(strict.decode::<u16>(&bytes), Err(Error::size_mismatch(2, 3)));
+
+        let lenient = Codec::builder()
+            .little_endian()
+            .trailing(Trailing::Return)
+            .build();
+        let (value, rest) = lenient
+            .decode_with_remainder::<u16>(&bytes)
+            .unwrap();
+        assert_eq!((value, rest.into_slice()), (0x0201, &bytes[2..]));
+        assert!(!lenient.is_strict());
+        assert!(strict
+            .decode_with_remainder::<u16>(&bytes)
+            .is_err());
+        assert!(Codec::le_packed()
+            .decode_with_remainder::<u16>(&bytes)
+            .unwrap()
+            .1
+            .is_empty());
+
+        let limited = Codec::builder()
+            .limit(Limit::bytes(2))
+            .build();
+        assert!(limited
+            .decode::<u16>(&bytes)
+            .is_ok());
+        assert_eq!(limited.decode::<u32>(&[0; 4]), Err(Error::limit_exceeded(2)));
+        // Inputs within the limit report truncation as usual.
+        assert!(limited
+            .decode::<u32>(&bytes[..1])
+            .unwrap_err()
+            .is_out_of_bounds());
+
+        let network = Codec::network();
+        assert_eq!((network.endian(), network.is_strict()), (Endian::Big, true));
+        assert_eq!(Codec::native(), Codec::new());
+        assert_eq!(
+            Codec::le_packed()
+                .decode::<u16>(&bytes)
+                .unwrap(),
+            (0x0201, 2)
+        );
+
+        let limit = Limit::NONE
+            .with_max_elements(4)
+            .with_max_depth(1);
+        assert_eq!(limit.check_elements(4), Ok(()));
+        assert_eq!(limit.check_elements(5), Err(Error::element_limit_exceeded(5, 4)));
+        let entered = limit.enter().unwrap();
+        assert_eq!(entered.enter(), Err(Error::depth_limit_exceeded(1)));
+        assert!(Limit::NONE.enter().is_ok());
+
+        let mut buf = [0u8; 4];
+        assert_eq!(be.encode(&0x0102u16, &mut buf), Ok(2));
+        assert_eq!(buf[..2], [0x01, 0x02]);
+        assert_eq!(limited.encode(&0u32, &mut buf), Err(Error::limit_exceeded(2)));
+    }
+
+    #[test]
+    fn compares_cancel_tokens_by_identity() {
+        static FIRST: CancelToken = CancelToken::new();
+        static SECOND: CancelToken = CancelToken::new();
+
+        let with = |token| {
+            Codec::builder()
+                .cancel_token(token)
+                .build()
+        };
+        assert_eq!(with(&FIRST), with(&FIRST));
+        assert_ne!(with(&FIRST), with(&SECOND));
+        assert_ne!(with(&FIRST), Codec::new());
+        assert!(ptr::eq(
+            with(&FIRST)
+                .cancel_token()
+                .unwrap(),
+            &FIRST
+        ));
+        assert!(Codec::new()
+            .cancel_token()
+            .is_none());
+
+        FIRST.cancel();
+        assert!(with(&FIRST)
+            .check_cancelled()
+            .unwrap_err()
+            .is_cancelled());
+        assert_eq!(with(&SECOND).check_cancelled(), Ok(()));
+        assert!(with(&FIRST)
+            .decode::<u8>(&[1])
+            .unwrap_err()
+            .is_cancelled());
+    }
+
+    #[test]
+    fn reports_failed_decodes_to_observers() {
+        #[derive(Default)]
+        struct Failures(usize);
+
+        impl Observer for Failures {
+            fn on_error(&mut self, error: &Error) {
+                assert!(error.is_out_of_bounds());
+                self.0 += 1;
+            }
+        }
+
+        let mut failures = Failures::default();
+        let codec = Codec::network();
+        assert_eq!(codec.decode_observed::<u16>(&[0x01, 0x02], &mut failures), Ok((0x0102, 2)));
+        assert!(codec
+            .decode_observed::<u32>(&[0x01, 0x02], &mut failures)
+            .is_err());
+        assert_eq!(failures.0, 1);
+    }
+}
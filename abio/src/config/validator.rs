@@ -0,0 +1,158 @@
+//! Module containing the [`Validator`] trait and the [`ValidatingCodec`] type, for
+//! checking decoded values against policies defined outside of their types.
+
+use super::Codec;
+use crate::{Bytes, Decode, Result};
+
+/// Trait for checks run on values after they were decoded, such as maximum sizes,
+/// allowed versions or deny-lists.
+///
+/// Validators are registered on a [`Codec`] with [`Codec::with_validator`], and
+/// apply to every value of type `T` decoded through the returned
+/// [`ValidatingCodec`]. Unlike the `#[abio(validate = "..")]` attribute, they do not
+/// require modifying the validated type, so different policies can be applied to
+/// the same type depending on where its input comes from.
+///
+/// This trait is implemented for closures taking the same arguments as
+/// [`validate`][Validator::validate], and for pairs of validators, which run one
+/// after the other.
+///
+/// # Examples
+///
+/// ```
+/// use abio::config::Codec;
+/// use abio::{Bytes, Error, Result};
+///
+/// fn max_version(version: &u16, _: &Bytes<'_>) -> Result<()> {
+///     match *version {
+///         0..=2 => Ok(()),
+///         _ => Err(Error::validation_failed("unsupported version")),
+///     }
+/// }
+///
+/// let codec = Codec::network().with_validator(max_version);
+/// assert_eq!(codec.decode::<u16>(&[0, 2]).unwrap(), (2, 2));
+/// assert!(codec
+///     .decode::<u16>(&[0, 3])
+///     .unwrap_err()
+///     .is_validation_failed());
+/// ```
+pub trait Validator<T: ?Sized> {
+    /// Checks `value`, decoded from the bytes of `src`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, usually built with [`Error::validation_failed`], if
+    /// `value` violates the policy of this validator.
+    ///
+    /// [`Error::validation_failed`]: crate::Error::validation_failed
+    fn validate(&self, value: &T, src: &Bytes<'_>) -> Result<()>;
+}
+
+impl<T: ?Sized, F> Validator<T> for F
+where
+    F: Fn(&T, &Bytes<'_>) -> Result<()>,
+{
+    #[inline]
+    fn validate(&self, value: &T, src: &Bytes<'_>) -> Result<()> {
+        self(value, src)
+    }
+}
+
+/// Both validators run in order, and the first error is returned.
+impl<T: ?Sized, A: Validator<T>, B: Validator<T>> Validator<T> for (A, B) {
+    #[inline]
+    fn validate(&self, value: &T, src: &Bytes<'_>) -> Result<()> {
+        self.0.validate(value, src)?;
+        self.1.validate(value, src)
+    }
+}
+
+/// A [`Codec`] running a [`Validator`] on every value it decodes, created by
+/// [`Codec::with_validator`].
+#[derive(Clone, Copy, Debug)]
+pub struct ValidatingCodec<V> {
+    codec: Codec,
+    validator: V,
+}
+
+impl<V> ValidatingCodec<V> {
+    /// Returns the codec values are decoded with, before being validated.
+    #[inline]
+    pub const fn codec(&self) -> &Codec {
+        &self.codec
+    }
+
+    /// Returns the validator run on decoded values.
+    #[inline]
+    pub const fn validator(&self) -> &V {
+        &self.validator
+    }
+
+    /// Registers `validator` to run on decoded values after the validators already
+    /// registered.
+    #[inline]
+    pub fn with_validator<W>(self, validator: W) -> ValidatingCodec<(V, W)> {
+        ValidatingCodec { codec: self.codec, validator: (self.validator, validator) }
+    }
+
+    /// Decodes a `T` from the start of `bytes` like [`Codec::decode`], then
+    /// validates it, returning the decoded value and the number of bytes consumed.
+    ///
+    /// The validator is given the bytes the value was decoded from.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Codec::decode`], and the error of the validator if
+    /// it rejects the value.
+    pub fn decode<'data, T>(&self, bytes: &'data [u8]) -> Result<(T, usize)>
+    where
+        T: Decode<'data>,
+        V: Validator<T>,
+    {
+        let (value, len) = self.codec.decode::<T>(bytes)?;
+        self.validator
+            .validate(&value, &Bytes::new_allow_empty(&bytes[..len]))?;
+        Ok((value, len))
+    }
+}
+
+impl Codec {
+    /// Returns a codec running `validator` on every value it decodes, in addition
+    /// to the checks of this codec.
+    #[inline]
+    pub const fn with_validator<V>(self, validator: V) -> ValidatingCodec<V> {
+        ValidatingCodec { codec: self, validator }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn runs_registered_validators_in_order() {
+        let max_len = |_: &u32, src: &Bytes<'_>| -> Result<()> {
+            assert_eq!(src.len(), 4);
+            Ok(())
+        };
+        let deny = |value: &u32, _: &Bytes<'_>| match *value {
+            0xdead_beef => Err(Error::validation_failed("denied value")),
+            _ => Ok(()),
+        };
+        let codec = Codec::network()
+            .with_validator(max_len)
+            .with_validator(deny);
+
+        assert_eq!(codec.decode::<u32>(&[0, 0, 0, 1]), Ok((1, 4)));
+        assert_eq!(
+            codec.decode::<u32>(&[0xde, 0xad, 0xbe, 0xef]),
+            Err(Error::validation_failed("denied value"))
+        );
+        // Decoding errors are returned before any validator runs.
+        assert!(codec
+            .decode::<u32>(&[0, 0, 0, 1, 0])
+            .is_err());
+    }
+}
@@ -12,6 +12,8 @@ use crate::{Abi, Endianness, Error, Result};
 pub struct LittleEndian;
 
 impl const Endianness for LittleEndian {
+    const ENDIAN: Endian = Endian::Little;
+
     fn is_little_endian(&self) -> bool {
         matches!(self, Endian::Little)
     }
@@ -216,17 +218,12 @@ macro_rules! impl_encoder_for_endian {
         $(
             #[inline]
             fn $method_name(buf: &mut [u8], value: $output) -> Result<usize> {
-                if buf.len() < <$output>::SIZE {
-                    Err(Error::out_of_bounds(<$output>::SIZE, buf.len()))
-                } else {
-                    unsafe {
-                        ::core::ptr::copy_nonoverlapping(
-                            value.$endian() as *const u8,
-                            buf.as_mut_ptr(),
-                            <$output>::SIZE,
-                        );
+                match buf.get_mut(..<$output>::SIZE) {
+                    Some(dst) => {
+                        dst.copy_from_slice(&value.$endian());
                         Ok(<$output>::SIZE)
                     }
+                    None => Err(Error::out_of_bounds(<$output>::SIZE, buf.len())),
                 }
             }
         )*
@@ -235,16 +232,16 @@ macro_rules! impl_encoder_for_endian {
 
 impl Encoder for LittleEndian {
     impl_encoder_for_endian! {
-        u8,     write_u8,   to_le,
-        u16,    write_u16,  to_le,
-        u32,    write_u32,  to_le,
-        u64,    write_u64,  to_le,
-        u128,   write_u128, to_le,
-        i8,     write_i8,   to_le,
-        i16,    write_i16,  to_le,
-        i32,    write_i32,  to_le,
-        i64,    write_i64,  to_le,
-        i128,   write_i128, to_le,
+        u8,     write_u8,   to_le_bytes,
+        u16,    write_u16,  to_le_bytes,
+        u32,    write_u32,  to_le_bytes,
+        u64,    write_u64,  to_le_bytes,
+        u128,   write_u128, to_le_bytes,
+        i8,     write_i8,   to_le_bytes,
+        i16,    write_i16,  to_le_bytes,
+        i32,    write_i32,  to_le_bytes,
+        i64,    write_i64,  to_le_bytes,
+        i128,   write_i128, to_le_bytes,
     }
 }
 
@@ -265,16 +262,16 @@ impl Decoder for LittleEndian {
 
 impl Encoder for BigEndian {
     impl_encoder_for_endian! {
-        u8,     write_u8,   to_be,
-        u16,    write_u16,  to_be,
-        u32,    write_u32,  to_be,
-        u64,    write_u64,  to_be,
-        u128,   write_u128, to_be,
-        i8,     write_i8,   to_be,
-        i16,    write_i16,  to_be,
-        i32,    write_i32,  to_be,
-        i64,    write_i64,  to_be,
-        i128,   write_i128, to_be,
+        u8,     write_u8,   to_be_bytes,
+        u16,    write_u16,  to_be_bytes,
+        u32,    write_u32,  to_be_bytes,
+        u64,    write_u64,  to_be_bytes,
+        u128,   write_u128, to_be_bytes,
+        i8,     write_i8,   to_be_bytes,
+        i16,    write_i16,  to_be_bytes,
+        i32,    write_i32,  to_be_bytes,
+        i64,    write_i64,  to_be_bytes,
+        i128,   write_i128, to_be_bytes,
     }
 }
 
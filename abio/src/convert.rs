@@ -0,0 +1,72 @@
+//! Conversions between wrapper types and the values they wrap.
+//!
+//! Many types in this crate are thin wrappers around another value, such as the
+//! aligned integers wrapping a primitive or [`Bytes`][crate::Bytes] wrapping a
+//! byte slice. The traits in this module convert between a wrapper and its inner
+//! value, and are implemented by the code generated by this crate's macros.
+//!
+//! Implementing them for your own wrapper types lets those types be used wherever
+//! this crate's macros expect a wrapper, such as an aligned integer type of your own
+//! that follows the layout of the ones provided by [`integer`][crate::integer].
+//!
+//! # Stability
+//!
+//! These traits are part of the public API and follow semantic versioning. Their
+//! names, methods and `const` support are only changed in breaking releases.
+//!
+//! # Examples
+//!
+//! ```
+//! use abio::convert::{FromInner, IntoInner};
+//!
+//! #[repr(transparent)]
+//! struct Offset(u32);
+//!
+//! impl FromInner<u32> for Offset {
+//!     fn from_inner(inner: u32) -> Offset {
+//!         Offset(inner)
+//!     }
+//! }
+//!
+//! impl IntoInner<u32> for Offset {
+//!     fn into_inner(self) -> u32 {
+//!         self.0
+//!     }
+//! }
+//!
+//! assert_eq!(Offset::from_inner(0x40).into_inner(), 0x40);
+//! ```
+
+/// Trait for wrapper types that can be constructed from the value they wrap.
+///
+/// This trait is `const`, so that wrappers can be constructed in constants when
+/// the implementation allows it.
+#[const_trait]
+pub trait FromInner<T: ?Sized> {
+    /// Wraps `inner`.
+    fn from_inner(inner: T) -> Self;
+}
+
+/// Trait for wrapper types that can be unwrapped into the value they wrap.
+///
+/// This trait is `const`, so that wrappers can be unwrapped in constants when the
+/// implementation allows it.
+#[const_trait]
+pub trait IntoInner<T> {
+    /// Consumes this wrapper, returning the value it wraps.
+    fn into_inner(self) -> T;
+}
+
+/// Trait for wrapper types that can be borrowed as the value they wrap.
+#[const_trait]
+pub trait AsInner<T: ?Sized> {
+    /// Returns a reference to the wrapped value.
+    fn as_inner(&self) -> &T;
+}
+
+/// Trait for wrapper types that can be mutably borrowed as the value they wrap.
+#[const_trait]
+pub trait AsInnerMut<T: ?Sized> {
+    /// Returns a mutable reference to the wrapped value.
+    fn as_inner_mut(&mut self) -> &mut T;
+}
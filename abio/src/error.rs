@@ -13,16 +13,24 @@ mod internal;
 /// These variants each represent a particular failure state.
 pub struct Error {
     kind: internal::ErrorKind,
+    /// Offset of the input the error occurred at, if known.
+    offset: Option<usize>,
 }
 
 // ISSUE: https://github.com/ellacrity/abio/issues/5
+//
+// Constructors are `#[cold]` and never inlined, so that building an error does not
+// bloat the success path of the caller, and branches returning an error are laid
+// out away from it.
 impl Error {
     /// Creates a new [`Error`] instance from an inner [`ErrorKind`].
     pub(crate) const fn new(kind: internal::ErrorKind) -> Error {
-        Error { kind }
+        Error { kind, offset: None }
     }
 
     /// The reading subroutine failed due to the presence of malformed data.
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn decoder_failed() -> Error {
         Error::new(internal::ErrorKind::EncodeFailed {
             message: "Deserialzation routine failed due to the presence of malformed data.",
@@ -30,6 +38,8 @@ impl Error {
     }
 
     /// The serialization subroutine failed due to the presence of malformed data.
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn encoder_failed() -> Error {
         Error::new(internal::ErrorKind::SerializationFailed {
             message: "Serialization routine failed due to the presence of malformed data.",
@@ -38,18 +48,28 @@ impl Error {
 
     /// The read operation failed due to an unexpected reason, described by
     /// `message`.
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn read_failed(message: &'static str) -> Error {
         Error::new(internal::ErrorKind::EncodeFailure { message })
     }
 
     /// The slice of data contains an unexpected or missing sentinel value.
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn invalid_sentinel_slice() -> Error {
         Error::new(internal::ErrorKind::InvalidSentinelOffset)
     }
 
     /// This operation failed due to an attempted memory access outside the bounds of
     /// the allocated object.
-    pub(crate) const fn out_of_bounds(needed: usize, available: usize) -> Error {
+    ///
+    /// This constructor is public so that code generated by `#[derive(Decode)]` and
+    /// `#[derive(Encode)]` can report truncated padding.
+    #[doc(hidden)]
+    #[cold]
+    #[inline(never)]
+    pub const fn out_of_bounds(needed: usize, available: usize) -> Error {
         Error::new(internal::ErrorKind::OutOfBounds(OutOfBoundsError::new(
             needed, available,
         )))
@@ -57,12 +77,181 @@ impl Error {
 
     /// The pointer is not aligned properly to meet the layout requirements of a
     /// type.
-    pub(crate) fn misaligned_access<T: crate::Abi>(ptr: *const T) -> Error {
+    ///
+    /// This constructor is public so that code generated by `#[derive(AbiUnion)]`
+    /// can report overlays that are not aligned for a field.
+    #[doc(hidden)]
+    #[cold]
+    #[inline(never)]
+    pub fn misaligned_access<T: crate::Abi>(ptr: *const T) -> Error {
         Error::new(internal::ErrorKind::MisalignedAccess { ptr: ptr.addr() })
     }
 
+    /// The requested region of memory cannot be borrowed because it is not
+    /// contiguous.
+    #[cold]
+    #[inline(never)]
+    pub(crate) const fn non_contiguous(offset: usize, len: usize) -> Error {
+        Error::new(internal::ErrorKind::NonContiguous { offset, len })
+    }
+
+    /// The arena being decoded into cannot hold an allocation of `size` bytes
+    /// aligned to `align`.
+    #[cold]
+    #[inline(never)]
+    pub(crate) const fn arena_exhausted(size: usize, align: usize) -> Error {
+        Error::new(internal::ErrorKind::ArenaExhausted { size, align })
+    }
+
+    /// The decode was stopped because its [`CancelToken`] was cancelled.
+    ///
+    /// [`CancelToken`]: crate::codec::CancelToken
+    #[cold]
+    #[inline(never)]
+    pub(crate) const fn cancelled() -> Error {
+        Error::new(internal::ErrorKind::Cancelled)
+    }
+
+    /// The signature found at `offset` does not match the `expected` magic bytes
+    /// required by the type being decoded.
+    ///
+    /// Only the first 16 bytes of `actual` are kept. This constructor is public so
+    /// that code generated for `#[abio(magic = b"..")]` attributes, as well as
+    /// hand-written parsers, can report mismatched signatures.
+    #[cold]
+    #[inline(never)]
+    pub const fn bad_magic(expected: &'static [u8], actual: &[u8], offset: usize) -> Error {
+        let mut found = [0u8; internal::MAX_MAGIC_LEN];
+        let mut idx = 0;
+        while idx < actual.len() && idx < found.len() {
+            found[idx] = actual[idx];
+            idx += 1;
+        }
+        Error::new(internal::ErrorKind::BadMagic { expected, actual: found, offset })
+    }
+
+    /// The tag read while decoding the enum `type_name` does not match the
+    /// discriminant of any of its variants.
+    ///
+    /// This constructor is public so that code generated by `#[derive(Decode)]` can
+    /// report unknown tags.
+    #[doc(hidden)]
+    #[cold]
+    #[inline(never)]
+    pub const fn unknown_discriminant(type_name: &'static str, value: i128) -> Error {
+        Error::new(internal::ErrorKind::UnknownDiscriminant { type_name, value })
+    }
+
+    /// The `width` bits of a field of type `type_name` hold a `value` that is not
+    /// valid for the type, or the field cannot hold `value` when encoding.
+    ///
+    /// This constructor is public so that code generated for `#[abio(bits = N)]`
+    /// attributes can report invalid bit fields.
+    #[doc(hidden)]
+    #[cold]
+    #[inline(never)]
+    pub const fn invalid_bit_field(type_name: &'static str, width: u32, value: u64) -> Error {
+        Error::new(internal::ErrorKind::InvalidBitField { type_name, width, value })
+    }
+
+    /// The bytes of a value of the primitive type `type_name`, read as the integer
+    /// `raw`, do not represent a valid value of the type, such as a `bool` other
+    /// than `0` or `1`.
+    ///
+    /// This constructor is public so that implementations of [`Decode`] for types
+    /// with invalid bit patterns can report them.
+    ///
+    /// [`Decode`]: crate::Decode
+    #[cold]
+    #[inline(never)]
+    pub const fn invalid_value(type_name: &'static str, raw: u64) -> Error {
+        Error::new(internal::ErrorKind::InvalidValue { type_name, value: raw })
+    }
+
+    /// A decoded value violates an invariant checked by a `#[abio(validate = "..")]`
+    /// function or a [`Validator`], described by `message`.
+    ///
+    /// This constructor is public so that validation functions can reject values
+    /// that decoded successfully but are not meaningful, such as an unsupported
+    /// version or a length that disagrees with another field.
+    ///
+    /// [`Validator`]: crate::config::Validator
+    #[cold]
+    #[inline(never)]
+    pub const fn validation_failed(message: &'static str) -> Error {
+        Error::new(internal::ErrorKind::ValidationFailed { message })
+    }
+
+    /// A peer reported the version tag `peer` for the type `type_name`, whose local
+    /// definition has the tag `local`.
+    #[cold]
+    #[inline(never)]
+    pub(crate) const fn version_mismatch(type_name: &'static str, local: u64, peer: u64) -> Error {
+        Error::new(internal::ErrorKind::VersionMismatch { type_name, local, peer })
+    }
+
+    /// The input ends before the value being decoded incrementally, which needs at
+    /// least `needed` more bytes.
+    #[cold]
+    #[inline(never)]
+    pub(crate) const fn incomplete(needed: usize) -> Error {
+        Error::new(internal::ErrorKind::Incomplete { needed })
+    }
+
+    /// Records that this error occurred at `offset` of the input, unless it already
+    /// records an offset closer to its cause.
+    pub(crate) const fn at_offset(mut self, offset: usize) -> Error {
+        if self.offset.is_none() {
+            self.offset = Some(offset);
+        }
+        self
+    }
+
+    /// Converts an out-of-bounds error into an [`incomplete`][Error::incomplete]
+    /// error needing the missing bytes, and returns any other error unchanged.
+    pub(crate) const fn into_incomplete(self) -> Error {
+        match self.kind {
+            internal::ErrorKind::OutOfBounds(OutOfBoundsError { needed, available }) => {
+                Error::incomplete(needed.saturating_sub(available))
+            }
+            _ => self,
+        }
+    }
+
+    /// The value being decoded or encoded extends past the `limit` of the
+    /// [`Codec`] used.
+    ///
+    /// [`Codec`]: crate::config::Codec
+    #[cold]
+    #[inline(never)]
+    pub(crate) const fn limit_exceeded(limit: usize) -> Error {
+        Error::new(internal::ErrorKind::LimitExceeded { limit })
+    }
+
+    /// A field declares `count` elements, more than the `limit` of the [`Codec`]
+    /// used.
+    ///
+    /// [`Codec`]: crate::config::Codec
+    #[cold]
+    #[inline(never)]
+    pub(crate) const fn element_limit_exceeded(count: usize, limit: usize) -> Error {
+        Error::new(internal::ErrorKind::ElementLimitExceeded { count, limit })
+    }
+
+    /// The value being decoded is nested more than `limit` levels deep, the limit
+    /// of the [`Codec`] used.
+    ///
+    /// [`Codec`]: crate::config::Codec
+    #[cold]
+    #[inline(never)]
+    pub(crate) const fn depth_limit_exceeded(limit: usize) -> Error {
+        Error::new(internal::ErrorKind::DepthLimitExceeded { limit })
+    }
+
     /// The number of bytes in the source buffer do not match the number of bytes
     /// comprising a concrete type of some type `T`.
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn size_mismatch(expected: usize, actual: usize) -> Error {
         Error::new(internal::ErrorKind::SizeMismatch { expected, actual })
     }
@@ -71,22 +260,30 @@ impl Error {
     ///
     /// This state may or may not be recoverable from, and it should be assumed that
     /// a runtime panic is likely if the failure is not handled properly.
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn internal_failure() -> Error {
         Error::new(internal::ErrorKind::InternalFailure)
     }
 
     /// A type conversion operation failed due to the types having incompatible
     /// layouts.
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn incompatible_types() -> Error {
         Error::new(internal::ErrorKind::IncompatibleTypes)
     }
 
     /// The operation would cause a dereference on a pointer known to be invalid.
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn null_reference() -> Error {
         Error::new(internal::ErrorKind::NullReference)
     }
 
     /// Error with a detailed message meant for debugging purposes.
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn verbose(message: &'static str) -> Error {
         Error::new(internal::ErrorKind::Verbose { message })
     }
@@ -95,6 +292,8 @@ impl Error {
     /// safety contract provided by the [`Span`] type.
     ///
     /// [`Span`]: https://docs
+    #[cold]
+    #[inline(never)]
     pub(crate) const fn invalid_span(range: Range<usize>) -> Error {
         Error::new(internal::ErrorKind::InvalidSpan {
             start: range.start,
@@ -105,21 +304,28 @@ impl Error {
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&self.kind, f)
+        match self.offset {
+            Some(offset) => f
+                .debug_struct("Error")
+                .field("kind", &self.kind)
+                .field("offset", &offset)
+                .finish(),
+            None => fmt::Debug::fmt(&self.kind, f),
+        }
     }
 }
 
 impl Eq for Error {}
 impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool {
-        self.kind == other.kind
+        self.kind == other.kind && self.offset == other.offset
     }
 }
 
 impl From<internal::ErrorKind> for Error {
     #[inline]
     fn from(kind: internal::ErrorKind) -> Error {
-        Error { kind }
+        Error::new(kind)
     }
 }
 
@@ -165,12 +371,130 @@ impl From<&'static str> for Error {
     }
 }
 
-impl Error {}
+impl Error {
+    /// Returns `true` if this error was caused by decoding an enum whose tag does not
+    /// match any of its variants.
+    #[inline]
+    #[must_use]
+    pub const fn is_unknown_discriminant(&self) -> bool {
+        self.kind
+            .is_unknown_discriminant()
+    }
+
+    /// Returns `true` if this error was caused by bytes that do not represent a valid
+    /// value of the primitive type being decoded, such as a `bool` other than `0` or
+    /// `1`.
+    #[inline]
+    #[must_use]
+    pub const fn is_invalid_value(&self) -> bool {
+        self.kind.is_invalid_value()
+    }
+
+    /// Returns `true` if this error was caused by a signature, or magic number, that
+    /// does not match the value required by the type being decoded.
+    #[inline]
+    #[must_use]
+    pub const fn is_bad_magic(&self) -> bool {
+        self.kind.is_bad_magic()
+    }
+
+    /// Returns `true` if this error was returned by a validation function declared
+    /// with `#[abio(validate = "..")]` or by a [`Validator`].
+    ///
+    /// [`Validator`]: crate::config::Validator
+    #[inline]
+    #[must_use]
+    pub const fn is_validation_failed(&self) -> bool {
+        self.kind.is_validation_failed()
+    }
+
+    /// Returns `true` if this error was caused by cancelling the [`CancelToken`]
+    /// checked by a decode.
+    ///
+    /// [`CancelToken`]: crate::codec::CancelToken
+    #[inline]
+    #[must_use]
+    pub const fn is_cancelled(&self) -> bool {
+        self.kind.is_cancelled()
+    }
+
+    /// Returns `true` if this error was caused by a peer using another definition of
+    /// a type, as reported by [`VersionTag::check_peer`].
+    ///
+    /// [`VersionTag::check_peer`]: crate::version::VersionTag::check_peer
+    #[inline]
+    #[must_use]
+    pub const fn is_version_mismatch(&self) -> bool {
+        self.kind.is_version_mismatch()
+    }
+
+    /// Returns `true` if this error was caused by accessing bytes past the end of a
+    /// slice, such as when an input is truncated or an output buffer is too small.
+    #[inline]
+    #[must_use]
+    pub const fn is_out_of_bounds(&self) -> bool {
+        self.kind.is_out_of_bounds()
+    }
+
+    /// Returns `true` if this error was caused by an input ending before the value
+    /// being decoded incrementally, which more input may complete.
+    #[inline]
+    #[must_use]
+    pub const fn is_incomplete(&self) -> bool {
+        self.kind.is_incomplete()
+    }
+
+    /// Returns the minimum number of additional bytes needed to make progress, if
+    /// this error was caused by an incomplete input.
+    #[inline]
+    #[must_use]
+    pub const fn needed(&self) -> Option<usize> {
+        match self.kind {
+            internal::ErrorKind::Incomplete { needed } => Some(needed),
+            _ => None,
+        }
+    }
+
+    /// Returns the offset of the input this error occurred at, if it is known.
+    ///
+    /// Errors returned by a [`Reader`][crate::Reader] record the position of the
+    /// reader when the failing read started, relative to the start of its region.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Returns `true` if this error was caused by a value exceeding the [`Limit`] of
+    /// the codec decoding or encoding it, whether by extending past its maximum
+    /// number of bytes, declaring too many elements or being nested too deeply.
+    ///
+    /// [`Limit`]: crate::config::Limit
+    #[inline]
+    #[must_use]
+    pub const fn is_limit_exceeded(&self) -> bool {
+        self.kind.is_limit_exceeded()
+    }
+}
 
+/// Errors recording the offset they occurred at, such as those returned by a
+/// [`Reader`][crate::Reader], mention it after the description of their kind.
 impl fmt::Display for Error {
     #[allow(clippy::missing_inline_in_public_items)]
+    #[cold]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.kind, f)?;
+        match self.offset {
+            Some(offset) => write!(f, " (at offset {offset})"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for internal::ErrorKind {
+    #[cold]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.kind {
+        match self {
             internal::ErrorKind::OutOfBounds(e) => fmt::Display::fmt(e, f),
             internal::ErrorKind::IncompatibleTypes => {
                 write!(
@@ -188,6 +512,30 @@ impl fmt::Display for Error {
             internal::ErrorKind::NullReference => {
                 write!(f, "Invalid pointer dereferenced to null",)
             }
+            internal::ErrorKind::NonContiguous { offset, len } => {
+                write!(f, "Region at offset {offset} with length {len} is not contiguous in memory and must be copied")
+            }
+            internal::ErrorKind::InvalidBitField { type_name, width, value } => {
+                write!(f, "Value {value:#x} is not a valid `{type_name}` in a {width}-bit field")
+            }
+            internal::ErrorKind::InvalidValue { type_name, value } => {
+                write!(f, "Value {value:#x} is not a valid `{type_name}`")
+            }
+            internal::ErrorKind::UnknownDiscriminant { type_name, value } => {
+                write!(f, "Unknown discriminant {value} for enum `{type_name}`")
+            }
+            internal::ErrorKind::Incomplete { needed } => {
+                write!(f, "Incomplete input; at least {needed} more bytes are needed")
+            }
+            internal::ErrorKind::LimitExceeded { limit } => {
+                write!(f, "Value extends past the limit of {limit} bytes")
+            }
+            internal::ErrorKind::ElementLimitExceeded { count, limit } => {
+                write!(f, "Field declares {count} elements, more than the limit of {limit}")
+            }
+            internal::ErrorKind::DepthLimitExceeded { limit } => {
+                write!(f, "Value is nested more than {limit} levels deep")
+            }
             internal::ErrorKind::SizeMismatch { expected, actual } => {
                 write!(
                     f,
@@ -200,6 +548,26 @@ impl fmt::Display for Error {
             internal::ErrorKind::SerializationFailed { message } => {
                 write!(f, "Encode failed: {message}")
             }
+            internal::ErrorKind::Cancelled => write!(f, "Decode was cancelled"),
+            internal::ErrorKind::ArenaExhausted { size, align } => {
+                write!(f, "Arena exhausted; cannot allocate {size} bytes aligned to {align}")
+            }
+            internal::ErrorKind::VersionMismatch { type_name, local, peer } => {
+                write!(
+                    f,
+                    "Peer uses version tag {peer:#018x} for `{type_name}`, expected {local:#018x}"
+                )
+            }
+            internal::ErrorKind::ValidationFailed { message } => {
+                write!(f, "Decoded value failed validation: {message}")
+            }
+            internal::ErrorKind::BadMagic { expected, actual, offset } => {
+                let actual = &actual[..expected.len().min(actual.len())];
+                write!(
+                    f,
+                    "Bad magic at offset {offset} (expected {expected:02x?}, got {actual:02x?})"
+                )
+            }
             internal::ErrorKind::InvalidSpan { start, end } => {
                 write!(f, "Span cannot be constructed; invariant violation (expected `start <= end`, got: {start} | {end})")
             }
@@ -217,6 +585,24 @@ impl fmt::Display for Error {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    #[cold]
+    fn from(err: Error) -> std::io::Error {
+        let kind = match err.kind {
+            internal::ErrorKind::OutOfBounds(_) | internal::ErrorKind::Incomplete { .. } => {
+                std::io::ErrorKind::UnexpectedEof
+            }
+            internal::ErrorKind::NonContiguous { .. } => std::io::ErrorKind::Unsupported,
+            _ => std::io::ErrorKind::InvalidData,
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
 /// Error originating from a failed attempt to inspect the target system.
 ///
 /// This is a rare error and should only happen in two circumstances:
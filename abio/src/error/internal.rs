@@ -1,5 +1,9 @@
 use super::OutOfBoundsError;
 
+/// Maximum number of bytes of a mismatched signature that are kept by a
+/// [`BadMagic`][ErrorKind::BadMagic] error.
+pub(crate) const MAX_MAGIC_LEN: usize = 16;
+
 /// Error variant, or kind, used to more precisely represent the failure
 /// condition.
 #[derive(Debug, Default, PartialEq)]
@@ -23,6 +27,33 @@ pub(crate) enum ErrorKind {
     /// Error caused by a failed conversion attempt due to the types having
     /// incompatible layouts.
     IncompatibleTypes,
+    /// Error caused by a decode that was stopped through its cancellation token.
+    Cancelled,
+    /// Error caused by an arena allocator that cannot satisfy an allocation made
+    /// while decoding into it.
+    ArenaExhausted {
+        /// Size of the requested allocation, in bytes.
+        size: usize,
+        /// Alignment of the requested allocation, in bytes.
+        align: usize,
+    },
+    /// Error caused by a signature, or magic number, that does not match the value
+    /// required by the type being decoded.
+    BadMagic {
+        /// Signature required by the type being decoded.
+        expected: &'static [u8],
+        /// Leading bytes of the signature found in the input, truncated to
+        /// [`MAX_MAGIC_LEN`] bytes.
+        actual: [u8; MAX_MAGIC_LEN],
+        /// Offset of the signature within the input.
+        offset: usize,
+    },
+    /// Error caused by a decoded value that violates an invariant checked by a
+    /// validation function.
+    ValidationFailed {
+        /// Message describing the violated invariant.
+        message: &'static str,
+    },
     /// Error caused by attempting to construct an invalid range. An invalid
     /// range is any range where the `start > end`.
     ///
@@ -42,9 +73,80 @@ pub(crate) enum ErrorKind {
     MisalignedAccess { ptr: *const () },
     /// Error caused by an invalid pointer that dereferences to null.
     NullReference,
+    /// Error caused by requesting a borrowed view of a region of memory that is not
+    /// contiguous, such as a region that must be byte-swapped or that straddles
+    /// the boundary between two segments.
+    NonContiguous {
+        /// Offset of the requested region.
+        offset: usize,
+        /// Length of the requested region.
+        len: usize,
+    },
     /// Error originating from an operation that caused an attempted memory
     /// access outside the bounds of a slice or array.
     OutOfBounds(OutOfBoundsError),
+    /// Error caused by a bit field holding a value that is not valid for its type,
+    /// or by encoding a value that is too wide for its bit field.
+    InvalidBitField {
+        /// Name of the type of the field.
+        type_name: &'static str,
+        /// Width of the field, in bits.
+        width: u32,
+        /// Value that was read from, or would be written to, the field.
+        value: u64,
+    },
+    /// Error caused by decoding a value of a primitive type, such as `bool` or
+    /// `char`, from bytes that do not represent a valid value of the type.
+    InvalidValue {
+        /// Name of the type being decoded.
+        type_name: &'static str,
+        /// Raw value that was read from the input.
+        value: u64,
+    },
+    /// Error caused by decoding a value from an input that ends before it, when
+    /// decoding incrementally. More input may complete the value.
+    Incomplete {
+        /// Minimum number of additional bytes needed to make progress.
+        needed: usize,
+    },
+    /// Error caused by a value extending past the limit of the codec decoding or
+    /// encoding it.
+    LimitExceeded {
+        /// Maximum number of bytes allowed by the codec.
+        limit: usize,
+    },
+    /// Error caused by a field declaring more elements than the limit of the codec
+    /// decoding it.
+    ElementLimitExceeded {
+        /// Number of elements declared by the field.
+        count: usize,
+        /// Maximum number of elements allowed by the codec.
+        limit: usize,
+    },
+    /// Error caused by values nested deeper than the limit of the codec decoding
+    /// them.
+    DepthLimitExceeded {
+        /// Maximum depth allowed by the codec.
+        limit: usize,
+    },
+    /// Error caused by decoding an enum whose tag does not match the discriminant
+    /// of any of its variants.
+    UnknownDiscriminant {
+        /// Name of the enum being decoded.
+        type_name: &'static str,
+        /// Value of the tag that was read from the input.
+        value: i128,
+    },
+    /// Error caused by a peer reporting a version tag for a type that differs from
+    /// the tag of its local definition.
+    VersionMismatch {
+        /// Name of the type whose tags differ.
+        type_name: &'static str,
+        /// Version tag of the local definition of the type.
+        local: u64,
+        /// Version tag reported by the peer.
+        peer: u64,
+    },
     /// Error occurring when the the sizes of two types, or regions of memory, do
     /// not have the same exact size.
     ///
@@ -142,6 +244,14 @@ impl ErrorKind {
         matches!(self, Self::NullReference)
     }
 
+    /// Returns `true` if the error kind is [`NonContiguous`].
+    ///
+    /// [`NonContiguous`]: ErrorKind::NonContiguous
+    #[must_use]
+    pub(crate) const fn is_non_contiguous(&self) -> bool {
+        matches!(self, Self::NonContiguous { .. })
+    }
+
     /// Returns `true` if the error kind is [`OutOfBounds`].
     ///
     /// [`OutOfBounds`]: ErrorKind::OutOfBounds
@@ -150,6 +260,86 @@ impl ErrorKind {
         matches!(self, Self::OutOfBounds { .. })
     }
 
+    /// Returns `true` if the error kind is [`Cancelled`].
+    ///
+    /// [`Cancelled`]: ErrorKind::Cancelled
+    #[must_use]
+    pub(crate) const fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled)
+    }
+
+    /// Returns `true` if the error kind is [`VersionMismatch`].
+    ///
+    /// [`VersionMismatch`]: ErrorKind::VersionMismatch
+    #[must_use]
+    pub(crate) const fn is_version_mismatch(&self) -> bool {
+        matches!(self, Self::VersionMismatch { .. })
+    }
+
+    /// Returns `true` if the error kind is [`ArenaExhausted`].
+    ///
+    /// [`ArenaExhausted`]: ErrorKind::ArenaExhausted
+    #[must_use]
+    pub(crate) const fn is_arena_exhausted(&self) -> bool {
+        matches!(self, Self::ArenaExhausted { .. })
+    }
+
+    /// Returns `true` if the error kind is [`ValidationFailed`].
+    ///
+    /// [`ValidationFailed`]: ErrorKind::ValidationFailed
+    #[must_use]
+    pub(crate) const fn is_validation_failed(&self) -> bool {
+        matches!(self, Self::ValidationFailed { .. })
+    }
+
+    /// Returns `true` if the error kind is [`InvalidValue`].
+    ///
+    /// [`InvalidValue`]: ErrorKind::InvalidValue
+    #[must_use]
+    pub(crate) const fn is_invalid_value(&self) -> bool {
+        matches!(self, Self::InvalidValue { .. })
+    }
+
+    /// Returns `true` if the error kind is [`BadMagic`].
+    ///
+    /// [`BadMagic`]: ErrorKind::BadMagic
+    #[must_use]
+    pub(crate) const fn is_bad_magic(&self) -> bool {
+        matches!(self, Self::BadMagic { .. })
+    }
+
+    /// Returns `true` if the error kind is [`UnknownDiscriminant`].
+    ///
+    /// [`UnknownDiscriminant`]: ErrorKind::UnknownDiscriminant
+    #[must_use]
+    pub(crate) const fn is_unknown_discriminant(&self) -> bool {
+        matches!(self, Self::UnknownDiscriminant { .. })
+    }
+
+    /// Returns `true` if the error kind is [`Incomplete`].
+    ///
+    /// [`Incomplete`]: ErrorKind::Incomplete
+    #[must_use]
+    pub(crate) const fn is_incomplete(&self) -> bool {
+        matches!(self, Self::Incomplete { .. })
+    }
+
+    /// Returns `true` if the error kind is [`LimitExceeded`],
+    /// [`ElementLimitExceeded`] or [`DepthLimitExceeded`].
+    ///
+    /// [`LimitExceeded`]: ErrorKind::LimitExceeded
+    /// [`ElementLimitExceeded`]: ErrorKind::ElementLimitExceeded
+    /// [`DepthLimitExceeded`]: ErrorKind::DepthLimitExceeded
+    #[must_use]
+    pub(crate) const fn is_limit_exceeded(&self) -> bool {
+        matches!(
+            self,
+            Self::LimitExceeded { .. }
+                | Self::ElementLimitExceeded { .. }
+                | Self::DepthLimitExceeded { .. }
+        )
+    }
+
     /// Returns `true` if the error kind is [`SizeMismatch`].
     ///
     /// [`SizeMismatch`]: ErrorKind::SizeMismatch
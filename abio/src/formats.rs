@@ -0,0 +1,4 @@
+//! Structural parsers for common binary formats, built on the [`Reader`][crate::Reader]
+//! cursor and the integer decoders of this crate.
+
+pub mod zip;
@@ -0,0 +1,513 @@
+//! Minimal structural parsing of ZIP archives.
+//!
+//! This module locates the end of central directory record by scanning backwards
+//! from the end of the input, walks the central directory it points to, and
+//! resolves the local file header and data of each entry. It does not decompress
+//! or verify the contents of entries, and does not support ZIP64 or multi-disk
+//! archives.
+//!
+//! All multi-byte fields in a ZIP archive are stored in little endian byte order.
+
+use crate::{Decode, Error, Reader, Result, SeekFrom, LE};
+
+/// Signature of the end of central directory record, `PK\x05\x06`.
+const EOCD_SIGNATURE: &[u8; 4] = b"PK\x05\x06";
+
+/// Signature of a central directory file header, `PK\x01\x02`.
+const CENTRAL_HEADER_SIGNATURE: &[u8; 4] = b"PK\x01\x02";
+
+/// Signature of a local file header, `PK\x03\x04`.
+const LOCAL_HEADER_SIGNATURE: &[u8; 4] = b"PK\x03\x04";
+
+#[inline]
+fn read_u16(reader: &mut Reader<'_>) -> Result<u16> {
+    let (value, _) = u16::decode::<LE>(reader.read_bytes(2)?)?;
+    Ok(value)
+}
+
+#[inline]
+fn read_u32(reader: &mut Reader<'_>) -> Result<u32> {
+    let (value, _) = u32::decode::<LE>(reader.read_bytes(4)?)?;
+    Ok(value)
+}
+
+/// Reads a signature, returning an error if it does not match `expected`.
+#[inline]
+fn expect_signature(reader: &mut Reader<'_>, expected: &'static [u8; 4]) -> Result<()> {
+    let offset = reader.position();
+    let actual = reader.read_bytes(expected.len())?;
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(Error::bad_magic(expected, actual, offset))
+    }
+}
+
+/// The end of central directory record, stored at the end of every ZIP archive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EndOfCentralDirectory<'data> {
+    /// Number of the disk containing this record.
+    pub disk_number: u16,
+    /// Number of the disk on which the central directory starts.
+    pub central_directory_disk: u16,
+    /// Number of central directory records stored on this disk.
+    pub disk_entries: u16,
+    /// Total number of central directory records.
+    pub total_entries: u16,
+    /// Size of the central directory, in bytes.
+    pub central_directory_size: u32,
+    /// Offset of the start of the central directory, relative to the start of the
+    /// archive.
+    pub central_directory_offset: u32,
+    /// Archive comment.
+    pub comment: &'data [u8],
+}
+
+impl<'data> EndOfCentralDirectory<'data> {
+    /// Size of the record, in bytes, excluding the variable-length comment.
+    pub const MIN_SIZE: usize = 22;
+
+    /// Scans backwards from the end of `bytes` for the end of central directory
+    /// record, returning its offset.
+    ///
+    /// The record ends with a comment of up to 65535 bytes, so only the tail of the
+    /// input is searched. A candidate is only accepted if its comment extends
+    /// exactly to the end of the input, which rejects signatures that happen to
+    /// appear inside the comment or the compressed data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no valid record is found.
+    pub fn locate(bytes: &'data [u8]) -> Result<usize> {
+        let last = bytes
+            .len()
+            .checked_sub(Self::MIN_SIZE)
+            .ok_or_else(|| Error::out_of_bounds(Self::MIN_SIZE, bytes.len()))?;
+        let first = last.saturating_sub(usize::from(u16::MAX));
+
+        let mut reader = Reader::new(bytes);
+        for offset in (first..=last).rev() {
+            reader.set_position(offset)?;
+            if reader.read_bytes(EOCD_SIGNATURE.len())? != EOCD_SIGNATURE {
+                continue;
+            }
+            reader.seek(SeekFrom::Current(16))?;
+            if usize::from(read_u16(&mut reader)?) == reader.remaining() {
+                return Ok(offset);
+            }
+        }
+        Err(Error::bad_magic(EOCD_SIGNATURE, &bytes[last..last + EOCD_SIGNATURE.len()], last))
+    }
+
+    /// Locates and parses the end of central directory record of the archive in
+    /// `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no valid record is found.
+    pub fn find(bytes: &'data [u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        reader.set_position(Self::locate(bytes)?)?;
+        Self::parse(&mut reader)
+    }
+
+    /// Parses a record starting at the position of `reader`, including its
+    /// signature, and advances the reader past it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature does not match, or if the reader ends
+    /// before the end of the record.
+    pub fn parse(reader: &mut Reader<'data>) -> Result<Self> {
+        expect_signature(reader, EOCD_SIGNATURE)?;
+        let disk_number = read_u16(reader)?;
+        let central_directory_disk = read_u16(reader)?;
+        let disk_entries = read_u16(reader)?;
+        let total_entries = read_u16(reader)?;
+        let central_directory_size = read_u32(reader)?;
+        let central_directory_offset = read_u32(reader)?;
+        let comment_len = read_u16(reader)?;
+        let comment = reader.read_bytes(comment_len.into())?;
+        Ok(Self {
+            disk_number,
+            central_directory_disk,
+            disk_entries,
+            total_entries,
+            central_directory_size,
+            central_directory_offset,
+            comment,
+        })
+    }
+}
+
+/// A central directory file header, describing a single entry of the archive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CentralDirectoryHeader<'data> {
+    /// Version of the specification used by the tool that created the entry, and
+    /// its host system.
+    pub version_made_by: u16,
+    /// Minimum version of the specification needed to extract the entry.
+    pub version_needed: u16,
+    /// General purpose bit flags.
+    pub flags: u16,
+    /// Compression method used to store the data of the entry.
+    pub compression: u16,
+    /// Last modification time, in MS-DOS format.
+    pub modified_time: u16,
+    /// Last modification date, in MS-DOS format.
+    pub modified_date: u16,
+    /// CRC-32 of the uncompressed data.
+    pub crc32: u32,
+    /// Size of the stored data, in bytes.
+    pub compressed_size: u32,
+    /// Size of the data after decompression, in bytes.
+    pub uncompressed_size: u32,
+    /// Number of the disk on which the entry starts.
+    pub disk_start: u16,
+    /// Internal file attributes.
+    pub internal_attributes: u16,
+    /// External file attributes, whose meaning depends on the host system.
+    pub external_attributes: u32,
+    /// Offset of the local file header of the entry, relative to the start of the
+    /// archive.
+    pub local_header_offset: u32,
+    /// Name of the entry.
+    pub file_name: &'data [u8],
+    /// Extra field.
+    pub extra: &'data [u8],
+    /// Entry comment.
+    pub comment: &'data [u8],
+}
+
+impl<'data> CentralDirectoryHeader<'data> {
+    /// Size of the header, in bytes, excluding its variable-length fields.
+    pub const MIN_SIZE: usize = 46;
+
+    /// Parses a header starting at the position of `reader`, including its
+    /// signature, and advances the reader past it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature does not match, or if the reader ends
+    /// before the end of the header.
+    pub fn parse(reader: &mut Reader<'data>) -> Result<Self> {
+        expect_signature(reader, CENTRAL_HEADER_SIGNATURE)?;
+        let version_made_by = read_u16(reader)?;
+        let version_needed = read_u16(reader)?;
+        let flags = read_u16(reader)?;
+        let compression = read_u16(reader)?;
+        let modified_time = read_u16(reader)?;
+        let modified_date = read_u16(reader)?;
+        let crc32 = read_u32(reader)?;
+        let compressed_size = read_u32(reader)?;
+        let uncompressed_size = read_u32(reader)?;
+        let file_name_len = read_u16(reader)?;
+        let extra_len = read_u16(reader)?;
+        let comment_len = read_u16(reader)?;
+        let disk_start = read_u16(reader)?;
+        let internal_attributes = read_u16(reader)?;
+        let external_attributes = read_u32(reader)?;
+        let local_header_offset = read_u32(reader)?;
+        let file_name = reader.read_bytes(file_name_len.into())?;
+        let extra = reader.read_bytes(extra_len.into())?;
+        let comment = reader.read_bytes(comment_len.into())?;
+        Ok(Self {
+            version_made_by,
+            version_needed,
+            flags,
+            compression,
+            modified_time,
+            modified_date,
+            crc32,
+            compressed_size,
+            uncompressed_size,
+            disk_start,
+            internal_attributes,
+            external_attributes,
+            local_header_offset,
+            file_name,
+            extra,
+            comment,
+        })
+    }
+}
+
+/// A local file header, stored immediately before the data of an entry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LocalFileHeader<'data> {
+    /// Minimum version of the specification needed to extract the entry.
+    pub version_needed: u16,
+    /// General purpose bit flags.
+    pub flags: u16,
+    /// Compression method used to store the data of the entry.
+    pub compression: u16,
+    /// Last modification time, in MS-DOS format.
+    pub modified_time: u16,
+    /// Last modification date, in MS-DOS format.
+    pub modified_date: u16,
+    /// CRC-32 of the uncompressed data, or zero if it is stored in a data
+    /// descriptor following the data.
+    pub crc32: u32,
+    /// Size of the stored data, or zero if it is stored in a data descriptor.
+    pub compressed_size: u32,
+    /// Size of the data after decompression, or zero if it is stored in a data
+    /// descriptor.
+    pub uncompressed_size: u32,
+    /// Name of the entry.
+    pub file_name: &'data [u8],
+    /// Extra field.
+    pub extra: &'data [u8],
+}
+
+impl<'data> LocalFileHeader<'data> {
+    /// Size of the header, in bytes, excluding its variable-length fields.
+    pub const MIN_SIZE: usize = 30;
+
+    /// Parses a header starting at the position of `reader`, including its
+    /// signature, and advances the reader past it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature does not match, or if the reader ends
+    /// before the end of the header.
+    pub fn parse(reader: &mut Reader<'data>) -> Result<Self> {
+        expect_signature(reader, LOCAL_HEADER_SIGNATURE)?;
+        let version_needed = read_u16(reader)?;
+        let flags = read_u16(reader)?;
+        let compression = read_u16(reader)?;
+        let modified_time = read_u16(reader)?;
+        let modified_date = read_u16(reader)?;
+        let crc32 = read_u32(reader)?;
+        let compressed_size = read_u32(reader)?;
+        let uncompressed_size = read_u32(reader)?;
+        let file_name_len = read_u16(reader)?;
+        let extra_len = read_u16(reader)?;
+        let file_name = reader.read_bytes(file_name_len.into())?;
+        let extra = reader.read_bytes(extra_len.into())?;
+        Ok(Self {
+            version_needed,
+            flags,
+            compression,
+            modified_time,
+            modified_date,
+            crc32,
+            compressed_size,
+            uncompressed_size,
+            file_name,
+            extra,
+        })
+    }
+}
+
+/// A ZIP archive, borrowed from a byte slice.
+#[derive(Clone, Copy, Debug)]
+pub struct Archive<'data> {
+    bytes: &'data [u8],
+    eocd: EndOfCentralDirectory<'data>,
+}
+
+impl<'data> Archive<'data> {
+    /// Parses the archive stored in `bytes` by locating its end of central
+    /// directory record.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no valid end of central directory record is found.
+    pub fn parse(bytes: &'data [u8]) -> Result<Self> {
+        let eocd = EndOfCentralDirectory::find(bytes)?;
+        Ok(Self { bytes, eocd })
+    }
+
+    /// Returns the end of central directory record of the archive.
+    #[inline]
+    pub const fn end_of_central_directory(&self) -> &EndOfCentralDirectory<'data> {
+        &self.eocd
+    }
+
+    /// Returns the number of entries recorded in the central directory.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.eocd.total_entries as usize
+    }
+
+    /// Returns `true` if the archive does not contain any entries.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.eocd.total_entries == 0
+    }
+
+    /// Returns an iterator over the central directory headers of the archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the central directory lies outside of the archive.
+    pub fn entries(&self) -> Result<CentralDirectory<'data>> {
+        let mut reader = Reader::new(self.bytes);
+        reader.seek(SeekFrom::Start(
+            self.eocd
+                .central_directory_offset
+                .into(),
+        ))?;
+        let reader = reader.take(self.eocd.central_directory_size as usize)?;
+        Ok(CentralDirectory { reader, remaining: self.eocd.total_entries })
+    }
+
+    /// Resolves the local file header of `entry`, returning it along with the
+    /// stored, possibly compressed, data of the entry.
+    ///
+    /// The size of the data is taken from the central directory, since local
+    /// headers may defer it to a data descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the local header is invalid, or if the header or data
+    /// lie outside of the archive.
+    pub fn local_entry(
+        &self,
+        entry: &CentralDirectoryHeader<'_>,
+    ) -> Result<(LocalFileHeader<'data>, &'data [u8])> {
+        let mut reader = Reader::new(self.bytes);
+        reader.seek(SeekFrom::Start(entry.local_header_offset.into()))?;
+        let header = LocalFileHeader::parse(&mut reader)?;
+        let data = reader.read_bytes(entry.compressed_size as usize)?;
+        Ok((header, data))
+    }
+}
+
+/// Iterator over the [`CentralDirectoryHeader`]s of an [`Archive`].
+///
+/// Iteration stops after the first error.
+#[derive(Clone, Copy, Debug)]
+pub struct CentralDirectory<'data> {
+    reader: Reader<'data>,
+    remaining: u16,
+}
+
+impl<'data> Iterator for CentralDirectory<'data> {
+    type Item = Result<CentralDirectoryHeader<'data>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let header = CentralDirectoryHeader::parse(&mut self.reader);
+        self.remaining = match header {
+            Ok(_) => self.remaining - 1,
+            Err(_) => 0,
+        };
+        Some(header)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining.into()))
+    }
+}
+
+impl core::iter::FusedIterator for CentralDirectory<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::BufBuilder;
+
+    /// Builds an archive holding a single stored entry named `hello.txt`.
+    fn single_entry_archive() -> alloc::vec::Vec<u8> {
+        let data = b"hello, zip";
+        let local = BufBuilder::new()
+            .bytes(LOCAL_HEADER_SIGNATURE)
+            .u16_le(10)
+            .u16_le(0)
+            .u16_le(0)
+            .u16_le(0x6000)
+            .u16_le(0x5821)
+            .u32_le(0xdead_beef)
+            .u32_le(data.len() as u32)
+            .u32_le(data.len() as u32)
+            .u16_le(9)
+            .u16_le(0)
+            .bytes(b"hello.txt")
+            .bytes(data);
+        let central_offset = local.len() as u32;
+        let archive = local
+            .bytes(CENTRAL_HEADER_SIGNATURE)
+            .u16_le(0x031e)
+            .u16_le(10)
+            .u16_le(0)
+            .u16_le(0)
+            .u16_le(0x6000)
+            .u16_le(0x5821)
+            .u32_le(0xdead_beef)
+            .u32_le(data.len() as u32)
+            .u32_le(data.len() as u32)
+            .u16_le(9)
+            .u16_le(0)
+            .u16_le(0)
+            .u16_le(0)
+            .u16_le(0)
+            .u32_le(0)
+            .u32_le(0)
+            .bytes(b"hello.txt");
+        let central_size = archive.len() as u32 - central_offset;
+        archive
+            .bytes(EOCD_SIGNATURE)
+            .u16_le(0)
+            .u16_le(0)
+            .u16_le(1)
+            .u16_le(1)
+            .u32_le(central_size)
+            .u32_le(central_offset)
+            .u16_le(8)
+            .bytes(b"PK\x05\x06 :-)")
+            .build()
+    }
+
+    #[test]
+    fn parses_single_entry_archive() {
+        let bytes = single_entry_archive();
+        let archive = Archive::parse(&bytes).unwrap();
+        assert_eq!(archive.len(), 1);
+        assert_eq!(
+            archive
+                .end_of_central_directory()
+                .comment,
+            b"PK\x05\x06 :-)"
+        );
+
+        let mut entries = archive.entries().unwrap();
+        let entry = entries.next().unwrap().unwrap();
+        assert!(entries.next().is_none());
+        assert_eq!(entry.file_name, b"hello.txt");
+        assert_eq!(entry.crc32, 0xdead_beef);
+
+        let (header, data) = archive
+            .local_entry(&entry)
+            .unwrap();
+        assert_eq!(header.file_name, b"hello.txt");
+        assert_eq!(data, b"hello, zip");
+    }
+
+    #[test]
+    fn rejects_truncated_archives() {
+        let bytes = single_entry_archive();
+        assert!(Archive::parse(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Archive::parse(&bytes[..10]).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_signatures() {
+        let mut bytes = single_entry_archive();
+        bytes[3] = 0x05;
+        let archive = Archive::parse(&bytes).unwrap();
+        let entry = archive
+            .entries()
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let err = archive
+            .local_entry(&entry)
+            .unwrap_err();
+        assert!(err.is_bad_magic());
+    }
+}
@@ -4,9 +4,7 @@
 #[macro_use]
 mod macros;
 
-use core::mem;
-
-use crate::{util, Abi, Alignment, Endianness, Error};
+use crate::{Endianness, Error};
 
 gen_aligned_integer! {
     "An 8-bit",  "signed", I8, i8, 1,
@@ -33,43 +31,56 @@ impl_fmt_traits! {
     I8, I16, I32, I64, I128, Isize, U8, U16, U32, U64, U128, Usize
 }
 
-macro_rules! impl_decode_aligned {
+macro_rules! impl_codec_integer {
     ($($ty:ty, $size:literal),* $(,)?) => {
         $(
-            impl<'de> $crate::codec::Decode<'de> for $ty {
-                fn decode<E: $crate::Endianness>(bytes: &'de [u8], offset: usize) -> $crate::Result<(&'de $ty, usize)> {
-                    let res = match E::ENDIAN {
-                        $crate::Endian::Little => $crate::util::read_le_bytes(bytes),
-                        $crate::Endian::Big => $crate::util::read_be_bytes(bytes),
+            impl<'data> $crate::codec::Decode<'data> for $ty {
+                #[inline]
+                fn decode<E: Endianness>(bytes: &'data [u8]) -> $crate::Result<($ty, usize)> {
+                    #[cfg(feature = "stats")]
+                    $crate::codec::stats::record_bounds_check();
+                    let Some(array) = bytes.first_chunk::<$size>() else {
+                        return Err(Error::out_of_bounds($size, bytes.len()));
+                    };
+                    #[cfg(feature = "stats")]
+                    $crate::codec::stats::record_read($size);
+                    let value = match E::ENDIAN {
+                        $crate::Endian::Little => <$ty>::from_le_bytes(*array),
+                        $crate::Endian::Big => <$ty>::from_be_bytes(*array),
+                    };
+                    Ok((value, $size))
+                }
+            }
+
+            impl $crate::codec::Encode for $ty {
+                #[inline]
+                fn encode<E: Endianness>(&self, buf: &mut [u8]) -> $crate::Result<usize> {
+                    let buf_len = buf.len();
+                    let Some(array) = buf.first_chunk_mut::<$size>() else {
+                        return Err(Error::out_of_bounds($size, buf_len));
+                    };
+                    *array = match E::ENDIAN {
+                        $crate::Endian::Little => self.to_le_bytes(),
+                        $crate::Endian::Big => self.to_be_bytes(),
                     };
-                    let ptr = res.as_ptr();
-                    if ptr.is_aligned_with::<Self>() {
-                        Ok(&*(ptr as *const Self, Self::SIZE))
-                    } else {
-                        Err($crate::Error::misaligned_access(ptr))
-                    }
+                    Ok($size)
                 }
             }
         )*
     };
 }
 
-impl<'de> crate::codec::Decode<'de> for u32 {
-    fn decode<E: Endianness>(bytes: &[u8]) -> crate::Result<(&'de Self, usize)> {
-        let res = match E::ENDIAN {
-            crate::Endian::Little => crate::util::read_le_bytes(bytes),
-            crate::Endian::Big => crate::util::read_be_bytes(bytes),
-        };
-        let ptr = res.as_ptr();
-        if ptr.is_aligned_with::<Self>() {
-            Ok(&*(ptr as *const u32, u32::SIZE))
-        } else {
-            Err(crate::Error::misaligned_access(ptr))
-        }
-    }
-}
-
-impl_decode_aligned! {
+impl_codec_integer! {
+    u8, 1,
+    u16, 2,
+    u32, 4,
+    u64, 8,
+    u128, 16,
+    i8, 1,
+    i16, 2,
+    i32, 4,
+    i64, 8,
+    i128, 16,
     U8, 1,
     U16, 2,
     U32, 4,
@@ -81,41 +92,3 @@ impl_decode_aligned! {
     I64, 8,
     I128, 16,
 }
-
-impl<'de> crate::Decode<'de> for u8 {
-    fn decode<E: Endianness>(bytes: &[u8]) -> crate::Result<(&'de Self, usize)> {
-        let res = unsafe {
-            bytes
-                .as_ptr()
-                .cast::<Self>()
-                .as_ref()
-        };
-        let res = res.ok_or_else(|| Error::decoder_failed())?;
-        Ok((res, mem::size_of_val(res)))
-    }
-}
-
-impl<'de> crate::Decode<'de> for u16 {
-    fn decode<E: crate::Endianness>(
-        bytes: &'de [u8],
-        offset: usize,
-    ) -> crate::Result<(&'de Self, usize)> {
-        // Try to read a chunk with given `$size`, starting at `offset`
-        let Ok(chunk) = crate::Chunk::<2>::read_bytes_offset::<E>(bytes, offset) else {
-            return Err(crate::Error::decoder_failed());
-        };
-        // Check if the returned chunk lies on a byte boundary with matching requirements of
-        // `T`.
-        let ptr = chunk.as_ptr();
-        if !ptr.is_aligned_with::<u16>() {
-            Err(crate::Error::misaligned_access::<Self>(chunk.as_ptr().addr()))
-        } else {
-            // Encode endianness stored in the `Context`, and decode chunk appropriately.
-            let target = match E::ENDIAN {
-                crate::Endian::Little => u16::from_le_bytes(chunk.to_le_bytes()),
-                crate::Endian::Big => u16::from_be_bytes(chunk.to_be_bytes()),
-            };
-            Ok((&target, 2))
-        }
-    }
-}
@@ -233,12 +233,12 @@ macro_rules! gen_aligned_integer {
                 }
             }
 
-            impl const $crate::util::FromInner<$inner> for $Type {
+            impl const $crate::convert::FromInner<$inner> for $Type {
                 fn from_inner(inner: $inner) -> $Type {
                     Self::from_ne(inner)
                 }
             }
-            impl const $crate::util::IntoInner<$inner> for $Type {
+            impl const $crate::convert::IntoInner<$inner> for $Type {
                 fn into_inner(self) -> $inner {
                     self.get_ne()
                 }
@@ -0,0 +1,90 @@
+//! Adapters for embedding [`abio`][crate] types inside messages produced by other
+//! serialization frameworks.
+//!
+//! This module is only available when the __`serde`__ feature is enabled. The
+//! adapters work with any `serde` data format, although they are primarily designed
+//! for compact binary formats such as `postcard`.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use crate::{AsBytes, Decode, NativeEndian};
+
+/// Wrapper that serializes a value as an opaque byte string containing its wire
+/// representation.
+///
+/// Serializing an [`Embedded`] value writes the bytes returned by
+/// [`AsBytes::as_bytes`], and deserializing it decodes them again using
+/// [`Decode`] with the [`NativeEndian`] byte order. This allows a type described by
+/// [`abio`][crate] to travel inside a `postcard` message without implementing the
+/// `serde` traits for the type itself.
+///
+/// Deserialization borrows the byte string from the input, so it requires a data
+/// format that can lend out borrowed bytes, such as `postcard::from_bytes`.
+///
+/// # Portability
+///
+/// The embedded bytes use the byte order of the target that serialized them. Use an
+/// explicit wire format, such as the aligned integer types, if the message must be
+/// read on targets with a different byte order.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct Embedded<T>(pub T);
+
+impl<T> Embedded<T> {
+    /// Creates a new [`Embedded`] wrapper around `value`.
+    #[inline]
+    pub const fn new(value: T) -> Embedded<T> {
+        Embedded(value)
+    }
+
+    /// Consumes the wrapper, returning the inner value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Embedded<T> {
+    #[inline]
+    fn from(value: T) -> Embedded<T> {
+        Embedded(value)
+    }
+}
+
+impl<T: AsBytes> Serialize for Embedded<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0.as_bytes())
+    }
+}
+
+impl<'de, T: Decode<'de>> Deserialize<'de> for Embedded<T> {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(EmbeddedVisitor(PhantomData))
+    }
+}
+
+/// Visitor that decodes the byte string written by [`Embedded`].
+struct EmbeddedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Decode<'de>> Visitor<'de> for EmbeddedVisitor<T> {
+    type Value = Embedded<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a byte string containing the wire representation of a value")
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, bytes: &'de [u8]) -> Result<Self::Value, E> {
+        let (value, consumed) = T::decode::<NativeEndian>(bytes).map_err(E::custom)?;
+        if consumed != bytes.len() {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+        Ok(Embedded(value))
+    }
+}
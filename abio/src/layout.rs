@@ -0,0 +1,73 @@
+//! Introspection of the wire layout of ABI-compatible types.
+//!
+//! # Fingerprints
+//!
+//! Every struct, enum and union deriving [`Abi`][crate::Abi] also receives a
+//! [`Fingerprint`] implementation. The fingerprint is a stable hash of the type's
+//! resolved wire layout, computed at compile time from its size, alignment and byte
+//! order, the offsets, sizes and alignments of its fields in declaration order, and
+//! the discriminant range of enums. Pinning the fingerprint with the
+//! [`assert_wire_compat!`] macro turns accidental layout-breaking edits into
+//! compile errors, rather than silently corrupting data that was persisted using
+//! an older definition of the type.
+//!
+//! [`assert_wire_compat!`]: crate::assert_wire_compat
+//!
+//! # Descriptors
+//!
+//! Structs deriving `Abi` also implement [`Describe`], exposing the name, offset and
+//! size of each field as a [`Descriptor`]. Descriptors drive
+//! [`decode_stream`][crate::codec::decode_stream], which walks the fields of an
+//! input without decoding them into typed values.
+//!
+//! The [`padding_spans`] of a type list the bytes that are not covered by any
+//! field, so that they can be zeroed or checked before a buffer holding the type
+//! is written to disk or sent over the network.
+//!
+//! # Layout reports
+//!
+//! [`Descriptor::to_json`] renders a layout as JSON, including the padding before
+//! each field and at the end of the type. Setting the [`ABIO_DEBUG`] environment
+//! variable to a directory while compiling types deriving `Abi` generates a test
+//! per type, writing its report into that directory when the tests are run, so
+//! that layouts can be diffed across targets. The tests are only generated with
+//! the `std` feature enabled.
+//!
+//! The variable is read when the derive expands, and the expansion records it as a
+//! dependency with `option_env!`, so changing it rebuilds the crates deriving `Abi`
+//! with or without the tests.
+//!
+//! With the `cgen` feature, [`c_header`] renders descriptors as C struct
+//! declarations with static assertions on their size, alignment and field
+//! offsets. Calling it from a build script keeps FFI headers in lockstep with the
+//! Rust definitions of the types.
+//!
+//! # Discriminants
+//!
+//! Fieldless enums with an explicit integer repr may also derive `Abi`, provided
+//! their discriminants are contiguous. Such enums receive a [`DiscriminantRange`]
+//! implementation instead, recording the range of integers that may be safely
+//! reinterpreted as the enum. [`DiscriminantRange::from_repr`] checks integers
+//! read from an input against that range before converting them.
+
+mod fingerprint;
+pub use fingerprint::{Fingerprint, FingerprintHasher};
+
+mod descriptor;
+pub use descriptor::{Describe, Descriptor, FieldDescriptor};
+
+mod padding;
+pub use padding::{padding_spans, PaddingSpans};
+
+mod report;
+#[cfg(feature = "std")]
+pub use report::write_report;
+pub use report::{Json, ABIO_DEBUG};
+
+#[cfg(feature = "cgen")]
+mod cgen;
+#[cfg(feature = "cgen")]
+pub use cgen::c_header;
+
+mod discriminant;
+pub use discriminant::DiscriminantRange;
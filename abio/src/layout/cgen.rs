@@ -0,0 +1,238 @@
+//! Rendering of wire layouts as C declarations.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use super::Descriptor;
+use crate::Endian;
+
+/// Renders `descriptors` as a C header, declaring a struct for each layout along
+/// with static assertions pinning its size, alignment and field offsets.
+///
+/// Nested layouts are declared before the first struct containing them, and each
+/// layout is declared once. Fields are declared as fixed-width integers where
+/// their size and offset allow it, and as byte arrays otherwise, with explicit
+/// padding members covering the bytes between fields. The declarations are
+/// wrapped in an include guard named `guard`.
+///
+/// This is intended to be called from a build script, keeping FFI headers in
+/// lockstep with the Rust definitions of the types:
+///
+/// ```no_run
+/// use abio::layout::Describe;
+///
+/// # #[derive(abio::Abi)]
+/// # #[repr(C)]
+/// # struct Header {
+/// #     magic: u32,
+/// #     len: u32,
+/// # }
+/// # fn main() -> std::io::Result<()> {
+/// # let out_dir = std::path::PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+/// let header = abio::layout::c_header("WIRE_H", &[&<Header as Describe>::DESCRIPTOR]);
+/// std::fs::write(out_dir.join("wire.h"), header)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn c_header(guard: &str, descriptors: &[&'static Descriptor]) -> String {
+    let mut ordered = Vec::new();
+    for descriptor in descriptors {
+        declare_order(descriptor, &mut ordered);
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "#ifndef {guard}\n#define {guard}\n\n#include <stddef.h>\n#include <stdint.h>"
+    );
+    for descriptor in ordered {
+        out.push('\n');
+        render_struct(descriptor, &mut out);
+    }
+    let _ = writeln!(out, "\n#endif /* {guard} */");
+    out
+}
+
+/// Pushes `descriptor` onto `ordered` after the layouts nested within it, unless it
+/// is already present.
+fn declare_order(descriptor: &'static Descriptor, ordered: &mut Vec<&'static Descriptor>) {
+    if ordered.contains(&descriptor) {
+        return;
+    }
+    for nested in descriptor
+        .fields
+        .iter()
+        .filter_map(|field| field.nested)
+    {
+        declare_order(nested, ordered);
+    }
+    ordered.push(descriptor);
+}
+
+fn render_struct(descriptor: &Descriptor, out: &mut String) {
+    let name = descriptor.name;
+    let packed = descriptor
+        .repr
+        .split(',')
+        .any(|item| item.trim() == "packed");
+
+    if packed {
+        out.push_str("#pragma pack(push, 1)\n");
+    }
+    let _ = writeln!(out, "struct {name} {{");
+    let mut end = 0;
+    let mut pads = 0;
+    for (idx, field) in descriptor
+        .fields
+        .iter()
+        .enumerate()
+    {
+        if field.offset > end {
+            let _ = writeln!(out, "    uint8_t _pad{pads}[{}];", field.offset - end);
+            pads += 1;
+        }
+        // The alignment of the struct may only be raised beyond that of its fields
+        // through its first member.
+        let align = if idx == 0 && descriptor.align > 1 {
+            alloc::format!("_Alignas({}) ", descriptor.align)
+        } else {
+            String::new()
+        };
+        let member = member_name(field.name);
+        let _ = match field.nested {
+            Some(nested) => write!(out, "    {align}struct {} {member};", nested.name),
+            None => match c_integer(field.size, field.offset, descriptor.align, packed) {
+                Some(ty) => write!(out, "    {align}{ty} {member};"),
+                None => write!(out, "    {align}uint8_t {member}[{}];", field.size),
+            },
+        };
+        match field.endian {
+            Some(Endian::Little) => out.push_str(" /* little endian */"),
+            Some(Endian::Big) => out.push_str(" /* big endian */"),
+            None => {}
+        }
+        out.push('\n');
+        end = end.max(field.offset + field.size);
+    }
+    if descriptor.size > end {
+        let _ = writeln!(out, "    uint8_t _pad{pads}[{}];", descriptor.size - end);
+    }
+    out.push_str("};\n");
+    if packed {
+        out.push_str("#pragma pack(pop)\n");
+    }
+
+    let _ = writeln!(
+        out,
+        "_Static_assert(sizeof(struct {name}) == {size}, \"size of {name}\");",
+        size = descriptor.size,
+    );
+    let _ = writeln!(
+        out,
+        "_Static_assert(_Alignof(struct {name}) == {align}, \"alignment of {name}\");",
+        align = descriptor.align,
+    );
+    for field in descriptor.fields {
+        let _ = writeln!(
+            out,
+            "_Static_assert(offsetof(struct {name}, {member}) == {offset}, \"offset of {name}.{field}\");",
+            member = member_name(field.name),
+            offset = field.offset,
+            field = field.name,
+        );
+    }
+}
+
+/// Returns the C integer type of a field, if one with the same size fits at its
+/// offset without raising the alignment of the struct.
+fn c_integer(size: usize, offset: usize, align: usize, packed: bool) -> Option<&'static str> {
+    let ty = match size {
+        1 => "uint8_t",
+        2 => "uint16_t",
+        4 => "uint32_t",
+        8 => "uint64_t",
+        _ => return None,
+    };
+    (packed || (offset % size == 0 && size <= align)).then_some(ty)
+}
+
+/// Returns the C member name of a field, prefixing the indices of tuple fields.
+fn member_name(name: &str) -> String {
+    let mut member = String::new();
+    if name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        member.push('_');
+    }
+    member.push_str(name);
+    member
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::FieldDescriptor;
+
+    static INNER: Descriptor = Descriptor {
+        name: "Inner",
+        size: 3,
+        align: 1,
+        repr: "C, packed",
+        fields: &[
+            FieldDescriptor { name: "0", offset: 0, size: 1, nested: None, endian: None },
+            FieldDescriptor { name: "1", offset: 1, size: 2, nested: None, endian: None },
+        ],
+    };
+
+    static OUTER: Descriptor = Descriptor {
+        name: "Outer",
+        size: 12,
+        align: 4,
+        repr: "C",
+        fields: &[
+            FieldDescriptor {
+                name: "inner",
+                offset: 0,
+                size: 3,
+                nested: Some(&INNER),
+                endian: None,
+            },
+            FieldDescriptor {
+                name: "length",
+                offset: 4,
+                size: 4,
+                nested: None,
+                endian: Some(Endian::Big),
+            },
+            FieldDescriptor { name: "id", offset: 8, size: 3, nested: None, endian: None },
+        ],
+    };
+
+    #[test]
+    fn renders_structs_with_static_assertions() {
+        let header = c_header("OUTER_H", &[&OUTER, &INNER]);
+        let expected = concat!(
+            "#ifndef OUTER_H\n#define OUTER_H\n\n#include <stddef.h>\n#include <stdint.h>\n",
+            "\n#pragma pack(push, 1)\n",
+            "struct Inner {\n    uint8_t _0;\n    uint16_t _1;\n};\n",
+            "#pragma pack(pop)\n",
+            "_Static_assert(sizeof(struct Inner) == 3, \"size of Inner\");\n",
+            "_Static_assert(_Alignof(struct Inner) == 1, \"alignment of Inner\");\n",
+            "_Static_assert(offsetof(struct Inner, _0) == 0, \"offset of Inner.0\");\n",
+            "_Static_assert(offsetof(struct Inner, _1) == 1, \"offset of Inner.1\");\n",
+            "\nstruct Outer {\n",
+            "    _Alignas(4) struct Inner inner;\n",
+            "    uint8_t _pad0[1];\n",
+            "    uint32_t length; /* big endian */\n",
+            "    uint8_t id[3];\n",
+            "    uint8_t _pad1[1];\n",
+            "};\n",
+            "_Static_assert(sizeof(struct Outer) == 12, \"size of Outer\");\n",
+            "_Static_assert(_Alignof(struct Outer) == 4, \"alignment of Outer\");\n",
+            "_Static_assert(offsetof(struct Outer, inner) == 0, \"offset of Outer.inner\");\n",
+            "_Static_assert(offsetof(struct Outer, length) == 4, \"offset of Outer.length\");\n",
+            "_Static_assert(offsetof(struct Outer, id) == 8, \"offset of Outer.id\");\n",
+            "\n#endif /* OUTER_H */\n",
+        );
+        assert_eq!(header, expected);
+    }
+}
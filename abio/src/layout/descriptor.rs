@@ -0,0 +1,149 @@
+//! Runtime descriptions of wire layouts.
+
+use crate::{Endian, Span};
+
+/// Description of the wire layout of a type, listing the byte range of each of its
+/// fields.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Descriptor {
+    /// Name of the type.
+    pub name: &'static str,
+    /// Number of bytes occupied by the type.
+    pub size: usize,
+    /// Alignment of the type, in bytes.
+    pub align: usize,
+    /// Contents of the `#[repr(...)]` attribute of the type, or `"Rust"` if it has
+    /// none.
+    pub repr: &'static str,
+    /// Fields of the type, in declaration order.
+    pub fields: &'static [FieldDescriptor],
+}
+
+impl Descriptor {
+    /// Returns the number of bytes of the type that are not covered by any of its
+    /// fields, including trailing padding.
+    #[inline]
+    pub const fn padding(&self) -> usize {
+        let mut covered = 0;
+        let mut end = 0;
+        let mut idx = 0;
+        while idx < self.fields.len() {
+            let field = &self.fields[idx];
+            let field_end = field.offset + field.size;
+            // Fields of unions overlap, so only the bytes past the furthest end seen
+            // so far are newly covered.
+            if field_end > end {
+                covered += field_end - if field.offset > end { field.offset } else { end };
+                end = field_end;
+            }
+            idx += 1;
+        }
+        self.size
+            .saturating_sub(covered)
+    }
+
+    /// Returns the field named `name`, if the type has one.
+    #[inline]
+    pub fn field(&self, name: &str) -> Option<&'static FieldDescriptor> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+    }
+
+    /// Returns the first field covering the byte at `offset`, if any.
+    ///
+    /// Padding bytes are not covered by any field.
+    #[inline]
+    pub fn field_at(&self, offset: usize) -> Option<&'static FieldDescriptor> {
+        self.fields
+            .iter()
+            .find(|field| (field.offset..field.offset + field.size).contains(&offset))
+    }
+}
+
+/// Description of a single field within a [`Descriptor`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FieldDescriptor {
+    /// Name of the field, or its index for tuple structs.
+    pub name: &'static str,
+    /// Offset of the first byte of the field, relative to the start of the
+    /// containing type.
+    pub offset: usize,
+    /// Number of bytes occupied by the field.
+    pub size: usize,
+    /// Layout of the field, if it should be described field by field rather than
+    /// as an opaque run of bytes.
+    pub nested: Option<&'static Descriptor>,
+    /// Byte order the field is always encoded in, or `None` if it follows the byte
+    /// order chosen for the containing type.
+    pub endian: Option<Endian>,
+}
+
+/// Trait for types with a [`Descriptor`] of their wire layout.
+///
+/// This trait is implemented automatically for structs deriving
+/// [`Abi`][crate::Abi]. Derived descriptors treat every field as an opaque run of
+/// bytes, except for fields marked with `#[abio(nested)]`, whose type must also
+/// implement `Describe`. Fields declaring a fixed byte order with
+/// `#[abio(endian = "...")]` record it in [`FieldDescriptor::endian`].
+pub trait Describe {
+    /// Description of the wire layout of this type.
+    const DESCRIPTOR: Descriptor;
+
+    /// Byte ranges of this type that are not covered by any of its fields, in
+    /// ascending order.
+    ///
+    /// Derived implementations compute the ranges from the descriptor at compile
+    /// time. Hand-written implementations can do the same with [`PaddingSpans`].
+    ///
+    /// [`PaddingSpans`]: super::PaddingSpans
+    const PADDING: &'static [Span];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_fields_by_name_and_offset() {
+        let descriptor = Descriptor {
+            name: "Header",
+            size: 8,
+            align: 4,
+            repr: "C",
+            fields: &[
+                FieldDescriptor { name: "kind", offset: 0, size: 1, nested: None, endian: None },
+                FieldDescriptor {
+                    name: "length",
+                    offset: 4,
+                    size: 4,
+                    nested: None,
+                    endian: Some(Endian::Big),
+                },
+            ],
+        };
+        assert_eq!(
+            descriptor
+                .field("length")
+                .map(|field| field.endian),
+            Some(Some(Endian::Big))
+        );
+        assert!(descriptor
+            .field("missing")
+            .is_none());
+        assert_eq!(
+            descriptor
+                .field_at(0)
+                .map(|field| field.name),
+            Some("kind")
+        );
+        assert_eq!(
+            descriptor
+                .field_at(7)
+                .map(|field| field.name),
+            Some("length")
+        );
+        assert!(descriptor.field_at(2).is_none());
+        assert!(descriptor.field_at(8).is_none());
+    }
+}
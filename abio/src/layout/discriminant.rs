@@ -0,0 +1,58 @@
+//! Valid discriminant ranges of fieldless enums.
+
+use crate::Result;
+
+/// Trait for fieldless enums whose discriminants form a contiguous range.
+///
+/// This trait is implemented automatically when deriving [`Abi`][crate::Abi] for
+/// a fieldless enum with an explicit integer repr, such as `#[repr(u8)]`. The
+/// derive rejects enums whose discriminants have gaps, so every value between
+/// [`MIN`][DiscriminantRange::MIN] and [`MAX`][DiscriminantRange::MAX] inclusive
+/// names a declared variant.
+///
+/// Not every bit pattern of the repr is a valid value of the enum. Integers read
+/// from untrusted input must be checked with
+/// [`contains`][DiscriminantRange::contains] before they are reinterpreted as the
+/// enum, which [`from_repr`][DiscriminantRange::from_repr] does on the caller's
+/// behalf.
+///
+/// ```
+/// use abio::layout::DiscriminantRange;
+/// use abio::Abi;
+///
+/// #[derive(Abi, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Opcode {
+///     Load = 4,
+///     Store,
+/// }
+///
+/// assert_eq!(Opcode::from_repr(5).unwrap(), Opcode::Store);
+/// assert!(Opcode::from_repr(6).unwrap_err().is_unknown_discriminant());
+/// ```
+pub trait DiscriminantRange: Sized {
+    /// Integer type declared in the `#[repr]` attribute of the enum.
+    type Repr: Copy + PartialOrd;
+
+    /// Smallest discriminant of any variant.
+    const MIN: Self::Repr;
+
+    /// Largest discriminant of any variant.
+    const MAX: Self::Repr;
+
+    /// Returns `true` if `value` is the discriminant of a declared variant.
+    #[inline]
+    fn contains(value: Self::Repr) -> bool {
+        Self::MIN <= value && value <= Self::MAX
+    }
+
+    /// Returns the variant whose discriminant is `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` is not the discriminant of any variant, for which
+    /// [`Error::is_unknown_discriminant`] returns `true`.
+    ///
+    /// [`Error::is_unknown_discriminant`]: crate::Error::is_unknown_discriminant
+    fn from_repr(value: Self::Repr) -> Result<Self>;
+}
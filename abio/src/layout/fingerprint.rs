@@ -0,0 +1,230 @@
+//! Stable, compile-time hashing of wire layouts.
+
+/// Trait for types with a stable fingerprint describing their wire layout.
+///
+/// This trait is implemented automatically when deriving [`Abi`][crate::Abi], for
+/// structs, enums and unions alike. The fingerprint is computed from the resolved
+/// layout of the type rather than from its source code: its size and alignment,
+/// the byte order of the target, the offset, size and alignment of each field in
+/// declaration order, and the range of discriminants of enums.
+///
+/// Renaming the type or its fields, or spelling the type of a field differently,
+/// such as `u32` and `core::primitive::u32`, does not change the fingerprint.
+/// Reordering, resizing or realigning fields does.
+///
+/// # Stability
+///
+/// The hashing algorithm is part of the public contract of this trait. A given
+/// layout produces the same fingerprint on every target with the same byte order,
+/// field sizes, alignments and offsets, across compilations and compiler versions.
+pub trait Fingerprint {
+    /// Stable hash of the wire layout of this type.
+    const FINGERPRINT: u64;
+}
+
+/// A `const`-compatible builder used to compute [`Fingerprint`] values.
+///
+/// The hasher uses the 64-bit [FNV-1a][fnv] algorithm, which is simple enough to
+/// be evaluated at compile time and stable across platforms.
+///
+/// [fnv]: https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FingerprintHasher {
+    state: u64,
+}
+
+impl FingerprintHasher {
+    /// FNV-1a 64-bit offset basis.
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+    /// FNV-1a 64-bit prime.
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// Creates a new hasher with the initial FNV-1a state.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { state: Self::OFFSET_BASIS }
+    }
+
+    /// Feeds a slice of bytes into the hasher.
+    #[inline]
+    pub const fn write_bytes(mut self, bytes: &[u8]) -> Self {
+        let mut pos = 0;
+        while pos < bytes.len() {
+            self.state ^= bytes[pos] as u64;
+            self.state = self
+                .state
+                .wrapping_mul(Self::PRIME);
+            pos += 1;
+        }
+        self
+    }
+
+    /// Feeds a string into the hasher, followed by a terminating NUL byte so that
+    /// adjacent strings cannot be confused for one another.
+    #[inline]
+    pub const fn write_str(self, s: &str) -> Self {
+        self.write_bytes(s.as_bytes())
+            .write_bytes(&[0])
+    }
+
+    /// Feeds an integer into the hasher using a fixed, little endian encoding.
+    ///
+    /// The value is widened to 64 bits first, so the result does not depend on the
+    /// pointer width of the target.
+    #[inline]
+    pub const fn write_usize(self, value: usize) -> Self {
+        self.write_bytes(&(value as u64).to_le_bytes())
+    }
+
+    /// Feeds the size and alignment of a type into the hasher, followed by the byte
+    /// order of the target, which values of the type are stored in.
+    #[inline]
+    pub const fn write_layout(self, size: usize, align: usize) -> Self {
+        self.write_usize(size)
+            .write_usize(align)
+            .write_bytes(&[cfg!(target_endian = "big") as u8])
+    }
+
+    /// Feeds the offset, size and alignment of a single field into the hasher.
+    #[inline]
+    pub const fn write_field(self, offset: usize, size: usize, align: usize) -> Self {
+        self.write_usize(offset)
+            .write_usize(size)
+            .write_usize(align)
+    }
+
+    /// Feeds the smallest and largest discriminants of an enum into the hasher.
+    #[inline]
+    pub const fn write_discriminants(self, min: i128, max: i128) -> Self {
+        self.write_bytes(&min.to_le_bytes())
+            .write_bytes(&max.to_le_bytes())
+    }
+
+    /// Returns the fingerprint computed from all of the data written so far.
+    #[inline]
+    pub const fn finish(self) -> u64 {
+        self.state
+    }
+}
+
+impl Default for FingerprintHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Asserts at compile time that the wire layout of a type matches a known
+/// [`Fingerprint`].
+///
+/// Use this macro to pin the layout of types that are persisted or exchanged with
+/// other programs. If the layout of the type changes, the build fails instead of
+/// silently producing incompatible data.
+///
+/// # Example
+///
+/// Pinning the fingerprint of the first version of a header to the fingerprint of
+/// every later version keeps them readable by each other. Renaming fields keeps
+/// the layout, and so the fingerprint:
+///
+/// ```
+/// use abio::layout::Fingerprint;
+/// use abio::{assert_wire_compat, Abi};
+///
+/// #[derive(Abi)]
+/// #[repr(C)]
+/// struct HeaderV1 {
+///     magic: u32,
+///     version: u32,
+/// }
+///
+/// #[derive(Abi)]
+/// #[repr(C)]
+/// struct HeaderV2 {
+///     signature: u32,
+///     revision: core::primitive::u32,
+/// }
+///
+/// const HEADER_V1: u64 = <HeaderV1 as Fingerprint>::FINGERPRINT;
+/// assert_wire_compat!(HeaderV2, HEADER_V1);
+/// ```
+///
+/// Changing the layout, here by narrowing a field, fails to compile:
+///
+/// ```compile_fail
+/// # use abio::layout::Fingerprint;
+/// # use abio::{assert_wire_compat, Abi};
+/// #
+/// # #[derive(Abi)]
+/// # #[repr(C)]
+/// # struct HeaderV1 {
+/// #     magic: u32,
+/// #     version: u32,
+/// # }
+/// #
+/// #[derive(Abi)]
+/// #[repr(C)]
+/// struct HeaderV3 {
+///     magic: u32,
+///     version: u16,
+///     flags: u16,
+/// }
+///
+/// assert_wire_compat!(HeaderV3, <HeaderV1 as Fingerprint>::FINGERPRINT);
+/// ```
+#[macro_export]
+macro_rules! assert_wire_compat {
+    ($ty:ty, $expected:expr $(,)?) => {
+        const _: () = ::core::assert!(
+            <$ty as $crate::layout::Fingerprint>::FINGERPRINT == $expected,
+            ::core::concat!(
+                "the wire layout of `",
+                ::core::stringify!($ty),
+                "` does not match the expected fingerprint"
+            ),
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_depends_on_field_layout() {
+        let base = FingerprintHasher::new()
+            .write_layout(8, 4)
+            .write_field(0, 4, 4)
+            .write_field(4, 2, 2)
+            .finish();
+        let reordered = FingerprintHasher::new()
+            .write_layout(8, 4)
+            .write_field(0, 2, 2)
+            .write_field(4, 4, 4)
+            .finish();
+        let resized = FingerprintHasher::new()
+            .write_layout(8, 4)
+            .write_field(0, 4, 4)
+            .write_field(4, 4, 4)
+            .finish();
+
+        assert_ne!(base, reordered);
+        assert_ne!(base, resized);
+        assert_eq!(
+            base,
+            FingerprintHasher::new()
+                .write_layout(8, 4)
+                .write_field(0, 4, 4)
+                .write_field(4, 2, 2)
+                .finish()
+        );
+        assert_ne!(
+            FingerprintHasher::new()
+                .write_discriminants(0, 3)
+                .finish(),
+            FingerprintHasher::new()
+                .write_discriminants(0, 4)
+                .finish()
+        );
+    }
+}
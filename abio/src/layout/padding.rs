@@ -0,0 +1,152 @@
+//! Compile-time lists of the padding bytes of a wire layout.
+
+use super::{Describe, Descriptor};
+use crate::Span;
+
+/// Returns the byte ranges of `T` that are not covered by any of its fields, in
+/// ascending order.
+///
+/// Padding bytes hold no value, so copying a `T` into a buffer may leave arbitrary
+/// memory contents in them. Encoders can zero these ranges before the buffer is
+/// written out, and scrubbers can check that they are zero before the buffer
+/// leaves the process.
+///
+/// # Examples
+///
+/// ```
+/// use abio::layout::padding_spans;
+/// use abio::{Abi, Span};
+///
+/// #[derive(Abi)]
+/// #[repr(C)]
+/// struct Entry<T> {
+///     tag: u8,
+///     value: T,
+/// }
+///
+/// assert_eq!(padding_spans::<Entry<u32>>(), [Span::new(1, 3)]);
+/// assert!(padding_spans::<Entry<u8>>().is_empty());
+/// ```
+#[inline]
+pub const fn padding_spans<T: Describe + ?Sized>() -> &'static [Span] {
+    T::PADDING
+}
+
+/// Padding bytes of a [`Descriptor`], computed at compile time.
+///
+/// This type exists to build the [`PADDING`][Describe::PADDING] list of a
+/// [`Describe`] implementation, and holds up to `N` ranges. A type with `F` fields
+/// has at most `F + 1` ranges of padding: one before each field, and one at the
+/// end of the type.
+///
+/// ```
+/// use abio::layout::{Describe, Descriptor, FieldDescriptor, PaddingSpans};
+/// use abio::Span;
+///
+/// struct Record;
+///
+/// impl Describe for Record {
+///     const DESCRIPTOR: Descriptor = Descriptor {
+///         name: "Record",
+///         size: 8,
+///         align: 4,
+///         repr: "C",
+///         fields: &[FieldDescriptor { name: "id", offset: 0, size: 2, nested: None, endian: None }],
+///     };
+///     const PADDING: &'static [Span] = PaddingSpans::<2>::new(&Self::DESCRIPTOR).as_slice();
+/// }
+///
+/// assert_eq!(Record::PADDING, [Span::new(2, 6)]);
+/// ```
+#[derive(Debug)]
+pub struct PaddingSpans<const N: usize> {
+    /// Ranges of padding, of which the first `len` are in use.
+    spans: [Span; N],
+    /// Number of ranges of padding.
+    len: usize,
+}
+
+impl<const N: usize> PaddingSpans<N> {
+    /// Placeholder for the unused ranges.
+    const UNUSED: Span = Span::new(0, 0);
+
+    /// Computes the padding bytes of `descriptor`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `descriptor` has more than `N` ranges of padding, which is a
+    /// compile error when evaluated in a constant.
+    pub const fn new(descriptor: &Descriptor) -> Self {
+        let mut spans = [Self::UNUSED; N];
+        let mut len = 0;
+        let mut end = 0;
+        let mut idx = 0;
+        loop {
+            // The end of the type is treated as an empty field, so that trailing
+            // padding is listed too.
+            let (offset, size) = if idx < descriptor.fields.len() {
+                (descriptor.fields[idx].offset, descriptor.fields[idx].size)
+            } else if idx == descriptor.fields.len() {
+                (descriptor.size, 0)
+            } else {
+                break;
+            };
+            // Fields of unions overlap, so only the bytes past the furthest end seen
+            // so far can be padding.
+            if offset > end {
+                assert!(len < N, "The descriptor has more ranges of padding than expected");
+                spans[len] = Span::from_range(end..offset);
+                len += 1;
+            }
+            if offset + size > end {
+                end = offset + size;
+            }
+            idx += 1;
+        }
+        Self { spans, len }
+    }
+
+    /// Returns the ranges of padding, in ascending order.
+    #[inline]
+    pub const fn as_slice(&'static self) -> &'static [Span] {
+        self.spans.split_at(self.len).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::FieldDescriptor;
+
+    const fn field(offset: usize, size: usize) -> FieldDescriptor {
+        FieldDescriptor { name: "field", offset, size, nested: None, endian: None }
+    }
+
+    #[test]
+    fn lists_gaps_between_fields_and_trailing_padding() {
+        const HEADER: Descriptor = Descriptor {
+            name: "Header",
+            size: 16,
+            align: 4,
+            repr: "C",
+            fields: &[field(0, 1), field(4, 4), field(8, 2)],
+        };
+        const PADDING: &[Span] = PaddingSpans::<4>::new(&HEADER).as_slice();
+        assert_eq!(PADDING, [Span::new(1, 3), Span::new(10, 6)]);
+
+        // Overlapping fields of a union only leave the bytes past the widest one.
+        const UNION: Descriptor = Descriptor {
+            name: "Union",
+            size: 8,
+            align: 4,
+            repr: "C",
+            fields: &[field(0, 4), field(0, 2)],
+        };
+        const UNION_PADDING: &[Span] = PaddingSpans::<3>::new(&UNION).as_slice();
+        assert_eq!(UNION_PADDING, [Span::new(4, 4)]);
+
+        const DENSE: Descriptor = Descriptor { fields: &[field(0, 8), field(8, 8)], ..HEADER };
+        const DENSE_PADDING: &[Span] = PaddingSpans::<3>::new(&DENSE).as_slice();
+        assert!(DENSE_PADDING.is_empty());
+    }
+}
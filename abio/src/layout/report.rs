@@ -0,0 +1,177 @@
+//! Machine-readable reports of wire layouts.
+
+use core::fmt;
+
+use super::Descriptor;
+use crate::Endian;
+
+/// Name of the environment variable enabling layout reports.
+pub const ABIO_DEBUG: &str = "ABIO_DEBUG";
+
+/// JSON rendering of a [`Descriptor`], created by [`Descriptor::to_json`].
+///
+/// The report lists the size, alignment, repr and padding of the type, along with
+/// the offset, size and preceding padding of each field. Fields with a fixed byte
+/// order list it under the `endian` key, and nested layouts are rendered inline
+/// under the `layout` key of their field.
+#[derive(Clone, Copy, Debug)]
+pub struct Json<'a>(&'a Descriptor);
+
+impl Descriptor {
+    /// Returns a JSON rendering of this layout, suitable for diffing layouts across
+    /// targets.
+    #[inline]
+    pub const fn to_json(&self) -> Json<'_> {
+        Json(self)
+    }
+}
+
+impl fmt::Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let descriptor = self.0;
+        write!(
+            f,
+            r#"{{"name":"{}","size":{},"align":{},"repr":"{}","padding":{},"fields":["#,
+            descriptor.name,
+            descriptor.size,
+            descriptor.align,
+            descriptor.repr,
+            descriptor.padding(),
+        )?;
+        let mut end = 0;
+        for (idx, field) in descriptor
+            .fields
+            .iter()
+            .enumerate()
+        {
+            if idx > 0 {
+                f.write_str(",")?;
+            }
+            write!(
+                f,
+                r#"{{"name":"{}","offset":{},"size":{},"padding_before":{}"#,
+                field.name,
+                field.offset,
+                field.size,
+                field.offset.saturating_sub(end),
+            )?;
+            if let Some(endian) = field.endian {
+                let endian = match endian {
+                    Endian::Little => "le",
+                    Endian::Big => "be",
+                };
+                write!(f, r#","endian":"{endian}""#)?;
+            }
+            if let Some(nested) = field.nested {
+                write!(f, r#","layout":{}"#, nested.to_json())?;
+            }
+            f.write_str("}")?;
+            end = end.max(field.offset + field.size);
+        }
+        f.write_str("]}")
+    }
+}
+
+/// Writes the JSON report of `descriptor` into the directory named by the
+/// [`ABIO_DEBUG`] environment variable, doing nothing if it is not set.
+///
+/// The report is written to `<module_path>.<name>.json`, with the `::` separators
+/// of `module_path` replaced by dots. Types deriving [`Abi`][crate::Abi] call this
+/// from a generated test when `ABIO_DEBUG` is set while they are compiled.
+///
+/// # Errors
+///
+/// Returns an error if the report cannot be written.
+#[cfg(feature = "std")]
+pub fn write_report(module_path: &str, descriptor: &Descriptor) -> std::io::Result<()> {
+    use std::string::ToString;
+
+    let Some(dir) = std::env::var_os(ABIO_DEBUG) else {
+        return Ok(());
+    };
+    let file = std::format!("{}.{}.json", module_path.replace("::", "."), descriptor.name);
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(std::path::Path::new(&dir).join(file), descriptor.to_json().to_string())
+}
+
+/// Expands to the layout report test generated for types deriving `Abi`.
+///
+/// Reports are written with [`write_report`], so the test is only emitted when the
+/// `std` feature of this crate is enabled, whatever the features of the crate
+/// deriving `Abi`.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! layout_report_test {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+/// Without the `std` feature, [`write_report`] does not exist and no test is
+/// emitted.
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! layout_report_test {
+    ($($item:item)*) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::layout::FieldDescriptor;
+
+    use super::*;
+
+    #[test]
+    fn renders_padding_and_nested_layouts() {
+        const INNER: Descriptor = Descriptor {
+            name: "Inner",
+            size: 2,
+            align: 2,
+            repr: "C",
+            fields: &[FieldDescriptor {
+                name: "a",
+                offset: 0,
+                size: 2,
+                nested: None,
+                endian: None,
+            }],
+        };
+        let outer = Descriptor {
+            name: "Outer",
+            size: 8,
+            align: 2,
+            repr: "C",
+            fields: &[
+                FieldDescriptor {
+                    name: "tag",
+                    offset: 0,
+                    size: 1,
+                    nested: None,
+                    endian: Some(Endian::Big),
+                },
+                FieldDescriptor {
+                    name: "inner",
+                    offset: 2,
+                    size: 2,
+                    nested: Some(&INNER),
+                    endian: None,
+                },
+            ],
+        };
+        assert_eq!(outer.padding(), 5);
+        assert_eq!(
+            outer.to_json().to_string(),
+            concat!(
+                r#"{"name":"Outer","size":8,"align":2,"repr":"C","padding":5,"fields":["#,
+                r#"{"name":"tag","offset":0,"size":1,"padding_before":0,"endian":"be"},"#,
+                r#"{"name":"inner","offset":2,"size":2,"padding_before":1,"layout":"#,
+                r#"{"name":"Inner","size":2,"align":2,"repr":"C","padding":0,"fields":["#,
+                r#"{"name":"a","offset":0,"size":2,"padding_before":0}]}}]}"#,
+            )
+        );
+    }
+}
@@ -9,27 +9,64 @@
     strict_provenance,
     trait_alias
 )]
+#![cfg_attr(
+    feature = "strict-provenance",
+    feature(strict_provenance_lints),
+    deny(fuzzy_provenance_casts, lossy_provenance_casts)
+)]
+
+#[cfg(any(test, feature = "alloc"))]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod integer;
 
 pub mod codec;
-pub use codec::{decoder, encoder, Decode, Decoder, Encode, Encoder};
+pub use codec::{
+    decoder, encoder, Decode, DecodeWith, Decoder, Encode, Encoder, Reader, SeekFrom, Writer,
+};
+
+pub mod config;
+
+pub mod convert;
 
 mod context;
 pub use context::{BigEndian, Endian, Endianness, LittleEndian, NativeEndian, BE, LE};
 
+pub mod formats;
+
+#[cfg(feature = "serde")]
+pub mod interop;
+
+/// Re-export of the [`arbitrary`] crate, whose `Arbitrary` trait is implemented by
+/// types deriving [`Decode`] when the `fuzzing` feature is enabled.
+#[cfg(feature = "fuzzing")]
+pub use arbitrary;
+
+pub mod layout;
+
 mod marker;
 pub use marker::{Abi, Alignment, AsBytes, Zeroable};
 
-mod source;
-pub use source::{Array, Bytes, BytesMut, Chunk, Span};
+pub mod source;
+pub use source::{
+    Array, Bytes, BytesMut, Chunk, ChunkWords, FixedSource, SegmentedSource, Source, SourceExt,
+    SourceMut, Span, Word, Words,
+};
+
+#[cfg(any(test, feature = "alloc"))]
+pub mod testing;
+
+pub mod version;
+pub use version::{negotiate, AbiVersion, Compat, ABI_VERSION};
 
 // FIXME: Remove `allow` attribute to get rid of dead code
 #[allow(dead_code)]
 mod error;
 // Enable traits to be derived if the `derived` feature is enabled
 #[cfg(feature = "derive")]
-pub use abio_derive::{Abi, AsBytes, Decode, Zeroable};
+pub use abio_derive::{Abi, AbiUnion, AsBytes, Decode, Encode, View, Zeroable};
 pub use error::{Error, Result};
 
 #[doc(hidden)]
@@ -37,3 +74,7 @@ mod sealed;
 // internal utilites local to this crate
 #[doc(hidden)]
 mod util;
+/// Provenance-preserving helpers for address arithmetic, exported when the
+/// `strict-provenance` feature is enabled.
+#[cfg(feature = "strict-provenance")]
+pub use util::{align_offset_checked, is_aligned_to};
@@ -41,6 +41,25 @@ use crate::{Chunk, Zeroable};
 ///   each field must implement ABI
 /// * contains no padding bytes. Padding bytes are currently **not allowed**.
 ///
+/// # Floating Point
+///
+/// `f32` and `f64` are `Abi`, since every bit pattern is a valid float. Reading
+/// a float from raw bytes, or decoding it, never canonicalizes NaNs, so the payload
+/// and signaling bit of a NaN survive a round trip. See the
+/// [`float`][crate::codec::float] module for the guarantees of `Decode` and `Encode`.
+///
+/// # Unions
+///
+/// `#[repr(C)]` unions may derive this trait when every field is `Abi` and the
+/// union is exactly as large as its largest field. Since every field can hold any
+/// bit pattern, reading a union from raw bytes always produces a valid value.
+///
+/// The bytes do not record which field is meaningful, however. Reading a field is
+/// still `unsafe`, and the caller must determine the field to read from context,
+/// such as a tag stored elsewhere in the input. Unions cannot derive
+/// [`AsBytes`][crate::AsBytes], since a value written through a smaller field
+/// leaves the remaining bytes uninitialized.
+///
 /// # Safety
 ///
 /// This trait must only be implemented for types with known alignment values. For
@@ -121,6 +140,7 @@ macro_rules! impl_abi_for_primitives {
 }
 
 impl_abi_for_primitives!((), bool, char);
+// Every bit pattern is a valid float, including NaNs with any payload.
 impl_abi_for_primitives!(f32, f64);
 impl_abi_for_primitives!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
 impl_abi_for_primitives!(U8, U16, U32, U64, U128, Usize, I8, I16, I32, I64, I128, Isize);
@@ -1,3 +1,4 @@
+use crate::util::is_aligned_to;
 use crate::{sealed, Abi};
 
 /// The `Alignment` trait provides methods for checking and obtaining alignment
@@ -37,7 +38,7 @@ unsafe impl<A: Abi> Alignment for *const A {
     #[inline(always)]
     fn is_aligned_with<T: Abi>(self) -> bool {
         assert_eq!(A::MIN_ALIGN, T::MIN_ALIGN);
-        self.align_to::<T>() == 0
+        is_aligned_to(self, T::MIN_ALIGN)
     }
 }
 
@@ -49,6 +50,6 @@ unsafe impl<T: Abi> Alignment for *mut T {
 
     #[inline(always)]
     fn is_aligned_with<A: Abi>(self) -> bool {
-        self.align_to::<A>() == 0
+        is_aligned_to(self, A::MIN_ALIGN)
     }
 }
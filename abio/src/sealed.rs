@@ -12,13 +12,22 @@
 pub trait Sealed {}
 
 mod private {
-    use crate::Alignment;
     use super::Sealed;
+    use crate::Alignment;
 
     impl<T> Sealed for T where T: Alignment {}
-    
+
     impl<const N: usize> Sealed for crate::Chunk<N> {}
 
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+    impl Sealed for i8 {}
+    impl Sealed for i16 {}
+    impl Sealed for i32 {}
+    impl Sealed for i64 {}
+
     impl Sealed for crate::context::endian::BigEndian {}
     impl Sealed for crate::context::endian::LittleEndian {}
 }
@@ -9,18 +9,48 @@
 //! Rust's zero-cost abstractions by extending the built-in `&[u8]` type. It provides
 //! additional methods for parsing and validating inputs, converting slices to and
 //! from arrays, and working with [`Span`] types.
+//!
+//! # Comparing Sources
+//!
+//! The [`eq`] and [`find`] functions work through the [`Source`] trait, so they
+//! behave the same whether the bytes are contiguous in memory or not.
+//!
+//! # Typed Reads
+//!
+//! The [`SourceExt`] trait is implemented for every [`Source`], and reads decoded
+//! values, plain structs and bounds-checked [`Span`]s from any of them.
+//!
+//! # In-Place Writes
+//!
+//! The [`SourceMut`] trait is implemented for `[u8]` and [`BytesMut`], and patches
+//! bytes, chunks and plain structs into an existing buffer with the same bounds
+//! checks as the reads of [`Source`].
 
 mod array;
 pub use array::Array;
 
-// ISSUE: Add support for mutable slice type and expose via `BytesMut`. Please see the open issue at: https://github.com/ellacrity/abio/issues/6
 mod slice;
 pub use slice::Bytes;
 mod slice_mut;
 pub use slice_mut::BytesMut;
 
 mod chunk;
-pub use chunk::Chunk;
+pub use chunk::{Chunk, ChunkWords};
 
 mod span;
 pub use span::Span;
+
+mod compare;
+pub use compare::{eq, find};
+
+pub(crate) mod traits;
+pub use traits::{FixedSource, Source, SourceMut};
+
+mod ext;
+pub use ext::SourceExt;
+
+mod segmented;
+pub use segmented::SegmentedSource;
+
+mod words;
+pub use words::{Word, Words};
@@ -6,12 +6,14 @@
 //! capacity, of its underlying backing buffer. This allows the compiler to make more
 //! aggressive optimizations, since the size of the slice is explicit.
 
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
 use core::mem::{self};
 use core::ops::{Range, RangeTo};
-use core::{ptr, slice};
+use core::{array, ptr, slice};
 
-use crate::source::Bytes;
-use crate::{util, Abi, Alignment, Endian, Endianness, Error, LittleEndian, Result};
+use crate::source::{Bytes, Word};
+use crate::{util, Abi, Endian, Endianness, Error, Result};
 
 /// A fixed-size array of bytes, or "chunk" guaranteed to contain bytes in native
 /// endian order.
@@ -139,6 +141,30 @@ impl<const N: usize> Chunk<N> {
         &self.inner
     }
 
+    /// Reinterprets a reference to an array of bytes as a reference to a `Chunk`,
+    /// without copying.
+    #[inline(always)]
+    pub const fn from_array_ref(array: &[u8; N]) -> &Self {
+        // SAFETY: `Chunk` is `#[repr(transparent)]` over `[u8; N]`, so both types have
+        // the same size, alignment and validity invariants.
+        unsafe { &*(array as *const [u8; N]).cast::<Self>() }
+    }
+
+    /// Returns a reference to the first `N` bytes of `bytes` as an array, or an error
+    /// if `bytes` is shorter than `N`.
+    ///
+    /// Unlike `<&[u8; N]>::try_from`, this accepts slices longer than `N`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes.len() < N`.
+    #[inline]
+    pub fn array_prefix(bytes: &[u8]) -> Result<&[u8; N]> {
+        bytes
+            .first_chunk::<N>()
+            .ok_or_else(|| Error::out_of_bounds(N, bytes.len()))
+    }
+
     /// Consumes this `Chunk`, returning the inner `[u8; N]` byte array.
     #[inline(always)]
     pub const fn into_array(self) -> [u8; N] {
@@ -160,39 +186,64 @@ impl<const N: usize> Chunk<N> {
     }
 
     /// Get a subslice of this chunk from `range.start..range.end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range.end > N` or if `range.start > range.end`.
     #[inline]
     pub const fn subslice(&self, range: Range<usize>) -> &[u8] {
-        debug_assert!(!self.is_empty() && N >= range.end);
-        unsafe {
-            let data = self
-                .inner
-                .as_ptr()
-                .add(range.start);
-            let len = range
-                .end
-                .saturating_sub(range.start);
-            core::slice::from_raw_parts(data, len)
-        }
+        let (head, _) = self
+            .as_slice()
+            .split_at(range.end);
+        let (_, bytes) = head.split_at(range.start);
+        bytes
     }
 
     /// Get a subslice of this chunk starting at `range.from`, spanning until the
     /// end.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `to.end > N`.
     #[inline]
     pub const fn slice_to(&self, to: RangeTo<usize>) -> &[u8] {
-        let end_offset = to.end;
-        debug_assert!(N >= end_offset);
-        debug_assert!(
-            end_offset > 0,
-            "The `slice_to(0)` operation on this Chunk produces an illegal type (ZST)."
-        );
-        unsafe {
-            let data = self
-                .inner
-                .as_ptr()
-                .add(end_offset);
-            let len = self.inner.len() - end_offset;
-            core::slice::from_raw_parts(data, len)
-        }
+        let (_, tail) = self
+            .as_slice()
+            .split_at(to.end);
+        tail
+    }
+
+    /// Copies the `M` bytes starting at `OFFSET` into a smaller chunk.
+    ///
+    /// Unlike [`Source::read_chunk`][crate::Source::read_chunk], the bounds are
+    /// checked at compile time, so a read past the end of the chunk fails to build
+    /// instead of returning an error:
+    ///
+    /// ```
+    /// use abio::Chunk;
+    ///
+    /// let header = Chunk::from_ne_bytes(*b"\x7fELF\x02\x01");
+    /// assert_eq!(header.sub_chunk::<1, 3>().into_array(), *b"ELF");
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use abio::Chunk;
+    ///
+    /// let header = Chunk::from_ne_bytes(*b"\x7fELF");
+    /// let _ = header.sub_chunk::<2, 3>();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn sub_chunk<const OFFSET: usize, const M: usize>(&self) -> Chunk<M> {
+        const { assert!(OFFSET + M <= N, "sub-chunk must lie within the chunk") };
+        // SAFETY: The assertion above ensures the `M` bytes starting at `OFFSET` lie
+        // within `self`, and byte arrays have no alignment requirements.
+        Chunk::from_ne_bytes(unsafe {
+            self.as_ptr()
+                .add(OFFSET)
+                .cast::<[u8; M]>()
+                .read()
+        })
     }
 
     /// Converts this chunk of bytes into a UTF-8 encoded `&str` slice.
@@ -213,11 +264,8 @@ impl<const N: usize> Chunk<N> {
     pub unsafe fn read<T: Abi>(self) -> Result<T> {
         if self.inner.len() != T::SIZE {
             Err(Error::size_mismatch(T::SIZE, self.inner.len()))
-        } else if self
-            .as_ptr()
-            .is_aligned_with::<T>()
-        {
-            Err(Error::misaligned_access::<T>(self.addr()))
+        } else if !self.is_abi_compatible::<T>() {
+            Err(Error::misaligned_access::<T>(self.as_ptr().cast::<T>()))
         } else {
             // SAFETY: `self` has the same size as `T`, fulfills its alignment requirements and
             // is thus safe to read. Note that we return a bitwise copy of `T`, and not a
@@ -227,7 +275,7 @@ impl<const N: usize> Chunk<N> {
     }
 
     pub(crate) fn is_abi_compatible<T: Abi>(&self) -> bool {
-        (self.inner.as_ptr() as usize) & (T::MIN_ALIGN.saturating_sub(1)) == 0
+        util::is_aligned_to(self.inner.as_ptr(), T::MIN_ALIGN)
     }
 
     const fn _read_endian_inner<E: Endianness>(bytes: &[u8]) -> Result<Self> {
@@ -443,6 +491,111 @@ impl<const N: usize> Chunk<N> {
     }
 }
 
+impl<const N: usize> Chunk<N> {
+    /// Returns an iterator over the bytes of this chunk.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, u8> {
+        self.inner.iter()
+    }
+
+    /// Returns an iterator over this chunk as a sequence of words of type `W`, each
+    /// serialized using the byte order `E`.
+    ///
+    /// If `N` is not a multiple of the size of `W`, the trailing bytes do not form a
+    /// whole word and are not yielded. They remain available through
+    /// [`ChunkWords::remainder`].
+    #[inline]
+    pub fn iter_words<W: Word, E: Endianness>(&self) -> ChunkWords<'_, W, E> {
+        ChunkWords::new(&self.inner)
+    }
+
+    /// Returns an iterator over this chunk as a sequence of [`u16`] words, each
+    /// serialized using the byte order `E`.
+    #[inline]
+    pub fn iter_u16<E: Endianness>(&self) -> ChunkWords<'_, u16, E> {
+        self.iter_words()
+    }
+
+    /// Returns an iterator over this chunk as a sequence of [`u32`] words, each
+    /// serialized using the byte order `E`.
+    #[inline]
+    pub fn iter_u32<E: Endianness>(&self) -> ChunkWords<'_, u32, E> {
+        self.iter_words()
+    }
+}
+
+impl<const N: usize> IntoIterator for Chunk<N> {
+    type IntoIter = array::IntoIter<u8, N>;
+    type Item = u8;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a Chunk<N> {
+    type IntoIter = slice::Iter<'a, u8>;
+    type Item = &'a u8;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the words of a [`Chunk`], created by [`Chunk::iter_words`].
+#[derive(Clone, Debug)]
+pub struct ChunkWords<'a, W: Word, E: Endianness> {
+    chunks: slice::ChunksExact<'a, u8>,
+    _marker: PhantomData<(W, E)>,
+}
+
+impl<'a, W: Word, E: Endianness> ChunkWords<'a, W, E> {
+    #[inline]
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            chunks: bytes.chunks_exact(mem::size_of::<W>()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the trailing bytes that do not form a whole word.
+    #[inline]
+    pub fn remainder(&self) -> &'a [u8] {
+        self.chunks.remainder()
+    }
+}
+
+impl<W: Word, E: Endianness> Iterator for ChunkWords<'_, W, E> {
+    type Item = W;
+
+    #[inline]
+    fn next(&mut self) -> Option<W> {
+        self.chunks
+            .next()
+            .and_then(|bytes| W::read_prefix(bytes, E::ENDIAN))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<W: Word, E: Endianness> DoubleEndedIterator for ChunkWords<'_, W, E> {
+    #[inline]
+    fn next_back(&mut self) -> Option<W> {
+        self.chunks
+            .next_back()
+            .and_then(|bytes| W::read_prefix(bytes, E::ENDIAN))
+    }
+}
+
+impl<W: Word, E: Endianness> ExactSizeIterator for ChunkWords<'_, W, E> {}
+
+impl<W: Word, E: Endianness> FusedIterator for ChunkWords<'_, W, E> {}
+
 impl<const N: usize> AsRef<[u8; N]> for Chunk<N> {
     #[inline]
     fn as_ref(&self) -> &[u8; N] {
@@ -466,11 +619,100 @@ impl<const N: usize> From<[u8; N]> for Chunk<N> {
     }
 }
 
+/// Copies the bytes as-is, without interpreting them in any byte order.
+impl<const N: usize> TryFrom<&[u8]> for Chunk<N> {
+    type Error = crate::Error;
+
+    #[inline]
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; N]>::try_from(slice)
+            .map(Chunk::from_ne_bytes)
+            .map_err(|_| Error::size_mismatch(N, slice.len()))
+    }
+}
+
+impl<'a, const N: usize> TryFrom<&'a [u8]> for &'a Chunk<N> {
+    type Error = crate::Error;
+
+    #[inline]
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        <&[u8; N]>::try_from(slice)
+            .map(Chunk::from_array_ref)
+            .map_err(|_| Error::size_mismatch(N, slice.len()))
+    }
+}
+
+/// Copies the bytes as-is, without interpreting them in any byte order.
 impl<'data, const N: usize> TryFrom<Bytes<'data>> for Chunk<N> {
     type Error = crate::Error;
 
     #[inline]
     fn try_from(slice: Bytes<'data>) -> Result<Self, Self::Error> {
-        Chunk::from_slice::<LittleEndian>(slice.as_slice())
+        Chunk::try_from(slice.as_slice())
+    }
+}
+
+#[cfg(any(test, feature = "alloc"))]
+impl<const N: usize> TryFrom<alloc::vec::Vec<u8>> for Chunk<N> {
+    type Error = crate::Error;
+
+    #[inline]
+    fn try_from(vec: alloc::vec::Vec<u8>) -> Result<Self, Self::Error> {
+        Chunk::try_from(vec.as_slice())
+    }
+}
+
+#[cfg(any(test, feature = "alloc"))]
+impl<const N: usize> TryFrom<alloc::boxed::Box<[u8]>> for Chunk<N> {
+    type Error = crate::Error;
+
+    #[inline]
+    fn try_from(boxed: alloc::boxed::Box<[u8]>) -> Result<Self, Self::Error> {
+        Chunk::try_from(&*boxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BigEndian, LittleEndian};
+
+    #[test]
+    fn iterate_chunk_as_bytes_and_words() {
+        let chunk = Chunk::from_ne_bytes([0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07]);
+
+        let sum = chunk
+            .into_iter()
+            .fold(0u32, |acc, byte| acc + u32::from(byte));
+        assert_eq!(sum, 28);
+
+        let mut words = chunk.iter_u16::<BigEndian>();
+        assert_eq!(words.len(), 3);
+        assert_eq!(words.next(), Some(0x0102));
+        assert_eq!(words.next_back(), Some(0x0506));
+        assert_eq!(words.remainder(), &[0x07]);
+
+        let words = chunk.iter_u32::<LittleEndian>();
+        assert!(words.eq([0x0403_0201]));
+    }
+
+    #[test]
+    fn convert_slices_and_buffers_into_chunks() {
+        let bytes = [0x01, 0x02, 0x03, 0x04];
+
+        let chunk = Chunk::<4>::try_from(&bytes[..]).unwrap();
+        assert_eq!(chunk.into_array(), bytes);
+        assert_eq!(Chunk::<4>::try_from(Bytes::new(&bytes)).unwrap(), chunk);
+        assert!(Chunk::<3>::try_from(&bytes[..]).is_err());
+        assert!(Chunk::<5>::try_from(&bytes[..]).is_err());
+
+        let borrowed = <&Chunk<4>>::try_from(&bytes[..]).unwrap();
+        assert_eq!(borrowed.as_ptr(), bytes.as_ptr());
+        assert_eq!(Chunk::<2>::array_prefix(&bytes).unwrap(), &[0x01, 0x02]);
+        assert!(Chunk::<5>::array_prefix(&bytes).is_err());
+
+        assert_eq!(Chunk::<4>::try_from(bytes.to_vec()).unwrap(), chunk);
+        assert_eq!(Chunk::<4>::try_from(bytes.to_vec().into_boxed_slice()).unwrap(), chunk);
+        assert!(Chunk::<2>::try_from(alloc::vec::Vec::new()).is_err());
     }
 }
@@ -0,0 +1,153 @@
+//! Module containing comparisons and searches generic over any [`Source`].
+//!
+//! Contiguous sources are compared through their borrowed slices. Other sources,
+//! such as [`Words`][crate::Words] in a non-native byte order, are copied through a
+//! small buffer on the stack, one block at a time.
+
+use crate::{Result, Source};
+
+/// Number of bytes copied out of a non-contiguous source at once.
+const BLOCK: usize = 256;
+
+/// Returns `true` if `a` and `b` hold the same bytes.
+///
+/// # Errors
+///
+/// Returns an error if either source fails to read a region within its bounds.
+pub fn eq<A, B>(a: &A, b: &B) -> Result<bool>
+where
+    A: Source + ?Sized,
+    B: Source + ?Sized,
+{
+    let len = a.len();
+    if len != b.len() {
+        return Ok(false);
+    }
+    if let (Ok(a), Ok(b)) = (a.read_slice(0, len), b.read_slice(0, len)) {
+        return Ok(a == b);
+    }
+
+    let mut lhs = [0u8; BLOCK];
+    let mut rhs = [0u8; BLOCK];
+    let mut offset = 0;
+    while offset < len {
+        let size = BLOCK.min(len - offset);
+        a.copy_to_slice(offset, &mut lhs[..size])?;
+        b.copy_to_slice(offset, &mut rhs[..size])?;
+        if lhs[..size] != rhs[..size] {
+            return Ok(false);
+        }
+        offset += size;
+    }
+    Ok(true)
+}
+
+/// Returns the offset of the first occurrence of `needle` within `haystack`, or
+/// `None` if it does not occur.
+///
+/// An empty `needle` is found at offset `0`.
+///
+/// # Errors
+///
+/// Returns an error if `haystack` fails to read a region within its bounds.
+pub fn find<S: Source + ?Sized>(haystack: &S, needle: &[u8]) -> Result<Option<usize>> {
+    let len = haystack.len();
+    if needle.is_empty() {
+        return Ok(Some(0));
+    }
+    if needle.len() > len {
+        return Ok(None);
+    }
+    if let Ok(bytes) = haystack.read_slice(0, len) {
+        return Ok(position(bytes, needle));
+    }
+    if needle.len() > BLOCK {
+        return find_long(haystack, needle);
+    }
+
+    // Consecutive blocks overlap by one byte less than the needle, so that matches
+    // straddling a block boundary are found in the later block.
+    let mut buf = [0u8; BLOCK];
+    let mut offset = 0;
+    loop {
+        let size = BLOCK.min(len - offset);
+        haystack.copy_to_slice(offset, &mut buf[..size])?;
+        if let Some(pos) = position(&buf[..size], needle) {
+            return Ok(Some(offset + pos));
+        }
+        if offset + size == len {
+            return Ok(None);
+        }
+        offset += size - (needle.len() - 1);
+    }
+}
+
+/// Searches a non-contiguous `haystack` for a `needle` that does not fit within a
+/// single block, comparing the needle block by block at every offset.
+fn find_long<S: Source + ?Sized>(haystack: &S, needle: &[u8]) -> Result<Option<usize>> {
+    let mut buf = [0u8; BLOCK];
+    'outer: for start in 0..=haystack.len() - needle.len() {
+        for (idx, expected) in needle.chunks(BLOCK).enumerate() {
+            let actual = &mut buf[..expected.len()];
+            haystack.copy_to_slice(start + idx * BLOCK, actual)?;
+            if actual != expected {
+                continue 'outer;
+            }
+        }
+        return Ok(Some(start));
+    }
+    Ok(None)
+}
+
+/// Returns the offset of the first occurrence of a non-empty `needle` in `bytes`.
+fn position(bytes: &[u8], needle: &[u8]) -> Option<usize> {
+    bytes
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{Words, BE, LE};
+
+    #[test]
+    fn compares_contiguous_and_word_sources() {
+        let words = [0x0102u16, 0x0304];
+        let be = Words::<u16, BE>::new(&words);
+        let le = Words::<u16, LE>::new(&words);
+
+        assert!(eq(&be, &[1u8, 2, 3, 4][..]).unwrap());
+        assert!(eq(&le, &[2u8, 1, 4, 3][..]).unwrap());
+        assert!(!eq(&be, &le).unwrap());
+        assert!(!eq(&be, &[1u8, 2, 3][..]).unwrap());
+
+        let long = (0..600u16).collect::<Vec<_>>();
+        let bytes = long
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        assert!(eq(&Words::<u16, BE>::new(&long), &bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn finds_needles_across_block_boundaries() {
+        let long = (0..600u16).collect::<Vec<_>>();
+        let words = Words::<u16, BE>::new(&long);
+
+        assert_eq!(find(&words, &[]).unwrap(), Some(0));
+        assert_eq!(find(&words, &[0, 3]).unwrap(), Some(6));
+        // `0x0080` starts at byte 256, so its bytes straddle the first block boundary.
+        assert_eq!(find(&words, &[0x7f, 0x00, 0x80]).unwrap(), Some(255));
+        assert_eq!(find(&words, &[0xff, 0xff]).unwrap(), None);
+
+        let needle = (100..300u16)
+            .flat_map(u16::to_be_bytes)
+            .collect::<Vec<_>>();
+        assert_eq!(find(&words, &needle).unwrap(), Some(200));
+        assert_eq!(find(&[1u8, 2, 3][..], &[2, 3]).unwrap(), Some(1));
+        assert_eq!(find(&[1u8, 2][..], &[1, 2, 3]).unwrap(), None);
+    }
+}
@@ -0,0 +1,139 @@
+//! Module containing the [`SourceExt`] trait, providing typed reads for every
+//! [`Source`].
+
+use core::mem::size_of;
+use core::slice;
+
+use super::traits::checked_end;
+use crate::{Abi, Decode, Endianness, Result, Source, Span, Zeroable};
+
+/// Extension methods for reading typed values from any [`Source`].
+///
+/// This trait is implemented for every type implementing [`Source`], such as
+/// `[u8]`, [`Bytes`][crate::Bytes] and the sources in [`codec`][crate::codec], so
+/// the same reads work regardless of the container holding the input.
+///
+/// # Examples
+///
+/// ```
+/// use abio::{SourceExt, Span, LE};
+///
+/// let input: &[u8] = &[0xff, 0x78, 0x56, 0x34, 0x12];
+/// assert_eq!(input.read_at::<u32, LE>(1).unwrap(), 0x1234_5678);
+/// assert_eq!(input.read_struct::<[u8; 2]>(3).unwrap(), [0x34, 0x12]);
+/// assert_eq!(input.span(1, 4).unwrap(), Span::new(1, 4));
+/// assert!(input.span(2, 4).is_err());
+/// ```
+pub trait SourceExt: Source {
+    /// Decodes a `T` from the bytes starting at `offset`, using the byte order `E`.
+    ///
+    /// The value may borrow from the source, so the source must be able to lend out
+    /// the bytes following `offset` as a contiguous slice.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset > self.len()`, if the bytes following `offset`
+    /// are not contiguous, or if they do not hold a valid `T`.
+    #[inline]
+    fn read_at<'data, T, E>(&'data self, offset: usize) -> Result<T>
+    where
+        T: Decode<'data>,
+        E: Endianness,
+    {
+        let rest = self.read_slice(
+            offset,
+            self.len()
+                .saturating_sub(offset),
+        )?;
+        T::decode::<E>(rest).map(|(value, _)| value)
+    }
+
+    /// Reads a `T` by copying its `size_of::<T>()` bytes, starting at `offset`, as
+    /// they are laid out in memory.
+    ///
+    /// No byte order conversion takes place, and `offset` does not need to be
+    /// aligned for `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + size_of::<T>() > self.len()`.
+    #[inline]
+    fn read_struct<T: Abi + Zeroable>(&self, offset: usize) -> Result<T> {
+        // SAFETY: `T` is `Zeroable`, so the all-zero bit pattern is a valid `T`.
+        let mut value = unsafe { T::zeroed() };
+        // SAFETY: The slice covers exactly the bytes of `value`, which is valid for
+        // reads and writes and not otherwise borrowed while the slice is alive. `T`
+        // is `Abi` and `Zeroable`, so it has no padding and any bytes copied into it
+        // form a valid `T`.
+        let bytes = unsafe {
+            slice::from_raw_parts_mut((&mut value as *mut T).cast::<u8>(), size_of::<T>())
+        };
+        self.copy_to_slice(offset, bytes)?;
+        Ok(value)
+    }
+
+    /// Returns a [`Span`] covering the `len` bytes starting at `offset`, after
+    /// checking that they lie within the source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + len > self.len()`.
+    #[inline]
+    fn span(&self, offset: usize, len: usize) -> Result<Span> {
+        checked_end(offset, len, self.len())?;
+        Ok(Span::new(offset, len))
+    }
+}
+
+impl<S: Source + ?Sized> SourceExt for S {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bytes, BE, LE};
+
+    #[test]
+    fn reads_typed_values_from_any_source() {
+        let input = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05];
+        let bytes = Bytes::new(&input);
+        assert_eq!(
+            input[..]
+                .read_at::<u16, BE>(1)
+                .unwrap(),
+            0x0102
+        );
+        assert_eq!(
+            bytes
+                .read_at::<u32, LE>(2)
+                .unwrap(),
+            0x0504_0302
+        );
+        assert!(bytes
+            .read_at::<u32, LE>(3)
+            .is_err());
+        assert!(bytes
+            .read_at::<u8, LE>(7)
+            .is_err());
+
+        assert_eq!(
+            bytes
+                .read_struct::<[u8; 3]>(3)
+                .unwrap(),
+            [0x03, 0x04, 0x05]
+        );
+        assert_eq!(
+            input[..]
+                .read_struct::<u16>(1)
+                .unwrap(),
+            u16::from_ne_bytes([0x01, 0x02])
+        );
+        assert!(bytes
+            .read_struct::<u32>(3)
+            .is_err());
+
+        assert_eq!(bytes.span(6, 0).unwrap(), Span::new(6, 0));
+        assert!(bytes
+            .span(usize::MAX, 2)
+            .is_err());
+    }
+}
@@ -66,7 +66,7 @@ impl<T: Abi> Deref for ConstPointer<T> {
         match self.inner {
             Some(ptr) => {
                 // ptr is `Some` value, and non null, so not `0`
-                if ptr.as_ptr().align_offset(T::MIN_ALIGN) != 0 {
+                if !crate::util::is_aligned_to(ptr.as_ptr(), T::MIN_ALIGN) {
                     return Option::<&T>::None;
                 } else {
                     Some(unsafe { &*ptr.as_ptr() })
@@ -0,0 +1,162 @@
+//! Module containing the [`SegmentedSource`] type, reading an ordered list of byte
+//! slices as a single [`Source`].
+//!
+//! Network payloads and scatter-gather buffers are often split across segments
+//! that are not contiguous in memory. Chaining the segments makes them readable
+//! like any other source, without first copying them into a single buffer.
+
+use crate::source::traits::checked_end;
+use crate::{Error, Result, Source};
+
+/// Byte [`Source`] over an ordered list of segments, read as if they were
+/// concatenated.
+///
+/// Reads lying within a single segment borrow from it directly. Reads straddling
+/// the boundary between two segments cannot be lent out as a single slice, so
+/// [`read_slice`][Source::read_slice] fails for them, while
+/// [`copy_to_slice`][Source::copy_to_slice] and
+/// [`read_chunk`][Source::read_chunk] copy the bytes from every segment they span.
+///
+/// # Examples
+///
+/// ```
+/// use abio::{Chunk, SegmentedSource, Source};
+///
+/// let segments: [&[u8]; 3] = [b"GET ", b"/ind", b"ex"];
+/// let source = SegmentedSource::new(&segments);
+/// assert_eq!(source.len(), 10);
+/// assert_eq!(source.read_slice(4, 2).unwrap(), b"/i");
+///
+/// // The read straddles the last two segments, so it can only be copied.
+/// assert!(source.read_slice(6, 4).is_err());
+/// assert_eq!(source.read_chunk::<4>(6).unwrap(), Chunk::from_ne_bytes(*b"ndex"));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SegmentedSource<'data> {
+    /// Segments, in the order they are read.
+    segments: &'data [&'data [u8]],
+    /// Total number of bytes of the segments.
+    len: usize,
+}
+
+impl<'data> SegmentedSource<'data> {
+    /// Creates a source reading `segments` in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total length of the segments overflows a `usize`.
+    #[inline]
+    pub fn new(segments: &'data [&'data [u8]]) -> SegmentedSource<'data> {
+        let len = segments
+            .iter()
+            .try_fold(0usize, |len, segment| len.checked_add(segment.len()))
+            .expect("total length of the segments must fit in a `usize`");
+        SegmentedSource { segments, len }
+    }
+
+    /// Returns the segments of this source.
+    #[inline]
+    pub const fn segments(&self) -> &'data [&'data [u8]] {
+        self.segments
+    }
+
+    /// Returns the index of the segment holding the byte at `offset`, and the
+    /// offset of that byte within the segment.
+    ///
+    /// Empty segments are skipped, so the segment returned is never empty unless
+    /// `offset` is the length of the source.
+    #[inline]
+    fn locate(&self, mut offset: usize) -> (usize, usize) {
+        for (idx, segment) in self.segments.iter().enumerate() {
+            if offset < segment.len() {
+                return (idx, offset);
+            }
+            offset -= segment.len();
+        }
+        (self.segments.len(), offset)
+    }
+}
+
+impl Source for SegmentedSource<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        checked_end(offset, len, self.len)?;
+        if len == 0 {
+            return Ok(&[]);
+        }
+        let (idx, start) = self.locate(offset);
+        self.segments[idx]
+            .get(start..start + len)
+            .ok_or_else(|| Error::non_contiguous(offset, len))
+    }
+
+    #[inline]
+    fn contiguous_len(&self, offset: usize) -> usize {
+        let (idx, start) = self.locate(offset);
+        self.segments
+            .get(idx)
+            .map_or(0, |segment| segment.len() - start)
+    }
+
+    fn copy_to_slice(&self, offset: usize, dst: &mut [u8]) -> Result<()> {
+        checked_end(offset, dst.len(), self.len)?;
+        let (idx, mut start) = self.locate(offset);
+        let mut copied = 0;
+        for segment in &self.segments[idx..] {
+            if copied == dst.len() {
+                break;
+            }
+            let len = (segment.len() - start).min(dst.len() - copied);
+            dst[copied..copied + len].copy_from_slice(&segment[start..start + len]);
+            copied += len;
+            start = 0;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_straddle_segment_boundaries() {
+        let segments: [&[u8]; 4] = [b"ab", b"", b"cde", b"f"];
+        let source = SegmentedSource::new(&segments);
+        assert_eq!(source.len(), 6);
+
+        assert_eq!(source.read_slice(2, 3).unwrap(), b"cde");
+        assert_eq!(source.read_slice(6, 0).unwrap(), b"");
+        assert_eq!(source.read_slice(1, 2), Err(Error::non_contiguous(1, 2)));
+        assert!(source.read_slice(5, 2).is_err());
+
+        let mut buf = [0u8; 6];
+        source
+            .copy_to_slice(0, &mut buf)
+            .unwrap();
+        assert_eq!(&buf, b"abcdef");
+        source
+            .copy_to_slice(1, &mut buf[..5])
+            .unwrap();
+        assert_eq!(&buf[..5], b"bcdef");
+        assert!(source
+            .copy_to_slice(2, &mut buf[..5])
+            .is_err());
+        assert_eq!(
+            source
+                .read_chunk_at_end::<3>()
+                .unwrap()
+                .into_array(),
+            *b"def"
+        );
+
+        assert_eq!(source.contiguous_len(0), 2);
+        assert_eq!(source.contiguous_len(2), 3);
+        assert_eq!(source.contiguous_len(4), 1);
+        assert_eq!(source.contiguous_len(6), 0);
+    }
+}
@@ -4,11 +4,13 @@
 //! [`Bytes`] type is local to the crate.
 
 use core::borrow::Borrow;
+use core::fmt;
 use core::marker::PhantomData;
 use core::ops::{Bound, Deref, Index, Range, RangeBounds, RangeFrom, RangeTo};
 use core::slice;
 
-use crate::util::{self, FromInner};
+use crate::convert::FromInner;
+use crate::util;
 use crate::{Chunk, Error, Result};
 
 /// Contiguous region of memory containing a borrowed sequence of bytes.
@@ -66,6 +68,20 @@ impl<'data> Bytes<'data> {
         }
     }
 
+    /// Wraps a borrowed slice of bytes, allowing it to be empty.
+    ///
+    /// Unlike [`Bytes::new`], this does not reject empty slices. It is used where an
+    /// empty region is a valid result, such as freezing an empty
+    /// [`BytesMut`][crate::BytesMut] or viewing a type without fields.
+    #[inline(always)]
+    pub const fn new_allow_empty(bytes: &'data [u8]) -> Bytes<'data> {
+        Bytes {
+            ptr: bytes.as_ptr(),
+            len: bytes.len(),
+            _lifetime: PhantomData,
+        }
+    }
+
     /// Creates a new [`Bytes`] instance from a given slice of bytes and offset.
     ///
     /// # Errors
@@ -82,27 +98,90 @@ impl<'data> Bytes<'data> {
         }
     }
 
-    /// Splits a byte slice at `offset` into two parts, using the given `offset`.
-    /// [`Bytes`] instance given a byte slice, an offset, and a size.
+    /// Splits a byte slice at `offset`, returning the bytes before and after it.
+    ///
+    /// This is equivalent to `Bytes::new_allow_empty(bytes).split_at(offset)`; see
+    /// [`Bytes::split_at`].
     ///
     /// # Errors
     ///
-    /// This function returns an error if `bytes.len() < offset + size`.
+    /// This function returns an error if `bytes.len() < offset`.
     #[inline]
     pub const fn new_from_split_at(
         bytes: &'data [u8],
         offset: usize,
     ) -> Result<(Bytes<'data>, Bytes<'data>)> {
-        let needed = offset;
-        if bytes.len() < needed {
-            Err(Error::out_of_bounds(needed, bytes.len()))
-        } else {
-            // SAFETY: The returned slices are bound by the lifetime `data, so they are valid as
-            // long as the data `bytes` references is live. The bounds check above ensures that
-            // `offset` is a valid offset and so this is a safe operation.
-            let (head, tail) = unsafe { util::split_at_unchecked(bytes, offset) };
-            Ok((Bytes::new(head), Bytes::new(tail)))
+        Bytes::new_allow_empty(bytes).split_at(offset)
+    }
+
+    /// Splits the bytes at `mid`, returning the bytes in `[0, mid)` and the bytes in
+    /// `[mid, len)`, in that order. Either half may be empty.
+    ///
+    /// Both halves keep the original lifetime, so parsing can continue with the
+    /// second half once the first has been decoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `mid > self.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abio::Bytes;
+    ///
+    /// let (magic, rest) = Bytes::new(b"\x7fELF\x02\x01").split_at(4).unwrap();
+    /// assert_eq!(magic.as_slice(), b"\x7fELF");
+    /// assert_eq!(rest.as_slice(), b"\x02\x01");
+    /// assert!(rest.split_at(3).is_err());
+    /// ```
+    #[inline]
+    pub const fn split_at(self, mid: usize) -> Result<(Bytes<'data>, Bytes<'data>)> {
+        if mid > self.len {
+            return Err(Error::out_of_bounds(mid, self.len));
+        }
+        // SAFETY: `mid <= self.len`, so both halves lie within the borrowed slice, which
+        // is valid for `'data`.
+        let (head, tail) = unsafe { util::split_at_unchecked(self.into_slice(), mid) };
+        Ok((Bytes::new_allow_empty(head), Bytes::new_allow_empty(tail)))
+    }
+
+    /// Splits off the first `n` bytes, returning them followed by the rest.
+    ///
+    /// This is the same as [`split_at(n)`][Bytes::split_at].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `n > self.len()`.
+    #[inline]
+    pub const fn take_front(self, n: usize) -> Result<(Bytes<'data>, Bytes<'data>)> {
+        self.split_at(n)
+    }
+
+    /// Splits off the last `n` bytes, returning the rest followed by them.
+    ///
+    /// The halves are returned in the order they appear in memory, like those of
+    /// [`split_at`][Bytes::split_at], so trailers such as checksums are the second
+    /// element.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `n > self.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abio::Bytes;
+    ///
+    /// let (body, crc) = Bytes::new(b"payload\xab\xcd").take_back(2).unwrap();
+    /// assert_eq!(body.as_slice(), b"payload");
+    /// assert_eq!(crc.as_slice(), b"\xab\xcd");
+    /// ```
+    #[inline]
+    pub const fn take_back(self, n: usize) -> Result<(Bytes<'data>, Bytes<'data>)> {
+        if n > self.len {
+            return Err(Error::out_of_bounds(n, self.len));
         }
+        self.split_at(self.len - n)
     }
 
     /// Creates a [`Bytes`] instance from a slice of bytes and and offset, without
@@ -209,6 +288,17 @@ impl<'data> Bytes<'data> {
         unsafe { slice::from_raw_parts(self.ptr, self.len) }
     }
 
+    /// Consumes this [`Bytes`] instance, returning the inner byte slice with its
+    /// original lifetime.
+    ///
+    /// Unlike [`as_slice`][Bytes::as_slice], the returned slice does not borrow from
+    /// `self`, so it may outlive the instance it was obtained from.
+    #[inline]
+    pub const fn into_slice(self) -> &'data [u8] {
+        // SAFETY: The pointer and length were taken from a slice borrowed for `'data`.
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
     /// Returns an iterator over the slice.
     ///
     /// The iterator yields all items from start to end.
@@ -289,6 +379,14 @@ impl<'data> Bytes<'data> {
     }
 }
 
+impl fmt::Debug for Bytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Bytes")
+            .field(&self.as_slice())
+            .finish()
+    }
+}
+
 impl<'data> AsRef<[u8]> for Bytes<'data> {
     #[inline]
     fn as_ref(&self) -> &[u8] {
@@ -366,9 +464,69 @@ impl<'data, S: AsRef<[u8]>> PartialEq<S> for Bytes<'data> {
     }
 }
 
+/// Reading from [`Bytes`] consumes bytes from the front of the slice, in the same
+/// way reading from a `&[u8]` does.
+#[cfg(feature = "std")]
+impl<'data> std::io::Read for Bytes<'data> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.len);
+        buf[..len].copy_from_slice(&self.as_slice()[..len]);
+        std::io::BufRead::consume(self, len);
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'data> std::io::BufRead for Bytes<'data> {
+    #[inline]
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.as_slice())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(self.len);
+        // SAFETY: `amt <= self.len`, so the advanced pointer remains within bounds of (or
+        // one past the end of) the borrowed slice.
+        self.ptr = unsafe { self.ptr.add(amt) };
+        self.len -= amt;
+    }
+}
+
 impl<'data> PartialEq<Bytes<'data>> for &'data [u8] {
     #[inline]
     fn eq(&self, other: &Bytes<'data>) -> bool {
         self == other
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_keep_the_original_lifetime() {
+        let buf = *b"headbodytail";
+        let (head, rest) = Bytes::new(&buf)
+            .take_front(4)
+            .unwrap();
+        let (body, tail) = rest.take_back(4).unwrap();
+        assert_eq!(head.as_slice(), b"head");
+        assert_eq!(body.as_slice(), b"body");
+        assert_eq!(tail.into_slice(), b"tail");
+
+        let (empty, all) = body.split_at(0).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(all.split_at(4).unwrap().1.len(), 0);
+        assert_eq!(body.split_at(5).err(), Some(Error::out_of_bounds(5, 4)));
+        assert_eq!(body.take_back(5).err(), Some(Error::out_of_bounds(5, 4)));
+        assert_eq!(
+            Bytes::new_from_split_at(&buf, 12)
+                .unwrap()
+                .0
+                .as_slice(),
+            buf
+        );
+    }
+}
@@ -1,18 +1,764 @@
+//! Module containing a newtype wrapper for mutable byte slices.
+//!
+//! The [`BytesMut`] type is the write-side counterpart of [`Bytes`]. It is meant for
+//! patching inputs in place, such as fixing up length prefixes and checksums after
+//! the rest of a message has been written.
+
+use core::borrow::{Borrow, BorrowMut};
+use core::fmt;
 use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut, Index, IndexMut, Range, RangeFrom, RangeTo};
+use core::slice;
+
+use crate::source::traits::checked_end;
+use crate::{Bytes, Error, Result, Source, Span};
 
-#[derive(Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// Contiguous region of memory containing a mutably borrowed sequence of bytes.
+///
+/// Unlike [`Bytes`], a [`BytesMut`] may be empty, since splitting a region with
+/// [`split_at`][BytesMut::split_at] may leave one of its halves without any
+/// bytes.
+///
+/// # Examples
+///
+/// ```
+/// use abio::BytesMut;
+///
+/// let mut buf = *b"\x00\x00hello";
+/// let mut bytes = BytesMut::new(&mut buf);
+/// bytes
+///     .copy_from_slice(0, &5u16.to_le_bytes())
+///     .unwrap();
+///
+/// let frozen = bytes.freeze();
+/// assert_eq!(frozen.as_slice(), b"\x05\x00hello");
+/// ```
 pub struct BytesMut<'data> {
     ptr: *mut u8,
-    end: *mut u8,
-    _lifetime: PhantomData<&'data u8>,
+    len: usize,
+    _lifetime: PhantomData<&'data mut [u8]>,
 }
 
 impl<'data> BytesMut<'data> {
-    pub fn new(bytes: &'data mut [u8]) -> BytesMut<'data> {
-        Self {
-            ptr: bytes.as_ptr(),
-            end: unsafe { bytes.as_ptr().add(bytes.len()) },
+    /// Create a new [`BytesMut`] type by wrapping a mutably borrowed slice of bytes.
+    #[inline(always)]
+    pub const fn new(bytes: &'data mut [u8]) -> BytesMut<'data> {
+        BytesMut {
+            ptr: bytes.as_mut_ptr(),
+            len: bytes.len(),
             _lifetime: PhantomData,
         }
     }
+
+    /// Creates a new [`BytesMut`] instance covering the bytes of `bytes` starting at
+    /// `offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes.len() < offset`.
+    #[inline]
+    pub const fn new_with_offset(bytes: &'data mut [u8], offset: usize) -> Result<BytesMut<'data>> {
+        if bytes.len() < offset {
+            Err(Error::out_of_bounds(offset, bytes.len()))
+        } else {
+            // SAFETY: The bounds check above ensures that `offset` lies within (or one past
+            // the end of) `bytes`, and the exclusive borrow is moved into the result.
+            Ok(unsafe {
+                BytesMut::from_raw_parts(bytes.as_mut_ptr().add(offset), bytes.len() - offset)
+            })
+        }
+    }
+
+    /// Creates a [`BytesMut`] instance from a pointer and a length.
+    ///
+    /// # Safety
+    ///
+    /// The caller must uphold the safety invariants of [`core::slice::from_raw_parts_mut`]
+    /// for the lifetime `'data`, including that no other reference to the region
+    /// exists while the returned instance is alive.
+    #[inline]
+    pub(crate) const unsafe fn from_raw_parts(data: *mut u8, len: usize) -> BytesMut<'data> {
+        BytesMut { ptr: data, len, _lifetime: PhantomData }
+    }
+
+    /// Acquires a pointer to the first byte of the underlying slice.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    /// Acquires a mutable pointer to the first byte of the underlying slice.
+    #[inline(always)]
+    pub const fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Acquires a pointer one past the last byte of the underlying slice.
+    #[inline(always)]
+    pub const fn as_end_ptr(&self) -> *const u8 {
+        // SAFETY: A valid instance of this type means that any pointer within bounds of the
+        // allocated object, or one past its end, is valid.
+        unsafe { self.as_ptr().add(self.len) }
+    }
+
+    /// Returns the two raw pointers spanning the slice.
+    #[inline]
+    pub const fn as_ptr_range(&self) -> Range<*const u8> {
+        self.as_ptr()..self.as_end_ptr()
+    }
+
+    /// Returns the two unsafe mutable pointers spanning the slice.
+    #[inline]
+    pub const fn as_mut_ptr_range(&mut self) -> Range<*mut u8> {
+        // SAFETY: The end pointer is one past the end of the borrowed slice.
+        let end = unsafe { self.ptr.add(self.len) };
+        self.ptr..end
+    }
+
+    /// Returns the inner byte slice comprising the [`BytesMut`] instance.
+    #[inline]
+    pub const fn as_slice(&self) -> &[u8] {
+        // SAFETY: The byte slice comprising this instance was constructed using a validated
+        // pointer and length.
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Returns the inner byte slice comprising the [`BytesMut`] instance, as a
+    /// mutable slice.
+    #[inline]
+    pub const fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: The byte slice comprising this instance was constructed using a validated
+        // pointer and length, and `&mut self` guarantees exclusive access to it.
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+
+    /// Returns an iterator over the slice.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, u8> {
+        self.as_slice().iter()
+    }
+
+    /// Returns an iterator that allows modifying each byte of the slice.
+    #[inline]
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, u8> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    /// Returns the number of bytes in the slice.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the slice has a length of 0.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a subslice of this [`BytesMut`] instance, using a [`Range<usize>`]
+    /// type to perform the indexing operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `range.start > range.end`, or if the range is out of
+    /// bounds of the underlying byte slice.
+    #[inline]
+    pub fn subslice(&self, range: Range<usize>) -> Result<&[u8]> {
+        self.checked_range(&range)?;
+        Ok(&self.as_slice()[range])
+    }
+
+    /// Returns a mutable subslice of this [`BytesMut`] instance, using a
+    /// [`Range<usize>`] type to perform the indexing operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `range.start > range.end`, or if the range is out of
+    /// bounds of the underlying byte slice.
+    #[inline]
+    pub fn subslice_mut(&mut self, range: Range<usize>) -> Result<&mut [u8]> {
+        self.checked_range(&range)?;
+        Ok(&mut self.as_mut_slice()[range])
+    }
+
+    /// Verifies that `range` is well-formed and lies within the slice.
+    #[inline]
+    const fn checked_range(&self, range: &Range<usize>) -> Result<()> {
+        if range.start > range.end {
+            return Err(Error::out_of_bounds(range.start, range.end));
+        }
+        match checked_end(range.start, range.end - range.start, self.len) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Overwrites `src.len()` bytes starting at `offset` with the contents of `src`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + src.len() > self.len()`. The slice is left
+    /// untouched on error.
+    #[inline]
+    pub fn copy_from_slice(&mut self, offset: usize, src: &[u8]) -> Result<()> {
+        let end = checked_end(offset, src.len(), self.len)?;
+        self.as_mut_slice()[offset..end].copy_from_slice(src);
+        Ok(())
+    }
+
+    /// Copies the bytes in `src` to the region starting at `dst`, as if by
+    /// `<[u8]>::copy_within`. The two regions may overlap.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `src` is not a well-formed range within the slice, or if
+    /// `dst + src.len() > self.len()`. The slice is left untouched on error.
+    #[inline]
+    pub fn copy_within(&mut self, src: Range<usize>, dst: usize) -> Result<()> {
+        self.checked_range(&src)?;
+        checked_end(dst, src.end - src.start, self.len)?;
+        self.as_mut_slice()
+            .copy_within(src, dst);
+        Ok(())
+    }
+
+    /// Copies the bytes covered by `src` to the region starting at `dst_offset`. The
+    /// two regions may overlap.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either region is out of bounds of the slice. The slice is
+    /// left untouched on error.
+    #[inline]
+    pub fn copy_within_span(&mut self, src: Span, dst_offset: usize) -> Result<()> {
+        self.copy_within(src.range(), dst_offset)
+    }
+
+    /// Moves the bytes covered by `src` to the region starting at `dst_offset`,
+    /// zeroing the bytes of `src` that are not overwritten by the move.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either region is out of bounds of the slice. The slice is
+    /// left untouched on error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abio::{BytesMut, Span};
+    ///
+    /// let mut buf = *b"abcdef";
+    /// BytesMut::new(&mut buf)
+    ///     .move_span(Span::new(0, 4), 2)
+    ///     .unwrap();
+    /// assert_eq!(buf, *b"\x00\x00abcd");
+    /// ```
+    pub fn move_span(&mut self, src: Span, dst_offset: usize) -> Result<()> {
+        let src = src.range();
+        self.copy_within(src.clone(), dst_offset)?;
+        let dst_end = dst_offset + (src.end - src.start);
+        let bytes = self.as_mut_slice();
+        // Only the bytes of `src` outside `[dst_offset, dst_end)` are stale, which are
+        // at most one run on either side of the destination.
+        bytes[src.start
+            ..src
+                .end
+                .min(dst_offset)
+                .max(src.start)]
+            .fill(0);
+        bytes[dst_end
+            .max(src.start)
+            .min(src.end)..src.end]
+            .fill(0);
+        Ok(())
+    }
+
+    /// Opens a zeroed gap of `len` bytes at `offset`, shifting the bytes that follow
+    /// it towards the end of the slice.
+    ///
+    /// The length of the slice is fixed, so the last `len` bytes are shifted out of
+    /// it and lost. Callers growing a region in place should reserve at least `len`
+    /// bytes of unused space at the end of the slice.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + len > self.len()`. The slice is left untouched
+    /// on error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abio::BytesMut;
+    ///
+    /// let mut buf = *b"hdrbody\x00\x00";
+    /// BytesMut::new(&mut buf)
+    ///     .insert_gap(3, 2)
+    ///     .unwrap();
+    /// assert_eq!(buf, *b"hdr\x00\x00body");
+    /// ```
+    pub fn insert_gap(&mut self, offset: usize, len: usize) -> Result<()> {
+        let end = checked_end(offset, len, self.len)?;
+        let bytes = self.as_mut_slice();
+        bytes.copy_within(offset..bytes.len() - len, end);
+        bytes[offset..end].fill(0);
+        Ok(())
+    }
+
+    /// Splits the region into two disjoint halves at `mid`, consuming it.
+    ///
+    /// The first half contains the bytes in `[0, mid)` and the second half the bytes
+    /// in `[mid, len)`. Either half may be empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `mid > self.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abio::BytesMut;
+    ///
+    /// let mut buf = [0u8; 6];
+    /// let (mut header, mut body) = BytesMut::new(&mut buf)
+    ///     .split_at(2)
+    ///     .unwrap();
+    /// body.copy_from_slice(0, b"abcd").unwrap();
+    /// header.copy_from_slice(0, &(body.len() as u16).to_be_bytes()).unwrap();
+    /// assert_eq!(buf, *b"\x00\x04abcd");
+    /// ```
+    #[inline]
+    pub const fn split_at(self, mid: usize) -> Result<(BytesMut<'data>, BytesMut<'data>)> {
+        if mid > self.len {
+            return Err(Error::out_of_bounds(mid, self.len));
+        }
+        // SAFETY: `mid <= self.len`, so both halves lie within the original region and do
+        // not overlap. The original instance is consumed, so its exclusive borrow is split
+        // between the halves.
+        Ok(unsafe {
+            (
+                BytesMut::from_raw_parts(self.ptr, mid),
+                BytesMut::from_raw_parts(self.ptr.add(mid), self.len - mid),
+            )
+        })
+    }
+
+    /// Splits off the first `n` bytes, returning them followed by the rest.
+    ///
+    /// This is the same as [`split_at(n)`][BytesMut::split_at].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `n > self.len()`.
+    #[inline]
+    pub const fn take_front(self, n: usize) -> Result<(BytesMut<'data>, BytesMut<'data>)> {
+        self.split_at(n)
+    }
+
+    /// Splits off the last `n` bytes, returning the rest followed by them.
+    ///
+    /// Like [`Bytes::take_back`], the halves are returned in the order they appear
+    /// in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `n > self.len()`.
+    #[inline]
+    pub const fn take_back(self, n: usize) -> Result<(BytesMut<'data>, BytesMut<'data>)> {
+        if n > self.len {
+            return Err(Error::out_of_bounds(n, self.len));
+        }
+        let mid = self.len - n;
+        self.split_at(mid)
+    }
+
+    /// Converts this instance into an immutable [`Bytes`] over the same region,
+    /// keeping the original lifetime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abio::BytesMut;
+    ///
+    /// let mut buf = *b"abio";
+    /// let mut bytes = BytesMut::new(&mut buf);
+    /// bytes[..1].copy_from_slice(b"A");
+    /// assert_eq!(bytes.freeze(), b"Abio");
+    /// ```
+    #[inline]
+    pub const fn freeze(self) -> Bytes<'data> {
+        // SAFETY: The instance is consumed, so the exclusive borrow is downgraded to a
+        // shared one for the remainder of `'data`.
+        Bytes::new_allow_empty(unsafe { slice::from_raw_parts(self.ptr, self.len) })
+    }
+}
+
+impl fmt::Debug for BytesMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BytesMut")
+            .field(&self.as_slice())
+            .finish()
+    }
+}
+
+impl AsRef<[u8]> for BytesMut<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsMut<[u8]> for BytesMut<'_> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl Borrow<[u8]> for BytesMut<'_> {
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl BorrowMut<[u8]> for BytesMut<'_> {
+    fn borrow_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl Deref for BytesMut<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl DerefMut for BytesMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<'data> From<&'data mut [u8]> for BytesMut<'data> {
+    #[inline(always)]
+    fn from(slice: &'data mut [u8]) -> BytesMut<'data> {
+        BytesMut::new(slice)
+    }
+}
+
+impl<'data> From<BytesMut<'data>> for Bytes<'data> {
+    #[inline]
+    fn from(bytes: BytesMut<'data>) -> Bytes<'data> {
+        bytes.freeze()
+    }
+}
+
+impl Index<Span> for BytesMut<'_> {
+    type Output = [u8];
+
+    fn index(&self, span: Span) -> &Self::Output {
+        &self.as_slice()[span.range()]
+    }
+}
+
+impl IndexMut<Span> for BytesMut<'_> {
+    fn index_mut(&mut self, span: Span) -> &mut Self::Output {
+        &mut self.as_mut_slice()[span.range()]
+    }
+}
+
+impl Index<RangeFrom<usize>> for BytesMut<'_> {
+    type Output = [u8];
+
+    #[inline]
+    fn index(&self, range: RangeFrom<usize>) -> &Self::Output {
+        &self.as_slice()[range]
+    }
+}
+
+impl IndexMut<RangeFrom<usize>> for BytesMut<'_> {
+    #[inline]
+    fn index_mut(&mut self, range: RangeFrom<usize>) -> &mut Self::Output {
+        &mut self.as_mut_slice()[range]
+    }
+}
+
+impl Index<RangeTo<usize>> for BytesMut<'_> {
+    type Output = [u8];
+
+    #[inline]
+    fn index(&self, range: RangeTo<usize>) -> &Self::Output {
+        &self.as_slice()[range]
+    }
+}
+
+impl IndexMut<RangeTo<usize>> for BytesMut<'_> {
+    #[inline]
+    fn index_mut(&mut self, range: RangeTo<usize>) -> &mut Self::Output {
+        &mut self.as_mut_slice()[range]
+    }
+}
+
+impl<'a> IntoIterator for &'a BytesMut<'_> {
+    type Item = &'a u8;
+
+    type IntoIter = slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut BytesMut<'_> {
+    type Item = &'a mut u8;
+
+    type IntoIter = slice::IterMut<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<S: AsRef<[u8]>> PartialEq<S> for BytesMut<'_> {
+    #[inline]
+    fn eq(&self, other: &S) -> bool {
+        self.as_slice()
+            .eq(other.as_ref())
+    }
+}
+
+impl Eq for BytesMut<'_> {}
+
+impl Source for BytesMut<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        BytesMut::len(self)
+    }
+
+    #[inline]
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.as_slice()
+            .read_slice(offset, len)
+    }
+}
+
+/// Writing into [`BytesMut`] fills bytes from the front of the slice and advances
+/// past them, in the same way writing into a `&mut [u8]` does.
+#[cfg(feature = "std")]
+impl std::io::Write for BytesMut<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.len);
+        self.as_mut_slice()[..len].copy_from_slice(&buf[..len]);
+        // SAFETY: `len <= self.len`, so the advanced pointer remains within bounds of (or
+        // one past the end of) the borrowed slice.
+        self.ptr = unsafe { self.ptr.add(len) };
+        self.len -= len;
+        Ok(len)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposes_region_and_bounds_checks_subslices() {
+        let mut buf = *b"\x01\x02\x03\x04\x05";
+        let mut bytes = BytesMut::new(&mut buf);
+        assert_eq!(bytes.len(), 5);
+        assert!(!bytes.is_empty());
+        assert_eq!(bytes.as_end_ptr().addr() - bytes.as_ptr().addr(), 5);
+        assert_eq!(bytes.as_ptr_range().start, bytes.as_ptr());
+        assert_eq!(bytes.subslice(1..3).unwrap(), b"\x02\x03");
+        assert_eq!(bytes.subslice(5..5).unwrap(), b"");
+        assert!(bytes.subslice(4..6).is_err());
+        assert!(bytes
+            .subslice_mut(Range { start: 3, end: 2 })
+            .is_err());
+        assert!(bytes
+            .subslice_mut(0..6)
+            .is_err());
+
+        bytes
+            .subslice_mut(0..2)
+            .unwrap()
+            .fill(0xff);
+        for byte in &mut bytes {
+            *byte = byte.wrapping_add(1);
+        }
+        assert_eq!(bytes, b"\x00\x00\x04\x05\x06");
+        assert_eq!(&bytes[Span::new(2, 2)], b"\x04\x05");
+        assert_eq!(&bytes[3..], b"\x05\x06");
+        assert_eq!(&bytes[..1], b"\x00");
+        assert_eq!(bytes.iter().sum::<u8>(), 15);
+
+        let tail = BytesMut::new_with_offset(&mut buf, 3).unwrap();
+        assert_eq!(tail, b"\x05\x06");
+        assert!(BytesMut::new_with_offset(&mut buf, 6).is_err());
+        assert!(BytesMut::new_with_offset(&mut buf, 5)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn copy_from_slice_patches_in_place() {
+        let mut buf = [0u8; 4];
+        let mut bytes = BytesMut::new(&mut buf);
+        bytes
+            .copy_from_slice(1, b"ab")
+            .unwrap();
+        assert!(bytes
+            .copy_from_slice(3, b"cd")
+            .is_err());
+        assert!(bytes
+            .copy_from_slice(usize::MAX, b"x")
+            .is_err());
+        if let Some(byte) = bytes.as_mut().last_mut() {
+            *byte = b'!';
+        }
+        assert_eq!(buf, *b"\x00ab!");
+    }
+
+    #[test]
+    fn copy_within_matches_slice_copy_within() {
+        const INPUT: [u8; 8] = *b"01234567";
+        for start in 0..=INPUT.len() {
+            for end in start..=INPUT.len() {
+                for dst in 0..=INPUT.len() - (end - start) {
+                    let mut expected = INPUT;
+                    expected.copy_within(start..end, dst);
+
+                    let mut buf = INPUT;
+                    BytesMut::new(&mut buf)
+                        .copy_within_span(Span::new(start, end - start), dst)
+                        .unwrap();
+                    assert_eq!(buf, expected);
+                }
+            }
+        }
+
+        let mut buf = INPUT;
+        let mut bytes = BytesMut::new(&mut buf);
+        assert!(bytes
+            .copy_within(4..9, 0)
+            .is_err());
+        assert!(bytes
+            .copy_within(Range { start: 3, end: 2 }, 0)
+            .is_err());
+        assert!(bytes
+            .copy_within_span(Span::new(0, 4), 5)
+            .is_err());
+        assert!(bytes
+            .copy_within(0..1, usize::MAX)
+            .is_err());
+        assert_eq!(buf, INPUT);
+    }
+
+    #[test]
+    fn move_span_zeroes_vacated_bytes() {
+        let mut buf = *b"abcdefgh";
+        let mut bytes = BytesMut::new(&mut buf);
+        bytes
+            .move_span(Span::new(2, 4), 0)
+            .unwrap();
+        assert_eq!(bytes, b"cdef\x00\x00gh");
+        bytes
+            .move_span(Span::new(0, 2), 6)
+            .unwrap();
+        assert_eq!(bytes, b"\x00\x00ef\x00\x00cd");
+        bytes
+            .move_span(Span::new(2, 2), 2)
+            .unwrap();
+        assert_eq!(bytes, b"\x00\x00ef\x00\x00cd");
+        assert!(bytes
+            .move_span(Span::new(4, 4), 5)
+            .is_err());
+    }
+
+    #[test]
+    fn insert_gap_shifts_tail_towards_end() {
+        let mut buf = *b"abcdef";
+        let mut bytes = BytesMut::new(&mut buf);
+        bytes.insert_gap(1, 2).unwrap();
+        assert_eq!(bytes, b"a\x00\x00bcd");
+        bytes.insert_gap(6, 0).unwrap();
+        bytes.insert_gap(4, 2).unwrap();
+        assert_eq!(bytes, b"a\x00\x00b\x00\x00");
+        assert!(bytes.insert_gap(5, 2).is_err());
+        assert!(bytes
+            .insert_gap(1, usize::MAX)
+            .is_err());
+        assert_eq!(buf, *b"a\x00\x00b\x00\x00");
+    }
+
+    #[test]
+    fn split_at_yields_disjoint_halves() {
+        let mut buf = *b"headbody";
+        let (mut head, mut body) = BytesMut::new(&mut buf)
+            .split_at(4)
+            .unwrap();
+        head.as_mut_slice()
+            .make_ascii_uppercase();
+        body[Span::new(0, 1)].copy_from_slice(b"B");
+        assert_eq!(head, b"HEAD");
+        assert_eq!(body.freeze(), b"Body");
+        assert_eq!(buf, *b"HEADBody");
+
+        let (empty, all) = BytesMut::new(&mut buf)
+            .split_at(0)
+            .unwrap();
+        assert!(empty.is_empty());
+        assert!(empty.freeze().is_empty());
+        let (all, empty) = all.split_at(8).unwrap();
+        assert_eq!(all.len(), 8);
+        assert!(empty.is_empty());
+        assert!(all.split_at(9).is_err());
+
+        let (body, crc) = BytesMut::new(&mut buf)
+            .take_back(2)
+            .unwrap();
+        let (head, body) = body.take_front(4).unwrap();
+        assert_eq!((head.len(), body.len(), crc.len()), (4, 2, 2));
+        assert!(crc.take_back(3).is_err());
+    }
+
+    #[test]
+    fn implements_source() {
+        let mut buf = *b"\x00\x01\x02\x03tail";
+        let bytes = BytesMut::new(&mut buf);
+        assert_eq!(bytes.read_slice(1, 2).unwrap(), b"\x01\x02");
+        assert!(bytes.read_slice(6, 3).is_err());
+        assert_eq!(bytes.read_at_end(4).unwrap(), b"tail");
+
+        let mut reader = crate::Reader::new(&bytes);
+        assert_eq!(reader.read_bytes(4).unwrap(), b"\x00\x01\x02\x03");
+        assert_eq!(reader.remaining(), 4);
+
+        let frozen: Bytes<'_> = bytes.into();
+        assert_eq!(frozen, b"\x00\x01\x02\x03tail");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_advances_past_written_bytes() {
+        use std::io::Write;
+
+        let mut buf = [0u8; 4];
+        let mut bytes = BytesMut::new(&mut buf);
+        assert_eq!(bytes.write(b"ab").unwrap(), 2);
+        assert_eq!(bytes.len(), 2);
+        assert_eq!(bytes.write(b"cde").unwrap(), 2);
+        assert!(bytes.is_empty());
+        assert!(bytes.write_all(b"f").is_err());
+        assert_eq!(buf, *b"abcd");
+    }
 }
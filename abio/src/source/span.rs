@@ -7,7 +7,7 @@
 use core::ops::{Index, Range};
 
 use crate::source::Chunk;
-use crate::Bytes;
+use crate::{Bytes, Error, Result};
 
 /// A bounded region of memory defined by a pair of indices that point to the same
 /// [allocated object][allocated-object].
@@ -90,6 +90,57 @@ impl Span {
         self.start..self.end
     }
 
+    /// Splits this span into the first `mid` bytes and the rest, like
+    /// [`Bytes::split_at`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `mid > self.size()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use abio::Span;
+    ///
+    /// let (header, body) = Span::new(16, 6).split_at(4).unwrap();
+    /// assert_eq!((header.range(), body.range()), (16..20, 20..22));
+    /// assert!(body.split_at(3).is_err());
+    /// ```
+    #[inline]
+    pub const fn split_at(self, mid: usize) -> Result<(Span, Span)> {
+        if mid > self.size() {
+            return Err(Error::out_of_bounds(mid, self.size()));
+        }
+        let mid = self.start + mid;
+        Ok((Span { start: self.start, end: mid }, Span { start: mid, end: self.end }))
+    }
+
+    /// Splits off the first `n` bytes, returning them followed by the rest.
+    ///
+    /// This is the same as [`split_at(n)`][Span::split_at].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `n > self.size()`.
+    #[inline]
+    pub const fn take_front(self, n: usize) -> Result<(Span, Span)> {
+        self.split_at(n)
+    }
+
+    /// Splits off the last `n` bytes, returning the rest followed by them, like
+    /// [`Bytes::take_back`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `n > self.size()`.
+    #[inline]
+    pub const fn take_back(self, n: usize) -> Result<(Span, Span)> {
+        if n > self.size() {
+            return Err(Error::out_of_bounds(n, self.size()));
+        }
+        self.split_at(self.size() - n)
+    }
+
     /// Advances the span forward by `T::SIZE` bytes.
     #[inline]
     pub fn advance(&mut self, count: usize) {
@@ -182,4 +233,22 @@ mod tests {
             "Span bytes (0..4) should equal 3363848277, got {value}"
         );
     }
+
+    #[test]
+    fn splits_spans_from_either_end() {
+        let span = Span::new(8, 10);
+        let (body, crc) = span.take_back(2).unwrap();
+        assert_eq!((body, crc), (Span::new(8, 8), Span::new(16, 2)));
+        let (magic, rest) = body.take_front(4).unwrap();
+        assert_eq!((magic.range(), rest.range()), (8..12, 12..16));
+        assert_eq!(
+            span.split_at(10)
+                .unwrap()
+                .1
+                .size(),
+            0
+        );
+        assert!(span.take_back(11).is_err());
+        assert!(span.take_front(11).is_err());
+    }
 }
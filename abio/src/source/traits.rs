@@ -0,0 +1,527 @@
+//! Module containing the [`Source`] trait, the common interface implemented by all
+//! readable inputs, its mutable counterpart [`SourceMut`], and [`FixedSource`] for
+//! sources of a fixed size.
+
+use crate::{AsBytes, Bytes, BytesMut, Chunk, Error, Result};
+
+#[doc = include_str!("../../docs/contiguous/source.md")]
+pub trait Source {
+    /// Returns the total number of bytes that can be read from this source.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the source does not contain any bytes.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a borrowed slice of `len` bytes, starting at `offset`.
+    ///
+    /// This is the zero-copy read path. Sources that cannot lend out a contiguous
+    /// view of the requested region should return an error, in which case the
+    /// caller can fall back to [`copy_to_slice`][Source::copy_to_slice].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + len > self.len()`, or if the requested region
+    /// is not contiguous in memory.
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]>;
+
+    /// Returns a borrowed slice of `len` bytes, starting at `offset`, without
+    /// counting the bytes as read.
+    ///
+    /// A [`Reader`][crate::Reader] decodes values from the bytes it peeks at, and
+    /// then reads only the bytes a value was decoded from, so sources that track the
+    /// regions read from them, such as `Coverage`, do not see the rest. The default
+    /// implementation forwards to [`read_slice`][Source::read_slice].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + len > self.len()`, or if the requested region
+    /// is not contiguous in memory.
+    #[inline]
+    fn peek_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.read_slice(offset, len)
+    }
+
+    /// Returns the number of bytes, starting at `offset`, that
+    /// [`read_slice`][Source::read_slice] can lend out as a single slice.
+    ///
+    /// The default implementation returns every byte after `offset`, which is
+    /// correct for sources that are contiguous in memory. Sources that are not
+    /// override it, such as [`SegmentedSource`][crate::SegmentedSource], which
+    /// returns the bytes left in the segment holding `offset`.
+    #[inline]
+    fn contiguous_len(&self, offset: usize) -> usize {
+        self.len()
+            .saturating_sub(offset)
+    }
+
+    /// Copies `dst.len()` bytes, starting at `offset`, into `dst`.
+    ///
+    /// The default implementation copies from [`read_slice`][Source::read_slice].
+    /// Sources that are not contiguous in memory must override this method.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + dst.len() > self.len()`.
+    #[inline]
+    fn copy_to_slice(&self, offset: usize, dst: &mut [u8]) -> Result<()> {
+        let src = self.read_slice(offset, dst.len())?;
+        dst.copy_from_slice(src);
+        Ok(())
+    }
+
+    /// Reads a [`Chunk`] of `N` bytes, starting at `offset`.
+    ///
+    /// The bytes are copied as-is, without any endianness conversions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + N > self.len()`.
+    #[inline]
+    fn read_chunk<const N: usize>(&self, offset: usize) -> Result<Chunk<N>> {
+        let mut buf = [0u8; N];
+        self.copy_to_slice(offset, &mut buf)?;
+        Ok(Chunk::from_ne_bytes(buf))
+    }
+
+    /// Returns a borrowed slice of the last `len` bytes of the source.
+    ///
+    /// Many formats store their index at the end of the input, so this is the
+    /// tail-oriented counterpart of [`read_slice`][Source::read_slice].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `len > self.len()`, or if the source cannot lend out a
+    /// contiguous view of the requested region.
+    #[inline]
+    fn read_at_end(&self, len: usize) -> Result<&[u8]> {
+        let offset = self
+            .len()
+            .checked_sub(len)
+            .ok_or_else(|| Error::out_of_bounds(len, self.len()))?;
+        self.read_slice(offset, len)
+    }
+
+    /// Reads a [`Chunk`] holding the last `N` bytes of the source.
+    ///
+    /// The bytes are copied as-is, without any endianness conversions. This method
+    /// is not named `last_chunk`, since that name is taken by an inherent method of
+    /// `[u8]` that would shadow it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `N > self.len()`.
+    #[inline]
+    fn read_chunk_at_end<const N: usize>(&self) -> Result<Chunk<N>> {
+        let offset = self
+            .len()
+            .checked_sub(N)
+            .ok_or_else(|| Error::out_of_bounds(N, self.len()))?;
+        self.read_chunk::<N>(offset)
+    }
+
+    /// Scans backwards through the last `window` bytes of the source for `needle`,
+    /// returning the offset of its last occurrence.
+    ///
+    /// The window is clamped to the length of the source, and a match must lie
+    /// entirely within it. An empty `needle` matches at the end of the source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source cannot lend out a contiguous view of the
+    /// window.
+    #[inline]
+    fn rscan_for(&self, needle: &[u8], window: usize) -> Result<Option<usize>> {
+        let window = window.min(self.len());
+        let start = self.len() - window;
+        if needle.is_empty() {
+            return Ok(Some(self.len()));
+        }
+        let haystack = self.read_slice(start, window)?;
+        Ok(haystack
+            .windows(needle.len())
+            .rposition(|candidate| candidate == needle)
+            .map(|idx| start + idx))
+    }
+}
+
+/// Trait for sources whose bytes can be overwritten in place.
+///
+/// This is the mutable counterpart of [`Source`], for patching fields such as
+/// checksums, lengths or relocations inside an existing buffer. Writes are bounds
+/// checked in the same way reads are, and a write that fails leaves the source
+/// untouched.
+///
+/// # Examples
+///
+/// ```
+/// use abio::{Chunk, SourceMut};
+///
+/// let mut image = [0u8; 8];
+/// image.write_slice_at(0, b"MZ").unwrap();
+/// image.write_chunk_at(2, Chunk::from_ne_bytes([0xff; 2])).unwrap();
+/// image.write(4, &0x0102_0304u32.to_be()).unwrap();
+/// assert_eq!(image, [b'M', b'Z', 0xff, 0xff, 1, 2, 3, 4]);
+/// assert!(image.write(6, &0u32).is_err());
+/// ```
+pub trait SourceMut: Source {
+    /// Overwrites `src.len()` bytes, starting at `offset`, with the contents of
+    /// `src`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + src.len() > self.len()`.
+    fn write_slice_at(&mut self, offset: usize, src: &[u8]) -> Result<()>;
+
+    /// Overwrites the `N` bytes starting at `offset` with the bytes of `chunk`.
+    ///
+    /// The bytes are copied as-is, without any endianness conversions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + N > self.len()`.
+    #[inline]
+    fn write_chunk_at<const N: usize>(&mut self, offset: usize, chunk: Chunk<N>) -> Result<()> {
+        self.write_slice_at(offset, chunk.as_slice())
+    }
+
+    /// Overwrites the `size_of_val(value)` bytes starting at `offset` with the bytes
+    /// of `value`, as they are laid out in memory.
+    ///
+    /// This is the counterpart of [`read_struct`][crate::SourceExt::read_struct].
+    /// No byte order conversion takes place, and `offset` does not need to be
+    /// aligned for `T`, since the bytes are copied rather than written through a
+    /// reference to `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset + size_of_val(value) > self.len()`.
+    #[inline]
+    fn write<T: AsBytes + ?Sized>(&mut self, offset: usize, value: &T) -> Result<()> {
+        self.write_slice_at(offset, value.as_bytes())
+    }
+}
+
+/// Trait for sources of exactly `N` bytes, whose reads at constant offsets are
+/// bounds checked at compile time.
+///
+/// Reads through [`Source`] take their offset at run time, and so check it at run
+/// time too. When both the offset and the length are constants, the methods of
+/// this trait check them while compiling instead, so a read past the end fails to
+/// build rather than returning an error:
+///
+/// ```
+/// use abio::{Chunk, FixedSource};
+///
+/// let header = *b"\x7fELF\x02\x01";
+/// assert_eq!(header.sub_array::<1, 3>(), b"ELF");
+/// assert_eq!(header.sub_chunk::<4, 2>(), Chunk::from_ne_bytes([2, 1]));
+/// ```
+///
+/// ```compile_fail
+/// use abio::FixedSource;
+///
+/// let header = *b"\x7fELF";
+/// let _ = header.sub_array::<2, 3>();
+/// ```
+pub trait FixedSource<const N: usize>: Source {
+    /// Returns the bytes of this source.
+    fn as_array(&self) -> &[u8; N];
+
+    /// Borrows the `M` bytes starting at `OFFSET`.
+    #[inline]
+    fn sub_array<const OFFSET: usize, const M: usize>(&self) -> &[u8; M] {
+        const { assert!(OFFSET <= N && M <= N - OFFSET, "read must lie within the source") };
+        // SAFETY: The assertion above ensures the `M` bytes starting at `OFFSET` lie
+        // within the array, and byte arrays have no alignment requirements.
+        unsafe {
+            &*self
+                .as_array()
+                .as_ptr()
+                .add(OFFSET)
+                .cast::<[u8; M]>()
+        }
+    }
+
+    /// Copies the `M` bytes starting at `OFFSET` into a chunk.
+    #[inline]
+    fn sub_chunk<const OFFSET: usize, const M: usize>(&self) -> Chunk<M> {
+        Chunk::from_ne_bytes(*self.sub_array::<OFFSET, M>())
+    }
+}
+
+/// Computes the end offset of a read, verifying it lies within `available` bytes.
+#[inline]
+pub(crate) const fn checked_end(offset: usize, len: usize, available: usize) -> Result<usize> {
+    match offset.checked_add(len) {
+        Some(end) if end <= available => Ok(end),
+        Some(end) => Err(Error::out_of_bounds(end, available)),
+        None => Err(Error::out_of_bounds(usize::MAX, available)),
+    }
+}
+
+impl Source for [u8] {
+    #[inline]
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    #[inline]
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        #[cfg(feature = "stats")]
+        crate::codec::stats::record_bounds_check();
+        let end = checked_end(offset, len, self.len())?;
+        #[cfg(feature = "stats")]
+        crate::codec::stats::record_read(len);
+        Ok(&self[offset..end])
+    }
+
+    #[inline]
+    fn peek_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        let end = checked_end(offset, len, self.len())?;
+        Ok(&self[offset..end])
+    }
+}
+
+impl<'data> Source for Bytes<'data> {
+    #[inline]
+    fn len(&self) -> usize {
+        Bytes::len(self)
+    }
+
+    #[inline]
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.as_slice()
+            .read_slice(offset, len)
+    }
+
+    #[inline]
+    fn peek_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.as_slice()
+            .peek_slice(offset, len)
+    }
+}
+
+impl<const N: usize> Source for [u8; N] {
+    #[inline]
+    fn len(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.as_slice()
+            .read_slice(offset, len)
+    }
+
+    #[inline]
+    fn peek_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.as_slice()
+            .peek_slice(offset, len)
+    }
+}
+
+impl<const N: usize> Source for Chunk<N> {
+    #[inline]
+    fn len(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.as_slice()
+            .read_slice(offset, len)
+    }
+
+    #[inline]
+    fn peek_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        self.as_slice()
+            .peek_slice(offset, len)
+    }
+}
+
+impl<const N: usize> FixedSource<N> for [u8; N] {
+    #[inline]
+    fn as_array(&self) -> &[u8; N] {
+        self
+    }
+}
+
+impl<const N: usize> FixedSource<N> for Chunk<N> {
+    #[inline]
+    fn as_array(&self) -> &[u8; N] {
+        self.as_byte_array()
+    }
+}
+
+/// Sources can be passed by reference to functions taking a source by value, such
+/// as `&[u8]` or `&Bytes`.
+impl<S: Source + ?Sized> Source for &S {
+    #[inline]
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    #[inline]
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        (**self).read_slice(offset, len)
+    }
+
+    #[inline]
+    fn peek_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        (**self).peek_slice(offset, len)
+    }
+
+    #[inline]
+    fn contiguous_len(&self, offset: usize) -> usize {
+        (**self).contiguous_len(offset)
+    }
+
+    #[inline]
+    fn copy_to_slice(&self, offset: usize, dst: &mut [u8]) -> Result<()> {
+        (**self).copy_to_slice(offset, dst)
+    }
+
+    #[inline]
+    fn read_chunk<const N: usize>(&self, offset: usize) -> Result<Chunk<N>> {
+        (**self).read_chunk::<N>(offset)
+    }
+
+    #[inline]
+    fn rscan_for(&self, needle: &[u8], window: usize) -> Result<Option<usize>> {
+        (**self).rscan_for(needle, window)
+    }
+}
+
+impl SourceMut for [u8] {
+    #[inline]
+    fn write_slice_at(&mut self, offset: usize, src: &[u8]) -> Result<()> {
+        let end = checked_end(offset, src.len(), self.len())?;
+        self[offset..end].copy_from_slice(src);
+        Ok(())
+    }
+}
+
+impl<const N: usize> SourceMut for [u8; N] {
+    #[inline]
+    fn write_slice_at(&mut self, offset: usize, src: &[u8]) -> Result<()> {
+        self.as_mut_slice()
+            .write_slice_at(offset, src)
+    }
+}
+
+impl SourceMut for BytesMut<'_> {
+    #[inline]
+    fn write_slice_at(&mut self, offset: usize, src: &[u8]) -> Result<()> {
+        self.copy_from_slice(offset, src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_reads_are_bounds_checked() {
+        let bytes: &[u8] = b"header PK\x05\x06 body PK\x05\x06 tail";
+        assert_eq!(bytes.read_at_end(4).unwrap(), b"tail");
+        assert_eq!(
+            bytes
+                .read_at_end(bytes.len())
+                .unwrap(),
+            bytes
+        );
+        assert!(bytes
+            .read_at_end(bytes.len() + 1)
+            .is_err());
+
+        assert_eq!(
+            bytes
+                .read_chunk_at_end::<2>()
+                .unwrap(),
+            Chunk::from_ne_bytes(*b"il")
+        );
+        assert!(bytes
+            .read_chunk_at_end::<64>()
+            .is_err());
+
+        assert_eq!(
+            bytes
+                .rscan_for(b"PK\x05\x06", usize::MAX)
+                .unwrap(),
+            Some(17)
+        );
+        assert_eq!(
+            bytes
+                .rscan_for(b"PK\x05\x06", 8)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            bytes
+                .rscan_for(b"PK\x05\x06", 9)
+                .unwrap(),
+            Some(17)
+        );
+        assert_eq!(bytes.rscan_for(b"", 4).unwrap(), Some(bytes.len()));
+    }
+
+    #[test]
+    fn writes_are_bounds_checked() {
+        let mut buf = [0u8; 6];
+        let mut bytes = BytesMut::new(&mut buf);
+        bytes
+            .write_chunk_at(4, Chunk::from_ne_bytes([1, 2]))
+            .unwrap();
+        bytes
+            .write(0, &[3u8, 4])
+            .unwrap();
+        assert!(bytes
+            .write_slice_at(5, b"ab")
+            .is_err());
+        assert!(bytes
+            .write(usize::MAX, &0u16)
+            .is_err());
+        assert_eq!(buf, [3, 4, 0, 0, 1, 2]);
+
+        let mut buf = *b"length: ??";
+        buf.write_slice_at(8, b"42")
+            .unwrap();
+        assert!(buf
+            .write_chunk_at::<3>(8, Chunk::from_ne_bytes(*b"100"))
+            .is_err());
+        assert_eq!(&buf, b"length: 42");
+    }
+
+    fn checksum<S: Source>(source: S) -> Result<u8> {
+        let bytes = source.read_slice(0, source.len())?;
+        Ok(bytes
+            .iter()
+            .fold(0, |sum, byte| sum.wrapping_add(*byte)))
+    }
+
+    #[test]
+    fn byte_containers_are_sources() {
+        let array = [1u8, 2, 3, 4];
+        let chunk = Chunk::from_ne_bytes(array);
+        let slice: &[u8] = &array;
+        assert_eq!(checksum(array), Ok(10));
+        assert_eq!(checksum(chunk), Ok(10));
+        assert_eq!(checksum(slice), Ok(10));
+        assert_eq!(checksum(Bytes::new(slice)), Ok(10));
+
+        assert_eq!(Source::len(&chunk), 4);
+        assert_eq!(
+            chunk
+                .read_chunk::<2>(2)
+                .unwrap(),
+            Chunk::from_ne_bytes([3, 4])
+        );
+        assert!(array.read_slice(3, 2).is_err());
+        assert_eq!(chunk.sub_chunk::<1, 2>(), Chunk::from_ne_bytes([2, 3]));
+        assert_eq!(array.sub_array::<2, 2>(), &[3, 4]);
+        assert_eq!(FixedSource::sub_chunk::<0, 4>(&chunk), chunk);
+    }
+}
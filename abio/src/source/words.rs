@@ -0,0 +1,185 @@
+//! Module containing adapters for reading word-oriented buffers as bytes.
+//!
+//! Some hardware, such as DSPs and DMA engines, hands out memory as buffers of
+//! 16-bit or 32-bit words rather than bytes. The [`Words`] adapter exposes such a
+//! buffer as a byte [`Source`] without first copying it into a temporary byte
+//! buffer.
+
+use core::marker::PhantomData;
+use core::{mem, slice};
+
+use crate::source::traits::checked_end;
+use crate::{sealed, Abi, Endian, Endianness, Error, Result, Source};
+
+/// Trait for unsigned integer primitives that can back a [`Words`] adapter.
+///
+/// This trait is sealed and implemented for [`u16`], [`u32`] and [`u64`].
+pub trait Word: Abi + Copy + sealed::Sealed {
+    /// Returns the byte at `idx` within this word, with the word serialized using
+    /// the byte order `endian`.
+    fn byte_at(self, idx: usize, endian: Endian) -> u8;
+
+    /// Reads a word from the first `size_of::<Self>()` bytes of `bytes`, serialized
+    /// using the byte order `endian`.
+    ///
+    /// Returns `None` if `bytes` is too short to contain a word.
+    fn read_prefix(bytes: &[u8], endian: Endian) -> Option<Self>;
+}
+
+macro_rules! impl_word {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Word for $ty {
+                #[inline(always)]
+                fn byte_at(self, idx: usize, endian: Endian) -> u8 {
+                    match endian {
+                        Endian::Little => self.to_le_bytes()[idx],
+                        Endian::Big => self.to_be_bytes()[idx],
+                    }
+                }
+
+                #[inline(always)]
+                fn read_prefix(bytes: &[u8], endian: Endian) -> Option<Self> {
+                    const SIZE: usize = mem::size_of::<$ty>();
+                    let array = *bytes.first_chunk::<SIZE>()?;
+                    Some(match endian {
+                        Endian::Little => <$ty>::from_le_bytes(array),
+                        Endian::Big => <$ty>::from_be_bytes(array),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u16, u32, u64);
+
+/// Byte [`Source`] backed by a borrowed slice of words.
+///
+/// The type parameter `W` is the word type, and `E` is the byte order the stream
+/// of words is serialized with. For example, a buffer of `u16` samples that should
+/// be read as a big endian byte stream is represented by `Words<'_, u16, BE>`.
+///
+/// # Zero-Copy Reads
+///
+/// When `E` matches the native byte order of the target, the bytes of each word
+/// are already laid out in memory in stream order. In that case, the adapter
+/// lends out borrowed slices via [`Source::read_slice`]. Otherwise, every read must
+/// swap the bytes of each word, so only the copying read methods are available
+/// and `read_slice` returns an error.
+///
+/// # Alignment
+///
+/// The byte view of the buffer always starts at an address aligned to `W`, so a
+/// read at an offset that is a multiple of `size_of::<W>()` yields a region that
+/// is suitably aligned for `W` as well.
+#[derive(Debug)]
+pub struct Words<'data, W: Word, E: Endianness> {
+    words: &'data [W],
+    _endian: PhantomData<E>,
+}
+
+impl<W: Word, E: Endianness> Clone for Words<'_, W, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<W: Word, E: Endianness> Copy for Words<'_, W, E> {}
+
+impl<'data, W: Word, E: Endianness> Words<'data, W, E> {
+    /// Creates a new adapter over a borrowed buffer of words.
+    #[inline]
+    pub const fn new(words: &'data [W]) -> Self {
+        Self { words, _endian: PhantomData }
+    }
+
+    /// Returns the underlying buffer of words.
+    #[inline]
+    pub const fn as_words(&self) -> &'data [W] {
+        self.words
+    }
+
+    /// Returns `true` if reads are zero-copy, which is the case when the byte order
+    /// `E` matches the native byte order of the target.
+    #[inline]
+    pub const fn is_zero_copy(&self) -> bool {
+        E::ENDIAN.is_native_endian()
+    }
+
+    /// Returns the memory of the buffer as a slice of bytes in native byte order.
+    #[inline]
+    pub fn as_native_bytes(&self) -> &'data [u8] {
+        // SAFETY: `W` is an unsigned integer primitive without padding bytes, so all of its
+        // bytes are initialized and any alignment is suitable for `u8`. The length of the
+        // slice is the size of the buffer in bytes, which cannot overflow an allocation.
+        unsafe {
+            slice::from_raw_parts(self.words.as_ptr().cast::<u8>(), mem::size_of_val(self.words))
+        }
+    }
+}
+
+impl<'data, W: Word, E: Endianness> Source for Words<'data, W, E> {
+    #[inline]
+    fn len(&self) -> usize {
+        mem::size_of_val(self.words)
+    }
+
+    fn read_slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        let end = checked_end(offset, len, self.len())?;
+        if self.is_zero_copy() {
+            Ok(&self.as_native_bytes()[offset..end])
+        } else {
+            Err(Error::non_contiguous(offset, len))
+        }
+    }
+
+    #[inline]
+    fn contiguous_len(&self, offset: usize) -> usize {
+        if self.is_zero_copy() {
+            self.len()
+                .saturating_sub(offset)
+        } else {
+            0
+        }
+    }
+
+    fn copy_to_slice(&self, offset: usize, dst: &mut [u8]) -> Result<()> {
+        checked_end(offset, dst.len(), self.len())?;
+        if self.is_zero_copy() {
+            dst.copy_from_slice(&self.as_native_bytes()[offset..offset + dst.len()]);
+        } else {
+            for (pos, byte) in dst.iter_mut().enumerate() {
+                let idx = offset + pos;
+                *byte = self.words[idx / W::SIZE].byte_at(idx % W::SIZE, E::ENDIAN);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BE, LE};
+
+    #[test]
+    fn word_buffers_read_as_byte_streams() {
+        let words = [0x1234u16, 0x5678];
+
+        let le = Words::<u16, LE>::new(&words);
+        let be = Words::<u16, BE>::new(&words);
+        assert_eq!(le.len(), 4);
+
+        let mut buf = [0u8; 3];
+        le.copy_to_slice(1, &mut buf)
+            .unwrap();
+        assert_eq!(buf, [0x12, 0x78, 0x56]);
+        be.copy_to_slice(1, &mut buf)
+            .unwrap();
+        assert_eq!(buf, [0x34, 0x56, 0x78]);
+
+        assert!(be
+            .copy_to_slice(2, &mut buf)
+            .is_err());
+    }
+}
@@ -0,0 +1,365 @@
+//! Helpers for writing tests against decoders.
+//!
+//! This module is only available when the __`alloc`__ feature is enabled.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+use crate::config::Codec;
+use crate::{Decode, Encode, Result};
+
+/// Builder for assembling synthetic input buffers with a chainable mini-DSL.
+///
+/// Decoder tests often need buffers with values at specific offsets. Writing them
+/// as hand-assembled byte literals quickly becomes unreadable, so [`BufBuilder`]
+/// lets each value be spelled out with its width and byte order instead.
+///
+/// # Examples
+///
+/// ```
+/// use abio::testing::BufBuilder;
+///
+/// let image = BufBuilder::new()
+///     .bytes(b"MZ")
+///     .pad_to(0x3c)
+///     .u32_le(0x80)
+///     .pad_to(0x80)
+///     .bytes(b"PE\0\0")
+///     .build();
+///
+/// assert_eq!(image.len(), 0x84);
+/// assert_eq!(&image[0x3c..0x40], &[0x80, 0, 0, 0]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BufBuilder {
+    buf: Vec<u8>,
+}
+
+macro_rules! builder_int_methods {
+    ($($ty:ty => $le:ident, $be:ident;)*) => {
+        $(
+            #[doc = concat!("Appends a [`", stringify!($ty), "`] in little endian byte order.")]
+            #[inline]
+            pub fn $le(self, value: $ty) -> Self {
+                self.bytes(&value.to_le_bytes())
+            }
+
+            #[doc = concat!("Appends a [`", stringify!($ty), "`] in big endian byte order.")]
+            #[inline]
+            pub fn $be(self, value: $ty) -> Self {
+                self.bytes(&value.to_be_bytes())
+            }
+        )*
+    };
+}
+
+impl BufBuilder {
+    /// Creates a new, empty [`BufBuilder`].
+    #[inline]
+    pub const fn new() -> BufBuilder {
+        BufBuilder { buf: Vec::new() }
+    }
+
+    /// Returns the number of bytes written so far, which is also the offset at which
+    /// the next value will be written.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if no bytes have been written yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Appends a slice of bytes verbatim.
+    #[inline]
+    pub fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.buf
+            .extend_from_slice(bytes);
+        self
+    }
+
+    /// Appends a single [`u8`].
+    #[inline]
+    pub fn u8(self, value: u8) -> Self {
+        self.bytes(&[value])
+    }
+
+    /// Appends a single [`i8`].
+    #[inline]
+    pub fn i8(self, value: i8) -> Self {
+        self.bytes(&value.to_ne_bytes())
+    }
+
+    builder_int_methods! {
+        u16 => u16_le, u16_be;
+        u32 => u32_le, u32_be;
+        u64 => u64_le, u64_be;
+        u128 => u128_le, u128_be;
+        i16 => i16_le, i16_be;
+        i32 => i32_le, i32_be;
+        i64 => i64_le, i64_be;
+        i128 => i128_le, i128_be;
+    }
+
+    /// Appends `len` copies of `byte`.
+    #[inline]
+    pub fn fill(mut self, byte: u8, len: usize) -> Self {
+        self.buf
+            .resize(self.buf.len() + len, byte);
+        self
+    }
+
+    /// Appends `len` zero bytes.
+    #[inline]
+    pub fn zeros(self, len: usize) -> Self {
+        self.fill(0, len)
+    }
+
+    /// Pads the buffer with zero bytes until it is exactly `offset` bytes long.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `offset` bytes have already been written.
+    #[track_caller]
+    pub fn pad_to(mut self, offset: usize) -> Self {
+        assert!(
+            self.buf.len() <= offset,
+            "cannot pad to offset {offset:#x}, buffer is already {:#x} bytes long",
+            self.buf.len()
+        );
+        self.buf.resize(offset, 0);
+        self
+    }
+
+    /// Pads the buffer with zero bytes until its length is a multiple of `align`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is zero.
+    #[track_caller]
+    pub fn align_to(self, align: usize) -> Self {
+        let offset = self
+            .buf
+            .len()
+            .next_multiple_of(align);
+        self.pad_to(offset)
+    }
+
+    /// Consumes the builder, returning the assembled bytes.
+    #[inline]
+    pub fn build(self) -> Vec<u8> {
+        self.buf
+    }
+
+    /// Consumes the builder, returning the assembled bytes as a fixed-size array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of bytes written is not exactly `N`.
+    #[track_caller]
+    pub fn build_array<const N: usize>(self) -> [u8; N] {
+        match <[u8; N]>::try_from(self.buf.as_slice()) {
+            Ok(array) => array,
+            Err(_) => panic!("expected a buffer of {N} bytes, found {} bytes", self.buf.len()),
+        }
+    }
+}
+
+impl From<BufBuilder> for Vec<u8> {
+    #[inline]
+    fn from(builder: BufBuilder) -> Vec<u8> {
+        builder.build()
+    }
+}
+
+/// Size of the first buffer a value is encoded into by [`assert_roundtrip`].
+const INITIAL_CAPACITY: usize = 64;
+
+/// Size past which [`assert_roundtrip`] stops growing the buffer a value is encoded
+/// into, and reports the value as too large.
+const MAX_CAPACITY: usize = 1 << 24;
+
+/// Encodes `value` with `codec`, decodes it back and asserts that the decoded value
+/// equals `value` and consumed every byte encoded, returning the encoded bytes.
+///
+/// This is the property every type implementing both [`Encode`] and [`Decode`]
+/// should uphold, so calling this function with generated values is a quick way
+/// to test derived or hand-written implementations. The [`assert_roundtrip!`]
+/// macro calls it with a default [`Codec`] if none is given.
+///
+/// # Panics
+///
+/// Panics if `value` cannot be encoded or decoded back, if decoding it consumes
+/// another number of bytes than encoding it wrote, or if the decoded value differs
+/// from `value`. In the last case, the decoded value is encoded again, and the
+/// message reports the offset of the first byte that differs between both
+/// encodings.
+///
+/// # Examples
+///
+/// ```
+/// use abio::config::Codec;
+/// use abio::testing::assert_roundtrip;
+///
+/// let bytes = assert_roundtrip(&Codec::builder().big_endian().build(), &0x0102u16);
+/// assert_eq!(bytes, [1, 2]);
+/// ```
+///
+/// [`assert_roundtrip!`]: crate::assert_roundtrip
+#[track_caller]
+pub fn assert_roundtrip<T>(codec: &Codec, value: &T) -> Vec<u8>
+where
+    T: Encode + for<'data> Decode<'data> + PartialEq + Debug,
+{
+    let encoded = encode_to_vec(codec, value)
+        .unwrap_or_else(|err| panic!("failed to encode {value:?}: {err}"));
+    let (decoded, len) = codec
+        .decode::<T>(&encoded)
+        .unwrap_or_else(|err| panic!("failed to decode {value:?} from {encoded:02x?}: {err}"));
+    assert_eq!(
+        len,
+        encoded.len(),
+        "decoding {value:?} consumed {len} of the {} bytes encoded",
+        encoded.len()
+    );
+    if decoded != *value {
+        let reencoded = encode_to_vec(codec, &decoded).unwrap_or_else(|err| {
+            panic!("{value:?} decoded as {decoded:?}, which fails to encode: {err}")
+        });
+        match first_difference(&encoded, &reencoded) {
+            Some(offset) => panic!(
+                "{value:?} decoded as {decoded:?}, whose encoding differs from byte {offset:#x} \
+                 (expected {:02x?}, found {:02x?})",
+                encoded.get(offset),
+                reencoded.get(offset)
+            ),
+            None => {
+                panic!("{value:?} decoded as {decoded:?}, although both encode to {encoded:02x?}")
+            }
+        }
+    }
+    encoded
+}
+
+/// Encodes `value` with `codec` into a buffer grown until it is large enough.
+fn encode_to_vec<T: Encode + ?Sized>(codec: &Codec, value: &T) -> Result<Vec<u8>> {
+    let mut buf = vec![0; INITIAL_CAPACITY];
+    loop {
+        match codec.encode(value, &mut buf) {
+            Ok(len) => {
+                buf.truncate(len);
+                return Ok(buf);
+            }
+            Err(err) if err.is_out_of_bounds() && buf.len() < MAX_CAPACITY => {
+                buf.resize(buf.len() * 2, 0);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Returns the offset of the first byte that differs between `lhs` and `rhs`,
+/// including bytes past the end of the shorter of both.
+fn first_difference(lhs: &[u8], rhs: &[u8]) -> Option<usize> {
+    lhs.iter()
+        .zip(rhs)
+        .position(|(lhs, rhs)| lhs != rhs)
+        .or_else(|| (lhs.len() != rhs.len()).then(|| lhs.len().min(rhs.len())))
+}
+
+/// Asserts that a value survives being encoded and decoded back, with
+/// [`assert_roundtrip`].
+///
+/// The value is encoded with the given [`Codec`][crate::config::Codec], or with a
+/// default codec if none is given.
+///
+/// # Examples
+///
+/// ```
+/// use abio::assert_roundtrip;
+/// use abio::config::Codec;
+///
+/// assert_roundtrip!(0xdead_beefu32);
+/// assert_roundtrip!(Codec::builder().big_endian().build(), -1i64);
+/// ```
+#[macro_export]
+macro_rules! assert_roundtrip {
+    ($value:expr $(,)?) => {
+        $crate::testing::assert_roundtrip(&$crate::config::Codec::new(), &$value)
+    };
+    ($codec:expr, $value:expr $(,)?) => {
+        $crate::testing::assert_roundtrip(&$codec, &$value)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use core::net::Ipv6Addr;
+
+    use super::*;
+
+    #[test]
+    fn builds_buffers_with_mixed_byte_orders() {
+        let bytes: [u8; 12] = BufBuilder::new()
+            .u16_be(0x0102)
+            .u16_le(0x0304)
+            .u8(0xff)
+            .align_to(4)
+            .u32_le(0xdead_beef)
+            .build_array();
+        assert_eq!(bytes, [1, 2, 4, 3, 0xff, 0, 0, 0, 0xef, 0xbe, 0xad, 0xde]);
+
+        let bytes = BufBuilder::new()
+            .bytes(b"MZ")
+            .pad_to(4)
+            .fill(0xcc, 2)
+            .build();
+        assert_eq!(bytes, b"MZ\0\0\xcc\xcc");
+    }
+
+    /// Three bytes, the last two of which are swapped when decoding.
+    #[derive(Debug, PartialEq)]
+    struct Swapped([u8; 3]);
+
+    impl<'data> Decode<'data> for Swapped {
+        fn decode<E: crate::Endianness>(bytes: &'data [u8]) -> Result<(Swapped, usize)> {
+            match *bytes {
+                [first, second, third, ..] => Ok((Swapped([first, third, second]), 3)),
+                _ => Err(crate::Error::out_of_bounds(3, bytes.len())),
+            }
+        }
+    }
+
+    impl Encode for Swapped {
+        fn encode<E: crate::Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+            let available = buf.len();
+            *buf.first_chunk_mut::<3>()
+                .ok_or_else(|| crate::Error::out_of_bounds(3, available))? = self.0;
+            Ok(3)
+        }
+    }
+
+    #[test]
+    fn round_trips_values_through_codecs() {
+        let codec = Codec::builder()
+            .little_endian()
+            .build();
+        assert_eq!(assert_roundtrip!(codec, 0x0102_0304u32), [4, 3, 2, 1]);
+        assert_eq!(assert_roundtrip!(Ipv6Addr::LOCALHOST).len(), 16);
+        assert_eq!(assert_roundtrip(&codec, &Swapped([7; 3])), [7; 3]);
+
+        assert_eq!(first_difference(b"abc", b"abd"), Some(2));
+        assert_eq!(first_difference(b"ab", b"abc"), Some(2));
+        assert_eq!(first_difference(b"abc", b"abc"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "whose encoding differs from byte 0x1")]
+    fn reports_the_first_differing_byte() {
+        assert_roundtrip!(Swapped([1, 2, 3]));
+    }
+}
@@ -22,6 +22,9 @@ mod internal;
 #[allow(unused_imports)]
 pub(crate) use internal::{array_assume_init, array_assume_init_reversed, split_at_unchecked};
 
+mod ptr;
+pub use ptr::{align_offset_checked, is_aligned_to};
+
 /// Interprets a slice of bytes as a reference of type `&T` where `T` is [`Abi`].
 ///
 /// Types that implement the `Abi` trait have a known, predictable layout and can
@@ -196,27 +199,3 @@ pub const fn const_max_value(lhs: usize, rhs: usize) -> usize {
         rhs
     }
 }
-
-#[doc(hidden)]
-#[const_trait]
-pub trait IntoInner<T> {
-    fn into_inner(self) -> T;
-}
-
-#[doc(hidden)]
-#[const_trait]
-pub trait AsInner<T: ?Sized> {
-    fn as_inner(&self) -> &T;
-}
-
-#[doc(hidden)]
-#[const_trait]
-pub trait AsInnerMut<T: ?Sized> {
-    fn as_inner_mut(&mut self) -> &mut T;
-}
-
-#[doc(hidden)]
-#[const_trait]
-pub trait FromInner<T: ?Sized> {
-    fn from_inner(inner: T) -> Self;
-}
@@ -0,0 +1,59 @@
+//! Address arithmetic on pointers that never casts between pointers and integers.
+//!
+//! Every helper here works on the address of a pointer through
+//! [`addr`][pointer::addr], which discards its provenance without exposing it, so
+//! the crate remains sound under the strict provenance model and Miri's
+//! `-Zmiri-strict-provenance` flag.
+
+/// Returns `true` if the address of `ptr` is a multiple of `align`.
+///
+/// Unlike [`pointer::is_aligned`], the alignment is not taken from the pointee,
+/// so byte pointers can be checked against the alignment of the type they are
+/// about to be read as.
+///
+/// # Panics
+///
+/// Panics if `align` is not a power of two.
+#[inline]
+pub fn is_aligned_to<T: ?Sized>(ptr: *const T, align: usize) -> bool {
+    assert!(align.is_power_of_two(), "alignment must be a power of two");
+    ptr.addr() & (align - 1) == 0
+}
+
+/// Returns the number of bytes that must be added to `ptr` for its address to be
+/// a multiple of `align`.
+///
+/// Returns `None` if `align` is not a power of two, or if the aligned address would
+/// not fit in a `usize`. Unlike [`pointer::align_offset`], the offset is counted in
+/// bytes rather than in elements of `T`, and is always computed exactly.
+#[inline]
+#[cfg_attr(not(feature = "strict-provenance"), allow(dead_code))]
+pub fn align_offset_checked<T: ?Sized>(ptr: *const T, align: usize) -> Option<usize> {
+    if !align.is_power_of_two() {
+        return None;
+    }
+    let addr = ptr.addr();
+    let offset = addr.wrapping_neg() & (align - 1);
+    addr.checked_add(offset)?;
+    Some(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_alignment_from_addresses() {
+        let words = [0u64; 2];
+        let base = words.as_ptr().cast::<u8>();
+        assert!(is_aligned_to(base, 8));
+        assert!(!is_aligned_to(base.wrapping_add(1), 2));
+        assert!(is_aligned_to(base.wrapping_add(4), 4));
+
+        assert_eq!(align_offset_checked(base, 8), Some(0));
+        assert_eq!(align_offset_checked(base.wrapping_add(3), 8), Some(5));
+        assert_eq!(align_offset_checked(base.wrapping_add(3), 4), Some(1));
+        assert_eq!(align_offset_checked(base, 3), None);
+        assert_eq!(align_offset_checked(core::ptr::without_provenance::<u8>(usize::MAX), 2), None);
+    }
+}
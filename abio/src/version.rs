@@ -0,0 +1,196 @@
+//! Versioning of the wire format, for processes exchanging encoded values.
+//!
+//! Two processes sharing memory or a socket may be built against different
+//! versions of this crate, or of the types they exchange. Checking both before
+//! reading anything turns such mismatches into errors, instead of values decoded
+//! from bytes laid out for another definition.
+//!
+//! The crate itself is versioned by [`ABI_VERSION`]. Peers exchange it once, and
+//! [`negotiate`] tells whether they can talk to each other:
+//!
+//! ```
+//! use abio::{negotiate, AbiVersion, Compat, ABI_VERSION};
+//!
+//! assert_eq!(negotiate(ABI_VERSION), Compat::Exact);
+//!
+//! let peer = AbiVersion::new(ABI_VERSION.major + 1, 0);
+//! assert!(!negotiate(peer).is_compatible());
+//! ```
+//!
+//! Each type deriving [`Abi`][crate::Abi] is versioned by its [`VersionTag`], which
+//! changes whenever its [fingerprint][crate::layout::Fingerprint] or the major
+//! version of the crate does. Peers exchange the tag of each type, for example in
+//! the header of a shared memory region, and check it with
+//! [`VersionTag::check_peer`].
+
+use crate::{Decode, Encode, Endianness, Error, Result};
+
+/// Version of the wire format produced by this crate.
+///
+/// The major version is incremented whenever a type is encoded differently than by
+/// previous releases, and the minor version whenever an encoding is added without
+/// changing existing ones.
+pub const ABI_VERSION: AbiVersion = AbiVersion::new(1, 0);
+
+/// Version of the wire format of the crate, as exchanged between peers.
+///
+/// A version is stored as its major version followed by its minor version, each
+/// as a 16-bit integer.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct AbiVersion {
+    /// Version incremented by changes to existing encodings.
+    pub major: u16,
+    /// Version incremented by additions of new encodings.
+    pub minor: u16,
+}
+
+impl AbiVersion {
+    /// Creates a version from its major and minor versions.
+    #[inline]
+    pub const fn new(major: u16, minor: u16) -> AbiVersion {
+        AbiVersion { major, minor }
+    }
+}
+
+impl<'data> Decode<'data> for AbiVersion {
+    #[inline]
+    fn decode<E: Endianness>(bytes: &'data [u8]) -> Result<(AbiVersion, usize)> {
+        let (major, len) = u16::decode::<E>(bytes)?;
+        let (minor, _) = u16::decode::<E>(&bytes[len..])?;
+        Ok((AbiVersion::new(major, minor), 4))
+    }
+}
+
+impl Encode for AbiVersion {
+    #[inline]
+    fn encode<E: Endianness>(&self, buf: &mut [u8]) -> Result<usize> {
+        let available = buf.len();
+        if available < 4 {
+            return Err(Error::out_of_bounds(4, available));
+        }
+        let len = self.major.encode::<E>(buf)?;
+        Ok(len
+            + self
+                .minor
+                .encode::<E>(&mut buf[len..])?)
+    }
+}
+
+/// Outcome of [`negotiate`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Compat {
+    /// The peer uses the same version as this crate.
+    Exact,
+    /// The peer uses another minor version of the same major version. Only the
+    /// encodings of the older of both versions, given here, may be exchanged.
+    Minor(AbiVersion),
+    /// The peer uses another major version, so values cannot be exchanged.
+    Incompatible {
+        /// Version of this crate.
+        local: AbiVersion,
+        /// Version of the peer.
+        peer: AbiVersion,
+    },
+}
+
+impl Compat {
+    /// Returns `true` if values can be exchanged with the peer.
+    #[inline]
+    #[must_use]
+    pub const fn is_compatible(&self) -> bool {
+        !matches!(self, Compat::Incompatible { .. })
+    }
+}
+
+/// Compares the version of a peer with [`ABI_VERSION`].
+#[inline]
+#[must_use]
+pub const fn negotiate(peer: AbiVersion) -> Compat {
+    let local = ABI_VERSION;
+    if local.major != peer.major {
+        Compat::Incompatible { local, peer }
+    } else if local.minor == peer.minor {
+        Compat::Exact
+    } else if local.minor < peer.minor {
+        Compat::Minor(local)
+    } else {
+        Compat::Minor(peer)
+    }
+}
+
+/// Trait for types with a tag identifying the definition they were encoded with.
+///
+/// This trait is implemented automatically when deriving [`Abi`][crate::Abi], with
+/// a tag computed by [`version_tag`] from the fingerprint of the type.
+pub trait VersionTag {
+    /// Tag identifying the layout of this type and the major version of the crate.
+    const VERSION_TAG: u64;
+
+    /// Checks that a peer exchanging values of this type uses the same definition,
+    /// given the tag it reported for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `peer_tag` differs from [`VERSION_TAG`][Self::VERSION_TAG].
+    #[inline]
+    fn check_peer(peer_tag: u64) -> Result<()> {
+        if peer_tag == Self::VERSION_TAG {
+            Ok(())
+        } else {
+            Err(Error::version_mismatch(
+                core::any::type_name::<Self>(),
+                Self::VERSION_TAG,
+                peer_tag,
+            ))
+        }
+    }
+}
+
+/// Returns the version tag of a type with the given fingerprint, combining it with
+/// the major version of [`ABI_VERSION`].
+#[inline]
+#[must_use]
+pub const fn version_tag(fingerprint: u64) -> u64 {
+    crate::layout::FingerprintHasher::new()
+        .write_usize(ABI_VERSION.major as usize)
+        .write_bytes(&fingerprint.to_le_bytes())
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BE, LE};
+
+    struct Header;
+
+    impl VersionTag for Header {
+        const VERSION_TAG: u64 = version_tag(0x1234);
+    }
+
+    #[test]
+    fn negotiates_with_older_and_newer_peers() {
+        let newer = AbiVersion::new(ABI_VERSION.major, ABI_VERSION.minor + 1);
+        assert_eq!(negotiate(newer), Compat::Minor(ABI_VERSION));
+        let other = AbiVersion::new(ABI_VERSION.major + 1, ABI_VERSION.minor);
+        assert_eq!(negotiate(other), Compat::Incompatible { local: ABI_VERSION, peer: other });
+
+        let mut buf = [0u8; 4];
+        assert_eq!(AbiVersion::new(1, 2).encode::<BE>(&mut buf), Ok(4));
+        assert_eq!(buf, [0, 1, 0, 2]);
+        assert_eq!(AbiVersion::decode::<BE>(&buf), Ok((AbiVersion::new(1, 2), 4)));
+        assert!(AbiVersion::decode::<LE>(&buf[..3]).is_err());
+        assert!(AbiVersion::new(1, 2)
+            .encode::<LE>(&mut buf[..3])
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_peers_with_other_tags() {
+        assert_ne!(version_tag(0x1234), version_tag(0x1235));
+        assert_eq!(Header::check_peer(Header::VERSION_TAG), Ok(()));
+        assert!(Header::check_peer(version_tag(0x1235))
+            .unwrap_err()
+            .is_version_mismatch());
+    }
+}
@@ -0,0 +1,109 @@
+//! Tests checking that the `Arbitrary` implementations emitted by the derives with
+//! the `fuzzing` feature generate values that survive being encoded and decoded.
+#![cfg(feature = "fuzzing")]
+
+use std::borrow::Cow;
+
+use abio::arbitrary::{Arbitrary, Unstructured};
+use abio::{Decode, Encode, Error, Result, LE};
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[repr(u8)]
+enum Body {
+    Ping = 1,
+    Data(u32) = 2,
+    #[abio(other)]
+    Other(u8),
+}
+
+fn check_version(version: &u8) -> Result<()> {
+    if *version <= 3 {
+        Ok(())
+    } else {
+        Err(Error::validation_failed("unsupported version"))
+    }
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[abio(magic = b"FZ", version = "version")]
+struct Frame<'data> {
+    #[abio(validate = "check_version")]
+    version: u8,
+    flags: u8,
+    #[abio(bits = 3)]
+    level: u8,
+    #[abio(bits = 5)]
+    channel: u8,
+    #[abio(if = "flags & 1 != 0")]
+    extension: Option<u32>,
+    #[abio(since = 2)]
+    checksum: Option<u16>,
+    #[abio(bytes = 4, pad_after = 2)]
+    reserved: [u8; 4],
+    len: u8,
+    #[abio(len = "len")]
+    payload: Cow<'data, [u8]>,
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+struct Packet {
+    #[abio(magic = b"PK")]
+    signature: u16,
+    kind: u8,
+    #[abio(tag_field = "kind")]
+    body: Body,
+}
+
+/// Generates values of `$ty` from pseudo-random inputs, checking that each of them
+/// is decoded back from its encoding, and evaluates to how many were generated.
+macro_rules! round_trip {
+    ($ty:ty, $check:expr) => {{
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut data = [0u8; 64];
+        let mut generated = 0;
+        for _ in 0..4096 {
+            for byte in &mut data {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                *byte = seed as u8;
+            }
+            let Ok(value) = <$ty>::arbitrary(&mut Unstructured::new(&data)) else {
+                continue;
+            };
+            generated += 1;
+            $check(&value);
+
+            let mut buf = [0u8; 128];
+            let len = value
+                .encode::<LE>(&mut buf)
+                .unwrap();
+            assert_eq!(<$ty>::decode::<LE>(&buf[..len]).unwrap(), (value, len));
+        }
+        generated
+    }};
+}
+
+#[test]
+fn generated_values_round_trip() {
+    let frames = round_trip!(Frame<'_>, |frame: &Frame<'_>| {
+        assert!(frame.version <= 3);
+        assert_eq!(frame.extension.is_some(), frame.flags & 1 != 0);
+        assert_eq!(frame.checksum.is_some(), frame.version >= 2);
+        assert_eq!(frame.payload.len(), usize::from(frame.len));
+    });
+    assert!(frames > 0);
+}
+
+#[test]
+fn generated_values_agree_with_magic_and_tag_fields() {
+    let packets = round_trip!(Packet, |packet: &Packet| {
+        assert_eq!(packet.signature.to_le_bytes(), *b"PK");
+        assert!(matches!(
+            (packet.kind, &packet.body),
+            (1, Body::Ping) | (2, Body::Data(_)) | (3.., Body::Other(_)) | (0, Body::Other(_))
+        ));
+        assert_eq!(packet.kind, packet.body.raw_value());
+    });
+    assert!(packets > 0);
+}
@@ -0,0 +1,212 @@
+//! Tests exercising the `unsafe` code paths behind the public API, including
+//! misaligned, zero-length and maximum-size inputs.
+//!
+//! These tests are written to run under Miri with strict provenance enabled:
+//!
+//! ```text
+//! MIRIFLAGS="-Zmiri-strict-provenance -Zmiri-symbolic-alignment-check" cargo +nightly miri test --all-features
+//! ```
+//!
+//! They also run as part of the regular test suite, so any behaviour checked here
+//! holds regardless of whether Miri is available.
+
+use abio::{BytesMut, Chunk, Reader, Source, Words, LE};
+
+#[test]
+fn sources_read_at_misaligned_offsets() {
+    let buf = [0x0706_0504_0302_0100u64, 0x0f0e_0d0c_0b0a_0908, 0x1716_1514_1312_1110];
+    // Every offset that is not a multiple of the word size is misaligned for `u64`.
+    let offset = 1;
+    let words = Words::<u64, LE>::new(&buf);
+
+    let mut bytes = [0u8; 13];
+    words
+        .copy_to_slice(offset, &mut bytes)
+        .unwrap();
+    assert_eq!(bytes, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]);
+    assert_eq!(
+        words
+            .read_chunk::<4>(offset + 6)
+            .unwrap()
+            .into_array(),
+        [7, 8, 9, 10]
+    );
+    assert_eq!(words.as_native_bytes().len(), 24);
+
+    let mut reader = Reader::new(&bytes[..]);
+    reader.read_bytes(1).unwrap();
+    let mut value = [0u8; 8];
+    reader
+        .copy_to_slice(&mut value)
+        .unwrap();
+    assert_eq!(u64::from_le_bytes(value), 0x0908_0706_0504_0302);
+}
+
+#[test]
+fn zero_length_regions_are_never_dereferenced() {
+    let mut empty: [u8; 0] = [];
+    let region = BytesMut::new(&mut empty);
+    assert!(region.is_empty());
+    assert_eq!(region.as_slice(), &[] as &[u8]);
+    assert_eq!(region.read_slice(0, 0).unwrap(), &[] as &[u8]);
+    assert!(region.read_slice(0, 1).is_err());
+
+    let (mut head, tail) = region.split_at(0).unwrap();
+    assert!(head.is_empty() && tail.is_empty());
+    head.copy_from_slice(0, &[])
+        .unwrap();
+    assert!(head.freeze().is_empty());
+
+    let bytes: &[u8] = &[];
+    let mut reader = Reader::new(bytes);
+    assert_eq!(reader.read_bytes(0).unwrap(), &[] as &[u8]);
+    assert!(reader
+        .take(0)
+        .unwrap()
+        .is_empty());
+    assert!(reader.read_bytes(1).is_err());
+}
+
+#[test]
+fn maximum_sizes_are_rejected_without_overflow() {
+    let bytes: &[u8] = &[0u8; 8];
+    assert!(bytes
+        .read_slice(usize::MAX, 1)
+        .is_err());
+    assert!(bytes
+        .read_slice(1, usize::MAX)
+        .is_err());
+    assert!(bytes
+        .read_slice(usize::MAX, usize::MAX)
+        .is_err());
+    assert!(bytes
+        .read_chunk::<4>(usize::MAX - 1)
+        .is_err());
+    assert!(bytes
+        .read_at_end(usize::MAX)
+        .is_err());
+
+    let mut buf = [0u8; 8];
+    let mut region = BytesMut::new(&mut buf);
+    assert!(region
+        .copy_from_slice(usize::MAX, &[1])
+        .is_err());
+    assert!(region
+        .subslice_mut(usize::MAX - 1..usize::MAX)
+        .is_err());
+    assert!(region
+        .split_at(usize::MAX)
+        .is_err());
+
+    let mut reader = Reader::new(bytes);
+    assert!(reader.take(usize::MAX).is_err());
+    assert!(reader
+        .set_position(usize::MAX)
+        .is_err());
+    assert_eq!(reader.position(), 0);
+}
+
+#[test]
+fn split_halves_do_not_alias() {
+    let mut buf = [0u8; 8];
+    let region = BytesMut::new(&mut buf[1..]);
+    let (mut head, mut tail) = region.split_at(3).unwrap();
+    head.as_mut_slice().fill(0xaa);
+    tail.as_mut_slice().fill(0xbb);
+    assert_eq!(head.freeze().as_slice(), &[0xaa; 3]);
+    assert_eq!(tail.freeze().as_slice(), &[0xbb; 4]);
+    assert_eq!(buf, [0, 0xaa, 0xaa, 0xaa, 0xbb, 0xbb, 0xbb, 0xbb]);
+}
+
+#[test]
+fn chunk_subslices_stay_in_bounds() {
+    let chunk = Chunk::from([1u8, 2, 3, 4]);
+    assert_eq!(chunk.subslice(1..3), &[2, 3]);
+    assert_eq!(chunk.subslice(4..4), &[] as &[u8]);
+    assert_eq!(chunk.slice_to(..1), &[2, 3, 4]);
+    assert_eq!(chunk.slice_to(..4), &[] as &[u8]);
+}
+
+#[test]
+#[should_panic]
+fn chunk_subslice_past_the_end_panics() {
+    let chunk = Chunk::from([1u8, 2, 3, 4]);
+    let _ = chunk.subslice(2..5);
+}
+
+#[cfg(feature = "arena")]
+mod arena {
+    use core::alloc::Layout;
+    use core::cell::{Cell, UnsafeCell};
+    use core::mem::MaybeUninit;
+    use core::ptr::NonNull;
+
+    use abio::codec::{decode_slice_in, decode_str_in, ArenaAlloc};
+    use abio::LE;
+
+    /// Minimal bump arena deriving every allocation from the pointer to its
+    /// buffer, so that allocations keep the provenance of the buffer.
+    struct Bump {
+        buf: UnsafeCell<[MaybeUninit<u8>; 64]>,
+        used: Cell<usize>,
+    }
+
+    impl Bump {
+        fn new() -> Bump {
+            Bump {
+                buf: UnsafeCell::new([MaybeUninit::uninit(); 64]),
+                used: Cell::new(0),
+            }
+        }
+    }
+
+    // SAFETY: Allocations are aligned, disjoint and borrowed from `buf`, which lives
+    // as long as the arena.
+    unsafe impl ArenaAlloc for Bump {
+        fn try_alloc_layout(&self, layout: Layout) -> Option<NonNull<u8>> {
+            let base = self.buf.get().cast::<u8>();
+            let start = self.used.get()
+                + base
+                    .wrapping_add(self.used.get())
+                    .align_offset(layout.align());
+            let end = start.checked_add(layout.size())?;
+            if end > 64 {
+                return None;
+            }
+            self.used.set(end);
+            // SAFETY: `start <= end <= 64`, so the pointer stays within `buf`.
+            NonNull::new(unsafe { base.add(start) })
+        }
+    }
+
+    #[test]
+    fn decodes_into_aligned_arena_memory() {
+        let arena = Bump::new();
+        // Skip a byte so that the next allocation must be realigned.
+        arena
+            .try_alloc_layout(Layout::new::<u8>())
+            .unwrap();
+
+        let bytes = [1u8, 0, 0, 0, 2, 0, 0, 0, 3];
+        let (values, len) = decode_slice_in::<u32, LE, _>(&bytes[..8], 2, &arena).unwrap();
+        assert_eq!(values, &[1, 2]);
+        assert_eq!(len, 8);
+        assert!(values.as_ptr().is_aligned());
+
+        let (text, len) = decode_str_in(b"abio", 4, &arena).unwrap();
+        assert_eq!(text, "abio");
+        assert_eq!(len, 4);
+    }
+
+    #[test]
+    fn empty_and_oversized_requests_do_not_allocate() {
+        let arena = Bump::new();
+        let (values, len) = decode_slice_in::<u64, LE, _>(&[], 0, &arena).unwrap();
+        assert!(values.is_empty());
+        assert_eq!(len, 0);
+
+        assert!(decode_slice_in::<u64, LE, _>(&[], usize::MAX, &arena).is_err());
+        assert!(decode_slice_in::<u64, LE, _>(&[0u8; 72], 9, &arena).is_err());
+        assert_eq!(arena.used.get(), 0);
+    }
+}
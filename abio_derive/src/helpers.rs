@@ -1,6 +1,5 @@
 #![allow(unused_imports)]
 use core::ops::{BitOr, Range};
-use core::sync::atomic::AtomicBool;
 
 use proc_macro::{Delimiter as Delimiter1, TokenStream as TokenStream1, TokenStream as TokenTree1};
 use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
@@ -10,15 +9,25 @@ use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{
     parenthesized, parse_macro_input, parse_quote, token, AngleBracketedGenericArguments,
-    AttrStyle, Data, DataStruct, DataUnion, DeriveInput, Error, Expr, Field, Fields, Generics,
-    ImplGenerics, Meta, Path, Result, Token, Type, TypeGenerics, Visibility, WhereClause,
+    AttrStyle, Data, DataEnum, DataStruct, DataUnion, DeriveInput, Error, Expr, Field, Fields,
+    Generics, ImplGenerics, Meta, Path, Result, Token, Type, TypeGenerics, Visibility, WhereClause,
 };
 
 mod markers;
 pub use markers::{Abi, AsBytes, BoundedField, Contract, Marker, Zeroable};
 
+use abio_derive_core::attrs;
+use abio_derive_core::errors::Errors;
+pub(crate) use abio_derive_core::layout::{parse_attr_repr, ComptimeLayout, Repr};
+
 mod general;
-pub use general::Decode;
+pub use general::{Decode, Encode};
+
+mod overlay;
+pub use overlay::AbiUnion;
+
+mod view;
+pub use view::View;
 
 pub struct Properties {
     is_unsafe: bool,
@@ -36,64 +45,92 @@ impl Contract for Abi {
 }
 
 impl Marker for Abi {
-    fn ident(_: &DeriveInput) -> syn::Path {
-        syn::parse_quote!(::abio::Abi)
-    }
-
-    fn asserts(input: &DeriveInput) -> Result<TokenStream> {
-        if let Ok(layout) = ComptimeLayout::parse_repr_attr(&input.attrs) {
-            let is_valid = layout.packed == Some(1) || layout.repr == Repr::Transparent;
-
-            let punctuated = &input.generics.params;
-            println!("input.generic.params: {punctuated:?}");
-            if !is_valid && !punctuated.is_empty() {
-                Error::new_spanned(
-                    input
-                        .generics
-                        .params
-                        .first()
-                        .expect("AST parser cannot get first generic parameter."),
-                    include_str!("../docs/derive_abi_message"),
-                );
-            }
+    fn ident(krate: &Path) -> syn::Path {
+        syn::parse_quote!(#krate::Abi)
+    }
+
+    fn asserts(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
+        let assert_layout = generate_layout_assertions(input)?;
+        if let Data::Enum(data) = &input.data {
+            let assert_enum_layout = generate_enum_layout_checks(input, data, krate)?;
+            return Ok(quote! {
+              #assert_layout
+              #assert_enum_layout
+            });
+        }
 
-            match &input.data {
-                Data::Struct(_) => {
-                    let assert_no_padding = if !is_valid {
-                        // generate code to check for padding
-                        Some(generate_padding_checks(input)?)
-                    } else {
-                        None
-                    };
+        let layout = ComptimeLayout::parse_repr_attr(&input.attrs)?;
+        if let Data::Union(data) = &input.data {
+            let assert_union_layout = generate_union_layout_checks(input, data, &layout)?;
+            let path = Self::ident(krate);
+            let assert_fields_are_abi_compat = generate_fields_are_trait(input, path)?;
+            return Ok(quote! {
+              #assert_layout
+              #assert_union_layout
+              #assert_fields_are_abi_compat
+            });
+        }
+        let mut errors = Errors::new();
+        errors.check(reject_rust_repr(input, &layout, "Abi"));
+        let is_valid = layout.is_valid();
+        if !is_valid {
+            errors.check(reject_multiple_type_params(
+                input,
+                include_str!("../docs/derive_abi_message"),
+            ));
+        }
+        errors.finish()?;
+
+        match &input.data {
+            Data::Struct(_) => {
+                // Padding of generic types depends on the type arguments, so it is checked
+                // from `Abi::SIZE` once the type is monomorphized instead.
+                let assert_no_padding = if !is_valid && !has_type_params(input) {
+                    Some(generate_padding_checks(input)?)
+                } else {
+                    None
+                };
 
-                    let path = Self::ident(input);
-                    let assert_fields_are_abi_compat = generate_fields_are_trait(input, path)?;
+                let path = Self::ident(krate);
+                let assert_fields_are_abi_compat = generate_fields_are_trait(input, path)?;
 
-                    Ok(quote! {
-                      #assert_no_padding
-                      #assert_fields_are_abi_compat
-                    })
-                }
-                Data::Enum(..) => {
-                    Err(Error::new(Span::call_site(), "Enum types cannot derive the `Abi` trait."))
-                }
-                Data::Union(..) => {
-                    Err(Error::new(Span::call_site(), "Union types cannot derive the `Abi` trait."))
-                }
+                Ok(quote! {
+                  #assert_layout
+                  #assert_no_padding
+                  #assert_fields_are_abi_compat
+                })
             }
-        } else {
-            return Err(Error::new_spanned(
-                input
-                    .attrs
-                    .first()
-                    .expect("AST parser cannot get first generic parameter."),
-                "AST parser cannot get `repr` attribute from this type.",
-            ));
+            Data::Enum(..) => unreachable!("enums are checked before parsing the layout"),
+            Data::Union(..) => unreachable!("unions are checked before checking for padding"),
         }
     }
 
-    fn trait_impl(_input: &DeriveInput) -> Result<(TokenStream, TokenStream)> {
-        Ok((quote!(), quote!()))
+    fn trait_impl(input: &DeriveInput, krate: &Path) -> Result<(TokenStream, TokenStream)> {
+        let (extras, body) = match &input.data {
+            Data::Struct(_) => {
+                let layout = ComptimeLayout::parse_repr_attr(&input.attrs)?;
+                let body = if !layout.is_valid() && has_type_params(input) {
+                    generate_generic_padding_checks(input)?
+                } else {
+                    quote!()
+                };
+                let fingerprint = generate_fingerprint_impl(input, krate)?;
+                let descriptor = generate_descriptor_impl(input, krate)?;
+                let accessors = if layout.is_packed() {
+                    Some(generate_packed_accessors(input)?)
+                } else {
+                    None
+                };
+                (quote!(#fingerprint #descriptor #accessors), body)
+            }
+            Data::Enum(data) => {
+                let discriminants = generate_discriminant_range_impl(input, data, krate)?;
+                let fingerprint = generate_fingerprint_impl(input, krate)?;
+                (quote!(#discriminants #fingerprint), quote!())
+            }
+            Data::Union(_) => (generate_fingerprint_impl(input, krate)?, quote!()),
+        };
+        Ok((extras, body))
     }
 }
 
@@ -112,66 +149,56 @@ impl Contract for AsBytes {
 }
 
 impl Marker for AsBytes {
-    fn ident(_input: &DeriveInput) -> syn::Path {
-        parse_quote!(::abio::AsBytes)
+    fn ident(krate: &Path) -> syn::Path {
+        parse_quote!(#krate::AsBytes)
     }
 
     fn fulfills_contract() -> Option<TokenStream> {
         None
     }
 
-    fn asserts(input: &DeriveInput) -> Result<TokenStream> {
-        if let Ok(layout) = ComptimeLayout::parse_repr_attr(&input.attrs) {
-            let is_valid = fun_name(layout);
-
-            let punctuated = &input.generics.params;
-            println!("input.generic.params: {punctuated:?}");
-
-            if !is_valid && !input.generics.params.is_empty() {
-                Error::new_spanned(
-                    input
-                        .generics
-                        .params
-                        .first()
-                        .expect("AST parser cannot get first generic parameter."),
-                    include_str!("../docs/derive_as_bytes_message"),
-                );
-            }
+    fn asserts(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
+        let layout = ComptimeLayout::parse_repr_attr(&input.attrs)?;
+        match &input.data {
+            Data::Struct(_) => {
+                let mut errors = Errors::new();
+                errors.check(reject_rust_repr(input, &layout, "AsBytes"));
+                if !fun_name(layout) {
+                    errors.check(reject_multiple_type_params(
+                        input,
+                        include_str!("../docs/derive_as_bytes_message"),
+                    ));
+                }
+                errors.check(reject_non_byte_fields(input));
+                errors.finish()?;
 
-            match &input.data {
-                Data::Struct(_) => {
-                    let path = Self::ident(input);
-                    let assert_fields_are_as_bytes = generate_fields_are_trait(input, path)?;
+                let path = Self::ident(krate);
+                let assert_fields_are_as_bytes = generate_fields_are_trait(input, path)?;
+                let assert_no_drop = generate_no_drop_check(input);
 
-                    Ok(quote! {
-                      #assert_fields_are_as_bytes
-                    })
-                }
-                Data::Enum(..) => {
-                    Err(Error::new(Span::call_site(), "Enum types cannot derive the `Abi` trait."))
-                }
-                Data::Union(..) => {
-                    Err(Error::new(Span::call_site(), "Union types cannot derive the `Abi` trait."))
-                }
+                Ok(quote! {
+                  #assert_fields_are_as_bytes
+                  #assert_no_drop
+                })
             }
-        } else {
-            return Err(Error::new_spanned(
-                input
-                    .attrs
-                    .first()
-                    .expect("AST parser cannot get first generic parameter."),
-                "AST parser cannot get `repr` attribute from this type.",
-            ));
+            Data::Enum(..) => Err(Error::new_spanned(
+                &input.ident,
+                "Enum types cannot derive the `AsBytes` trait.",
+            )),
+            Data::Union(..) => Err(Error::new_spanned(
+                &input.ident,
+                "Union types cannot derive the `AsBytes` trait, since the bytes outside of their active field may be uninitialized.",
+            )),
         }
     }
 
-    fn trait_impl(_input: &DeriveInput) -> Result<(TokenStream, TokenStream)> {
+    fn trait_impl(_input: &DeriveInput, _krate: &Path) -> Result<(TokenStream, TokenStream)> {
         Ok((quote!(), quote!()))
     }
 }
 
 fn fun_name(layout: ComptimeLayout) -> bool {
-    layout.packed == Some(1) || layout.repr == Repr::Transparent
+    layout.is_valid()
 }
 
 impl Contract for Zeroable {
@@ -189,8 +216,21 @@ impl Contract for Zeroable {
 }
 
 impl Marker for Zeroable {
-    fn ident(_input: &DeriveInput) -> syn::Path {
-        syn::parse_quote!(::abio::Zeroable)
+    fn ident(krate: &Path) -> syn::Path {
+        syn::parse_quote!(#krate::Zeroable)
+    }
+
+    fn asserts(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
+        match &input.data {
+            Data::Struct(_) | Data::Union(_) => {
+                let path = Self::ident(krate);
+                generate_field_elements_are_trait(input, path)
+            }
+            Data::Enum(..) => Err(Error::new_spanned(
+                &input.ident,
+                "Enum types cannot derive the `Zeroable` trait.",
+            )),
+        }
     }
 }
 
@@ -198,7 +238,7 @@ fn get_struct_fields(input: &DeriveInput) -> Result<&Fields> {
     if let Data::Struct(DataStruct { fields, .. }) = &input.data {
         Ok(fields)
     } else {
-        Err(Error::new(Span::call_site(), "deriving this trait is only supported for structs"))
+        Err(Error::new_spanned(&input.ident, "deriving this trait is only supported for structs"))
     }
 }
 
@@ -207,7 +247,7 @@ fn get_fields(input: &DeriveInput) -> Result<Fields> {
         Data::Struct(DataStruct { fields, .. }) => Ok(fields.clone()),
         Data::Union(DataUnion { fields, .. }) => Ok(Fields::Named(fields.clone())),
         Data::Enum(_) => {
-            Err(Error::new(Span::call_site(), "deriving this trait is not supported for enums"))
+            Err(Error::new_spanned(&input.ident, "deriving this trait is not supported for enums"))
         }
     }
 }
@@ -216,6 +256,168 @@ fn get_field_types(fields: &Fields) -> impl Iterator<Item = &Type> {
     fields.iter().map(|f| &f.ty)
 }
 
+/// Returns `true` if the type declares any generic type parameters.
+fn has_type_params(input: &DeriveInput) -> bool {
+    input
+        .generics
+        .type_params()
+        .next()
+        .is_some()
+}
+
+/// Rejects structs using the default `repr(Rust)` layout, whose field order and
+/// padding are unspecified.
+///
+/// Single-field structs may opt out with `#[abio(allow_rust_repr)]`, since their
+/// layout is fully determined by the field once the generated padding checks prove
+/// that the struct is no larger than it.
+fn reject_rust_repr(input: &DeriveInput, layout: &ComptimeLayout, trait_name: &str) -> Result<()> {
+    if !matches!(input.data, Data::Struct(_))
+        || matches!(layout.repr(), Repr::C | Repr::Transparent)
+    {
+        return Ok(());
+    }
+    if !attrs::ContainerAttrs::parse(&input.attrs)?.allow_rust_repr {
+        return Err(Error::new_spanned(
+            &input.ident,
+            format!(
+                "`{trait_name}` cannot be derived for types using the default `repr(Rust)` layout, \
+                 whose field order and padding are unspecified; add `#[repr(C)]` or \
+                 `#[repr(transparent)]`"
+            ),
+        ));
+    }
+    if get_fields(input)?.len() != 1 {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`#[abio(allow_rust_repr)]` is only supported for structs with a single field",
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects fields whose bytes do not represent their value, or may change while
+/// they are viewed through a shared reference.
+///
+/// References are rejected because their bytes are an address rather than the data
+/// they point to, and cells and atomics because they may be written through the
+/// same shared reference that their bytes are borrowed from. Fields are inspected
+/// through arrays, tuples and parentheses, but not through the generic arguments of
+/// other types, which are checked by the `AsBytes` bound on each field instead.
+fn reject_non_byte_fields(input: &DeriveInput) -> Result<()> {
+    fn check(ty: &Type, errors: &mut Errors) {
+        match ty {
+            Type::Array(array) => check(&array.elem, errors),
+            Type::Group(group) => check(&group.elem, errors),
+            Type::Paren(paren) => check(&paren.elem, errors),
+            Type::Tuple(tuple) => {
+                for elem in &tuple.elems {
+                    check(elem, errors);
+                }
+            }
+            Type::Reference(_) => errors.push(Error::new_spanned(
+                ty,
+                "`AsBytes` cannot be derived for types with reference fields, whose bytes are \
+                 an address rather than the data they point to",
+            )),
+            Type::Path(path) => match path.path.segments.last() {
+                Some(segment) if is_interior_mutable(&segment.ident) => {
+                    errors.push(Error::new_spanned(
+                        ty,
+                        "`AsBytes` cannot be derived for types with interior mutability, whose \
+                         bytes may change while they are borrowed",
+                    ))
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    let mut errors = Errors::new();
+    for field in &get_fields(input)? {
+        check(&field.ty, &mut errors);
+    }
+    errors.finish()
+}
+
+/// Returns `true` if `ident` names one of the standard library types providing
+/// interior mutability.
+fn is_interior_mutable(ident: &Ident) -> bool {
+    let name = ident.to_string();
+    matches!(name.as_str(), "UnsafeCell" | "Cell" | "RefCell" | "OnceCell")
+        || name.starts_with("Atomic")
+}
+
+/// Fails to compile if the type implements `Drop`, since types with a destructor
+/// typically own resources, such as heap allocations, whose handles are not
+/// meaningful as bytes.
+///
+/// A blanket implementation of a private trait for every `T: Drop` conflicts with
+/// the implementation for the type itself only if the type implements `Drop`,
+/// which also works for generic types whose fields are not known to be trivially
+/// dropped.
+fn generate_no_drop_check(input: &DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let must_not_impl_drop = quote::format_ident!("{}MustNotImplementDrop", name);
+    quote_spanned! {name.span() =>
+      const _: () = {
+        trait #must_not_impl_drop {}
+        #[allow(drop_bounds)]
+        impl<T: ::core::ops::Drop> #must_not_impl_drop for T {}
+        impl #impl_generics #must_not_impl_drop for #name #ty_generics #where_clause {}
+      };
+    }
+}
+
+/// Rejects types with more than one generic type parameter, whose layout cannot be
+/// proven without a `packed` or `transparent` representation.
+fn reject_multiple_type_params(input: &DeriveInput, message: &str) -> Result<()> {
+    match input
+        .generics
+        .type_params()
+        .nth(1)
+    {
+        Some(param) => Err(Error::new_spanned(param, message)),
+        None => Ok(()),
+    }
+}
+
+/// Check that a generic struct has no padding by overriding `Abi::SIZE` with a
+/// constant that asserts the size of the struct equals the sum of the size of its
+/// fields.
+///
+/// Unlike the check emitted by `generate_padding_checks`, this assertion is only
+/// evaluated once the type is monomorphized, so it holds for the concrete type
+/// arguments the struct is used with.
+fn generate_generic_padding_checks(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let span = name.span();
+    let fields = get_fields(input)?;
+    let mut field_types = get_field_types(&fields);
+
+    let fields_size = if let Some(first) = field_types.next() {
+        quote_spanned!(span => ::core::mem::size_of::<#first>() #( + ::core::mem::size_of::<#field_types>() )*)
+    } else {
+        quote_spanned!(span => 0)
+    };
+
+    Ok(quote_spanned! {span =>
+      const SIZE: usize = {
+        ::core::assert!(
+          ::core::mem::size_of::<Self>() == #fields_size,
+          ::core::concat!(
+            "`",
+            ::core::stringify!(#name),
+            "` contains padding bytes for the given type arguments"
+          ),
+        );
+        ::core::mem::size_of::<Self>()
+      };
+    })
+}
+
 /// Check that a struct has no padding by asserting that the size of the struct
 /// is equal to the sum of the size of it's fields
 fn generate_padding_checks(input: &DeriveInput) -> Result<TokenStream> {
@@ -235,423 +437,507 @@ fn generate_padding_checks(input: &DeriveInput) -> Result<TokenStream> {
     };
 
     Ok(quote_spanned! {span => const _: fn() = || {
+      #[allow(dead_code)]
       #[doc(hidden)]
       struct WithAbiCompatPadding([u8; #type_size]);
       let _ = ::core::mem::transmute::<#struct_type, WithAbiCompatPadding>;
     };})
 }
 
-/// Check that all fields implement a given trait
-fn generate_fields_are_trait(input: &DeriveInput, trait_: syn::Path) -> Result<TokenStream> {
-    let (impl_generics, _ty_generics, where_clause) = input.generics.split_for_impl();
-    let fields = get_fields(input)?;
-    let span = input.span();
-    let field_types = get_field_types(&fields);
-    Ok(quote_spanned! {span => #(const _: fn() = || {
-        #[allow(clippy::missing_const_for_fn)]
-        #[doc(hidden)]
-        fn check #impl_generics () #where_clause {
-          fn assert_impl<T: #trait_>() {}
-          assert_impl::<#field_types>();
+/// Check that the size and alignment of a type match the values declared with
+/// `#[abio(assert_size = N, assert_align = N)]`, so that a type mirroring a C
+/// struct fails to build when its layout drifts from the documented ABI.
+fn generate_layout_assertions(input: &DeriveInput) -> Result<TokenStream> {
+    let attrs = attrs::ContainerAttrs::parse(&input.attrs)?;
+    if attrs.assert_size.is_none() && attrs.assert_align.is_none() {
+        return Ok(quote!());
+    }
+    if let Some(param) = input
+        .generics
+        .type_params()
+        .next()
+    {
+        return Err(Error::new_spanned(
+            param,
+            "`assert_size` and `assert_align` are not supported for generic types, whose layout depends on their type arguments",
+        ));
+    }
+
+    let name = &input.ident;
+    let span = name.span();
+    let assert_size = attrs.assert_size.map(|size| {
+        let size = proc_macro2::Literal::usize_unsuffixed(size);
+        quote_spanned! {span =>
+          ::core::assert!(
+            ::core::mem::size_of::<#name>() == #size,
+            ::core::concat!(
+              "the size of `",
+              ::core::stringify!(#name),
+              "` does not match `assert_size = ",
+              ::core::stringify!(#size),
+              "`"
+            ),
+          );
         }
-      };)*
-    })
+    });
+    let assert_align = attrs.assert_align.map(|align| {
+        let align = proc_macro2::Literal::usize_unsuffixed(align);
+        quote_spanned! {span =>
+          ::core::assert!(
+            ::core::mem::align_of::<#name>() == #align,
+            ::core::concat!(
+              "the alignment of `",
+              ::core::stringify!(#name),
+              "` does not match `assert_align = ",
+              ::core::stringify!(#align),
+              "`"
+            ),
+          );
+        }
+    });
+
+    Ok(quote_spanned! {span => const _: () = {
+      #assert_size
+      #assert_align
+    };})
 }
 
-fn get_ident_from_stream(tokens: TokenStream) -> Option<Ident> {
-    match tokens.into_iter().next() {
-        Some(TokenTree::Group(group)) => get_ident_from_stream(group.stream()),
-        Some(TokenTree::Ident(ident)) => Some(ident),
-        _ => None,
-    }
+/// Check that a union has a `#[repr(C)]` layout, placing every field at offset
+/// zero, and that it is no larger than its largest field.
+///
+/// Trailing padding would leave bytes of the union that no field can be read from,
+/// so the size of the union must match the size of its largest field exactly.
+fn generate_union_layout_checks(
+    input: &DeriveInput,
+    data: &DataUnion,
+    layout: &ComptimeLayout,
+) -> Result<TokenStream> {
+    let name = &input.ident;
+    if layout.repr() != Repr::C {
+        return Err(Error::new_spanned(
+            name,
+            "unions deriving the `Abi` trait must declare `#[repr(C)]`, so that every field starts at offset zero",
+        ));
+    }
+    if let Some(param) = input
+        .generics
+        .type_params()
+        .next()
+    {
+        return Err(Error::new_spanned(
+            param,
+            "generic unions cannot derive the `Abi` trait, since their padding depends on their type arguments",
+        ));
+    }
+
+    let span = name.span();
+    let field_types = data
+        .fields
+        .named
+        .iter()
+        .map(|field| &field.ty);
+    Ok(quote_spanned! {span => const _: () = {
+      let max = 0usize;
+      #(let max = if ::core::mem::size_of::<#field_types>() > max {
+        ::core::mem::size_of::<#field_types>()
+      } else {
+        max
+      };)*
+      ::core::assert!(
+        ::core::mem::size_of::<#name>() == max,
+        ::core::concat!(
+          "`",
+          ::core::stringify!(#name),
+          "` contains padding bytes beyond its largest field"
+        ),
+      );
+    };})
 }
 
-/// get a simple #[foo(bar)] attribute, returning "bar".
-pub fn get_simple_attr(attributes: &[syn::Attribute], attr_name: &str) -> Option<Ident> {
-    for attr in attributes {
-        if let Some(value) = attr_match_recursive(attr, attr_name) {
-            return Some(value);
-        } else {
-            continue;
-        }
+/// Returns the integer repr of a fieldless enum, rejecting enums with fields,
+/// without variants, or without an explicit integer repr.
+fn get_enum_repr(input: &DeriveInput, data: &DataEnum) -> Result<Ident> {
+    if let Some(variant) = data
+        .variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, Fields::Unit))
+    {
+        return Err(Error::new_spanned(variant, "only fieldless enums can derive the `Abi` trait"));
+    }
+    if data.variants.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "enums without variants cannot derive the `Abi` trait",
+        ));
+    }
+    match get_simple_attr(&input.attrs, "repr") {
+        Some(repr) if attrs::is_tag_type(&repr) => Ok(repr),
+        _ => Err(Error::new_spanned(
+            &input.ident,
+            "enums deriving the `Abi` trait must declare an integer repr, such as `#[repr(u8)]`",
+        )),
     }
+}
 
-    None
+/// Check that a fieldless enum has the size and alignment of its repr, and that
+/// its discriminants form a contiguous range.
+fn generate_enum_layout_checks(
+    input: &DeriveInput,
+    data: &DataEnum,
+    krate: &Path,
+) -> Result<TokenStream> {
+    let name = &input.ident;
+    let span = name.span();
+    let repr = get_enum_repr(input, data)?;
+    let count = data.variants.len() as u128;
+
+    Ok(quote_spanned! {span => const _: () = {
+      ::core::assert!(::core::mem::size_of::<#name>() == ::core::mem::size_of::<#repr>());
+      ::core::assert!(::core::mem::align_of::<#name>() == ::core::mem::align_of::<#repr>());
+      ::core::assert!(
+        (<#name as #krate::layout::DiscriminantRange>::MAX as i128)
+          - (<#name as #krate::layout::DiscriminantRange>::MIN as i128)
+          + 1
+          == #count as i128,
+        "the discriminants of an enum deriving `Abi` must be contiguous",
+      );
+    };})
 }
 
-/// Check a single attribute path to see if it matches the expected ident.
-pub fn attr_match_recursive(attr: &syn::Attribute, attr_name: &str) -> Option<Ident> {
-    if let (AttrStyle::Outer, Meta::List(list)) = (&attr.style, &attr.meta) {
-        if list.path.is_ident(attr_name) {
-            if let Some(ident) = get_ident_from_stream(list.tokens.clone()) {
-                return Some(ident);
+/// Implement the `DiscriminantRange` trait by folding over the discriminants of
+/// each variant at compile time. Integers are only converted into the enum once
+/// they are known to fall within the range.
+fn generate_discriminant_range_impl(
+    input: &DeriveInput,
+    data: &DataEnum,
+    krate: &Path,
+) -> Result<TokenStream> {
+    let name = &input.ident;
+    let repr = get_enum_repr(input, data)?;
+    let mut variants = data
+        .variants
+        .iter()
+        .map(|variant| &variant.ident);
+    let first = variants
+        .next()
+        .expect("enum variants were checked to be non-empty");
+    let rest = variants.collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl #krate::layout::DiscriminantRange for #name {
+            type Repr = #repr;
+
+            const MIN: #repr = {
+                let min = Self::#first as #repr;
+                #(let min = if (Self::#rest as #repr) < min { Self::#rest as #repr } else { min };)*
+                min
+            };
+
+            const MAX: #repr = {
+                let max = Self::#first as #repr;
+                #(let max = if (Self::#rest as #repr) > max { Self::#rest as #repr } else { max };)*
+                max
+            };
+
+            #[inline]
+            fn from_repr(value: #repr) -> #krate::Result<Self> {
+                if !<Self as #krate::layout::DiscriminantRange>::contains(value) {
+                    return ::core::result::Result::Err(#krate::Error::unknown_discriminant(
+                        ::core::any::type_name::<Self>(),
+                        ::core::primitive::i128::from(value),
+                    ));
+                }
+                // SAFETY: The enum has the size of its repr and contiguous discriminants,
+                // both asserted at compile time, so every value between `MIN` and `MAX`
+                // is the discriminant of one of its variants.
+                ::core::result::Result::Ok(unsafe { ::core::mem::transmute::<#repr, Self>(value) })
             }
         }
-    }
-    None
+    })
 }
 
-/// Marker trait to define valid integer primitives that may be used within
-/// `#[repr($int)]` style attributes.
+/// Implement the `Fingerprint` trait by hashing the resolved layout of the type: its
+/// size and alignment, the offset, size and alignment of each field in declaration
+/// order, and the discriminant range of enums. The `VersionTag` trait is derived
+/// from the resulting fingerprint.
 ///
-/// # Safety
-///
-/// TODO: Safety comment
-pub(crate) unsafe trait Integer {}
-macro_rules! impl_integer_for_primitives {
-    ($($ty:ty),* $(,)?) => {
-        $(
-            unsafe impl Integer for $ty {}
-        )*
-    }
-}
-
-impl_integer_for_primitives! {
-    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
-}
-
-pub(crate) enum StandardLayouts {
-    Rust,
-    Transparent,
-    C,
-    Integer(Box<dyn Integer>),
-}
-
-macro_rules! impl_repr_type {
-  (
-    $(#[$attr:meta])*
-    @$enum_ty:ident: $($name:ident => $int:ident),* $(,)?
-  ) => {
-
-        /// Type to represent the possible layout hints, indicated by the `#[repr(...)]`
-        /// attribute.
-        $(#[$attr])*
-        #[derive(Default)]
-        enum $enum_ty {
-            #[doc = "Default representation used by the compiler when there is no `repr` attribute."]
-            #[doc = ""]
-            #[doc = "# Warning"]
-            #[doc = ""]
-            #[doc = "Using this layout representation allows the compiler to freely rearrange"]
-            #[doc = "the layout of your type, which may result in the addition of padding bytes"]
-            #[doc = "or other incompatible layouts with the ABI defined by the [`abio`][crate] crate."]
-            #[default]
-            Rust,
-
-            #[doc = "Representation hint that asks compiler to treat the data as if it were structured"]
-            #[doc = "like C. Types that are `#[repr(C)]` should be fully C-compatible."]
-            C,
-
-            /// Layout hint to indicate to the compiler that the type shares an
-            /// identical layout.
-            ///
-            /// This representation is useful for FFI types since it encourages
-            /// creating safer abstractions through newtypes with added or extended
-            /// functionality.
-            Transparent,
-
-            $(
-
-                $name,
-            )*
+/// Sizes and alignments are read from the `Abi` constants of each type, so the
+/// fingerprint does not depend on how the types are spelled in the source.
+fn generate_fingerprint_impl(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Enum(_) => quote! {
+            .write_discriminants(
+                <Self as #krate::layout::DiscriminantRange>::MIN as i128,
+                <Self as #krate::layout::DiscriminantRange>::MAX as i128,
+            )
+        },
+        Data::Struct(_) | Data::Union(_) => {
+            let fields = get_fields(input)?;
+            let writes = fields
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| {
+                    let ty = &field.ty;
+                    let member = match &field.ident {
+                        Some(ident) => quote!(#ident),
+                        None => {
+                            let index = syn::Index::from(idx);
+                            quote!(#index)
+                        }
+                    };
+                    quote! {
+                        .write_field(
+                            ::core::mem::offset_of!(Self, #member),
+                            <#ty as #krate::Abi>::SIZE,
+                            <#ty as #krate::Abi>::MIN_ALIGN,
+                        )
+                    }
+                });
+            quote!(#(#writes)*)
         }
+    };
 
-        impl Clone for $enum_ty {
-            fn clone(&self) -> Self {
-                *self
-            }
+    Ok(quote! {
+        impl #impl_generics #krate::layout::Fingerprint for #name #ty_generics #where_clause {
+            const FINGERPRINT: u64 = #krate::layout::FingerprintHasher::new()
+                .write_layout(<Self as #krate::Abi>::SIZE, <Self as #krate::Abi>::MIN_ALIGN)
+                #body
+                .finish();
         }
-        impl Copy for $enum_ty {}
-        impl ::core::fmt::Debug for $enum_ty {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                std::fmt::Debug::fmt(&self, f)
-            }
+
+        impl #impl_generics #krate::version::VersionTag for #name #ty_generics #where_clause {
+            const VERSION_TAG: u64 = #krate::version::version_tag(
+                <Self as #krate::layout::Fingerprint>::FINGERPRINT,
+            );
         }
-    };
-    (@$enum_ty:ident: $($outer:ident => $inner:ident),* $(,)?) => {
-        $(
-            stringify!($inner) => $enum_ty::$outer,
-        )*
-    };
-    () => {};
-}
-
-impl_repr_type!(
-    #[derive(Eq, Ord, PartialEq, PartialOrd)]
-    @Repr:
-    U8 => u8,
-    U16 => u16,
-    U32 => u32,
-    U64 => u64,
-    U128 => u128,
-    Usize => usize,
-    I8 => i8,
-    I16 => i16,
-    I32 => i32,
-    I64 => i64,
-    I128 => i128,
-    Isize => isize,
-
-);
-
-pub struct Packed(Option<u32>);
-
-pub struct Align(Option<u32>);
-
-pub struct C(AtomicBool);
-
-pub struct Transparent(bool);
-
-#[derive(Debug, PartialEq)]
-pub struct ComptimeLayout {
-    /// The type is decorated with `align`, increasing its alignment requirements.
-    align: Option<u32>,
-    /// The type uses a packed layout, decreasing alignment requirements for the
-    /// type.
-    packed: Option<u32>,
-    /// [`C`][c-layout]-ABI compiler hint regarding type layout.
-    c_layout: bool,
-    /// Whether the `transparent` hint exists on the type.
-    transparent: bool,
-    repr: Repr,
+    })
 }
 
-impl ComptimeLayout {}
-
-impl Default for ComptimeLayout {
-    fn default() -> Self {
-        Self {
-            packed: None,
-            align: None,
-            repr: Repr::Rust,
-            c_layout: false,
-            transparent: false,
+/// Generates a by-value getter for each named field of a packed struct, since
+/// references to its fields may be misaligned.
+fn generate_packed_accessors(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let getters = get_struct_fields(input)?
+        .iter()
+        .filter_map(|field| {
+            let ident = field.ident.as_ref()?;
+            let (vis, ty) = (&field.vis, &field.ty);
+            let doc = format!(
+                " Returns a copy of the `{ident}` field, read without requiring it to be aligned."
+            );
+            Some(quote! {
+                #[doc = #doc]
+                #[inline]
+                #vis const fn #ident(&self) -> #ty {
+                    // SAFETY: The pointer is derived from a reference to `self`, so it is
+                    // valid for reads of the field, and `read_unaligned` places no
+                    // alignment requirement on it. Fields of `Abi` types are plain data,
+                    // so copying the bytes out produces a valid value.
+                    unsafe { ::core::ptr::addr_of!(self.#ident).read_unaligned() }
+                }
+            })
+        });
+    Ok(quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#getters)*
         }
-    }
+    })
 }
 
-impl ComptimeLayout {
-    fn new(
-        align: Option<u32>,
-        packed: Option<u32>,
-        c_layout: bool,
-        transparent: bool,
-        repr: Repr,
-    ) -> Self {
-        Self { align, packed, c_layout, transparent, repr }
-    }
-
-    pub(crate) fn is_valid(&self) -> bool {
-        self.packed == Some(1) || self.repr == Repr::Transparent
-    }
-
-    pub(crate) fn peel_packed(self) -> u32 {
-        self.packed.unwrap_or(0)
-    }
-
-    pub(crate) fn peel_align(self) -> u32 {
-        self.align.unwrap_or(0)
-    }
-
-    pub(crate) fn is_c_layout(&self) -> bool {
-        self.c_layout
-    }
-
-    pub(crate) fn is_transparent(&self) -> bool {
-        self.transparent
-    }
-
-    /// Parses the `repr` attribute from the list of attributes in the source code
-    /// for the target type.
-    ///
-    /// # Accumulator / Fold Method
-    ///    
-    /// By choosing to initialize a default, legal representation, we can ensure we
-    /// start with a valid layout. Using this instance, we can declartively parse
-    /// related attributes and their associated values and obtain all relevant
-    /// compiler hints.
-    ///
-    /// The final instance includes a `ComptimeLayout` instance with its `repr`
-    /// attribute parsed with graceful failures for any unsupported layout type..
-    pub(crate) fn parse_repr_attr(attributes: &[syn::Attribute]) -> Result<Self> {
-        attributes
-            .iter()
-            .filter_map(|attr| parse_attr_repr(attr, "repr").ok())
-            .try_fold(Self::default(), |layout, input| {
-                let repr = layout.parse_repr(&input)?;
-                let packed = layout.parse_packed(&input)?;
-                let align = layout.parse_align(&input)?;
-
-                Ok(Self {
-                    align: Some(align),
-                    packed: Some(packed),
-                    repr,
-                    ..Self::default()
-                })
+/// Implement the `Describe` trait by listing the name, offset and size of each
+/// field in declaration order, referring to the descriptor of fields marked with
+/// `#[abio(nested)]`.
+fn generate_descriptor_impl(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = get_struct_fields(input)?;
+
+    let descriptors = fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let ty = &field.ty;
+            let (member, member_str) = match &field.ident {
+                Some(ident) => (quote!(#ident), ident.to_string()),
+                None => {
+                    let index = syn::Index::from(idx);
+                    (quote!(#index), idx.to_string())
+                }
+            };
+            let field_attrs = attrs::FieldAttrs::parse(&field.attrs)?;
+            let nested = if field_attrs.nested {
+                quote!(::core::option::Option::Some(&<#ty as #krate::layout::Describe>::DESCRIPTOR))
+            } else {
+                quote!(::core::option::Option::None)
+            };
+            let endian = match field_attrs.endian {
+                Some(attrs::EndianAttr::Little) => {
+                    quote!(::core::option::Option::Some(#krate::Endian::Little))
+                }
+                Some(attrs::EndianAttr::Big) => {
+                    quote!(::core::option::Option::Some(#krate::Endian::Big))
+                }
+                None => quote!(::core::option::Option::None),
+            };
+            Ok(quote! {
+                #krate::layout::FieldDescriptor {
+                    name: #member_str,
+                    offset: ::core::mem::offset_of!(Self, #member),
+                    size: ::core::mem::size_of::<#ty>(),
+                    nested: #nested,
+                    endian: #endian,
+                }
             })
-    }
-
-    fn parse_repr(&self, input: &Self) -> Result<Repr> {
-        Ok(match (self.repr, input.repr) {
-            (lhs, Repr::Rust) => lhs,
-            (Repr::Rust, rhs) => rhs,
-            _ => {
-                return Err(Error::new(
-                    Span::call_site(),
-                    "Compiler hints contain conflicting layout representations.",
-                ))
-            }
         })
-    }
-
-    fn parse_align(self, input: &Self) -> Result<u32> {
-        match (self.align, input.align) {
-            (Some(curr), None) => Ok(curr),
-            (None, Some(input)) => Ok(input),
-            _ => Err(Error::new(
-                Span::call_site(),
-                "Compiler hints contain conflicting layout representations.",
-            )),
+        .collect::<Result<Vec<_>>>()?;
+
+    // Each field may be preceded by padding, and the type may end with padding.
+    let max_padding = descriptors.len() + 1;
+
+    let repr = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .filter_map(|attr| attr.meta.require_list().ok())
+        .map(|list| {
+            list.tokens
+                .to_string()
+                .replace(" ,", ",")
+                .replace(" (", "(")
+                .replace("( ", "(")
+                .replace(" )", ")")
+        })
+        .collect::<Vec<_>>();
+    let repr = if repr.is_empty() { String::from("Rust") } else { repr.join(", ") };
+
+    // Reports are written by a generated test, since the layout is only known once
+    // the type has been compiled. Generic types have no single layout to report.
+    // Reading `ABIO_DEBUG` here is invisible to the compiler, so the expansion also
+    // reads it with `option_env!`, which records it as a dependency of the crate.
+    let report = (std::env::var_os("ABIO_DEBUG").is_some() && input.generics.params.is_empty())
+        .then(|| {
+            let test_name = quote::format_ident!("__abio_layout_report_{}", name);
+            quote! {
+                #krate::layout_report_test! {
+                    #[cfg(test)]
+                    #[test]
+                    #[allow(non_snake_case)]
+                    fn #test_name() {
+                        #krate::layout::write_report(
+                            ::core::module_path!(),
+                            &<#name as #krate::layout::Describe>::DESCRIPTOR,
+                        )
+                        .expect("failed to write the layout report");
+                    }
+                }
+            }
+        });
+
+    Ok(quote! {
+        impl #impl_generics #krate::layout::Describe for #name #ty_generics #where_clause {
+            const DESCRIPTOR: #krate::layout::Descriptor = #krate::layout::Descriptor {
+                name: #name_str,
+                size: ::core::mem::size_of::<Self>(),
+                align: ::core::mem::align_of::<Self>(),
+                repr: #repr,
+                fields: &[#(#descriptors),*],
+            };
+
+            const PADDING: &'static [#krate::Span] =
+                #krate::layout::PaddingSpans::<#max_padding>::new(&Self::DESCRIPTOR).as_slice();
         }
-    }
 
-    fn with_align(self, input: &Self) -> Result<Self> {
-        let align = match (self.align, input.align) {
-            (Some(curr), None) => curr,
-            (None, Some(input)) => input,
-            _ => {
-                return Err(Error::new(
-                    Span::call_site(),
-                    "Compiler hints contain conflicting layout representations.",
-                ))
-            }
-        };
-        Ok(Self {
-            align: self
-                .align
-                .map(|curr| curr | align),
-            ..self
-        })
-    }
+        const _: ::core::option::Option<&str> = ::core::option_env!("ABIO_DEBUG");
 
-    fn parse_packed(&self, input: &Self) -> Result<u32> {
-        match (self.packed, input.packed) {
-            (Some(curr), None) => Ok(curr),
-            (None, Some(input)) => Ok(input),
-            _ => Err(Error::new(
-                Span::call_site(),
-                "Compiler hints contain conflicting layout representations.",
-            )),
-        }
-    }
+        #report
+    })
 }
 
-macro_rules! impl_comptime_layout {
-    (@$parent:ident with $enum_ty:ident: $($outer:ident => $inner:ident),* $(,)?) => {
-        impl ::syn::parse::Parse for $parent {
-        fn parse(input: ::syn::parse::ParseStream<'_>) -> Result<$parent> {
-            let mut ret = $parent::default();
-            while !input.is_empty() {
-                let keyword = input.parse::<::syn::Ident>()?;
-                let keyword_str = keyword.to_string();
-                let new_repr = match keyword_str.as_str() {
-                    "C" => $enum_ty::C,
-                    "transparent" => $enum_ty::Transparent,
-                    "packed" => {
-                        ret.packed = Some(if input.peek(syn::token::Paren) {
-                        let contents; parenthesized!(contents in input);
-                        ::syn::LitInt::base10_parse::<u32>(&contents.parse()?)?
-                        } else {
-                            1
-                        });
-                        let _: ::core::option::Option<Token![,]> = input.parse()?;
-                        continue;
-                    },
-                    "align" => {
-                        let contents; parenthesized!(contents in input);
-                        ret.align = Some(syn::LitInt::base10_parse::<u32>(&contents.parse()?)?);
-                        let _: ::core::option::Option<Token![,]> = input.parse()?;
-                        continue;
-                    },
-                    $(
-                        stringify!($inner) => $enum_ty::$outer,
-                    )*
-                    _ => return Err(input.error("unrecognized representation hint"))
-                };
-                if ::core::mem::replace(&mut ret.repr, new_repr) != $enum_ty::Rust {
-                    input.error("duplicate representation hint");
-                }
-                let _: ::core::option::Option<Token![,]> = input.parse()?;
-            }
-          Ok(ret)
-        }
-      }
-
-        impl ::quote::ToTokens for $parent {
-            fn to_tokens(&self, tokens: &mut TokenStream) {
-                let repr = match self.repr {
-                    $enum_ty::Rust => None,
-                    $enum_ty::C => Some(quote!(C)),
-                    $enum_ty::Transparent => Some(quote!(transparent)),
-                    $(
-                        $enum_ty::$outer => Some(quote!($inner)),
-                    )*
-                };
+/// Check that all fields implement a given trait
+fn generate_fields_are_trait(input: &DeriveInput, trait_: syn::Path) -> Result<TokenStream> {
+    let fields = get_fields(input)?;
+    Ok(generate_types_are_trait(input, trait_, get_field_types(&fields)))
+}
 
-                let packed = self.packed.map(|p| {
-                    let lit = ::syn::LitInt::new(&p.to_string(), Span::call_site());
-                    quote!(packed(#lit))
-                });
+/// Like [`generate_fields_are_trait`], but asserts that the element type of each
+/// array field implements the trait, rather than the array itself.
+///
+/// An array of `N` values is valid for a property such as "all zeroes is a valid
+/// bit pattern" exactly when its element type is, including when `N` is a const
+/// generic parameter of the type. This avoids requiring the extra bounds placed on
+/// the element type by the blanket implementations for arrays.
+fn generate_field_elements_are_trait(
+    input: &DeriveInput,
+    trait_: syn::Path,
+) -> Result<TokenStream> {
+    let fields = get_fields(input)?;
+    Ok(generate_types_are_trait(input, trait_, get_field_types(&fields).map(array_element_type)))
+}
 
-                let comma = if packed.is_some() && repr.is_some() {
-                    Some(quote!(,))
-                } else {
-                    None
-                };
+/// Returns the innermost element type of a (possibly nested) array type, or `ty`
+/// itself if it is not an array.
+fn array_element_type(ty: &Type) -> &Type {
+    match ty {
+        Type::Array(array) => array_element_type(&array.elem),
+        Type::Group(group) => array_element_type(&group.elem),
+        Type::Paren(paren) => array_element_type(&paren.elem),
+        _ => ty,
+    }
+}
 
-                tokens.extend(quote!(
-                    #[repr( #repr #comma #packed )]
-                ));
+fn generate_types_are_trait<'a>(
+    input: &DeriveInput,
+    trait_: syn::Path,
+    field_types: impl Iterator<Item = &'a Type>,
+) -> TokenStream {
+    let (impl_generics, _ty_generics, where_clause) = input.generics.split_for_impl();
+    let span = input.span();
+    quote_spanned! {span => #(const _: fn() = || {
+        #[allow(clippy::missing_const_for_fn, dead_code)]
+        #[doc(hidden)]
+        fn check #impl_generics () #where_clause {
+          fn assert_impl<T: #trait_>() {}
+          assert_impl::<#field_types>();
         }
-      }
+      };)*
     }
 }
 
-impl_comptime_layout! {
-    @ComptimeLayout with Repr:
-    U8 => u8,
-    U16 => u16,
-    U32 => u32,
-    U64 => u64,
-    U128 => u128,
-    Usize => usize,
-    I8 => i8,
-    I16 => i16,
-    I32 => i32,
-    I64 => i64,
-    I128 => i128,
-    Isize => isize,
+fn get_ident_from_stream(tokens: TokenStream) -> Option<Ident> {
+    match tokens.into_iter().next() {
+        Some(TokenTree::Group(group)) => get_ident_from_stream(group.stream()),
+        Some(TokenTree::Ident(ident)) => Some(ident),
+        _ => None,
+    }
 }
 
-pub struct Attribute {
-    ident: String,
-}
+/// get a simple #[foo(bar)] attribute, returning "bar".
+pub fn get_simple_attr(attributes: &[syn::Attribute], attr_name: &str) -> Option<Ident> {
+    for attr in attributes {
+        if let Some(value) = attr_match_recursive(attr, attr_name) {
+            return Some(value);
+        } else {
+            continue;
+        }
+    }
 
-pub enum AttributeKind {
-    /// Outer elements or items comprising an attribute.
-    ///
-    /// The "repr" in the following source code: `#[repr(C, packed(2))]`
-    Outer,
-    /// Inner elements or items comprising an attribute.
-    ///
-    /// The "repr" in the following source code: `#[repr(C, packed(2))]`
-    Inner(Meta),
+    None
 }
 
-pub fn parse_attr_repr(attr: &syn::Attribute, ident: &str) -> syn::Result<ComptimeLayout> {
-    if attr.path().is_ident(ident) {
-        attr.parse_args::<ComptimeLayout>()
-    } else {
-        Err(Error::new_spanned(attr, "Failed while attempting to parse attribute for layout"))
+/// Check a single attribute path to see if it matches the expected ident.
+pub fn attr_match_recursive(attr: &syn::Attribute, attr_name: &str) -> Option<Ident> {
+    if let (AttrStyle::Outer, Meta::List(list)) = (&attr.style, &attr.meta) {
+        if list.path.is_ident(attr_name) {
+            if let Some(ident) = get_ident_from_stream(list.tokens.clone()) {
+                return Some(ident);
+            }
+        }
     }
+    None
 }
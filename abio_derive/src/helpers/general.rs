@@ -1,10 +1,2103 @@
 use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Data, DataEnum, DeriveInput, Error, Field, Fields, GenericArgument, Generics,
+    Ident, Index, LitByteStr, Member, Path, PathArguments, Result, Type, Variant,
+};
+
+use super::attrs::{
+    is_tag_type, BitOrderAttr, ContainerAttrs, EndianAttr, FieldAttrs, VariantAttrs,
+};
+use super::{get_simple_attr, Errors};
 
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub struct Decode;
 
 impl Decode {
-    pub fn impl_block(input: TokenStream) -> syn::Result<TokenStream> {
-        Ok(input)
+    pub fn impl_block(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
+        let attrs = ContainerAttrs::parse(&input.attrs)?;
+        let name = &input.ident;
+        let type_name = name.to_string();
+
+        // Structs may additionally be decodable in a `const` context or forward the
+        // decoding of their field, and enums may be decoded from a tag stored apart
+        // from their payload.
+        let (generics, decode, decode_traced, decode_in, extra) = match &input.data {
+            Data::Struct(data) => {
+                if attrs.display {
+                    return Err(Error::new_spanned(
+                        &input.ident,
+                        "`display` is only supported on enums.",
+                    ));
+                }
+                let fields = CodecField::parse_all(&data.fields, &attrs)?;
+                check_endianness_param(&attrs, &fields)?;
+                let generics = decode_generics(
+                    &input.generics,
+                    &fields,
+                    attrs.trace,
+                    attrs.ctx.as_ref(),
+                    krate,
+                );
+                let body = |kind| {
+                    gen_decode_fn(gen_struct_body(&fields, kind, krate), &attrs, kind, krate)
+                };
+                let decode = decode_kinds(&attrs)
+                    .into_iter()
+                    .map(body)
+                    .collect::<Vec<_>>();
+                let decode_traced = attrs
+                    .ctx
+                    .is_none()
+                    .then(|| body(DecodeFn::Traced(&type_name, attrs.trace)));
+                let decode_in =
+                    (cfg!(feature = "arena") && attrs.ctx.is_none()).then(|| body(DecodeFn::Arena));
+                let decode_const = attrs
+                    .const_decode
+                    .then(|| gen_const_decode(input, &fields, &attrs, krate))
+                    .transpose()?;
+                let forward = attrs
+                    .transparent
+                    .then(|| gen_transparent_decode(input, &fields, &attrs, krate))
+                    .transpose()?;
+                // Expressions referring to the context cannot be evaluated here.
+                let arbitrary = (cfg!(feature = "fuzzing") && attrs.ctx.is_none()).then(|| {
+                    let body = gen_arbitrary_fields(&parse_quote!(Self), &fields, krate);
+                    gen_arbitrary(input, &fields, body, &attrs, krate)
+                });
+                let extra = quote!(#decode_const #forward #arbitrary);
+                (generics, decode, decode_traced, decode_in, Some(extra))
+            }
+            Data::Enum(data) => {
+                if attrs.const_decode || attrs.transparent {
+                    return Err(Error::new_spanned(
+                        &input.ident,
+                        "`const_decode` and `transparent` are only supported on structs.",
+                    ));
+                }
+                let tag = enum_tag_type(input, &attrs)?;
+                let (variants, other) = codec_variants(data, &attrs)?;
+                let fields = variants
+                    .iter()
+                    .flat_map(|(_, fields)| fields)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                check_endianness_param(&attrs, &fields)?;
+                let generics = decode_generics(
+                    &input.generics,
+                    &fields,
+                    attrs.trace,
+                    attrs.ctx.as_ref(),
+                    krate,
+                );
+                let body = |kind| {
+                    let body = gen_enum_body(&variants, other, &tag, &attrs, kind, krate);
+                    gen_decode_fn(body, &attrs, kind, krate)
+                };
+                let decode = decode_kinds(&attrs)
+                    .into_iter()
+                    .map(body)
+                    .collect::<Vec<_>>();
+                let decode_traced = attrs
+                    .ctx
+                    .is_none()
+                    .then(|| body(DecodeFn::Traced(&type_name, attrs.trace)));
+                let decode_in =
+                    (cfg!(feature = "arena") && attrs.ctx.is_none()).then(|| body(DecodeFn::Arena));
+                // Variants decoded from a separate tag have no way to receive a context.
+                let decode_tagged = attrs.ctx.is_none().then(|| {
+                    let decode_tagged = body(DecodeFn::Tagged(&tag));
+                    let (impl_generics, _, where_clause) = generics.split_for_impl();
+                    let (_, ty_generics, _) = input.generics.split_for_impl();
+                    quote! {
+                        impl #impl_generics #krate::codec::DecodeTagged<'__data> for #name #ty_generics #where_clause {
+                            type Tag = #tag;
+
+                            #decode_tagged
+                        }
+                    }
+                });
+                // Expressions referring to the context cannot be evaluated here.
+                let arbitrary = (cfg!(feature = "fuzzing") && attrs.ctx.is_none()).then(|| {
+                    let body = gen_arbitrary_variants(&variants, other, &tag, krate);
+                    gen_arbitrary(input, &fields, body, &attrs, krate)
+                });
+                let raw_value = other.map(|_| gen_raw_value(input, &variants, other, &tag));
+                let display = attrs
+                    .display
+                    .then(|| gen_enum_display(input, &variants, other));
+                let extra = quote!(#decode_tagged #arbitrary #raw_value #display);
+                (generics, decode, decode_traced, decode_in, Some(extra))
+            }
+            Data::Union(_) => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "Union types cannot derive the `Decode` trait.",
+                ))
+            }
+        };
+
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let (_, ty_generics, _) = input.generics.split_for_impl();
+        let decode_trait = match &attrs.ctx {
+            Some(ctx) => quote!(#krate::codec::DecodeWith<'__data, #ctx>),
+            None => quote!(#krate::Decode<'__data>),
+        };
+
+        Ok(quote! {
+            impl #impl_generics #decode_trait for #name #ty_generics #where_clause {
+                #(#decode)*
+                #decode_traced
+                #decode_in
+            }
+
+            #extra
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Encode;
+
+impl Encode {
+    pub fn impl_block(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
+        let attrs = ContainerAttrs::parse(&input.attrs)?;
+        let name = &input.ident;
+
+        let (fields, body, tagged) = match &input.data {
+            Data::Struct(data) => {
+                let fields = CodecField::parse_all(&data.fields, &attrs)?;
+                if attrs.transparent {
+                    transparent_field(input, &fields)?;
+                }
+                let body = gen_encode_struct_body(&fields, krate);
+                (fields, body, None)
+            }
+            Data::Enum(_) if attrs.transparent => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "`transparent` is only supported on structs.",
+                ))
+            }
+            Data::Enum(data) => {
+                let tag = enum_tag_type(input, &attrs)?;
+                let (variants, other) = codec_variants(data, &attrs)?;
+                let body = gen_encode_enum_body(&variants, other, &tag, Some(&attrs), krate);
+                let untagged = gen_encode_enum_body(&variants, other, &tag, None, krate);
+                let tag_of = gen_tag_of(&variants, other, &tag);
+                let fields = variants
+                    .into_iter()
+                    .flat_map(|(_, fields)| fields)
+                    .collect();
+                (fields, body, Some((tag, tag_of, untagged)))
+            }
+            Data::Union(_) => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "Union types cannot derive the `Encode` trait.",
+                ))
+            }
+        };
+        check_endianness_param(&attrs, &fields)?;
+
+        let mut generics = input.generics.clone();
+        let where_clause = generics.make_where_clause();
+        for field in fields
+            .iter()
+            .filter(|field| !field.attrs.skip)
+        {
+            let decode_ty = &field.decode_ty;
+            if field.attrs.tag_field.is_some() {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#decode_ty: #krate::codec::EncodeTagged));
+            } else if field.attrs.bits.is_some() {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#decode_ty: #krate::codec::BitField));
+            } else if let Some(len) = field.attrs.bytes {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#decode_ty: #krate::codec::RawBytes<#len>));
+            } else {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#decode_ty: #krate::Encode));
+            }
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let magic = attrs
+            .magic
+            .as_ref()
+            .map(|magic| gen_write_magic(magic, krate));
+        let align = attrs
+            .align_to
+            .map(|align| gen_write_alignment(align, krate));
+        let encode_tagged = tagged.map(|(tag, tag_of, untagged)| {
+            quote! {
+                impl #impl_generics #krate::codec::EncodeTagged for #name #ty_generics #where_clause {
+                    type Tag = #tag;
+
+                    #[inline]
+                    fn tag(&self) -> #tag {
+                        #tag_of
+                    }
+
+                    #[inline]
+                    fn encode_untagged<__E: #krate::Endianness>(
+                        &self,
+                        __buf: &mut [u8],
+                    ) -> #krate::Result<usize> {
+                        #[allow(unused_mut)]
+                        let mut __offset = 0usize;
+                        #magic
+                        #untagged
+                        #align
+                        ::core::result::Result::Ok(__offset)
+                    }
+                }
+            }
+        });
+
+        Ok(quote! {
+            impl #impl_generics #krate::Encode for #name #ty_generics #where_clause {
+                #[inline]
+                fn encode<__E: #krate::Endianness>(
+                    &self,
+                    __buf: &mut [u8],
+                ) -> #krate::Result<usize> {
+                    #[allow(unused_mut)]
+                    let mut __offset = 0usize;
+                    #magic
+                    #body
+                    #align
+                    ::core::result::Result::Ok(__offset)
+                }
+            }
+
+            #encode_tagged
+        })
+    }
+}
+
+/// A field of a struct or enum variant, along with its `#[abio(...)]` options.
+#[derive(Clone)]
+struct CodecField<'a> {
+    /// Member used to initialize the field.
+    member: Member,
+    /// Local variable holding the decoded value. Named fields are bound to their
+    /// own name, so that attribute expressions can refer to earlier fields.
+    binding: Ident,
+    /// Declared type of the field.
+    ty: &'a Type,
+    /// Type that is decoded from the input to produce the field.
+    decode_ty: Type,
+    attrs: FieldAttrs,
+    /// Whether the field is an `Option<T>` holding `None` when it is absent from the
+    /// input, in which case `decode_ty` is `T`.
+    optional: bool,
+    /// Expression deciding whether a versioned field is present in the input.
+    presence: Option<TokenStream>,
+    /// Position of a bit field within the run of consecutive bit fields it is
+    /// packed into.
+    bits: Option<BitSlot>,
+}
+
+/// Position of a bit field within a run of consecutive bit fields, which are
+/// packed together into an integer of `group_len` bytes.
+#[derive(Clone, Copy)]
+struct BitSlot {
+    /// Number of bits the value is shifted left by within the integer.
+    shift: u32,
+    /// Width of the field, in bits.
+    width: u32,
+    /// Number of bytes occupied by the whole run.
+    group_len: usize,
+    /// Whether the integer is stored with its most significant byte first.
+    msb_first: bool,
+}
+
+impl BitSlot {
+    /// Returns the mask selecting the bits of the field, before shifting.
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+}
+
+impl<'a> CodecField<'a> {
+    /// Parses every field, reporting the errors of all of them at once.
+    fn parse_all(fields: &'a Fields, container: &ContainerAttrs) -> Result<Vec<Self>> {
+        let mut errors = Errors::new();
+        let mut fields = fields
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, field)| errors.check(Self::parse(idx, field, container)))
+            .collect::<Vec<_>>();
+        // Bit fields are grouped with their neighbours, so their runs are only known
+        // once every field parsed.
+        if errors.is_empty() {
+            errors.check(assign_bit_slots(
+                &mut fields,
+                container
+                    .bit_order
+                    .unwrap_or_default(),
+            ));
+        }
+        errors.finish_with(fields)
+    }
+
+    /// Parses the field at position `idx` of its type or variant.
+    fn parse(idx: usize, field: &'a Field, container: &ContainerAttrs) -> Result<Self> {
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        let (member, binding) = match &field.ident {
+            Some(ident) => (Member::Named(ident.clone()), ident.clone()),
+            None => (Member::Unnamed(Index::from(idx)), format_ident!("__field{}", idx)),
+        };
+        let (decode_ty, optional) = if attrs.cond.is_some() {
+            let inner = option_inner_type(&field.ty).cloned().ok_or_else(|| {
+                Error::new_spanned(
+                    &field.ty,
+                    "Conditional fields declared with `#[abio(if = \"..\")]` must have the type `Option<T>`.",
+                )
+            })?;
+            (inner, true)
+        } else if attrs.is_versioned() {
+            match option_inner_type(&field.ty) {
+                Some(inner) => (inner.clone(), true),
+                None => (field.ty.clone(), false),
+            }
+        } else {
+            (field.ty.clone(), false)
+        };
+        let presence = attrs
+            .is_versioned()
+            .then(|| gen_version_check(field, &attrs, container))
+            .transpose()?;
+        Ok(CodecField {
+            member,
+            binding,
+            ty: &field.ty,
+            decode_ty,
+            attrs,
+            optional,
+            presence,
+            bits: None,
+        })
+    }
+
+    /// Returns the tokens initializing or destructuring this field from its binding,
+    /// using the shorthand syntax for named fields.
+    fn init(&self) -> TokenStream {
+        let binding = &self.binding;
+        match &self.member {
+            Member::Named(_) => quote!(#binding),
+            Member::Unnamed(index) => quote!(#index: #binding),
+        }
+    }
+
+    /// Returns the tokens destructuring this field from `self` when encoding. Skipped
+    /// fields are not written, so they are not bound.
+    fn pattern(&self) -> TokenStream {
+        if self.attrs.skip {
+            let member = &self.member;
+            quote!(#member: _)
+        } else {
+            self.init()
+        }
+    }
+
+    /// Returns the name of the field as it appears in decode traces.
+    fn trace_name(&self) -> String {
+        match &self.member {
+            Member::Named(ident) => ident.to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        }
+    }
+}
+
+/// Assigns each field declaring `bits` its position within the run of consecutive
+/// bit fields it belongs to, rejecting runs that do not fill between one and eight
+/// whole bytes.
+fn assign_bit_slots(fields: &mut [CodecField<'_>], order: BitOrderAttr) -> Result<()> {
+    let mut errors = Errors::new();
+    let mut start = 0;
+    while start < fields.len() {
+        let run = fields[start..]
+            .iter()
+            .take_while(|field| field.attrs.bits.is_some())
+            .count();
+        if run == 0 {
+            start += 1;
+            continue;
+        }
+        let group = &mut fields[start..start + run];
+        let total = group
+            .iter()
+            .filter_map(|field| field.attrs.bits)
+            .sum::<u32>();
+        if total % 8 != 0 || total > 64 {
+            errors.push(Error::new_spanned(
+                group[run - 1].ty,
+                format!(
+                    "consecutive bit fields must fill between one and eight whole bytes, but these span {total} bits"
+                ),
+            ));
+            start += run;
+            continue;
+        }
+        let msb_first = order == BitOrderAttr::MsbFirst;
+        let mut offset = 0;
+        for field in group {
+            let width = field
+                .attrs
+                .bits
+                .unwrap_or_default();
+            // The first field takes the most significant bits of an integer stored
+            // most significant byte first, or the least significant bits of one
+            // stored least significant byte first.
+            let shift = if msb_first { total - offset - width } else { offset };
+            field.bits = Some(BitSlot { shift, width, group_len: total as usize / 8, msb_first });
+            offset += width;
+        }
+        start += run;
+    }
+    errors.finish()
+}
+
+/// Returns `T` if `ty` is spelled `Option<T>`.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else { return None };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Adds the `'__data` lifetime to the generics of the type, outliving each of its
+/// lifetime parameters, and bounds the decoded type of every field by
+/// `Decode<'__data>`, or `DecodeWith<'__data, Ctx>` if the type declares a `ctx`,
+/// `DecodeCounted<'__data>` if it declares a `len`, or `BitField` if it declares
+/// `bits`, and its declared type by `Debug` if traces record values. Fields declaring
+/// a `tag_field` are left unbounded.
+/// Skipped fields are instead bounded by `Default` unless they declare an
+/// expression to initialize them with.
+fn decode_generics(
+    generics: &Generics,
+    fields: &[CodecField<'_>],
+    trace: bool,
+    ctx: Option<&Type>,
+    krate: &Path,
+) -> Generics {
+    let mut generics = generics.clone();
+    // Borrowed fields may only outlive the input if the input outlives them.
+    let lifetimes = generics
+        .lifetimes()
+        .map(|param| param.lifetime.clone())
+        .collect::<Vec<_>>();
+    generics
+        .params
+        .insert(0, parse_quote!('__data: #(#lifetimes)+*));
+    let where_clause = generics.make_where_clause();
+    for field in fields {
+        if field.attrs.skip {
+            if field.attrs.default.is_none() {
+                let ty = field.ty;
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#ty: ::core::default::Default));
+            }
+            continue;
+        }
+        let decode_ty = &field.decode_ty;
+        if field.attrs.len.is_some() {
+            where_clause
+                .predicates
+                .push(parse_quote!(#decode_ty: #krate::codec::DecodeCounted<'__data>));
+        } else if field.attrs.bits.is_some() {
+            where_clause
+                .predicates
+                .push(parse_quote!(#decode_ty: #krate::codec::BitField));
+        } else if let Some(len) = field.attrs.bytes {
+            where_clause
+                .predicates
+                .push(parse_quote!(#decode_ty: #krate::codec::RawBytes<#len>));
+        } else if field.attrs.tag_field.is_none() {
+            // Bounding fields declaring a `tag_field` by `DecodeTagged` would hide
+            // the type of their tag behind its associated type.
+            let predicate = match ctx {
+                Some(ctx) => parse_quote!(#decode_ty: #krate::codec::DecodeWith<'__data, #ctx>),
+                None => parse_quote!(#decode_ty: #krate::Decode<'__data>),
+            };
+            where_clause
+                .predicates
+                .push(predicate);
+        }
+        if trace {
+            let ty = field.ty;
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: ::core::fmt::Debug));
+        }
+    }
+    generics
+}
+
+/// Returns the integer type of the discriminant of an enum, taken from the `tag`
+/// attribute, or from its `#[repr]` if the attribute is absent.
+fn enum_tag_type(input: &DeriveInput, attrs: &ContainerAttrs) -> Result<Ident> {
+    if let Some(tag) = &attrs.tag {
+        return Ok(tag.clone());
+    }
+    match get_simple_attr(&input.attrs, "repr") {
+        Some(repr) if is_tag_type(&repr) => Ok(repr),
+        _ => Err(Error::new_spanned(
+            &input.ident,
+            "Enum types deriving `Decode` require a `#[abio(tag = \"..\")]` attribute or an integer `#[repr]`.",
+        )),
+    }
+}
+
+/// A variant of an enum, along with its fields.
+type CodecVariant<'a> = (&'a Variant, Vec<CodecField<'a>>);
+
+/// Parses the variants of an enum along with their fields, separating the variant
+/// declaring `other`, which holds the raw tag of unknown variants instead.
+///
+/// The `other` variant must come last, so that the implicit discriminants of the
+/// remaining variants are not affected by leaving it out.
+fn codec_variants<'a>(
+    data: &'a DataEnum,
+    attrs: &ContainerAttrs,
+) -> Result<(Vec<CodecVariant<'a>>, Option<&'a Variant>)> {
+    let mut errors = Errors::new();
+    let mut variants = Vec::with_capacity(data.variants.len());
+    let mut other = None;
+    for (idx, variant) in data.variants.iter().enumerate() {
+        let Some(variant_attrs) = errors.check(VariantAttrs::parse(&variant.attrs)) else {
+            continue;
+        };
+        if !variant_attrs.other {
+            if let Some(fields) = errors.check(CodecField::parse_all(&variant.fields, attrs)) {
+                variants.push((variant, fields));
+            }
+            continue;
+        }
+        if idx + 1 != data.variants.len() {
+            errors.push(Error::new_spanned(
+                &variant.ident,
+                "The `other` variant must be the last variant of the enum.",
+            ));
+        }
+        if !matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1) {
+            errors.push(Error::new_spanned(
+                &variant.ident,
+                "The `other` variant must hold the raw tag in its only, unnamed field.",
+            ));
+        }
+        other = Some(variant);
+    }
+    errors.finish_with((variants, other))
+}
+
+/// Emits an inherent `raw_value` method returning the tag of the variant held by
+/// an enum, for enums with an `other` variant.
+fn gen_raw_value(
+    input: &DeriveInput,
+    variants: &[(&Variant, Vec<CodecField<'_>>)],
+    other: Option<&Variant>,
+    tag: &Ident,
+) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let tag_of = gen_tag_of(variants, other, tag);
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the tag identifying this variant, or the raw tag held by the
+            /// variant of unknown tags.
+            #[inline]
+            pub fn raw_value(&self) -> #tag {
+                #tag_of
+            }
+        }
+    }
+}
+
+/// Emits a `Display` implementation writing the name of the variant held by an
+/// enum, or `Unknown(0x..)` with the raw tag held by its `other` variant.
+fn gen_enum_display(
+    input: &DeriveInput,
+    variants: &[(&Variant, Vec<CodecField<'_>>)],
+    other: Option<&Variant>,
+) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let arms = variants
+        .iter()
+        .map(|(variant, _)| {
+            let ident = &variant.ident;
+            let text = ident.to_string();
+            quote!(Self::#ident { .. } => __f.write_str(#text),)
+        });
+    let other = other.map(|variant| {
+        let ident = &variant.ident;
+        quote!(Self::#ident(__raw) => ::core::write!(__f, "Unknown({:#X})", __raw),)
+    });
+    quote! {
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, __f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    #(#arms)*
+                    #other
+                }
+            }
+        }
+    }
+}
+
+/// The method of the decoding traits being generated.
+#[derive(Clone, Copy)]
+enum DecodeFn<'a> {
+    /// `decode`, reading each field with `decode`.
+    Plain,
+    /// `decode_traced`, recording each field of the named type into `__tracer`,
+    /// along with its decoded value if the type declares `trace`.
+    Traced(&'a str, bool),
+    /// `decode_in`, forwarding `__arena` to each field.
+    Arena,
+    /// `decode_configured`, entering one level of nesting of `__codec`, checking
+    /// its cancel token before each field and the count of each length-prefixed
+    /// field against its limit, and forwarding it to each field.
+    Configured,
+    /// `DecodeTagged::decode_tagged`, taking the tag of the given type as
+    /// `__tag` instead of reading it from the input.
+    Tagged(&'a Ident),
+    /// `DecodeWith::decode_ctx`, taking a context of the given type as `__ctx`,
+    /// which attribute expressions refer to as `ctx`, and forwarding it to each
+    /// field.
+    Context(&'a Type),
+}
+
+/// Returns the methods generated for every type, implementing `DecodeWith` for the
+/// context of types declaring a `ctx`, and `Decode` otherwise.
+fn decode_kinds(attrs: &ContainerAttrs) -> Vec<DecodeFn<'_>> {
+    match &attrs.ctx {
+        Some(ctx) => vec![DecodeFn::Context(ctx)],
+        None => vec![DecodeFn::Plain, DecodeFn::Configured],
+    }
+}
+
+/// Emits the signature of the method selected by `kind`, wrapping a body that
+/// evaluates to `Self` and advances `__offset`.
+///
+/// The container's `magic` signature, if any, is matched before the body runs.
+/// Afterwards, the reserved bytes up to its `align_to` boundary are skipped, and
+/// the decoded value is passed to its `validate` function before it is returned.
+fn gen_decode_fn(
+    body: TokenStream,
+    attrs: &ContainerAttrs,
+    kind: DecodeFn<'_>,
+    krate: &Path,
+) -> TokenStream {
+    let signature = match kind {
+        DecodeFn::Plain => quote! {
+            fn decode<__E: #krate::Endianness>(
+                __bytes: &'__data [u8],
+            ) -> #krate::Result<(Self, usize)>
+        },
+        DecodeFn::Traced(..) => quote! {
+            fn decode_traced<__E: #krate::Endianness>(
+                __bytes: &'__data [u8],
+                __tracer: &mut #krate::codec::Tracer<'_>,
+            ) -> #krate::Result<(Self, usize)>
+        },
+        DecodeFn::Arena => quote! {
+            fn decode_in<'__arena, __E: #krate::Endianness, __A: #krate::codec::ArenaAlloc + ?Sized>(
+                __bytes: &'__data [u8],
+                __arena: &'__arena __A,
+            ) -> #krate::Result<(Self, usize)>
+            where
+                '__arena: '__data,
+        },
+        DecodeFn::Configured => quote! {
+            fn decode_configured<__E: #krate::Endianness>(
+                __bytes: &'__data [u8],
+                __codec: &#krate::config::Codec,
+            ) -> #krate::Result<(Self, usize)>
+        },
+        DecodeFn::Tagged(tag) => quote! {
+            fn decode_tagged<__E: #krate::Endianness>(
+                __bytes: &'__data [u8],
+                __tag: #tag,
+            ) -> #krate::Result<(Self, usize)>
+        },
+        DecodeFn::Context(ctx) => quote! {
+            fn decode_ctx<__E: #krate::Endianness>(
+                __bytes: &'__data [u8],
+                __ctx: &#ctx,
+            ) -> #krate::Result<(Self, usize)>
+        },
+    };
+
+    let magic = attrs
+        .magic
+        .as_ref()
+        .map(|magic| {
+            let matched = gen_match_magic(magic, krate);
+            quote!(#matched __offset += #magic.len();)
+        });
+    let align = attrs
+        .align_to
+        .map(|align| gen_skip_alignment(align, krate));
+    let validate = attrs
+        .validate
+        .as_ref()
+        .map(|validate| quote!(#validate(&__value)?;));
+    // The token is also checked up front, so that types without fields observe it.
+    let check = gen_check_cancelled(kind);
+    let enter = match kind {
+        DecodeFn::Configured => Some(quote!(let __codec = &__codec.enter()?;)),
+        DecodeFn::Traced(..) => Some(quote!(let __tracer = &mut __tracer.enter()?;)),
+        _ => None,
+    };
+    let ctx = matches!(kind, DecodeFn::Context(_)).then(|| {
+        quote! {
+            #[allow(unused_variables)]
+            let ctx = __ctx;
+        }
+    });
+
+    quote! {
+        #[inline]
+        #signature {
+            #check
+            #enter
+            #ctx
+            #[allow(unused_mut)]
+            let mut __offset = 0usize;
+            let __value = { #magic #body };
+            #align
+            #validate
+            ::core::result::Result::Ok((__value, __offset))
+        }
+    }
+}
+
+/// Emits a `ConstDecode` implementation for a struct declaring `const_decode`,
+/// along with an inherent `decode_const` method forwarding to it.
+///
+/// Only the options that can be evaluated in a `const fn` are supported: `endian`,
+/// padding and `magic` signatures. Fields are read at offsets computed from the
+/// `SIZE` of the fields before them.
+fn gen_const_decode(
+    input: &DeriveInput,
+    fields: &[CodecField<'_>],
+    attrs: &ContainerAttrs,
+    krate: &Path,
+) -> Result<TokenStream> {
+    let name = &input.ident;
+    if let Some(param) = input.generics.params.first() {
+        return Err(Error::new_spanned(param, "`const_decode` is not supported on generic types."));
+    }
+    if let Some(validate) = &attrs.validate {
+        return Err(Error::new_spanned(
+            validate,
+            "`validate` functions cannot be called from `decode_const`.",
+        ));
+    }
+    if attrs.align_to.is_some() {
+        return Err(Error::new_spanned(name, "`align_to` is not supported by `const_decode`."));
+    }
+    if let Some(field) = fields.iter().find(|field| {
+        field.attrs.skip
+            || field.attrs.cond.is_some()
+            || field.attrs.is_versioned()
+            || field.attrs.validate.is_some()
+            || field.attrs.len.is_some()
+            || field.attrs.tag_field.is_some()
+            || field.attrs.bits.is_some()
+            || field.attrs.bytes.is_some()
+            || field.attrs.align_to.is_some()
+    }) {
+        return Err(Error::new_spanned(
+            field.ty,
+            "fields of types declaring `const_decode` cannot be skipped, conditional, versioned, validated, counted, tagged, aligned, bit or raw byte fields.",
+        ));
+    }
+
+    let mut sizes = Vec::new();
+    let mut reads = Vec::new();
+    if let Some(magic) = &attrs.magic {
+        reads.push(gen_const_match_magic(magic, krate));
+        sizes.push(quote!(#magic.len()));
+    }
+    for field in fields {
+        let CodecField { binding, decode_ty, .. } = field;
+        let endian = endian(field.attrs.endian, krate);
+        let (pad_before, pad_after) = (field.attrs.pad_before, field.attrs.pad_after);
+        let magic = field
+            .attrs
+            .magic
+            .as_ref()
+            .map(|magic| gen_const_match_magic(magic, krate));
+        reads.push(quote! {
+            __offset += #pad_before;
+            #magic
+            let #binding = match <#decode_ty as #krate::codec::ConstDecode>::decode_const::<#endian>(
+                __bytes, __offset,
+            ) {
+                ::core::result::Result::Ok(value) => value,
+                ::core::result::Result::Err(err) => return ::core::result::Result::Err(err),
+            };
+            __offset += <#decode_ty as #krate::codec::ConstDecode>::SIZE + #pad_after;
+        });
+        sizes.push(
+            quote!(#pad_before + <#decode_ty as #krate::codec::ConstDecode>::SIZE + #pad_after),
+        );
+    }
+    let inits = fields
+        .iter()
+        .map(CodecField::init);
+
+    Ok(quote! {
+        impl const #krate::codec::ConstDecode for #name {
+            const SIZE: usize = 0 #(+ #sizes)*;
+
+            #[inline]
+            fn decode_const<__E: #krate::Endianness>(
+                __bytes: &[u8],
+                __offset: usize,
+            ) -> #krate::Result<Self> {
+                // Reject short inputs up front, so that trailing padding is checked too.
+                let __available = __bytes.len().saturating_sub(__offset);
+                if __available < <Self as #krate::codec::ConstDecode>::SIZE {
+                    return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                        <Self as #krate::codec::ConstDecode>::SIZE,
+                        __available,
+                    ));
+                }
+                #[allow(unused_mut)]
+                let mut __offset = __offset;
+                #(#reads)*
+                ::core::result::Result::Ok(Self { #(#inits),* })
+            }
+        }
+
+        impl #name {
+            /// Decodes a value from the bytes of `bytes` starting at `offset`, using
+            /// the byte order `E`, in a `const` context.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if `bytes` ends before the value, or if the bytes do
+            /// not hold a valid value.
+            #[inline]
+            pub const fn decode_const<__E: #krate::Endianness>(
+                bytes: &[u8],
+                offset: usize,
+            ) -> #krate::Result<Self> {
+                <Self as #krate::codec::ConstDecode>::decode_const::<__E>(bytes, offset)
+            }
+        }
+    })
+}
+
+/// Emits statements comparing the bytes at `__offset` against `magic` in a `const`
+/// context, then advancing `__offset` past them.
+fn gen_const_match_magic(magic: &LitByteStr, krate: &Path) -> TokenStream {
+    quote! {
+        if let ::core::result::Result::Err(err) =
+            #krate::codec::constant::match_magic(__bytes, __offset, #magic)
+        {
+            return ::core::result::Result::Err(err);
+        }
+        __offset += #magic.len();
+    }
+}
+
+/// Emits an `arbitrary::Arbitrary` implementation generating values that survive
+/// being encoded and decoded again, for the `fuzzing` feature.
+///
+/// Fields are generated in declaration order, so that the expressions of later
+/// fields can refer to them in the same way as when decoding. Values rejected by a
+/// `validate` function are reported as `IncorrectFormat`, which makes the fuzzer
+/// discard its input.
+fn gen_arbitrary(
+    input: &DeriveInput,
+    fields: &[CodecField<'_>],
+    body: TokenStream,
+    attrs: &ContainerAttrs,
+    krate: &Path,
+) -> TokenStream {
+    let name = &input.ident;
+    let generics = arbitrary_generics(&input.generics, fields, krate);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let validate = attrs
+        .validate
+        .as_ref()
+        .map(|validate| gen_arbitrary_validate(validate, &quote!(__value), krate));
+
+    quote! {
+        impl #impl_generics #krate::arbitrary::Arbitrary<'__data> for #name #ty_generics #where_clause {
+            fn arbitrary(
+                __u: &mut #krate::arbitrary::Unstructured<'__data>,
+            ) -> #krate::arbitrary::Result<Self> {
+                let __value = { #body };
+                #validate
+                ::core::result::Result::Ok(__value)
+            }
+        }
+    }
+}
+
+/// Bounds each field by the trait used to generate it, on top of the lifetimes
+/// added by [`decode_generics`].
+///
+/// Fields that are counted or select their variant from a tag are decoded from the
+/// unstructured bytes, so that they agree with the fields they depend on.
+fn arbitrary_generics(generics: &Generics, fields: &[CodecField<'_>], krate: &Path) -> Generics {
+    let mut generics = decode_generics(generics, &[], false, None, krate);
+    let where_clause = generics.make_where_clause();
+    for field in fields {
+        let decode_ty = &field.decode_ty;
+        let predicate: syn::WherePredicate = if field.attrs.skip {
+            if field.attrs.default.is_some() {
+                continue;
+            }
+            let ty = field.ty;
+            parse_quote!(#ty: ::core::default::Default)
+        } else if field.attrs.magic.is_some() {
+            parse_quote!(#decode_ty: #krate::Decode<'__data>)
+        } else if field.attrs.len.is_some() {
+            parse_quote!(#decode_ty: #krate::codec::DecodeCounted<'__data>)
+        } else if field.attrs.tag_field.is_some() {
+            continue;
+        } else if field.attrs.bits.is_some() {
+            parse_quote!(#decode_ty: #krate::codec::BitField)
+        } else if let Some(len) = field.attrs.bytes {
+            parse_quote!(#decode_ty: #krate::codec::RawBytes<#len>)
+        } else {
+            parse_quote!(#decode_ty: #krate::arbitrary::Arbitrary<'__data>)
+        };
+        where_clause
+            .predicates
+            .push(predicate);
+    }
+    generics
+}
+
+/// Emits an expression picking one of the variants of an enum, followed by its
+/// fields.
+///
+/// The `other` variant is only generated with tags that do not belong to another
+/// variant, since decoding such a tag produces that variant instead.
+fn gen_arbitrary_variants(
+    variants: &[(&Variant, Vec<CodecField<'_>>)],
+    other: Option<&Variant>,
+    tag: &Ident,
+    krate: &Path,
+) -> TokenStream {
+    let count = variants.len() + usize::from(other.is_some());
+    let arms = variants
+        .iter()
+        .enumerate()
+        .map(|(idx, (variant, fields))| {
+            let ident = &variant.ident;
+            let body = gen_arbitrary_fields(&parse_quote!(Self::#ident), fields, krate);
+            quote!(#idx => { #body })
+        });
+    let other = other.map(|variant| {
+        let ident = &variant.ident;
+        let idx = variants.len();
+        let (consts, tags) = gen_tag_consts(variants, tag);
+        quote! {
+            #idx => {
+                #(#consts)*
+                let __tag = <#tag as #krate::arbitrary::Arbitrary<'__data>>::arbitrary(__u)?;
+                let __known: &[#tag] = &[#(#tags),*];
+                if __known.contains(&__tag) {
+                    return ::core::result::Result::Err(#krate::arbitrary::Error::IncorrectFormat);
+                }
+                Self::#ident(__tag)
+            }
+        }
+    });
+    quote! {
+        match __u.choose_index(#count)? {
+            #(#arms)*
+            #other
+            _ => ::core::unreachable!(),
+        }
+    }
+}
+
+/// Emits statements generating each field in declaration order, followed by an
+/// expression constructing `path` from them.
+fn gen_arbitrary_fields(path: &Path, fields: &[CodecField<'_>], krate: &Path) -> TokenStream {
+    let generates = fields.iter().map(|field| {
+        let CodecField { binding, decode_ty, .. } = field;
+        if field.attrs.skip {
+            let default = match &field.attrs.default {
+                Some(default) => quote!(#default),
+                None => quote!(::core::default::Default::default()),
+            };
+            return quote!(let #binding = #default;);
+        }
+        let endian = match field.attrs.endian {
+            Some(attr) => endian(Some(attr), krate),
+            None => quote!(#krate::LittleEndian),
+        };
+        let rest = quote!(__u.peek_bytes(__u.len()).unwrap_or_default());
+        let decoded = |decode: TokenStream| {
+            quote! {{
+                let (__decoded, __len) = #decode
+                    .map_err(|_| #krate::arbitrary::Error::IncorrectFormat)?;
+                __u.bytes(__len)?;
+                __decoded
+            }}
+        };
+
+        let mut generate = if let Some(magic) = &field.attrs.magic {
+            // The field holds the signature itself, so it has a single valid value.
+            quote! {
+                <#decode_ty as #krate::Decode<'__data>>::decode::<#endian>(#magic)
+                    .map_err(|_| #krate::arbitrary::Error::IncorrectFormat)?
+                    .0
+            }
+        } else if let Some(len) = &field.attrs.len {
+            decoded(quote! {
+                <usize as ::core::convert::TryFrom<_>>::try_from(#len)
+                    .map_err(|_| #krate::Error::out_of_bounds(usize::MAX, __u.len()))
+                    .and_then(|__count| {
+                        <#decode_ty as #krate::codec::DecodeCounted<'__data>>::decode_counted::<#endian>(
+                            #rest, __count,
+                        )
+                    })
+            })
+        } else if let Some(tag) = &field.attrs.tag_field {
+            decoded(quote! {
+                <#decode_ty as #krate::codec::DecodeTagged<'__data>>::decode_tagged::<#endian>(
+                    #rest, #tag,
+                )
+            })
+        } else if let Some(slot) = field.bits {
+            let mask = slot.mask();
+            quote! {
+                <#decode_ty as #krate::codec::BitField>::from_bits(
+                    <u64 as #krate::arbitrary::Arbitrary<'__data>>::arbitrary(__u)? & #mask,
+                )
+                .ok_or(#krate::arbitrary::Error::IncorrectFormat)?
+            }
+        } else if let Some(len) = field.attrs.bytes {
+            quote! {
+                <#decode_ty as #krate::codec::RawBytes<#len>>::from_raw(
+                    <[u8; #len] as #krate::arbitrary::Arbitrary<'__data>>::arbitrary(__u)?,
+                )
+            }
+        } else {
+            quote!(<#decode_ty as #krate::arbitrary::Arbitrary<'__data>>::arbitrary(__u)?)
+        };
+
+        // Conditional and versioned fields are only generated when they would be
+        // present in the input, since they are otherwise lost when encoding.
+        let presence = field
+            .attrs
+            .cond
+            .as_ref()
+            .map(|cond| quote!(#cond))
+            .or_else(|| field.presence.clone());
+        if let Some(presence) = presence {
+            let absent = if field.optional {
+                generate = quote!(::core::option::Option::Some(#generate));
+                quote!(::core::option::Option::None)
+            } else {
+                quote!(::core::default::Default::default())
+            };
+            generate = quote!(if #presence { #generate } else { #absent });
+        }
+
+        let validate = field
+            .attrs
+            .validate
+            .as_ref()
+            .map(|validate| gen_arbitrary_validate(validate, &quote!(#binding), krate));
+        quote! {
+            let #binding = #generate;
+            #validate
+        }
+    });
+    let inits = fields
+        .iter()
+        .map(CodecField::init);
+
+    quote! {
+        #(#generates)*
+        #path { #(#inits),* }
+    }
+}
+
+/// Emits a statement rejecting `value` if the `validate` function returns an error.
+fn gen_arbitrary_validate(validate: &Path, value: &TokenStream, krate: &Path) -> TokenStream {
+    quote! {
+        if #validate(&#value).is_err() {
+            return ::core::result::Result::Err(#krate::arbitrary::Error::IncorrectFormat);
+        }
+    }
+}
+
+fn gen_struct_body(fields: &[CodecField<'_>], kind: DecodeFn<'_>, krate: &Path) -> TokenStream {
+    gen_fields_body(&parse_quote!(Self), fields, kind, krate)
+}
+
+/// Returns the only field of a `transparent` struct that is part of the wire
+/// layout, rejecting structs with more or fewer of them, and fields whose options
+/// encode them differently from their type.
+fn transparent_field<'f, 'a>(
+    input: &DeriveInput,
+    fields: &'f [CodecField<'a>],
+) -> Result<&'f CodecField<'a>> {
+    let mut wire = fields
+        .iter()
+        .filter(|field| !field.attrs.skip);
+    let (Some(field), None) = (wire.next(), wire.next()) else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`transparent` types must have exactly one field that is not skipped.",
+        ));
+    };
+    let attrs = &field.attrs;
+    if attrs.cond.is_some()
+        || attrs.is_versioned()
+        || attrs.magic.is_some()
+        || attrs.pad_before > 0
+        || attrs.pad_after > 0
+        || attrs.align_to.is_some()
+        || attrs.len.is_some()
+        || attrs.tag_field.is_some()
+        || attrs.bits.is_some()
+        || attrs.bytes.is_some()
+    {
+        return Err(Error::new_spanned(
+            field.ty,
+            "the field of a `transparent` type may only declare `endian` and `validate`.",
+        ));
+    }
+    Ok(field)
+}
+
+/// Emits `DecodeCounted` and `DecodeTagged` implementations for a `transparent`
+/// struct, forwarding to the implementations of its field. Each only applies if the
+/// type of the field implements the trait.
+fn gen_transparent_decode(
+    input: &DeriveInput,
+    fields: &[CodecField<'_>],
+    attrs: &ContainerAttrs,
+    krate: &Path,
+) -> Result<TokenStream> {
+    let wire = transparent_field(input, fields)?;
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let decode_ty = &wire.decode_ty;
+    let endian = endian(wire.attrs.endian, krate);
+
+    let skipped = fields
+        .iter()
+        .filter(|field| field.attrs.skip)
+        .cloned()
+        .collect::<Vec<_>>();
+    let bounded = |bound: TokenStream| {
+        let mut generics = decode_generics(&input.generics, &skipped, false, None, krate);
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#decode_ty: #bound));
+        generics
+    };
+    // Reads the wire field with `read`, initializing skipped fields around it.
+    let body = |read: TokenStream| {
+        let reads = fields.iter().map(|field| {
+            if field.attrs.skip {
+                return gen_field_read(field, DecodeFn::Plain, krate);
+            }
+            let binding = &field.binding;
+            let validate = field
+                .attrs
+                .validate
+                .as_ref()
+                .map(|validate| quote!(#validate(&#binding)?;));
+            quote! {
+                let (#binding, __len) = #read;
+                #validate
+            }
+        });
+        let inits = fields
+            .iter()
+            .map(CodecField::init);
+        let validate = attrs
+            .validate
+            .as_ref()
+            .map(|validate| quote!(#validate(&__value)?;));
+        quote! {
+            #(#reads)*
+            let __value = Self { #(#inits),* };
+            #validate
+            ::core::result::Result::Ok((__value, __len))
+        }
+    };
+
+    let counted = bounded(quote!(#krate::codec::DecodeCounted<'__data>));
+    let (counted_impl, _, counted_where) = counted.split_for_impl();
+    let decode_counted = body(quote! {
+        <#decode_ty as #krate::codec::DecodeCounted<'__data>>::decode_counted::<#endian>(
+            __bytes, __count,
+        )?
+    });
+    let tagged = bounded(quote!(#krate::codec::DecodeTagged<'__data>));
+    let (tagged_impl, _, tagged_where) = tagged.split_for_impl();
+    let decode_tagged = body(quote! {
+        <#decode_ty as #krate::codec::DecodeTagged<'__data>>::decode_tagged::<#endian>(
+            __bytes, __tag,
+        )?
+    });
+
+    Ok(quote! {
+        impl #counted_impl #krate::codec::DecodeCounted<'__data> for #name #ty_generics #counted_where {
+            #[inline]
+            fn decode_counted<__E: #krate::Endianness>(
+                __bytes: &'__data [u8],
+                __count: usize,
+            ) -> #krate::Result<(Self, usize)> {
+                #decode_counted
+            }
+        }
+
+        impl #tagged_impl #krate::codec::DecodeTagged<'__data> for #name #ty_generics #tagged_where {
+            type Tag = <#decode_ty as #krate::codec::DecodeTagged<'__data>>::Tag;
+
+            #[inline]
+            fn decode_tagged<__E: #krate::Endianness>(
+                __bytes: &'__data [u8],
+                __tag: Self::Tag,
+            ) -> #krate::Result<(Self, usize)> {
+                #decode_tagged
+            }
+        }
+    })
+}
+
+/// Rejects fields with a fixed byte order on types declaring `endianness_param`.
+fn check_endianness_param(attrs: &ContainerAttrs, fields: &[CodecField<'_>]) -> Result<()> {
+    match fields
+        .iter()
+        .find(|field| field.attrs.endian.is_some())
+    {
+        Some(field) if attrs.endianness_param => Err(Error::new_spanned(
+            field.ty,
+            "fields of types declaring `endianness_param` follow the byte order chosen by the caller and cannot declare `endian`",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the byte order declared by an `endian` or `tag_endian` attribute,
+/// falling back to the byte order the type is decoded or encoded with.
+pub(super) fn endian(attr: Option<EndianAttr>, krate: &Path) -> TokenStream {
+    match attr {
+        Some(EndianAttr::Little) => quote!(#krate::LittleEndian),
+        Some(EndianAttr::Big) => quote!(#krate::BigEndian),
+        None => quote!(__E),
+    }
+}
+
+/// Emits one constant per variant holding its discriminant, returning the constant
+/// declarations and their names.
+///
+/// Variants without an explicit discriminant follow the same rules as Rust: the
+/// first is zero, and every other is one more than the variant before it.
+fn gen_tag_consts(
+    variants: &[(&Variant, Vec<CodecField<'_>>)],
+    tag: &Ident,
+) -> (Vec<TokenStream>, Vec<Ident>) {
+    let mut consts = Vec::with_capacity(variants.len());
+    let mut names = Vec::with_capacity(variants.len());
+    for (idx, (variant, _)) in variants.iter().enumerate() {
+        let konst = format_ident!("__TAG_{}", idx);
+        let value = match (&variant.discriminant, names.last()) {
+            (Some((_, expr)), _) => quote!(#expr),
+            (None, Some(prev)) => quote!(#prev + 1),
+            (None, None) => quote!(0),
+        };
+        consts.push(quote!(const #konst: #tag = #value;));
+        names.push(konst);
+    }
+    (consts, names)
+}
+
+/// Emits a body that reads the tag, then decodes the fields of the variant it
+/// selects. Unknown tags are held by the `other` variant if there is one, and
+/// produce an error otherwise.
+fn gen_enum_body(
+    variants: &[(&Variant, Vec<CodecField<'_>>)],
+    other: Option<&Variant>,
+    tag: &Ident,
+    attrs: &ContainerAttrs,
+    kind: DecodeFn<'_>,
+    krate: &Path,
+) -> TokenStream {
+    let tag_endian = endian(attrs.tag_endian, krate);
+    let read_tag = match kind {
+        DecodeFn::Tagged(_) => None,
+        DecodeFn::Traced(type_name, _) => Some(quote! {
+            __tracer.start(#type_name, "tag", __offset);
+            let (__tag, __len) = <#tag as #krate::Decode<'__data>>::decode::<#tag_endian>(
+                __bytes.get(__offset..).unwrap_or_default(),
+            )?;
+            __tracer.record(
+                #type_name,
+                "tag",
+                __offset,
+                &__bytes[__offset..__offset + __len],
+                ::core::option::Option::Some(&__tag),
+            );
+            __offset += __len;
+        }),
+        _ => Some(quote! {
+            let (__tag, __len) = <#tag as #krate::Decode<'__data>>::decode::<#tag_endian>(
+                __bytes.get(__offset..).unwrap_or_default(),
+            )?;
+            __offset += __len;
+        }),
+    };
+
+    let (consts, tags) = gen_tag_consts(variants, tag);
+    let arms = variants
+        .iter()
+        .zip(&tags)
+        .map(|((variant, fields), konst)| {
+            let ident = &variant.ident;
+            let path = parse_quote!(Self::#ident);
+            let body = match kind {
+                DecodeFn::Traced(type_name, values) => {
+                    let variant_name = format!("{type_name}::{ident}");
+                    gen_fields_body(&path, fields, DecodeFn::Traced(&variant_name, values), krate)
+                }
+                _ => gen_fields_body(&path, fields, kind, krate),
+            };
+            quote!(#konst => { #body })
+        });
+    let unknown = match other {
+        Some(variant) => {
+            let ident = &variant.ident;
+            quote!(_ => Self::#ident(__tag),)
+        }
+        None => quote! {
+            _ => {
+                return ::core::result::Result::Err(#krate::Error::unknown_discriminant(
+                    ::core::any::type_name::<Self>(),
+                    ::core::primitive::i128::from(__tag),
+                ))
+            }
+        },
+    };
+
+    quote! {
+        #(#consts)*
+        #read_tag
+        match __tag {
+            #(#arms)*
+            #unknown
+        }
+    }
+}
+
+/// Emits statements reading each field in declaration order, followed by an
+/// expression constructing `path` from the decoded fields.
+fn gen_fields_body(
+    path: &Path,
+    fields: &[CodecField<'_>],
+    kind: DecodeFn<'_>,
+    krate: &Path,
+) -> TokenStream {
+    let mut reads = Vec::with_capacity(fields.len());
+    let mut idx = 0;
+    while let Some(field) = fields.get(idx) {
+        if let Some(slot) = field.bits {
+            let run = fields[idx..]
+                .iter()
+                .take_while(|field| field.bits.is_some())
+                .count();
+            reads.push(gen_bits_read(&fields[idx..idx + run], slot, kind, krate));
+            idx += run;
+            continue;
+        }
+        let pad_before = gen_skip_padding(field.attrs.pad_before, krate);
+        let align = field
+            .attrs
+            .align_to
+            .map(|align| gen_skip_alignment(align, krate));
+        let magic = field
+            .attrs
+            .magic
+            .as_ref()
+            .map(|magic| gen_match_magic(magic, krate));
+        let read = gen_field_read(field, kind, krate);
+        let pad_after = gen_skip_padding(field.attrs.pad_after, krate);
+        reads.push(quote!(#pad_before #align #magic #read #pad_after));
+        idx += 1;
+    }
+    let inits = fields
+        .iter()
+        .map(CodecField::init);
+
+    quote! {
+        #(#reads)*
+        #path { #(#inits),* }
+    }
+}
+
+/// Emits statements advancing `__offset` past `len` reserved bytes, failing if the
+/// input ends before them.
+fn gen_skip_padding(len: usize, krate: &Path) -> Option<TokenStream> {
+    (len > 0).then(|| {
+        quote! {
+            if __bytes.len().saturating_sub(__offset) < #len {
+                return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                    #len,
+                    __bytes.len().saturating_sub(__offset),
+                ));
+            }
+            __offset += #len;
+        }
+    })
+}
+
+/// Emits statements advancing `__offset` to the next multiple of `align`, failing
+/// if the input ends before it.
+fn gen_skip_alignment(align: usize, krate: &Path) -> TokenStream {
+    quote! {
+        {
+            let __len = __offset.wrapping_neg() & (#align - 1);
+            if __bytes.len().saturating_sub(__offset) < __len {
+                return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                    __len,
+                    __bytes.len().saturating_sub(__offset),
+                ));
+            }
+            __offset += __len;
+        }
+    }
+}
+
+/// Emits statements comparing the bytes at `__offset` against `magic`, failing if
+/// they differ or if the input ends before them. The offset is not advanced.
+fn gen_match_magic(magic: &LitByteStr, krate: &Path) -> TokenStream {
+    quote! {
+        {
+            const __MAGIC: &[u8] = #magic;
+            let __rest = __bytes
+                .get(__offset..)
+                .unwrap_or_default();
+            match __rest.get(..__MAGIC.len()) {
+                ::core::option::Option::Some(__actual) if __actual == __MAGIC => {}
+                ::core::option::Option::Some(__actual) => {
+                    return ::core::result::Result::Err(#krate::Error::bad_magic(
+                        __MAGIC, __actual, __offset,
+                    ))
+                }
+                ::core::option::Option::None => {
+                    return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                        __MAGIC.len(),
+                        __rest.len(),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Emits the statements reading a single field into its binding and advancing
+/// `__offset` past it. Traced reads also report the start of the field to
+/// `__tracer`, and record the field into it.
+///
+/// Skipped fields do not read anything, and are initialized from their `default`
+/// expression or from `Default::default()` instead. Fields declaring a `len` are
+/// read with `DecodeCounted`, which cannot be traced or allocated into an arena.
+fn gen_field_read(field: &CodecField<'_>, kind: DecodeFn<'_>, krate: &Path) -> TokenStream {
+    let CodecField { binding, decode_ty, .. } = field;
+    if field.attrs.skip {
+        let default = match &field.attrs.default {
+            Some(default) => quote!(#default),
+            None => quote!(::core::default::Default::default()),
+        };
+        return quote!(let #binding = #default;);
+    }
+    let endian = endian(field.attrs.endian, krate);
+    let rest = quote!(__bytes
+        .get(__offset..)
+        .unwrap_or_default());
+
+    let mut read = if let Some(tag) = &field.attrs.tag_field {
+        quote! {
+            <#decode_ty as #krate::codec::DecodeTagged<'__data>>::decode_tagged::<#endian>(
+                #rest, #tag,
+            )?
+        }
+    } else if let Some(len) = field.attrs.bytes {
+        quote! {
+            match #rest.first_chunk::<#len>() {
+                ::core::option::Option::Some(__raw) => {
+                    (<#decode_ty as #krate::codec::RawBytes<#len>>::from_raw(*__raw), #len)
+                }
+                ::core::option::Option::None => {
+                    return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                        #len,
+                        __bytes.len().saturating_sub(__offset),
+                    ))
+                }
+            }
+        }
+    } else if let Some(len) = &field.attrs.len {
+        let check_count = match kind {
+            DecodeFn::Configured => Some(quote!(__codec.limit().check_elements(__count)?;)),
+            DecodeFn::Traced(..) => Some(quote!(__tracer.check_elements(__count)?;)),
+            _ => None,
+        };
+        quote! {{
+            let __count = <usize as ::core::convert::TryFrom<_>>::try_from(#len).map_err(|_| {
+                #krate::Error::out_of_bounds(usize::MAX, __bytes.len().saturating_sub(__offset))
+            })?;
+            #check_count
+            <#decode_ty as #krate::codec::DecodeCounted<'__data>>::decode_counted::<#endian>(
+                #rest, __count,
+            )?
+        }}
+    } else {
+        match kind {
+            DecodeFn::Plain | DecodeFn::Tagged(_) => quote! {
+                <#decode_ty as #krate::Decode<'__data>>::decode::<#endian>(#rest)?
+            },
+            DecodeFn::Traced(..) => quote! {
+                <#decode_ty as #krate::Decode<'__data>>::decode_traced::<#endian>(
+                    #rest,
+                    &mut __tracer.nested(__offset),
+                )?
+            },
+            DecodeFn::Arena => quote! {
+                <#decode_ty as #krate::Decode<'__data>>::decode_in::<#endian, __A>(#rest, __arena)?
+            },
+            DecodeFn::Configured => quote! {
+                <#decode_ty as #krate::Decode<'__data>>::decode_configured::<#endian>(
+                    #rest, __codec,
+                )?
+            },
+            DecodeFn::Context(ctx) => quote! {
+                <#decode_ty as #krate::codec::DecodeWith<'__data, #ctx>>::decode_ctx::<#endian>(
+                    #rest, __ctx,
+                )?
+            },
+        }
+    };
+
+    if let Some(cond) = &field.attrs.cond {
+        read = quote! {
+            if #cond {
+                let (__present, __len) = #read;
+                (::core::option::Option::Some(__present), __len)
+            } else {
+                (::core::option::Option::None, 0)
+            }
+        };
+    } else if let Some(presence) = &field.presence {
+        let (present, absent) = if field.optional {
+            (
+                quote! {
+                    let (__present, __len) = #read;
+                    (::core::option::Option::Some(__present), __len)
+                },
+                quote!(::core::option::Option::None),
+            )
+        } else {
+            (read, quote!(::core::default::Default::default()))
+        };
+        read = quote! {
+            if #presence {
+                #present
+            } else {
+                (#absent, 0)
+            }
+        };
     }
+
+    let (start, record) = match kind {
+        DecodeFn::Traced(type_name, values) => {
+            let field_name = field.trace_name();
+            let value = gen_trace_value(binding, values);
+            let start = quote!(__tracer.start(#type_name, #field_name, __offset););
+            let record = quote! {
+                __tracer.record(
+                    #type_name,
+                    #field_name,
+                    __offset,
+                    &__bytes[__offset..__offset + __len],
+                    #value,
+                );
+            };
+            (Some(start), Some(record))
+        }
+        _ => (None, None),
+    };
+
+    let validate = field
+        .attrs
+        .validate
+        .as_ref()
+        .map(|validate| quote!(#validate(&#binding)?;));
+
+    let check = gen_check_cancelled(kind);
+
+    quote! {
+        #check
+        #start
+        let (#binding, __len) = #read;
+        #record
+        __offset += __len;
+        #validate
+    }
+}
+
+/// Emits a statement stopping a decode with a codec once its cancel token is
+/// cancelled.
+fn gen_check_cancelled(kind: DecodeFn<'_>) -> Option<TokenStream> {
+    match kind {
+        DecodeFn::Configured => Some(quote!(__codec.check_cancelled()?;)),
+        DecodeFn::Traced(..) => Some(quote!(__tracer.check_cancelled()?;)),
+        _ => None,
+    }
+}
+
+/// Emits the value recorded for a traced field. Values are only recorded for types
+/// declaring `trace`, whose fields are bounded by `Debug`.
+fn gen_trace_value(binding: &Ident, values: bool) -> TokenStream {
+    if values {
+        quote!(::core::option::Option::Some(&#binding))
+    } else {
+        quote!(::core::option::Option::None)
+    }
+}
+
+/// Emits statements reading a run of consecutive bit fields, packed into the
+/// integer described by the `slot` of any of them, and extracting each field from
+/// it.
+fn gen_bits_read(
+    fields: &[CodecField<'_>],
+    slot: BitSlot,
+    kind: DecodeFn<'_>,
+    krate: &Path,
+) -> TokenStream {
+    let len = slot.group_len;
+    let bytes = if slot.msb_first {
+        quote!(__group.iter())
+    } else {
+        quote!(__group.iter().rev())
+    };
+    let extracts = fields.iter().map(|field| {
+        let CodecField { binding, decode_ty, .. } = field;
+        let slot = field.bits.unwrap_or(slot);
+        let (shift, width, mask) = (slot.shift, slot.width, slot.mask());
+        let record = match kind {
+            DecodeFn::Traced(type_name, values) => {
+                let field_name = field.trace_name();
+                let value = gen_trace_value(binding, values);
+                Some(quote! {
+                    __tracer.record(#type_name, #field_name, __offset, __group, #value);
+                })
+            }
+            _ => None,
+        };
+        let validate = field
+            .attrs
+            .validate
+            .as_ref()
+            .map(|validate| quote!(#validate(&#binding)?;));
+        quote! {
+            let #binding = <#decode_ty as #krate::codec::BitField>::from_bits(
+                (__bits >> #shift) & #mask,
+            )
+            .ok_or_else(|| {
+                #krate::Error::invalid_bit_field(
+                    ::core::any::type_name::<#decode_ty>(),
+                    #width,
+                    (__bits >> #shift) & #mask,
+                )
+            })?;
+            #record
+            #validate
+        }
+    });
+
+    let check = gen_check_cancelled(kind);
+    let starts = fields
+        .iter()
+        .filter_map(|field| match kind {
+            DecodeFn::Traced(type_name, _) => {
+                let field_name = field.trace_name();
+                Some(quote!(__tracer.start(#type_name, #field_name, __offset);))
+            }
+            _ => None,
+        });
+
+    quote! {
+        #check
+        #(#starts)*
+        let __group = __bytes
+            .get(__offset..)
+            .and_then(|__rest| __rest.get(..#len))
+            .ok_or_else(|| #krate::Error::out_of_bounds(#len, __bytes.len().saturating_sub(__offset)))?;
+        let __bits = #bytes.fold(0u64, |__bits, &__byte| (__bits << 8) | u64::from(__byte));
+        #(#extracts)*
+        __offset += #len;
+    }
+}
+
+/// Emits an expression checking the version of the format against the `since`
+/// and `until` bounds of a versioned field.
+///
+/// The version expression is copied out first, since fields are bound by value
+/// when decoding but by reference when encoding.
+fn gen_version_check(
+    field: &syn::Field,
+    attrs: &FieldAttrs,
+    container: &ContainerAttrs,
+) -> Result<TokenStream> {
+    let version = container
+        .version
+        .as_ref()
+        .ok_or_else(|| {
+            Error::new_spanned(
+                field,
+                "Versioned fields require the type to declare `#[abio(version = \"..\")]`.",
+            )
+        })?;
+    let since = attrs
+        .since
+        .map(proc_macro2::Literal::u64_unsuffixed);
+    let until = attrs
+        .until
+        .map(proc_macro2::Literal::u64_unsuffixed);
+    let range = match until {
+        Some(until) => quote!(#since..=#until),
+        None => quote!(#since..),
+    };
+    Ok(quote! {{
+        #[allow(clippy::clone_on_copy)]
+        let __version = (#version).clone();
+        (#range).contains(&__version)
+    }})
+}
+
+/// Emits statements writing `value` at `__offset` and advancing `__offset` past it.
+fn gen_write(ty: &Type, value: TokenStream, endian: TokenStream, krate: &Path) -> TokenStream {
+    quote! {
+        __offset += <#ty as #krate::Encode>::encode::<#endian>(
+            #value,
+            __buf.get_mut(__offset..).unwrap_or_default(),
+        )?;
+    }
+}
+
+/// Emits statements writing the value of `field` at `__offset`, either through its
+/// `Encode` implementation or as raw bytes if it declares `bytes`.
+fn gen_field_write(
+    field: &CodecField<'_>,
+    value: TokenStream,
+    endian: TokenStream,
+    krate: &Path,
+) -> TokenStream {
+    let decode_ty = &field.decode_ty;
+    let Some(len) = field.attrs.bytes else {
+        return gen_write(decode_ty, value, endian, krate);
+    };
+    quote! {
+        match __buf.get_mut(__offset..).and_then(|__rest| __rest.first_chunk_mut::<#len>()) {
+            ::core::option::Option::Some(__raw) => {
+                *__raw = *<#decode_ty as #krate::codec::RawBytes<#len>>::as_raw(#value);
+            }
+            ::core::option::Option::None => {
+                return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                    #len,
+                    __buf.len().saturating_sub(__offset),
+                ))
+            }
+        }
+        __offset += #len;
+    }
+}
+
+/// Emits statements writing `len` zeroed reserved bytes at `__offset`, failing if
+/// the output ends before them.
+fn gen_write_padding(len: usize, krate: &Path) -> Option<TokenStream> {
+    (len > 0).then(|| {
+        quote! {
+            match __buf.get_mut(__offset..).and_then(|__rest| __rest.get_mut(..#len)) {
+                ::core::option::Option::Some(__pad) => __pad.fill(0),
+                ::core::option::Option::None => {
+                    return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                        #len,
+                        __buf.len().saturating_sub(__offset),
+                    ))
+                }
+            }
+            __offset += #len;
+        }
+    })
+}
+
+/// Emits statements writing zeroed reserved bytes at `__offset` up to the next
+/// multiple of `align`, failing if the output ends before it.
+fn gen_write_alignment(align: usize, krate: &Path) -> TokenStream {
+    quote! {
+        {
+            let __len = __offset.wrapping_neg() & (#align - 1);
+            match __buf.get_mut(__offset..).and_then(|__rest| __rest.get_mut(..__len)) {
+                ::core::option::Option::Some(__pad) => __pad.fill(0),
+                ::core::option::Option::None => {
+                    return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                        __len,
+                        __buf.len().saturating_sub(__offset),
+                    ))
+                }
+            }
+            __offset += __len;
+        }
+    }
+}
+
+/// Emits statements writing the `magic` signature at `__offset`, failing if the
+/// output ends before it.
+fn gen_write_magic(magic: &LitByteStr, krate: &Path) -> TokenStream {
+    quote! {
+        {
+            const __MAGIC: &[u8] = #magic;
+            match __buf.get_mut(__offset..).and_then(|__rest| __rest.get_mut(..__MAGIC.len())) {
+                ::core::option::Option::Some(__dst) => __dst.copy_from_slice(__MAGIC),
+                ::core::option::Option::None => {
+                    return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                        __MAGIC.len(),
+                        __buf.len().saturating_sub(__offset),
+                    ))
+                }
+            }
+            __offset += __MAGIC.len();
+        }
+    }
+}
+
+/// Emits statements writing each field bound by `bindings` in declaration order,
+/// surrounded by its padding. Conditional fields are only written when they hold
+/// a value, and skipped fields are never written.
+fn gen_encode_fields(fields: &[CodecField<'_>], krate: &Path) -> TokenStream {
+    let mut writes = Vec::with_capacity(fields.len());
+    let mut idx = 0;
+    while let Some(field) = fields.get(idx) {
+        if let Some(slot) = field.bits {
+            let run = fields[idx..]
+                .iter()
+                .take_while(|field| field.bits.is_some())
+                .count();
+            writes.push(gen_bits_write(&fields[idx..idx + run], slot, krate));
+            idx += run;
+            continue;
+        }
+        let CodecField { binding, decode_ty, .. } = field;
+        let endian = endian(field.attrs.endian, krate);
+        let write = if field.attrs.skip {
+            None
+        } else if field.attrs.tag_field.is_some() {
+            Some(quote! {
+                __offset += <#decode_ty as #krate::codec::EncodeTagged>::encode_untagged::<#endian>(
+                    #binding,
+                    __buf.get_mut(__offset..).unwrap_or_default(),
+                )?;
+            })
+        } else if field.optional {
+            let write = gen_field_write(field, quote!(__present), endian, krate);
+            Some(quote! {
+                if let ::core::option::Option::Some(__present) = #binding {
+                    #write
+                }
+            })
+        } else {
+            Some(gen_field_write(field, quote!(#binding), endian, krate))
+        };
+        let write = match (&field.presence, write) {
+            (Some(presence), Some(write)) => Some(quote! {
+                if #presence {
+                    #write
+                }
+            }),
+            (_, write) => write,
+        };
+        let pad_before = gen_write_padding(field.attrs.pad_before, krate);
+        let align = field
+            .attrs
+            .align_to
+            .map(|align| gen_write_alignment(align, krate));
+        let pad_after = gen_write_padding(field.attrs.pad_after, krate);
+        writes.push(quote!(#pad_before #align #write #pad_after));
+        idx += 1;
+    }
+    quote!(#(#writes)*)
+}
+
+/// Emits statements packing a run of consecutive bit fields into the integer
+/// described by the `slot` of any of them, and writing it at `__offset`. Values
+/// wider than their field are rejected rather than truncated.
+fn gen_bits_write(fields: &[CodecField<'_>], slot: BitSlot, krate: &Path) -> TokenStream {
+    let len = slot.group_len;
+    let packs = fields.iter().map(|field| {
+        let CodecField { binding, decode_ty, .. } = field;
+        let slot = field.bits.unwrap_or(slot);
+        let (shift, width, mask) = (slot.shift, slot.width, slot.mask());
+        // Fields spanning all 64 bits hold any value.
+        let check = (slot.width < 64).then(|| {
+            quote! {
+                if __value > #mask {
+                    return ::core::result::Result::Err(#krate::Error::invalid_bit_field(
+                        ::core::any::type_name::<#decode_ty>(),
+                        #width,
+                        __value,
+                    ));
+                }
+            }
+        });
+        quote! {
+            let __value = <#decode_ty as #krate::codec::BitField>::into_bits(*#binding);
+            #check
+            __bits |= __value << #shift;
+        }
+    });
+    let byte_shift = if slot.msb_first {
+        quote!(8 * (#len - 1 - __idx))
+    } else {
+        quote!(8 * __idx)
+    };
+
+    quote! {
+        let mut __bits = 0u64;
+        #(#packs)*
+        match __buf.get_mut(__offset..).and_then(|__rest| __rest.get_mut(..#len)) {
+            ::core::option::Option::Some(__group) => {
+                for (__idx, __byte) in __group.iter_mut().enumerate() {
+                    *__byte = (__bits >> (#byte_shift)) as u8;
+                }
+            }
+            ::core::option::Option::None => {
+                return ::core::result::Result::Err(#krate::Error::out_of_bounds(
+                    #len,
+                    __buf.len().saturating_sub(__offset),
+                ))
+            }
+        }
+        __offset += #len;
+    }
+}
+
+fn gen_encode_struct_body(fields: &[CodecField<'_>], krate: &Path) -> TokenStream {
+    let patterns = fields
+        .iter()
+        .map(CodecField::pattern);
+    let writes = gen_encode_fields(fields, krate);
+    quote! {
+        let Self { #(#patterns),* } = self;
+        #writes
+    }
+}
+
+/// Emits a body that writes the tag of the variant held by `self`, followed by
+/// the fields of that variant. The tag is left out if `attrs` is `None`, for
+/// variants whose tag is stored elsewhere.
+///
+/// The `other` variant writes the raw tag it holds, and has no fields.
+fn gen_encode_enum_body(
+    variants: &[(&Variant, Vec<CodecField<'_>>)],
+    other: Option<&Variant>,
+    tag: &Ident,
+    attrs: Option<&ContainerAttrs>,
+    krate: &Path,
+) -> TokenStream {
+    let (consts, tags) = gen_tag_consts(variants, tag);
+    let tag_ty: Type = parse_quote!(#tag);
+    let arms = variants
+        .iter()
+        .zip(&tags)
+        .map(|((variant, fields), konst)| {
+            let ident = &variant.ident;
+            let patterns = fields
+                .iter()
+                .map(CodecField::pattern);
+            let write_tag = attrs.map(|attrs| {
+                gen_write(&tag_ty, quote!(&#konst), endian(attrs.tag_endian, krate), krate)
+            });
+            let writes = gen_encode_fields(fields, krate);
+            quote! {
+                Self::#ident { #(#patterns),* } => {
+                    #write_tag
+                    #writes
+                }
+            }
+        });
+    let other = other.map(|variant| {
+        let ident = &variant.ident;
+        match attrs {
+            Some(attrs) => {
+                let write_tag =
+                    gen_write(&tag_ty, quote!(__raw), endian(attrs.tag_endian, krate), krate);
+                quote!(Self::#ident(__raw) => { #write_tag })
+            }
+            None => quote!(Self::#ident(_) => {}),
+        }
+    });
+
+    quote! {
+        #(#consts)*
+        match self {
+            #(#arms)*
+            #other
+        }
+    }
+}
+
+/// Emits an expression evaluating to the tag of the variant held by `self`.
+fn gen_tag_of(
+    variants: &[(&Variant, Vec<CodecField<'_>>)],
+    other: Option<&Variant>,
+    tag: &Ident,
+) -> TokenStream {
+    let (consts, tags) = gen_tag_consts(variants, tag);
+    let arms = variants
+        .iter()
+        .zip(&tags)
+        .map(|((variant, _), konst)| {
+            let ident = &variant.ident;
+            quote!(Self::#ident { .. } => #konst,)
+        });
+    let other = other.map(|variant| {
+        let ident = &variant.ident;
+        quote!(Self::#ident(__raw) => *__raw,)
+    });
+
+    quote! {{
+        #(#consts)*
+        match self {
+            #(#arms)*
+            #other
+        }
+    }}
 }
@@ -34,11 +34,11 @@ pub struct BoundedField(Ident, Punctuated<TypeParamBound, Plus>);
 
 /// Trait to define a marker trait in source code, or syntax token trees.
 pub trait Marker: Contract {
-    /// Fully-qualified identifier emitted as a derived trait for the type.
-    fn ident(input: &DeriveInput) -> syn::Path;
+    /// Fully-qualified path of the derived trait, within the `abio` crate at `krate`.
+    fn ident(krate: &syn::Path) -> syn::Path;
 
     /// Assertions generated to ensure ABI-compatibilty at compile time.
-    fn asserts(_input: &DeriveInput) -> syn::Result<TokenStream> {
+    fn asserts(_input: &DeriveInput, _krate: &syn::Path) -> syn::Result<TokenStream> {
         Ok(quote! {
             const: fn ||
         })
@@ -50,7 +50,10 @@ pub trait Marker: Contract {
     }
 
     /// Emit the source code comprising the trait implementation.
-    fn trait_impl(_input: &DeriveInput) -> syn::Result<(TokenStream, TokenStream)> {
+    fn trait_impl(
+        _input: &DeriveInput,
+        _krate: &syn::Path,
+    ) -> syn::Result<(TokenStream, TokenStream)> {
         Ok((quote!(), quote!()))
     }
 
@@ -62,7 +65,6 @@ pub trait Marker: Contract {
             .type_params_mut()
             .map(|param| {
                 let param_ident = &param.ident;
-                dbg!(&param);
                 parse_quote!(
                   #param_ident: #trait_name
                 )
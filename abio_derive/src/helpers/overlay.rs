@@ -0,0 +1,120 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Error, Path, Result};
+
+use super::{generate_fields_are_trait, ComptimeLayout, Repr};
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct AbiUnion;
+
+impl AbiUnion {
+    /// Generates a `{Name}Overlay` struct borrowing the bytes of the union, with one
+    /// `as_{field}` accessor per field that reinterprets the bytes as that field.
+    pub fn impl_block(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
+        let name = &input.ident;
+        let vis = &input.vis;
+        let overlay = format_ident!("{}Overlay", name);
+
+        let fields = match &input.data {
+            Data::Union(data) => &data.fields.named,
+            _ => return Err(Error::new_spanned(
+                name,
+                "`AbiUnion` can only be derived for unions, whose fields overlay the same bytes.",
+            )),
+        };
+        if let Some(param) = input.generics.params.first() {
+            return Err(Error::new_spanned(
+                param,
+                "`AbiUnion` cannot be derived for generic unions, whose size depends on their generic arguments.",
+            ));
+        }
+        let layout = ComptimeLayout::parse_repr_attr(&input.attrs)?;
+        if layout.repr() != Repr::C {
+            return Err(Error::new_spanned(
+                name,
+                "`AbiUnion` requires `#[repr(C)]`, so that every field starts at offset zero.",
+            ));
+        }
+
+        // The accessors hand out references to the fields, so any bytes must form a
+        // valid value of each of them.
+        let assert_fields_are_abi = generate_fields_are_trait(input, parse_quote!(#krate::Abi))?;
+
+        let accessors = fields.iter().map(|field| {
+            let ident = field
+                .ident
+                .as_ref()
+                .expect("union fields are always named");
+            let accessor = format_ident!("as_{}", ident);
+            let ty = &field.ty;
+            let doc =
+                format!("Views the bytes of the overlay as the `{ident}` field of [`{name}`].");
+            quote! {
+                #[doc = #doc]
+                ///
+                /// # Errors
+                ///
+                /// Returns an error if the bytes are not aligned for the type of the field.
+                #[inline]
+                #vis fn #accessor(&self) -> #krate::Result<&'__data #ty> {
+                    let __ptr = self.bytes.as_ptr().cast::<#ty>();
+                    if !__ptr.is_aligned() {
+                        return ::core::result::Result::Err(
+                            #krate::Error::misaligned_access::<#ty>(__ptr),
+                        );
+                    }
+                    // SAFETY: The overlay spans the bytes of the whole union, which are at
+                    // least as many as those of the field, and the pointer was checked to be
+                    // aligned. The field is `Abi`, so any bytes form a valid value.
+                    ::core::result::Result::Ok(unsafe { &*__ptr })
+                }
+            }
+        });
+
+        let doc = format!(
+            "Checked overlay of the types of the fields of [`{name}`] over the same bytes, replacing reads of the fields of the union."
+        );
+        Ok(quote! {
+            #assert_fields_are_abi
+
+            #[doc = #doc]
+            #[derive(Clone, Copy, Debug)]
+            #vis struct #overlay<'__data> {
+                bytes: &'__data [u8],
+            }
+
+            impl<'__data> #overlay<'__data> {
+                /// Number of bytes spanned by the overlay.
+                #vis const SIZE: usize = ::core::mem::size_of::<#name>();
+
+                /// Creates an overlay over the first `SIZE` bytes of `bytes`.
+                ///
+                /// # Errors
+                ///
+                /// Returns an error if `bytes` is shorter than `SIZE`.
+                #[inline]
+                #vis fn new(bytes: &'__data [u8]) -> #krate::Result<Self> {
+                    match bytes.get(..Self::SIZE) {
+                        ::core::option::Option::Some(bytes) => {
+                            ::core::result::Result::Ok(Self { bytes })
+                        }
+                        ::core::option::Option::None => ::core::result::Result::Err(
+                            #krate::Error::out_of_bounds(Self::SIZE, bytes.len()),
+                        ),
+                    }
+                }
+
+                /// Returns the bytes spanned by the overlay.
+                ///
+                /// This method is not named `as_bytes`, since that name is taken by the
+                /// accessor of a field named `bytes`.
+                #[inline]
+                #vis const fn bytes(&self) -> &'__data [u8] {
+                    self.bytes
+                }
+
+                #(#accessors)*
+            }
+        })
+    }
+}
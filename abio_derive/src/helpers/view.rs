@@ -0,0 +1,135 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields, Path, Result};
+
+use super::attrs::FieldAttrs;
+use super::general::endian;
+use super::{ComptimeLayout, Repr};
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct View;
+
+impl View {
+    /// Generates a `{Name}View` struct borrowing the wire representation of the
+    /// type, with one getter per field that decodes the field at its constant
+    /// offset when called.
+    pub fn impl_block(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
+        let name = &input.ident;
+        let vis = &input.vis;
+        let view = format_ident!("{}View", name);
+
+        let fields = match &input.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(fields) if !fields.named.is_empty() => &fields.named,
+                _ => {
+                    return Err(Error::new_spanned(
+                        name,
+                        "`View` can only be derived for structs with named fields.",
+                    ))
+                }
+            },
+            _ => {
+                return Err(Error::new_spanned(
+                    name,
+                    "`View` can only be derived for structs with named fields.",
+                ))
+            }
+        };
+        if let Some(param) = input.generics.params.first() {
+            return Err(Error::new_spanned(
+                param,
+                "`View` cannot be derived for generic types, whose field offsets depend on their generic arguments.",
+            ));
+        }
+        let layout = ComptimeLayout::parse_repr_attr(&input.attrs)?;
+        if !matches!(layout.repr(), Repr::C | Repr::Transparent) {
+            return Err(Error::new_spanned(
+                name,
+                "`View` requires `#[repr(C)]` or `#[repr(transparent)]`, so that field offsets match the wire layout.",
+            ));
+        }
+
+        let getters = fields
+            .iter()
+            .map(|field| {
+                let attrs = FieldAttrs::parse(&field.attrs)?;
+                let ident = field
+                    .ident
+                    .as_ref()
+                    .expect("fields were checked to be named");
+                let ty = &field.ty;
+                let endian = endian(attrs.endian, krate);
+                let doc = format!("Decodes the `{ident}` field of the underlying [`{name}`].");
+                Ok(quote! {
+                    #[doc = #doc]
+                    ///
+                    /// # Errors
+                    ///
+                    /// Returns an error if the bytes of the field do not hold a valid value.
+                    #[inline]
+                    #vis fn #ident(&self) -> #krate::Result<#ty> {
+                        const OFFSET: usize = ::core::mem::offset_of!(#name, #ident);
+                        let __bytes = self.bytes.into_slice();
+                        let (value, _) = <#ty as #krate::Decode<'__data>>::decode::<#endian>(
+                            __bytes.get(OFFSET..).unwrap_or_default(),
+                        )?;
+                        ::core::result::Result::Ok(value)
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let doc = format!(
+            "Zero-copy view over the wire representation of [`{name}`], decoding each field only when it is accessed."
+        );
+        Ok(quote! {
+            #[doc = #doc]
+            #vis struct #view<'__data, __E: #krate::Endianness> {
+                bytes: #krate::Bytes<'__data>,
+                _endian: ::core::marker::PhantomData<__E>,
+            }
+
+            impl<'__data, __E: #krate::Endianness> ::core::clone::Clone for #view<'__data, __E> {
+                #[inline]
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<'__data, __E: #krate::Endianness> ::core::marker::Copy for #view<'__data, __E> {}
+
+            // Getters are named after the fields, which may include `len`.
+            #[allow(clippy::len_without_is_empty)]
+            impl<'__data, __E: #krate::Endianness> #view<'__data, __E> {
+                /// Number of bytes spanned by the view.
+                #vis const SIZE: usize = ::core::mem::size_of::<#name>();
+
+                /// Creates a view over the first `SIZE` bytes of `bytes`.
+                ///
+                /// # Errors
+                ///
+                /// Returns an error if `bytes` is shorter than `SIZE`.
+                #[inline]
+                #vis fn new(bytes: &'__data [u8]) -> #krate::Result<Self> {
+                    match bytes.get(..Self::SIZE) {
+                        ::core::option::Option::Some(bytes) => ::core::result::Result::Ok(Self {
+                            bytes: #krate::Bytes::new_allow_empty(bytes),
+                            _endian: ::core::marker::PhantomData,
+                        }),
+                        ::core::option::Option::None => ::core::result::Result::Err(
+                            #krate::Error::out_of_bounds(Self::SIZE, bytes.len()),
+                        ),
+                    }
+                }
+
+                /// Returns the bytes spanned by the view.
+                #[inline]
+                #vis const fn as_bytes(&self) -> #krate::Bytes<'__data> {
+                    self.bytes
+                }
+
+                #(#getters)*
+            }
+        })
+    }
+}
@@ -1,63 +1,75 @@
 #![allow(dead_code)]
 
-use proc_macro2::{Span, TokenStream};
-use quote::{format_ident, quote, ToTokens};
-use syn::{parse_macro_input, DeriveInput, Error, Result};
+use abio_derive_core::attrs::ContainerAttrs;
+use abio_derive_core::errors::Errors;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput, Path, Result};
 
 mod helpers;
-use helpers::{Abi, AsBytes, Decode, Marker, Zeroable};
+use helpers::{Abi, AbiUnion, AsBytes, Decode, Encode, Marker, View, Zeroable};
 mod traits;
 
-#[proc_macro_derive(Abi)]
+#[proc_macro_derive(Abi, attributes(abio))]
 pub fn derive_abi(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    match gen_marker_trait_impl::<Abi>(&input) {
-        Ok(imp) => imp.into(),
-        Err(e) => e.to_compile_error().into(),
-    }
+    expand(&input, gen_marker_trait_impl::<Abi>)
 }
 
-#[proc_macro_derive(AsBytes)]
+#[proc_macro_derive(AsBytes, attributes(abio))]
 pub fn derive_as_bytes(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    match gen_marker_trait_impl::<AsBytes>(&input) {
-        Ok(imp) => imp.into(),
-        Err(e) => e.to_compile_error().into(),
-    }
+    expand(&input, gen_marker_trait_impl::<AsBytes>)
 }
 
-#[proc_macro_derive(Zeroable)]
+#[proc_macro_derive(Zeroable, attributes(abio))]
 pub fn derive_zeroable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    match gen_marker_trait_impl::<Zeroable>(&input) {
-        Ok(imp) => imp.into(),
-        Err(e) => e.to_compile_error().into(),
-    }
+    expand(&input, gen_marker_trait_impl::<Zeroable>)
 }
 
-#[proc_macro_derive(Decode)]
+#[proc_macro_derive(Decode, attributes(abio))]
 pub fn derive_decode(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    // Parse the input AST from the `Decode<T>` trait.
-    let expanded = parse_decode_input(&input);
+    expand(&input, Decode::impl_block)
+}
 
-    // Return the generated implementation as tokens
-    proc_macro::TokenStream::from(expanded)
+#[proc_macro_derive(Encode, attributes(abio))]
+pub fn derive_encode(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, Encode::impl_block)
 }
 
-fn parse_decode_input(input: &DeriveInput) -> TokenStream {
-    let ident = &input.ident;
-    quote!()
+#[proc_macro_derive(View, attributes(abio))]
+pub fn derive_view(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, View::impl_block)
 }
 
-fn derive_decode_trait(_input: &DeriveInput) -> TokenStream {
-    unimplemented!()
+#[proc_macro_derive(AbiUnion, attributes(abio))]
+pub fn derive_abi_union(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, AbiUnion::impl_block)
+}
+
+/// Runs `derive` on `input`, passing it the path of the `abio` crate that the
+/// generated code refers to, as declared with `#[abio(crate = "...")]`.
+fn expand(
+    input: &DeriveInput,
+    derive: impl FnOnce(&DeriveInput, &Path) -> Result<TokenStream>,
+) -> proc_macro::TokenStream {
+    let expanded =
+        ContainerAttrs::parse(&input.attrs).and_then(|attrs| derive(input, &attrs.abio_path()));
+    match expanded {
+        Ok(imp) => imp.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
 
-fn gen_marker_trait_impl<G: Marker>(input: &DeriveInput) -> Result<TokenStream> {
+fn gen_marker_trait_impl<G: Marker>(input: &DeriveInput, krate: &Path) -> Result<TokenStream> {
     // Ensure that each field of the type implements the trait
     let mut input = input.clone();
-    let trait_name = G::ident(&input);
+    let trait_name = G::ident(krate);
 
     let input = G::add_trait_marker(&mut input, &trait_name);
 
@@ -65,22 +77,19 @@ fn gen_marker_trait_impl<G: Marker>(input: &DeriveInput) -> Result<TokenStream>
 
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-    _ = G::validate_attributes(&input.data, &input.attrs).map_err(|err| {
-        eprintln!("{err:?}");
-        Error::new(
-            Span::call_site(),
-            "Cannot implement this trait for this type due to invalid attribute values.",
-        )
-    });
-
-    let assertions = match G::asserts(&input) {
-        Ok(asserts) => asserts,
-        Err(err) => err
-            .into_compile_error()
-            .to_token_stream(),
-    };
-
-    let (trait_impl_extras, trait_impl) = G::trait_impl(&input)?;
+    // Every check runs even if an earlier one failed, so that all errors are
+    // reported at once. The implementation is still emitted alongside the errors,
+    // which keeps uses of the trait from failing with errors of their own.
+    let mut errors = Errors::new();
+    errors.check(G::validate_attributes(&input.data, &input.attrs));
+    let assertions = errors.check(G::asserts(&input, krate));
+    let (trait_impl_extras, trait_impl) = errors
+        .check(G::trait_impl(&input, krate))
+        .unwrap_or_default();
+    let errors = errors
+        .finish()
+        .err()
+        .map(syn::Error::into_compile_error);
 
     let impl_prefix = if G::is_unsafe(&input) {
         quote! {
@@ -103,6 +112,8 @@ fn gen_marker_trait_impl<G: Marker>(input: &DeriveInput) -> Result<TokenStream>
     let where_clause = if G::requires_where_clause() { where_clause } else { None };
 
     Ok(quote! {
+      #errors
+
       #assertions
 
       #trait_impl_extras
@@ -70,8 +70,8 @@ fn is_matching_attr(attribute: &Attribute, other: &str) -> Option<syn::Ident> {
     }
 }
 
-pub fn make_compiler_error(message: &str) -> TokenStream {
-    syn::Error::new(Span::call_site(), message)
+pub fn make_compiler_error(tokens: impl ToTokens, message: &str) -> TokenStream {
+    syn::Error::new_spanned(tokens, message)
         .into_compile_error()
         .into()
 }
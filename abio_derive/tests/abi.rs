@@ -0,0 +1,292 @@
+use abio::codec::{decode_stream, Patcher, StreamEvent};
+use abio::layout::{padding_spans, Describe, DiscriminantRange, Fingerprint};
+use abio::version::VersionTag;
+use abio::{Abi, BytesMut, Endian, Span, Zeroable, BE};
+
+#[derive(Abi, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+enum Opcode {
+    Load = 4,
+    Store,
+    Jump,
+}
+
+#[derive(Abi, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(i32)]
+enum Level {
+    Low = -1,
+    Mid = 0,
+    High = 1,
+}
+
+#[test]
+fn fieldless_enums_record_discriminant_range() {
+    assert_eq!(Opcode::MIN, 4);
+    assert_eq!(Opcode::MAX, 6);
+    assert!(Opcode::contains(Opcode::Store as u8));
+    assert!(!Opcode::contains(3));
+    assert!(!Opcode::contains(7));
+
+    assert_eq!(Level::MIN, -1);
+    assert_eq!(Level::MAX, 1);
+    assert!(Level::contains(Level::High as i32));
+    assert!(!Level::contains(2));
+}
+
+#[test]
+fn fieldless_enums_are_converted_from_declared_discriminants_only() {
+    assert_eq!(Opcode::from_repr(4).unwrap(), Opcode::Load);
+    assert_eq!(Opcode::from_repr(6).unwrap(), Opcode::Jump);
+    assert!(Opcode::from_repr(3)
+        .unwrap_err()
+        .is_unknown_discriminant());
+    assert!(Opcode::from_repr(0xff).is_err());
+    assert_eq!(Level::from_repr(-1).unwrap(), Level::Low);
+    assert!(Level::from_repr(i32::MIN).is_err());
+}
+
+#[derive(Abi)]
+#[repr(transparent)]
+struct Wrapper<T>(T);
+
+#[derive(Abi)]
+#[repr(C)]
+struct Pair<T> {
+    lo: T,
+    hi: T,
+}
+
+#[derive(Abi)]
+#[repr(C, packed)]
+struct Tagged<T, U> {
+    tag: T,
+    value: U,
+}
+
+#[test]
+fn generic_structs_are_checked_per_instantiation() {
+    assert_eq!(<Wrapper<u64> as Abi>::SIZE, 8);
+    assert_eq!(<Pair<u16> as Abi>::SIZE, 4);
+    assert_eq!(<Pair<u64> as Abi>::SIZE, 16);
+    assert_eq!(<Tagged<u8, u32> as Abi>::SIZE, 5);
+    assert_ne!(<Pair<u16> as Fingerprint>::FINGERPRINT, <Pair<u32> as Fingerprint>::FINGERPRINT);
+    assert!(<Pair<u16> as VersionTag>::check_peer(<Pair<u32> as VersionTag>::VERSION_TAG).is_err());
+}
+
+#[derive(Abi)]
+#[repr(C)]
+struct Span32 {
+    start: u32,
+    end: core::primitive::u32,
+}
+
+#[derive(Abi)]
+#[repr(C)]
+struct Range32 {
+    first: u32,
+    last: u32,
+}
+
+#[test]
+fn fingerprints_follow_the_resolved_layout() {
+    assert_eq!(<Span32 as Fingerprint>::FINGERPRINT, <Range32 as Fingerprint>::FINGERPRINT);
+    assert_ne!(<Span32 as Fingerprint>::FINGERPRINT, <Pair<u16> as Fingerprint>::FINGERPRINT);
+    assert_ne!(<Opcode as Fingerprint>::FINGERPRINT, <Level as Fingerprint>::FINGERPRINT);
+    assert_ne!(<SectionMisc as Fingerprint>::FINGERPRINT, <Register as Fingerprint>::FINGERPRINT);
+    assert!(<Opcode as VersionTag>::check_peer(<Opcode as VersionTag>::VERSION_TAG).is_ok());
+}
+
+#[test]
+fn packed_structs_expose_unaligned_getters() {
+    let tagged = Tagged { tag: 1u8, value: 0x0102_0304u32 };
+    assert_eq!(tagged.tag(), 1);
+    assert_eq!(tagged.value(), 0x0102_0304);
+}
+
+#[test]
+fn structs_describe_their_fields() {
+    let descriptor = <Tagged<u8, u32> as Describe>::DESCRIPTOR;
+    assert_eq!((descriptor.name, descriptor.size), ("Tagged", 5));
+    assert_eq!((descriptor.align, descriptor.repr, descriptor.padding()), (1, "C, packed", 0));
+    assert_eq!(descriptor.fields[1].name, "value");
+    assert_eq!((descriptor.fields[1].offset, descriptor.fields[1].size), (1, 4));
+    assert_eq!(<Wrapper<u16> as Describe>::DESCRIPTOR.fields[0].name, "0");
+
+    let bytes = [7, 1, 2, 3, 4];
+    let events = decode_stream::<Tagged<u8, u32>>(&bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert!(matches!(events[0], StreamEvent::StructStart { offset: 0, .. }));
+    assert!(matches!(events[1], StreamEvent::Field { field, offset: 0 } if field.name == "tag"));
+    assert_eq!(events[2], StreamEvent::Bytes(&[7]));
+    assert_eq!(events[4], StreamEvent::Bytes(&[1, 2, 3, 4]));
+    assert_eq!(events[5], StreamEvent::End);
+    assert_eq!(events.len(), 6);
+    assert!(decode_stream::<Tagged<u8, u32>>(&bytes[..4])
+        .next()
+        .unwrap()
+        .is_err());
+}
+
+#[derive(Abi)]
+#[repr(C)]
+struct Entry<T> {
+    tag: u8,
+    value: T,
+}
+
+#[derive(Abi)]
+#[repr(C)]
+struct Trailer<T> {
+    value: T,
+    tag: u8,
+}
+
+#[test]
+fn structs_list_their_padding() {
+    assert_eq!(padding_spans::<Entry<u32>>(), [Span::new(1, 3)]);
+    assert_eq!(<Trailer<u64> as Describe>::PADDING, [Span::new(9, 7)]);
+    assert!(padding_spans::<Entry<u8>>().is_empty());
+    assert!(padding_spans::<Tagged<u8, u64>>().is_empty());
+}
+
+#[derive(Abi)]
+#[repr(C)]
+struct FileHeader {
+    machine: u16,
+    #[abio(endian = "be")]
+    sections: u16,
+    timestamp: u32,
+}
+
+#[derive(Abi)]
+#[repr(C)]
+struct NtHeaders {
+    signature: u32,
+    #[abio(nested)]
+    file: FileHeader,
+}
+
+#[test]
+fn nested_fields_are_patched_by_path() {
+    let mut buf = [0u8; 12];
+    let mut bytes = BytesMut::new(&mut buf);
+    let mut patcher = Patcher::<NtHeaders, BE>::new(&mut bytes);
+    assert_eq!(
+        patcher
+            .locate("file.timestamp")
+            .unwrap()
+            .range(),
+        8..12
+    );
+    patcher
+        .set("file.sections", 3u16)
+        .unwrap()
+        .set("signature", 0x5045_0000u32)
+        .unwrap();
+    assert_eq!(buf, [0x50, 0x45, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0]);
+    assert_eq!(
+        <NtHeaders as Describe>::DESCRIPTOR.fields[1]
+            .nested
+            .unwrap()
+            .name,
+        "FileHeader"
+    );
+    let file = <FileHeader as Describe>::DESCRIPTOR;
+    assert_eq!(
+        file.field("sections")
+            .unwrap()
+            .endian,
+        Some(Endian::Big)
+    );
+    assert_eq!(file.field_at(5).unwrap().name, "timestamp");
+    assert!(file.fields[0].endian.is_none());
+
+    let mut bytes = BytesMut::new(&mut buf);
+    assert!(Patcher::<NtHeaders, BE>::new(&mut bytes)
+        .set("signature.machine", 0u16)
+        .is_err());
+}
+
+#[derive(Abi)]
+#[abio(allow_rust_repr)]
+struct Meters(u32);
+
+#[test]
+fn single_field_structs_may_use_rust_repr() {
+    assert_eq!(<Meters as Abi>::SIZE, 4);
+}
+
+#[derive(Abi)]
+#[repr(C)]
+#[abio(assert_size = 16, assert_align = 8)]
+struct TimeSpec {
+    seconds: i64,
+    nanoseconds: i64,
+}
+
+#[derive(Abi, Clone, Copy)]
+#[repr(u16)]
+#[abio(assert_size = 2, assert_align = 2)]
+enum Priority {
+    Low = 1,
+    Normal,
+    High,
+}
+
+#[test]
+fn layout_assertions_hold() {
+    assert_eq!(<TimeSpec as Abi>::SIZE, 16);
+    assert_eq!(<Priority as Abi>::SIZE, 2);
+}
+
+/// The `Misc` field of `IMAGE_SECTION_HEADER`.
+#[derive(Abi, Zeroable, Clone, Copy)]
+#[repr(C)]
+union SectionMisc {
+    physical_address: u32,
+    virtual_size: u32,
+}
+
+#[derive(Abi, Zeroable, Clone, Copy)]
+#[repr(C)]
+#[abio(assert_size = 8)]
+union Register {
+    wide: u64,
+    halves: [u32; 2],
+    bytes: [u8; 8],
+}
+
+#[test]
+fn unions_are_checked_against_their_largest_field() {
+    assert_eq!(<SectionMisc as Abi>::SIZE, 4);
+    assert_eq!(<Register as Abi>::SIZE, 8);
+
+    let register = Register { wide: 0x0102_0304_0506_0708 };
+    // SAFETY: Every field of `Register` is `Abi`, so any initialized bytes are valid.
+    assert_eq!(unsafe { register.bytes }, 0x0102_0304_0506_0708u64.to_ne_bytes());
+}
+
+/// A slot of a [`Ring`], which is not `Abi` since its layout is unspecified.
+#[derive(Clone, Copy, Debug, PartialEq, Zeroable)]
+struct Slot {
+    id: u32,
+    grid: [[u8; 4]; 2],
+}
+
+#[derive(Zeroable)]
+#[repr(C)]
+struct Ring<T, const N: usize> {
+    head: u32,
+    slots: [T; N],
+    scratch: [u32; 16],
+}
+
+#[test]
+fn zeroable_accepts_array_fields() {
+    // SAFETY: `Ring<Slot, 4>` derives `Zeroable`, since each of its fields does.
+    let ring = unsafe { <Ring<Slot, 4> as Zeroable>::zeroed() };
+    assert_eq!(ring.head, 0);
+    assert_eq!(ring.slots, [Slot { id: 0, grid: [[0; 4]; 2] }; 4]);
+    assert_eq!(ring.scratch, [0; 16]);
+}
@@ -0,0 +1,56 @@
+#![feature(const_trait_impl)]
+
+use abio::{Decode, BE, LE};
+
+#[derive(Debug, Decode, PartialEq)]
+#[abio(const_decode)]
+pub struct Version {
+    major: u8,
+    minor: u8,
+}
+
+#[derive(Debug, Decode, PartialEq)]
+#[abio(const_decode, magic = b"CFG\0")]
+pub struct Config {
+    version: Version,
+    #[abio(pad_before = 2)]
+    flags: u16,
+    #[abio(endian = "be")]
+    limit: u32,
+}
+
+const BLOB: &[u8] = &[b'C', b'F', b'G', 0, 1, 2, 0xff, 0xff, 0x34, 0x12, 0, 0, 1, 0];
+
+const CONFIG: Config = match Config::decode_const::<LE>(BLOB, 0) {
+    Ok(config) => config,
+    Err(_) => panic!("`BLOB` holds a valid configuration"),
+};
+
+#[test]
+fn decodes_at_compile_time() {
+    let expected = Config {
+        version: Version { major: 1, minor: 2 },
+        flags: 0x1234,
+        limit: 0x100,
+    };
+    assert_eq!(CONFIG, expected);
+    assert_eq!(<Config as abio::codec::ConstDecode>::SIZE, 14);
+    assert_eq!(Config::decode::<LE>(BLOB).unwrap(), (expected, 14));
+}
+
+#[test]
+fn decodes_at_an_offset() {
+    let bytes = [0xaa, 3, 4];
+    assert_eq!(Version::decode_const::<BE>(&bytes, 1).unwrap(), Version { major: 3, minor: 4 });
+    assert!(Version::decode_const::<BE>(&bytes, 2).is_err());
+    assert!(Version::decode_const::<BE>(&bytes, usize::MAX).is_err());
+}
+
+#[test]
+fn rejects_bad_magic_and_short_input() {
+    let mut bytes = BLOB.to_vec();
+    bytes[0] = b'X';
+    assert!(Config::decode_const::<LE>(&bytes, 0).is_err());
+    // The trailing field is cut short.
+    assert!(Config::decode_const::<LE>(&BLOB[..13], 0).is_err());
+}
@@ -0,0 +1,79 @@
+mod facade {
+    pub use abio as wire;
+}
+
+use facade::wire::layout::DiscriminantRange;
+use facade::wire::{Abi, Decode, Encode, View, Zeroable, BE};
+
+#[derive(Abi, Clone, Copy, Debug, Decode, Encode, PartialEq, View, Zeroable)]
+#[abio(crate = "crate::facade::wire")]
+#[repr(C)]
+pub struct Entry {
+    kind: u16,
+    #[abio(endian = "le")]
+    length: u16,
+}
+
+#[test]
+fn derives_resolve_through_the_declared_crate_path() {
+    let bytes = [0, 1, 2, 0];
+    let (entry, len) = Entry::decode::<BE>(&bytes).unwrap();
+    assert_eq!((entry, len), (Entry { kind: 1, length: 2 }, 4));
+    assert_eq!(<Entry as Abi>::SIZE, 4);
+    assert_eq!(
+        EntryView::<BE>::new(&bytes)
+            .unwrap()
+            .length()
+            .unwrap(),
+        2
+    );
+
+    let mut buf = [0u8; 4];
+    assert_eq!(
+        entry
+            .encode::<BE>(&mut buf)
+            .unwrap(),
+        4
+    );
+    assert_eq!(buf, bytes);
+}
+
+#[derive(Abi, Clone, Copy, Debug, PartialEq)]
+#[abio(crate = "crate::facade::wire")]
+#[repr(u8)]
+pub enum Kind {
+    File = 1,
+    Directory,
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[abio(crate = "crate::facade::wire", tag = "u8")]
+pub enum Command {
+    Stat {
+        kind: u8,
+    },
+    Flags {
+        #[abio(bits = 3)]
+        mode: u8,
+        #[abio(bits = 5)]
+        level: u8,
+    },
+}
+
+#[test]
+fn enums_and_bit_fields_resolve_through_the_declared_crate_path() {
+    assert_eq!((Kind::MIN, Kind::MAX), (1, 2));
+    assert_eq!(Kind::from_repr(2).unwrap(), Kind::Directory);
+
+    let command = Command::Flags { mode: 5, level: 3 };
+    let mut buf = [0u8; 2];
+    assert_eq!(
+        command
+            .encode::<BE>(&mut buf)
+            .unwrap(),
+        2
+    );
+    assert_eq!(buf, [1, 0b1010_0011]);
+    assert_eq!(Command::decode::<BE>(&buf).unwrap(), (command, 2));
+    assert!(Command::decode::<BE>(&[2, 0]).is_err());
+}
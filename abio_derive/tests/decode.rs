@@ -0,0 +1,617 @@
+use abio::{Decode, Encode, BE, LE};
+
+#[derive(Debug, Decode, PartialEq)]
+pub struct Header {
+    magic: u16,
+    version: u8,
+    length: u32,
+}
+
+#[derive(Debug, Decode, PartialEq)]
+pub struct Pair(u16, u16);
+
+#[derive(Debug, Decode, PartialEq)]
+pub struct Nested<T> {
+    header: Header,
+    trailer: T,
+}
+
+#[test]
+fn decodes_fields_in_declaration_order() {
+    let bytes = [0x4d, 0x5a, 0x02, 0x10, 0x00, 0x00, 0x00];
+    let (header, len) = Header::decode::<LE>(&bytes).unwrap();
+    assert_eq!(len, 7);
+    assert_eq!(header, Header { magic: 0x5a4d, version: 2, length: 0x10 });
+
+    assert_eq!(Pair::decode::<BE>(&[0, 1, 0, 2]).unwrap(), (Pair(1, 2), 4));
+}
+
+#[test]
+fn propagates_field_errors() {
+    let bytes = [0x4d, 0x5a, 0x02, 0x10, 0x00, 0x00, 0x00, 0xff];
+    let (nested, len) = Nested::<u8>::decode::<LE>(&bytes).unwrap();
+    assert_eq!(len, 8);
+    assert_eq!(nested.trailer, 0xff);
+
+    assert!(Nested::<u16>::decode::<LE>(&bytes).is_err());
+    assert!(Header::decode::<LE>(&bytes[..6]).is_err());
+}
+
+const HAS_EXT: u8 = 0x01;
+
+#[derive(Debug, Decode, PartialEq)]
+pub struct Versioned {
+    flags: u8,
+    #[abio(if = "flags & HAS_EXT != 0")]
+    ext: Option<u16>,
+    len: u8,
+}
+
+#[test]
+fn decodes_conditional_fields() {
+    let (value, len) = Versioned::decode::<LE>(&[0x01, 0x34, 0x12, 0x09]).unwrap();
+    assert_eq!(len, 4);
+    assert_eq!(value, Versioned { flags: 0x01, ext: Some(0x1234), len: 0x09 });
+
+    let (value, len) = Versioned::decode::<LE>(&[0x00, 0x09]).unwrap();
+    assert_eq!(len, 2);
+    assert_eq!(value, Versioned { flags: 0x00, ext: None, len: 0x09 });
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct Cached {
+    width: u16,
+    height: u16,
+    #[abio(skip)]
+    dirty: bool,
+    #[abio(default = "u32::from(width) * u32::from(height)")]
+    area: u32,
+}
+
+#[test]
+fn initializes_skipped_fields() {
+    let (value, len) = Cached::decode::<LE>(&[0x02, 0x00, 0x03, 0x00]).unwrap();
+    assert_eq!(len, 4);
+    assert_eq!(value, Cached { width: 2, height: 3, dirty: false, area: 6 });
+
+    let mut buf = [0u8; 4];
+    let value = Cached { width: 1, height: 2, dirty: true, area: 0 };
+    assert_eq!(
+        value
+            .encode::<BE>(&mut buf)
+            .unwrap(),
+        4
+    );
+    assert_eq!(buf, [0x00, 0x01, 0x00, 0x02]);
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[abio(version = "version")]
+pub struct Record {
+    version: u8,
+    #[abio(until = 1)]
+    legacy_flags: u16,
+    #[abio(since = 2)]
+    checksum: Option<u32>,
+    #[abio(since = 2, until = 3)]
+    reserved: u8,
+    kind: u8,
+}
+
+#[test]
+fn decodes_fields_present_in_the_version() {
+    let v1 = Record {
+        version: 1,
+        legacy_flags: 0x0102,
+        checksum: None,
+        reserved: 0,
+        kind: 9,
+    };
+    assert_eq!(Record::decode::<BE>(&[1, 0x01, 0x02, 9]).unwrap(), (v1, 4));
+
+    let v2 = Record {
+        version: 2,
+        legacy_flags: 0,
+        checksum: Some(7),
+        reserved: 5,
+        kind: 9,
+    };
+    let bytes = [2, 0, 0, 0, 7, 5, 9];
+    assert_eq!(Record::decode::<BE>(&bytes).unwrap(), (v2, 7));
+
+    let v4 = Record {
+        version: 4,
+        legacy_flags: 0,
+        checksum: Some(7),
+        reserved: 0,
+        kind: 9,
+    };
+    assert_eq!(Record::decode::<BE>(&[4, 0, 0, 0, 7, 9]).unwrap(), (v4, 6));
+
+    let v2 = Record {
+        version: 2,
+        legacy_flags: 0xffff,
+        checksum: Some(7),
+        reserved: 5,
+        kind: 9,
+    };
+    let mut buf = [0u8; 8];
+    assert_eq!(
+        v2.encode::<BE>(&mut buf)
+            .unwrap(),
+        7
+    );
+    assert_eq!(buf[..7], bytes);
+}
+
+fn nonzero_version(version: &u8) -> abio::Result<()> {
+    if *version == 0 {
+        return Err(abio::Error::validation_failed("version must not be zero"));
+    }
+    Ok(())
+}
+
+fn ordered_range(range: &Range) -> abio::Result<()> {
+    if range.start > range.end {
+        return Err(abio::Error::validation_failed("range start is past its end"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Decode, PartialEq)]
+#[abio(validate = "ordered_range")]
+pub struct Range {
+    #[abio(validate = "nonzero_version")]
+    version: u8,
+    start: u8,
+    end: u8,
+}
+
+#[test]
+fn rejects_values_failing_validation() {
+    let (value, len) = Range::decode::<LE>(&[0x01, 0x02, 0x03]).unwrap();
+    assert_eq!(len, 3);
+    assert_eq!(value, Range { version: 1, start: 2, end: 3 });
+
+    assert!(Range::decode::<LE>(&[0x00, 0x02, 0x03]).is_err());
+    assert!(Range::decode::<LE>(&[0x01, 0x03, 0x02]).is_err());
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct Glyph {
+    visible: bool,
+    symbol: char,
+}
+
+#[test]
+fn validates_bool_and_char_fields() {
+    let bytes = [0x01, 0x3b, 0x26, 0x00, 0x00];
+    let (value, len) = Glyph::decode::<LE>(&bytes).unwrap();
+    assert_eq!(len, 5);
+    assert_eq!(value, Glyph { visible: true, symbol: '\u{263b}' });
+
+    let mut buf = [0u8; 5];
+    assert_eq!(
+        value
+            .encode::<LE>(&mut buf)
+            .unwrap(),
+        5
+    );
+    assert_eq!(buf, bytes);
+
+    // Only `0` and `1` are valid booleans, and surrogates are not valid chars.
+    for bytes in [
+        [0x02, 0x41, 0x00, 0x00, 0x00],
+        [0x00, 0x00, 0xd8, 0x00, 0x00],
+        [0x00, 0x00, 0x00, 0x11, 0x00],
+    ] {
+        assert!(Glyph::decode::<LE>(&bytes)
+            .unwrap_err()
+            .is_invalid_value());
+    }
+    assert_eq!(
+        Glyph::decode::<BE>(&[0x00, 0x00, 0x00, 0x00, 0x41])
+            .unwrap()
+            .0,
+        Glyph { visible: false, symbol: 'A' }
+    );
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[abio(magic = b"PE\0\0")]
+pub struct PeSignature {
+    machine: u16,
+    sections: u16,
+}
+
+#[derive(Debug, Decode, PartialEq)]
+pub struct DosHeader {
+    #[abio(magic = b"MZ")]
+    signature: u16,
+    pages: u16,
+}
+
+#[test]
+fn matches_magic_signatures() {
+    let bytes = [b'P', b'E', 0x00, 0x00, 0x64, 0x86, 0x03, 0x00];
+    let (value, len) = PeSignature::decode::<LE>(&bytes).unwrap();
+    assert_eq!(len, 8);
+    assert_eq!(value, PeSignature { machine: 0x8664, sections: 3 });
+
+    let mut buf = [0u8; 8];
+    assert_eq!(
+        value
+            .encode::<LE>(&mut buf)
+            .unwrap(),
+        8
+    );
+    assert_eq!(buf, bytes);
+    assert!(value
+        .encode::<LE>(&mut buf[..3])
+        .is_err());
+
+    assert!(PeSignature::decode::<LE>(b"PE\0\x01\x64\x86\x03\x00").is_err());
+    assert!(PeSignature::decode::<LE>(b"PE").is_err());
+
+    let (value, len) = DosHeader::decode::<LE>(b"MZ\x90\x00").unwrap();
+    assert_eq!(len, 4);
+    assert_eq!(value, DosHeader { signature: 0x5a4d, pages: 0x90 });
+    assert!(DosHeader::decode::<LE>(b"ZM\x90\x00").is_err());
+}
+
+#[derive(Decode)]
+pub struct Table<'data> {
+    count: u8,
+    #[abio(len = "count")]
+    entries: abio::codec::Counted<'data, u16>,
+    name_len: u8,
+    #[abio(len = "name_len")]
+    name: abio::Bytes<'data>,
+}
+
+#[test]
+fn decodes_counted_fields() {
+    let bytes = [2, 0x01, 0x00, 0x02, 0x00, 3, b'a', b'b', b'c', 0xff];
+    let (table, len) = Table::decode::<LE>(&bytes).unwrap();
+    assert_eq!(len, 9);
+    assert_eq!((table.count, table.name_len), (2, 3));
+    assert_eq!(table.entries.len(), 2);
+    assert_eq!(
+        table
+            .entries
+            .get(1)
+            .unwrap()
+            .unwrap(),
+        2
+    );
+    assert_eq!(table.name.into_slice(), b"abc");
+
+    assert!(Table::decode::<LE>(&bytes[..4]).is_err());
+    assert!(Table::decode::<LE>(&[3, 0, 0, 0, 0, 0]).is_err());
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[abio(transparent)]
+pub struct Rva(u32);
+
+#[derive(Debug, Decode)]
+#[abio(transparent)]
+pub struct Name<'data, T> {
+    bytes: abio::Bytes<'data>,
+    #[abio(skip)]
+    _marker: core::marker::PhantomData<T>,
+}
+
+#[derive(Debug, Decode)]
+pub struct Export<'data> {
+    rva: Rva,
+    name_len: u8,
+    #[abio(len = "name_len")]
+    name: Name<'data, u8>,
+}
+
+#[test]
+fn forwards_decoding_to_the_field_of_transparent_types() {
+    let bytes = [0x00, 0x10, 0x00, 0x00, 2, b'o', b'k'];
+    assert_eq!(Rva::decode::<LE>(&bytes).unwrap(), (Rva(0x1000), 4));
+
+    let (export, len) = Export::decode::<LE>(&bytes).unwrap();
+    assert_eq!(len, 7);
+    assert_eq!((export.rva, export.name_len), (Rva(0x1000), 2));
+    assert_eq!(export.name.bytes.into_slice(), b"ok");
+    assert!(Export::decode::<LE>(&bytes[..6]).is_err());
+}
+
+static WATCHDOG: abio::codec::CancelToken = abio::codec::CancelToken::new();
+
+/// Field cancelling `WATCHDOG` once decoded, as a deadline expiring mid-decode would.
+#[derive(Debug, PartialEq)]
+pub struct Expire;
+
+impl<'data> Decode<'data> for Expire {
+    fn decode<E: abio::Endianness>(_: &'data [u8]) -> abio::Result<(Self, usize)> {
+        WATCHDOG.cancel();
+        Ok((Expire, 0))
+    }
+}
+
+#[derive(Debug, Decode, PartialEq)]
+pub struct Slow {
+    first: u8,
+    expire: Expire,
+    last: u8,
+}
+
+#[test]
+fn stops_cancelled_decodes_at_field_boundaries() {
+    use abio::codec::CancelToken;
+    use abio::config::Codec;
+
+    static TOKEN: CancelToken = CancelToken::new();
+
+    let codec = |token| {
+        Codec::builder()
+            .little_endian()
+            .cancel_token(token)
+            .build()
+    };
+    let bytes = [0x4d, 0x5a, 2, 0x10, 0, 0, 0];
+    assert_eq!(codec(&TOKEN).decode::<Header>(&bytes), Header::decode::<LE>(&bytes));
+    TOKEN.cancel();
+    assert!(codec(&TOKEN)
+        .decode::<Header>(&bytes)
+        .unwrap_err()
+        .is_cancelled());
+
+    assert!(Slow::decode::<LE>(&[1, 2]).is_ok());
+    assert!(codec(&WATCHDOG)
+        .decode::<Slow>(&[1, 2])
+        .unwrap_err()
+        .is_cancelled());
+}
+
+#[test]
+fn enforces_element_and_nesting_limits() {
+    use abio::config::{Codec, Limit};
+
+    let codec = |limit| {
+        Codec::builder()
+            .little_endian()
+            .limit(limit)
+            .build()
+    };
+    let bytes = [2, 0x01, 0x00, 0x02, 0x00, 3, b'a', b'b', b'c'];
+    let few = codec(Limit::NONE.with_max_elements(2));
+    assert!(matches!(
+        few.decode::<Table<'_>>(&bytes),
+        Err(err) if err.is_limit_exceeded()
+    ));
+    let enough = codec(Limit::NONE.with_max_elements(3));
+    assert_eq!(
+        enough
+            .decode::<Table<'_>>(&bytes)
+            .unwrap()
+            .1,
+        9
+    );
+
+    // The header and the pair are nested one level below the value itself.
+    let bytes = [0x4d, 0x5a, 2, 0x10, 0, 0, 0, 1, 0, 2, 0];
+    let shallow = codec(Limit::NONE.with_max_depth(1));
+    assert!(shallow
+        .decode::<Header>(&bytes)
+        .is_ok());
+    assert!(shallow
+        .decode::<Nested<Pair>>(&bytes)
+        .unwrap_err()
+        .is_limit_exceeded());
+    let deep = codec(Limit::NONE.with_max_depth(2));
+    assert_eq!(
+        deep.decode::<Nested<Pair>>(&bytes)
+            .unwrap()
+            .1,
+        11
+    );
+}
+
+/// Options of the format that are not stored in the input.
+struct Options {
+    version: u8,
+    wide: bool,
+}
+
+#[derive(Debug, PartialEq, Decode)]
+#[abio(ctx = "Options", version = "ctx.version")]
+struct Entry {
+    id: u16,
+    #[abio(since = 2)]
+    flags: Option<u8>,
+    #[abio(if = "ctx.wide")]
+    high: Option<u16>,
+}
+
+#[derive(Debug, PartialEq, Decode)]
+#[abio(ctx = "Options")]
+struct Entries {
+    count: u8,
+    first: Entry,
+    second: Entry,
+}
+
+#[test]
+fn threads_the_context_through_nested_decodes() {
+    use abio::DecodeWith;
+
+    let entry = |id, flags, high| Entry { id, flags, high };
+    let old = Options { version: 1, wide: false };
+    let bytes = [2, 0x01, 0x00, 0x02, 0x00];
+    assert_eq!(
+        Entries::decode_ctx::<LE>(&bytes, &old).unwrap(),
+        (
+            Entries {
+                count: 2,
+                first: entry(1, None, None),
+                second: entry(2, None, None)
+            },
+            5
+        )
+    );
+
+    let new = Options { version: 2, wide: true };
+    let bytes = [2, 0x01, 0x00, 7, 0x02, 0x00, 0x03, 0x00, 8, 0x04, 0x00];
+    assert_eq!(
+        Entries::decode_ctx::<LE>(&bytes, &new).unwrap(),
+        (
+            Entries {
+                count: 2,
+                first: entry(1, Some(7), Some(2)),
+                second: entry(3, Some(8), Some(4))
+            },
+            11
+        )
+    );
+    assert!(Entry::decode_ctx::<LE>(&bytes[..4], &new).is_err());
+}
+
+#[derive(Debug, PartialEq, Decode)]
+#[abio(trace)]
+struct Extent {
+    start: u16,
+    len: u8,
+}
+
+#[test]
+fn reports_traced_fields_to_observers() {
+    use abio::codec::{Observer, TraceEvent, Tracer};
+
+    #[derive(Default)]
+    struct Log {
+        started: Vec<(&'static str, usize)>,
+        ended: usize,
+    }
+
+    impl Observer for Log {
+        fn on_field_start(&mut self, _: &'static str, field: &'static str, offset: usize) {
+            self.started
+                .push((field, offset));
+        }
+
+        fn on_field_end(&mut self, _: &TraceEvent<'_>) {
+            self.ended += 1;
+        }
+    }
+
+    let mut log = Log::default();
+    assert_eq!(
+        Extent::decode_traced::<LE>(&[1, 0, 2], &mut Tracer::observed(&mut log)).unwrap(),
+        (Extent { start: 1, len: 2 }, 3)
+    );
+    assert_eq!(log.started, [("start", 0), ("len", 2)]);
+    assert_eq!(log.ended, 2);
+
+    // The field a decode failed in is the last one started.
+    let mut log = Log::default();
+    assert!(Extent::decode_traced::<LE>(&[1, 0], &mut Tracer::observed(&mut log)).is_err());
+    assert_eq!(log.started.last(), Some(&("len", 2)));
+    assert_eq!(log.ended, 1);
+}
+
+#[test]
+fn traces_fields_of_types_without_recorded_values() {
+    use abio::codec::{TraceEvent, Tracer};
+
+    // Without `#[abio(trace)]`, fields are traced without their values, so the
+    // type does not need to implement `Debug`.
+    #[derive(Decode)]
+    struct Opaque {
+        id: u16,
+        extent: Extent,
+    }
+
+    let mut fields = Vec::new();
+    {
+        let mut sink = |event: &TraceEvent<'_>| {
+            fields.push((event.field, event.offset, event.value.is_some()));
+        };
+        let (opaque, len) =
+            Opaque::decode_traced::<LE>(&[1, 0, 2, 0, 3], &mut Tracer::new(&mut sink)).unwrap();
+        assert_eq!((opaque.id, opaque.extent, len), (1, Extent { start: 2, len: 3 }, 5));
+    }
+    assert_eq!(
+        fields,
+        [("id", 0, false), ("start", 2, true), ("len", 4, true), ("extent", 2, false)]
+    );
+}
+
+#[test]
+fn traces_decodes_through_the_codec() {
+    use abio::codec::TraceEvent;
+    use abio::config::Codec;
+
+    let mut lines = Vec::new();
+    let mut sink = |event: &TraceEvent<'_>| lines.push(event.to_string());
+    let codec = Codec::builder()
+        .little_endian()
+        .build();
+    assert_eq!(
+        codec
+            .decode_traced::<Extent>(&[1, 0, 2], &mut sink)
+            .unwrap(),
+        (Extent { start: 1, len: 2 }, 3)
+    );
+    assert_eq!(lines, ["0x00000000 Extent.start = 1 [01 00]", "0x00000002 Extent.len = 2 [02]"]);
+}
+
+#[derive(Debug, Decode)]
+#[abio(trace)]
+struct Segment<'data> {
+    name_len: u8,
+    #[abio(len = "name_len")]
+    name: abio::Bytes<'data>,
+    extent: Extent,
+}
+
+#[test]
+fn enforces_codec_limits_on_observed_decodes() {
+    use abio::codec::Observer;
+    use abio::config::{Codec, Limit};
+
+    struct Ignore;
+
+    impl Observer for Ignore {}
+
+    let codec = |limit| {
+        Codec::builder()
+            .little_endian()
+            .limit(limit)
+            .build()
+    };
+    let bytes = [3, b'a', b'b', b'c', 1, 0, 2];
+    let few = codec(Limit::NONE.with_max_elements(2));
+    assert!(few
+        .decode_observed::<Segment<'_>>(&bytes, &mut Ignore)
+        .unwrap_err()
+        .is_limit_exceeded());
+    let enough = codec(Limit::NONE.with_max_elements(3));
+    assert_eq!(
+        enough
+            .decode_observed::<Segment<'_>>(&bytes, &mut Ignore)
+            .unwrap()
+            .1,
+        7
+    );
+
+    // The extent is nested one level below the segment itself.
+    let shallow = codec(Limit::NONE.with_max_depth(1));
+    assert!(shallow
+        .decode_observed::<Extent>(&bytes[4..], &mut Ignore)
+        .is_ok());
+    assert!(shallow
+        .decode_observed::<Segment<'_>>(&bytes, &mut Ignore)
+        .unwrap_err()
+        .is_limit_exceeded());
+    let deep = codec(Limit::NONE.with_max_depth(2));
+    assert!(deep
+        .decode_observed::<Segment<'_>>(&bytes, &mut Ignore)
+        .is_ok());
+}
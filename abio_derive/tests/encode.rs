@@ -0,0 +1,379 @@
+use std::borrow::Cow;
+
+use abio::{Chunk, Decode, Encode, BE, LE};
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct Header {
+    magic: u16,
+    version: u8,
+    length: u32,
+}
+
+const HAS_EXT: u8 = 0x01;
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct Versioned {
+    flags: u8,
+    #[abio(if = "flags & HAS_EXT != 0")]
+    ext: Option<u16>,
+    len: u8,
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[repr(u8)]
+pub enum Message {
+    Ping,
+    Data(u16, u32),
+    Close { code: u16 } = 7,
+}
+
+#[test]
+fn encodes_fields_in_declaration_order() {
+    let header = Header { magic: 0x1234, version: 1, length: 9 };
+    let mut buf = [0u8; 8];
+    assert_eq!(
+        header
+            .encode::<BE>(&mut buf)
+            .unwrap(),
+        7
+    );
+    assert_eq!(buf, [0x12, 0x34, 0x01, 0x00, 0x00, 0x00, 0x09, 0x00]);
+    assert_eq!(Header::decode::<BE>(&buf).unwrap(), (header, 7));
+
+    assert!(Header { magic: 0, version: 0, length: 0 }
+        .encode::<LE>(&mut buf[..6])
+        .is_err());
+}
+
+#[test]
+fn encodes_conditional_fields_and_enums() {
+    let mut buf = [0u8; 8];
+    let value = Versioned { flags: 0x00, ext: None, len: 0x09 };
+    assert_eq!(
+        value
+            .encode::<LE>(&mut buf)
+            .unwrap(),
+        2
+    );
+    assert_eq!(buf[..2], [0x00, 0x09]);
+
+    let value = Versioned { flags: 0x01, ext: Some(0x1234), len: 0x09 };
+    assert_eq!(
+        value
+            .encode::<LE>(&mut buf)
+            .unwrap(),
+        4
+    );
+    assert_eq!(Versioned::decode::<LE>(&buf[..4]).unwrap(), (value, 4));
+
+    for message in [Message::Ping, Message::Data(1, 2), Message::Close { code: 3 }] {
+        let len = message
+            .encode::<LE>(&mut buf)
+            .unwrap();
+        assert_eq!(Message::decode::<LE>(&buf[..len]).unwrap(), (message, len));
+    }
+    assert_eq!(buf[..3], [7, 3, 0]);
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct Mixed {
+    len: u16,
+    #[abio(endian = "be")]
+    port: u16,
+    #[abio(endian = "le")]
+    addr: u32,
+}
+
+#[test]
+fn per_field_endianness_overrides_type_endianness() {
+    let value = Mixed { len: 0x0102, port: 0x0304, addr: 0x05060708 };
+    let mut buf = [0u8; 8];
+    assert_eq!(
+        value
+            .encode::<LE>(&mut buf)
+            .unwrap(),
+        8
+    );
+    assert_eq!(buf, [0x02, 0x01, 0x03, 0x04, 0x08, 0x07, 0x06, 0x05]);
+    assert_eq!(Mixed::decode::<LE>(&buf).unwrap(), (value, 8));
+
+    let (value, _) = Mixed::decode::<BE>(&buf).unwrap();
+    assert_eq!(value.len, 0x0201);
+    assert_eq!(value.port, 0x0304);
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct Reserved {
+    kind: u8,
+    #[abio(pad_before = 1, pad_after = 2)]
+    len: u16,
+    crc: u8,
+}
+
+#[test]
+fn pads_around_fields() {
+    let bytes = [0x01, 0xff, 0x34, 0x12, 0xee, 0xee, 0x07];
+    let (value, len) = Reserved::decode::<LE>(&bytes).unwrap();
+    assert_eq!(len, 7);
+    assert_eq!(value, Reserved { kind: 0x01, len: 0x1234, crc: 0x07 });
+    assert!(Reserved::decode::<LE>(&bytes[..5]).is_err());
+
+    let mut buf = [0xaau8; 7];
+    assert_eq!(
+        value
+            .encode::<LE>(&mut buf)
+            .unwrap(),
+        7
+    );
+    assert_eq!(buf, [0x01, 0x00, 0x34, 0x12, 0x00, 0x00, 0x07]);
+    assert!(value
+        .encode::<LE>(&mut buf[..5])
+        .is_err());
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[abio(align_to = 8)]
+pub struct Aligned {
+    kind: u8,
+    #[abio(align_to = 4)]
+    len: u32,
+    crc: u8,
+}
+
+#[test]
+fn aligns_fields_and_records() {
+    let value = Aligned { kind: 0x01, len: 0x0403_0201, crc: 0x07 };
+    let mut buf = [0xaau8; 16];
+    assert_eq!(
+        value
+            .encode::<LE>(&mut buf)
+            .unwrap(),
+        16
+    );
+    assert_eq!(buf, [0x01, 0, 0, 0, 0x01, 0x02, 0x03, 0x04, 0x07, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(Aligned::decode::<LE>(&buf).unwrap(), (value, 16));
+
+    // The trailing padding is part of the record.
+    assert!(Aligned::decode::<LE>(&buf[..9]).is_err());
+    assert!(Aligned { kind: 0, len: 0, crc: 0 }
+        .encode::<LE>(&mut buf[..9])
+        .is_err());
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[abio(endianness_param, tag = "u16")]
+pub enum Record {
+    Empty,
+    Sized { len: u32 },
+}
+
+#[test]
+fn endianness_param_follows_caller_byte_order() {
+    let value = Record::Sized { len: 0x0102_0304 };
+    let mut le = [0u8; 6];
+    let mut be = [0u8; 6];
+    value
+        .encode::<LE>(&mut le)
+        .unwrap();
+    value
+        .encode::<BE>(&mut be)
+        .unwrap();
+    assert_eq!(le, [0x01, 0x00, 0x04, 0x03, 0x02, 0x01]);
+    assert_eq!(be, [0x00, 0x01, 0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(Record::decode::<LE>(&le).unwrap(), (value, 6));
+    assert_eq!(
+        Record::decode::<BE>(&be)
+            .unwrap()
+            .0,
+        Record::Sized { len: 0x0102_0304 }
+    );
+    assert_eq!(Record::decode::<BE>(&[0x00, 0x00]).unwrap(), (Record::Empty, 2));
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[repr(u8)]
+pub enum Body {
+    Ping = 1,
+    Data(u32) = 2,
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct Packet {
+    kind: u8,
+    length: u16,
+    #[abio(tag_field = "kind")]
+    body: Body,
+}
+
+#[test]
+fn decodes_payloads_selected_by_earlier_fields() {
+    use abio::codec::EncodeTagged;
+
+    let body = Body::Data(0x0102_0304);
+    let packet = Packet { kind: body.tag(), length: 4, body };
+    let mut buf = [0u8; 7];
+    assert_eq!(
+        packet
+            .encode::<BE>(&mut buf)
+            .unwrap(),
+        7
+    );
+    assert_eq!(buf, [0x02, 0x00, 0x04, 0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(Packet::decode::<BE>(&buf).unwrap(), (packet, 7));
+
+    assert_eq!(
+        Packet::decode::<BE>(&[0x01, 0x00, 0x00]).unwrap(),
+        (Packet { kind: 1, length: 0, body: Body::Ping }, 3)
+    );
+    assert!(Packet::decode::<BE>(&[0x03, 0x00, 0x00]).is_err());
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[abio(display)]
+#[repr(u8)]
+pub enum Opcode {
+    Nop,
+    Jump {
+        target: u16,
+    } = 4,
+    #[abio(other)]
+    Other(u8),
+}
+
+#[test]
+fn keeps_the_raw_tag_of_unknown_variants() {
+    assert_eq!(Opcode::decode::<LE>(&[0x7f, 0xaa]).unwrap(), (Opcode::Other(0x7f), 1));
+    assert_eq!(
+        Opcode::decode::<LE>(&[0x04, 0x10, 0x00]).unwrap(),
+        (Opcode::Jump { target: 0x10 }, 3)
+    );
+
+    let mut buf = [0u8; 3];
+    assert_eq!(
+        Opcode::Other(0x7f)
+            .encode::<LE>(&mut buf)
+            .unwrap(),
+        1
+    );
+    assert_eq!(buf[0], 0x7f);
+
+    assert_eq!(Opcode::Nop.raw_value(), 0);
+    assert_eq!(Opcode::Jump { target: 0 }.raw_value(), 4);
+    assert_eq!(Opcode::Other(0x7f).raw_value(), 0x7f);
+    assert_eq!(Opcode::Jump { target: 0 }.to_string(), "Jump");
+    assert_eq!(Opcode::Other(0x7f).to_string(), "Unknown(0x7F)");
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct TcpFlags {
+    #[abio(bits = 4)]
+    data_offset: u8,
+    #[abio(bits = 3)]
+    reserved: u8,
+    #[abio(bits = 9)]
+    flags: u16,
+    window: u16,
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+#[abio(bit_order = "lsb")]
+pub struct Register {
+    #[abio(bits = 1)]
+    enabled: bool,
+    #[abio(bits = 7)]
+    mode: u8,
+    #[abio(bits = 12)]
+    divider: u16,
+    #[abio(bits = 4)]
+    channel: u8,
+}
+
+#[test]
+fn packs_bit_fields_in_the_declared_bit_order() {
+    let header = TcpFlags { data_offset: 5, reserved: 0, flags: 0x012, window: 0xffff };
+    let mut buf = [0u8; 4];
+    assert_eq!(
+        header
+            .encode::<LE>(&mut buf)
+            .unwrap(),
+        4
+    );
+    assert_eq!(buf, [0x50, 0x12, 0xff, 0xff]);
+    assert_eq!(TcpFlags::decode::<LE>(&buf).unwrap(), (header, 4));
+
+    let register = Register { enabled: true, mode: 0x05, divider: 0xabc, channel: 0x3 };
+    let mut buf = [0u8; 3];
+    register
+        .encode::<BE>(&mut buf)
+        .unwrap();
+    assert_eq!(buf, [0x0b, 0xbc, 0x3a]);
+    assert_eq!(Register::decode::<BE>(&buf).unwrap(), (register, 3));
+
+    assert!(TcpFlags { data_offset: 16, reserved: 0, flags: 0, window: 0 }
+        .encode::<LE>(&mut [0u8; 4])
+        .is_err());
+    assert!(TcpFlags::decode::<LE>(&[0x50]).is_err());
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct Note<'data> {
+    len: u8,
+    #[abio(len = "len")]
+    text: Cow<'data, [u8]>,
+}
+
+#[test]
+fn borrows_byte_strings_until_they_are_modified() {
+    let bytes = [3, b'a', b'b', b'c'];
+    let (mut note, len) = Note::decode::<LE>(&bytes).unwrap();
+    assert_eq!(len, 4);
+    assert!(matches!(note.text, Cow::Borrowed(b"abc")));
+
+    note.text.to_mut().push(b'!');
+    note.len += 1;
+    let mut buf = [0u8; 5];
+    assert_eq!(
+        note.encode::<LE>(&mut buf)
+            .unwrap(),
+        5
+    );
+    assert_eq!(buf, *b"\x04abc!");
+}
+
+#[derive(Debug, Decode, Encode, PartialEq)]
+pub struct Block {
+    length: u16,
+    #[abio(bytes = 4)]
+    digest: Chunk<4>,
+    #[abio(bytes = 2, pad_after = 1)]
+    reserved: [u8; 2],
+    tail: u8,
+}
+
+#[test]
+fn copies_raw_byte_fields_without_interpreting_them() {
+    let bytes = [0x00, 0x08, 0x01, 0x02, 0x03, 0x04, 0xaa, 0xbb, 0x00, 0x7f];
+    let (block, len) = Block::decode::<BE>(&bytes).unwrap();
+    assert_eq!(len, 10);
+    assert_eq!(block.length, 8);
+    assert_eq!(block.digest, Chunk::from_ne_bytes([0x01, 0x02, 0x03, 0x04]));
+    assert_eq!(block.reserved, [0xaa, 0xbb]);
+    assert_eq!(block.tail, 0x7f);
+
+    let (swapped, _) = Block::decode::<LE>(&bytes).unwrap();
+    assert_eq!(swapped.length, 0x0800);
+    assert_eq!(swapped.digest, block.digest);
+
+    let mut buf = [0xffu8; 10];
+    assert_eq!(
+        block
+            .encode::<BE>(&mut buf)
+            .unwrap(),
+        10
+    );
+    assert_eq!(buf, bytes);
+    assert!(block
+        .encode::<BE>(&mut [0u8; 5])
+        .is_err());
+    assert!(Block::decode::<BE>(&bytes[..5]).is_err());
+}
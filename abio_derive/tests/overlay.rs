@@ -0,0 +1,37 @@
+use abio::AbiUnion;
+
+#[derive(AbiUnion)]
+#[repr(C)]
+pub union Operand {
+    imm: u32,
+    bytes: [u8; 4],
+    halves: [u16; 2],
+}
+
+#[repr(C, align(4))]
+struct Aligned([u8; 9]);
+
+#[test]
+fn overlays_view_the_same_bytes_as_each_field() {
+    let buf = Aligned([1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    let operand = OperandOverlay::new(&buf.0).unwrap();
+    assert_eq!(OperandOverlay::SIZE, 4);
+    assert_eq!(operand.bytes(), [1, 2, 3, 4]);
+    assert_eq!(*operand.as_imm().unwrap(), u32::from_ne_bytes([1, 2, 3, 4]));
+    assert_eq!(*operand.as_bytes().unwrap(), [1, 2, 3, 4]);
+    assert_eq!(
+        *operand.as_halves().unwrap(),
+        [u16::from_ne_bytes([1, 2]), u16::from_ne_bytes([3, 4])]
+    );
+}
+
+#[test]
+fn overlays_check_size_and_alignment() {
+    let buf = Aligned([0; 9]);
+    let operand = OperandOverlay::new(&buf.0[1..]).unwrap();
+    assert!(operand.as_imm().is_err());
+    assert!(operand.as_halves().is_err());
+    assert_eq!(*operand.as_bytes().unwrap(), [0; 4]);
+
+    assert!(OperandOverlay::new(&buf.0[..3]).is_err());
+}
@@ -0,0 +1,6 @@
+#[test]
+#[cfg_attr(miri, ignore = "Miri cannot spawn the compiler processes used by trybuild")]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
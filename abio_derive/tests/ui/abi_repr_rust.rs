@@ -0,0 +1,9 @@
+use abio::Abi;
+
+#[derive(Abi)]
+struct Header {
+    magic: u32,
+    len: u16,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use abio::Abi;
+
+#[derive(Abi, Clone, Copy)]
+#[repr(C)]
+union Value {
+    byte: u8,
+    triple: [u8; 3],
+    half: u16,
+}
+
+#[derive(Abi, Clone, Copy)]
+union Untagged {
+    int: u32,
+    bytes: [u8; 4],
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use abio::Abi;
+
+#[derive(Abi)]
+#[abio(allow_rust_repr)]
+struct Pair {
+    lo: u32,
+    hi: u32,
+}
+
+fn main() {}
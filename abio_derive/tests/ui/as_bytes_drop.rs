@@ -0,0 +1,13 @@
+use abio::AsBytes;
+
+#[derive(AsBytes)]
+#[repr(transparent)]
+struct Handle<T> {
+    raw: T,
+}
+
+impl<T> Drop for Handle<T> {
+    fn drop(&mut self) {}
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use core::cell::Cell;
+
+use abio::AsBytes;
+
+#[derive(AsBytes)]
+#[repr(C)]
+struct Counter {
+    hits: [Cell<u32>; 2],
+}
+
+#[derive(AsBytes)]
+#[repr(C)]
+struct Slice<'data> {
+    data: &'data [u8],
+}
+
+fn main() {}
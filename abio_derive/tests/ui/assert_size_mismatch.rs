@@ -0,0 +1,11 @@
+use abio::Abi;
+
+#[derive(Abi)]
+#[repr(C)]
+#[abio(assert_size = 12)]
+struct TimeSpec {
+    seconds: i64,
+    nanoseconds: i64,
+}
+
+fn main() {}
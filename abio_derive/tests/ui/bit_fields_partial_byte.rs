@@ -0,0 +1,12 @@
+use abio::Decode;
+
+#[derive(Decode)]
+pub struct Flags {
+    #[abio(bits = 3)]
+    kind: u8,
+    #[abio(bits = 4)]
+    level: u8,
+    length: u16,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use abio::Decode;
+
+#[derive(Decode)]
+pub struct Header {
+    #[abio(skip, len = "4")]
+    names: Vec<u8>,
+    #[abio(pad_before = "2")]
+    flags: u8,
+    #[abio(if = "flags != 0")]
+    extra: u16,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use abio::Decode;
+
+#[derive(Decode)]
+union Value {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
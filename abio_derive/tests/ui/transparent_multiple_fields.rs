@@ -0,0 +1,10 @@
+use abio::Decode;
+
+#[derive(Decode)]
+#[abio(transparent)]
+pub struct Span {
+    start: u32,
+    end: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use abio::Decode;
+
+#[derive(Decode)]
+pub struct Record {
+    kind: u8,
+    #[abio(since = 2)]
+    checksum: Option<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use abio::Zeroable;
+
+#[derive(Zeroable)]
+#[repr(u8)]
+enum State {
+    Idle,
+    Busy,
+}
+
+fn main() {}
@@ -0,0 +1,60 @@
+use abio::{Decode, Endianness, Result, View, LE};
+
+#[derive(View)]
+#[repr(C)]
+pub struct DosHeader {
+    e_magic: u16,
+    e_cblp: u16,
+    e_lfanew: u32,
+}
+
+#[derive(View)]
+#[repr(C)]
+pub struct Record {
+    len: u16,
+    #[abio(endian = "be")]
+    checksum: u16,
+}
+
+#[test]
+fn views_decode_fields_at_their_offsets() {
+    let bytes = [0x4d, 0x5a, 0x90, 0x00, 0x80, 0x00, 0x00, 0x00, 0xff];
+    let header = DosHeaderView::<LE>::new(&bytes).unwrap();
+    assert_eq!(DosHeaderView::<LE>::SIZE, 8);
+    assert_eq!(header.as_bytes().len(), 8);
+    assert_eq!(header.e_magic().unwrap(), 0x5a4d);
+    assert_eq!(header.e_cblp().unwrap(), 0x90);
+    assert_eq!(header.e_lfanew().unwrap(), 0x80);
+
+    assert!(DosHeaderView::<LE>::new(&bytes[..7]).is_err());
+}
+
+#[test]
+fn views_honor_field_byte_order() {
+    let record = RecordView::<LE>::new(&[0x02, 0x00, 0x12, 0x34]).unwrap();
+    assert_eq!(record.len().unwrap(), 2);
+    assert_eq!(record.checksum().unwrap(), 0x1234);
+}
+
+/// Zero-sized field, so that views of [`Marker`] span no bytes.
+pub struct Nothing;
+
+impl<'data> Decode<'data> for Nothing {
+    fn decode<E: Endianness>(_: &'data [u8]) -> Result<(Nothing, usize)> {
+        Ok((Nothing, 0))
+    }
+}
+
+#[derive(View)]
+#[repr(C)]
+pub struct Marker {
+    tag: Nothing,
+}
+
+#[test]
+fn views_of_types_without_fields_accept_empty_input() {
+    assert_eq!(MarkerView::<LE>::SIZE, 0);
+    let marker = MarkerView::<LE>::new(&[]).unwrap();
+    assert!(marker.as_bytes().is_empty());
+    assert!(marker.tag().is_ok());
+}
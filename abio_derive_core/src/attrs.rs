@@ -0,0 +1,657 @@
+//! Parsing for the `#[abio(...)]` helper attribute.
+
+use syn::{parse_quote, Attribute, Expr, Ident, LitByteStr, LitInt, LitStr, Path, Result, Type};
+
+use crate::errors::Errors;
+
+/// Name of the helper attribute shared by the derive macros of `abio_derive`.
+const ATTR_NAME: &str = "abio";
+
+/// Integer primitives that may be used as the tag of an enum.
+const TAG_TYPES: &[&str] = &["u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"];
+
+/// Byte order declared with `tag_endian` on an enum, or with `endian` on a field.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EndianAttr {
+    Little,
+    Big,
+}
+
+/// Order in which bit fields are packed into bytes, declared with `bit_order` on
+/// the type.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BitOrderAttr {
+    /// The first bit field occupies the most significant bits of the first byte.
+    #[default]
+    MsbFirst,
+    /// The first bit field occupies the least significant bits of the first byte.
+    LsbFirst,
+}
+
+/// Options declared with `#[abio(...)]` on the type itself.
+#[derive(Clone, Debug, Default)]
+pub struct ContainerAttrs {
+    /// Record the decoded value of each field into the tracer passed to
+    /// `decode_traced`, which requires the type of every field to implement `Debug`.
+    pub trace: bool,
+    /// Integer type of the discriminant preceding the fields of an enum variant.
+    pub tag: Option<Ident>,
+    /// Byte order of the tag, overriding the byte order used for the fields.
+    pub tag_endian: Option<EndianAttr>,
+    /// Require the whole type to follow the byte order chosen by the caller,
+    /// rejecting fixed `endian` and `tag_endian` overrides.
+    pub endianness_param: bool,
+    /// Accept a single-field struct without a `#[repr(...)]` attribute, relying on
+    /// the generated size assertion instead.
+    pub allow_rust_repr: bool,
+    /// Function called with the decoded value, rejecting it if it returns an error.
+    pub validate: Option<Path>,
+    /// Signature preceding the fields of the type.
+    pub magic: Option<LitByteStr>,
+    /// Size of the type, in bytes, asserted at compile time.
+    pub assert_size: Option<usize>,
+    /// Alignment of the type, in bytes, asserted at compile time.
+    pub assert_align: Option<usize>,
+    /// Also generate a `const fn decode_const`, for types composed solely of
+    /// fields implementing `ConstDecode`.
+    pub const_decode: bool,
+    /// Path of the `abio` crate, for resolving generated code through a re-export.
+    pub crate_path: Option<Path>,
+    /// Expression over previously decoded fields giving the version of the format,
+    /// against which the `since` and `until` bounds of fields are checked.
+    pub version: Option<Expr>,
+    /// Order in which consecutive bit fields are packed into bytes.
+    pub bit_order: Option<BitOrderAttr>,
+    /// Encode the type exactly like its only non-skipped field, forwarding the
+    /// traits for decoding fields with a known count or tag to it.
+    pub transparent: bool,
+    /// Alignment, in bytes, that the length of the encoded type is padded to, so
+    /// that a record following it in a stream starts aligned too.
+    pub align_to: Option<usize>,
+    /// Implement `Display` for an enum, writing the name of its variant, or the raw
+    /// value of its tag for the `other` variant.
+    pub display: bool,
+    /// Type of the context decoding depends on, implementing `DecodeWith` for it
+    /// instead of `Decode`.
+    pub ctx: Option<Type>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut errors = Errors::new();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident(ATTR_NAME))
+        {
+            errors.check(attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("trace") {
+                    parsed.trace = true;
+                } else if meta.path.is_ident("tag") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.tag = Some(parse_tag_type(&lit)?);
+                } else if meta.path.is_ident("tag_endian") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.tag_endian = Some(parse_endian(&lit)?);
+                } else if meta
+                    .path
+                    .is_ident("endianness_param")
+                {
+                    parsed.endianness_param = true;
+                } else if meta
+                    .path
+                    .is_ident("allow_rust_repr")
+                {
+                    parsed.allow_rust_repr = true;
+                } else if meta.path.is_ident("validate") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.validate = Some(lit.parse::<Path>()?);
+                } else if meta.path.is_ident("magic") {
+                    parsed.magic = Some(parse_magic(meta.value()?.parse()?)?);
+                } else if meta
+                    .path
+                    .is_ident("assert_size")
+                {
+                    parsed.assert_size = Some(
+                        meta.value()?
+                            .parse::<LitInt>()?
+                            .base10_parse()?,
+                    );
+                } else if meta
+                    .path
+                    .is_ident("assert_align")
+                {
+                    parsed.assert_align = Some(
+                        meta.value()?
+                            .parse::<LitInt>()?
+                            .base10_parse()?,
+                    );
+                } else if meta
+                    .path
+                    .is_ident("const_decode")
+                {
+                    parsed.const_decode = true;
+                } else if meta.path.is_ident("crate") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.crate_path = Some(lit.parse::<Path>()?);
+                } else if meta.path.is_ident("version") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.version = Some(lit.parse::<Expr>()?);
+                } else if meta
+                    .path
+                    .is_ident("transparent")
+                {
+                    parsed.transparent = true;
+                } else if meta.path.is_ident("bit_order") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.bit_order = Some(parse_bit_order(&lit)?);
+                } else if meta.path.is_ident("align_to") {
+                    parsed.align_to = Some(parse_align_to(meta.value()?.parse()?)?);
+                } else if meta.path.is_ident("display") {
+                    parsed.display = true;
+                } else if meta.path.is_ident("ctx") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.ctx = Some(lit.parse::<Type>()?);
+                } else {
+                    return Err(meta.error("unsupported `abio` container attribute"));
+                }
+                Ok(())
+            }));
+        }
+        if let Some(attr) = attrs
+            .iter()
+            .rev()
+            .find(|attr| attr.path().is_ident(ATTR_NAME))
+        {
+            parsed.check_combinations(attr, &mut errors);
+        }
+        errors.finish_with(parsed)
+    }
+
+    /// Returns the path of the `abio` crate that generated code refers to, which is
+    /// `::abio` unless the type declares another one with `crate`.
+    pub fn abio_path(&self) -> Path {
+        self.crate_path
+            .clone()
+            .unwrap_or_else(|| parse_quote!(::abio))
+    }
+
+    /// Reports every combination of options that cannot be declared together on
+    /// a type.
+    fn check_combinations(&self, attr: &Attribute, errors: &mut Errors) {
+        if self.endianness_param && self.tag_endian.is_some() {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "types declaring `endianness_param` follow the byte order chosen by the caller and cannot declare `tag_endian`",
+            ));
+        }
+        if self.transparent
+            && (self.magic.is_some()
+                || self.version.is_some()
+                || self.tag.is_some()
+                || self.align_to.is_some())
+        {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "`transparent` types are encoded exactly like their field and cannot declare a `magic`, `version`, `tag` or `align_to`",
+            ));
+        }
+        if self.ctx.is_some() && (self.trace || self.const_decode || self.transparent) {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "types declaring a `ctx` can only be decoded with a context and cannot declare `trace`, `const_decode` or `transparent`",
+            ));
+        }
+    }
+}
+
+/// Options declared with `#[abio(...)]` on an enum variant.
+#[derive(Clone, Debug, Default)]
+pub struct VariantAttrs {
+    /// Decode tags that do not match any other variant into this variant, which
+    /// holds the raw tag in its only field.
+    pub other: bool,
+}
+
+impl VariantAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut errors = Errors::new();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident(ATTR_NAME))
+        {
+            errors.check(attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("other") {
+                    parsed.other = true;
+                } else {
+                    return Err(meta.error("unsupported `abio` variant attribute"));
+                }
+                Ok(())
+            }));
+        }
+        errors.finish_with(parsed)
+    }
+}
+
+/// Options declared with `#[abio(...)]` on a field.
+#[derive(Clone, Debug, Default)]
+pub struct FieldAttrs {
+    /// Predicate over previously decoded fields that decides whether an optional
+    /// field is present in the input.
+    pub cond: Option<Expr>,
+    /// Byte order of the field, overriding the byte order used for the type.
+    pub endian: Option<EndianAttr>,
+    /// Leave the field out of the wire layout, initializing it when decoding.
+    pub skip: bool,
+    /// Expression initializing a skipped field, instead of `Default::default()`.
+    pub default: Option<Expr>,
+    /// Number of reserved bytes preceding the field.
+    pub pad_before: usize,
+    /// Number of reserved bytes following the field.
+    pub pad_after: usize,
+    /// Alignment, in bytes, of the offset of the field from the start of the
+    /// encoded type, reached by inserting reserved bytes before the field.
+    pub align_to: Option<usize>,
+    /// Function called with the decoded field, rejecting it if it returns an error.
+    pub validate: Option<Path>,
+    /// Signature the raw bytes of the field must match.
+    pub magic: Option<LitByteStr>,
+    /// Expression over previously decoded fields giving the number of elements of
+    /// the field.
+    pub len: Option<Expr>,
+    /// Expression over previously decoded fields giving the tag that selects the
+    /// variant of the field, which is stored apart from its payload.
+    pub tag_field: Option<Expr>,
+    /// Describe the layout of the field with its own descriptor, rather than as an
+    /// opaque run of bytes.
+    pub nested: bool,
+    /// First version of the format in which the field is present.
+    pub since: Option<u64>,
+    /// Last version of the format in which the field is present.
+    pub until: Option<u64>,
+    /// Width of the field in bits, packing it together with the bit fields next to
+    /// it instead of giving it whole bytes.
+    pub bits: Option<u32>,
+    /// Number of raw bytes held by the field, which are copied without being
+    /// interpreted.
+    pub bytes: Option<usize>,
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut errors = Errors::new();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident(ATTR_NAME))
+        {
+            errors.check(attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    parsed.skip = true;
+                } else if meta.path.is_ident("default") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.skip = true;
+                    parsed.default = Some(lit.parse::<Expr>()?);
+                } else if meta.path.is_ident("if") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.cond = Some(lit.parse::<Expr>()?);
+                } else if meta.path.is_ident("pad_before") {
+                    parsed.pad_before = meta
+                        .value()?
+                        .parse::<LitInt>()?
+                        .base10_parse()?;
+                } else if meta.path.is_ident("pad_after") {
+                    parsed.pad_after = meta
+                        .value()?
+                        .parse::<LitInt>()?
+                        .base10_parse()?;
+                } else if meta.path.is_ident("align_to") {
+                    parsed.align_to = Some(parse_align_to(meta.value()?.parse()?)?);
+                } else if meta.path.is_ident("endian") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.endian = Some(parse_endian(&lit)?);
+                } else if meta.path.is_ident("validate") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.validate = Some(lit.parse::<Path>()?);
+                } else if meta.path.is_ident("magic") {
+                    parsed.magic = Some(parse_magic(meta.value()?.parse()?)?);
+                } else if meta.path.is_ident("len") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.len = Some(lit.parse::<Expr>()?);
+                } else if meta.path.is_ident("tag_field") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitStr>()?;
+                    parsed.tag_field = Some(lit.parse::<Expr>()?);
+                } else if meta.path.is_ident("nested") {
+                    parsed.nested = true;
+                } else if meta.path.is_ident("since") {
+                    parsed.since = Some(
+                        meta.value()?
+                            .parse::<LitInt>()?
+                            .base10_parse()?,
+                    );
+                } else if meta.path.is_ident("bits") {
+                    let lit = meta
+                        .value()?
+                        .parse::<LitInt>()?;
+                    let bits = lit.base10_parse()?;
+                    if !(1..=64).contains(&bits) {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "bit fields must be between 1 and 64 bits wide",
+                        ));
+                    }
+                    parsed.bits = Some(bits);
+                } else if meta.path.is_ident("bytes") {
+                    parsed.bytes = Some(
+                        meta.value()?
+                            .parse::<LitInt>()?
+                            .base10_parse()?,
+                    );
+                } else if meta.path.is_ident("until") {
+                    parsed.until = Some(
+                        meta.value()?
+                            .parse::<LitInt>()?
+                            .base10_parse()?,
+                    );
+                } else {
+                    return Err(meta.error("unsupported `abio` field attribute"));
+                }
+                Ok(())
+            }));
+        }
+        if let Some(attr) = attrs
+            .iter()
+            .rev()
+            .find(|attr| attr.path().is_ident(ATTR_NAME))
+        {
+            parsed.check_combinations(attr, &mut errors);
+        }
+        errors.finish_with(parsed)
+    }
+
+    /// Reports every combination of options that cannot be declared together on
+    /// a field.
+    fn check_combinations(&self, attr: &Attribute, errors: &mut Errors) {
+        if self.skip && self.cond.is_some() {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "skipped fields are not part of the wire layout and cannot be conditional",
+            ));
+        }
+        if self.is_versioned() && (self.skip || self.cond.is_some()) {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "versioned fields are already conditional and cannot be skipped or declare `if`",
+            ));
+        }
+        if let (Some(since), Some(until)) = (self.since, self.until) {
+            if since > until {
+                errors.push(syn::Error::new_spanned(
+                    attr,
+                    "`since` must not be greater than `until`",
+                ));
+            }
+        }
+        if self.magic.is_some() && (self.skip || self.cond.is_some() || self.is_versioned()) {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "fields matching a `magic` signature must always be present in the input",
+            ));
+        }
+        if self.skip && self.len.is_some() {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "skipped fields are not part of the wire layout and cannot declare a `len`",
+            ));
+        }
+        if self.tag_field.is_some() && (self.skip || self.len.is_some()) {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "fields declaring a `tag_field` cannot be skipped or declare a `len`",
+            ));
+        }
+        if self.bits.is_some()
+            && (self.skip
+                || self.cond.is_some()
+                || self.is_versioned()
+                || self.endian.is_some()
+                || self.magic.is_some()
+                || self.len.is_some()
+                || self.tag_field.is_some()
+                || self.pad_before > 0
+                || self.pad_after > 0
+                || self.align_to.is_some())
+        {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "bit fields are packed with their neighbours and only support `validate`",
+            ));
+        }
+        if self.bytes.is_some()
+            && (self.skip
+                || self.endian.is_some()
+                || self.magic.is_some()
+                || self.len.is_some()
+                || self.tag_field.is_some()
+                || self.bits.is_some())
+        {
+            errors.push(syn::Error::new_spanned(
+                attr,
+                "raw byte fields are copied as-is and cannot be skipped or declare `endian`, \
+                 `magic`, `len`, `tag_field` or `bits`",
+            ));
+        }
+    }
+
+    /// Returns `true` if the field declares the versions of the format it is
+    /// present in.
+    pub fn is_versioned(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+}
+
+/// Returns `true` if `ident` names an integer primitive that is supported as a tag.
+pub fn is_tag_type(ident: &Ident) -> bool {
+    TAG_TYPES.contains(&ident.to_string().as_str())
+}
+
+fn parse_tag_type(lit: &LitStr) -> Result<Ident> {
+    let ident = lit.parse::<Ident>()?;
+    if is_tag_type(&ident) {
+        Ok(ident)
+    } else {
+        Err(syn::Error::new_spanned(lit, "tag must be an integer primitive no wider than 64 bits"))
+    }
+}
+
+fn parse_endian(lit: &LitStr) -> Result<EndianAttr> {
+    match lit.value().as_str() {
+        "le" | "little" => Ok(EndianAttr::Little),
+        "be" | "big" => Ok(EndianAttr::Big),
+        _ => Err(syn::Error::new_spanned(lit, "expected \"le\" or \"be\"")),
+    }
+}
+
+fn parse_bit_order(lit: &LitStr) -> Result<BitOrderAttr> {
+    match lit.value().as_str() {
+        "msb" | "msb_first" => Ok(BitOrderAttr::MsbFirst),
+        "lsb" | "lsb_first" => Ok(BitOrderAttr::LsbFirst),
+        _ => Err(syn::Error::new_spanned(lit, "expected \"msb\" or \"lsb\"")),
+    }
+}
+
+fn parse_align_to(lit: LitInt) -> Result<usize> {
+    let align = lit.base10_parse::<usize>()?;
+    if align.is_power_of_two() {
+        Ok(align)
+    } else {
+        Err(syn::Error::new_spanned(lit, "`align_to` must be a power of two"))
+    }
+}
+
+fn parse_magic(lit: LitByteStr) -> Result<LitByteStr> {
+    if lit.value().is_empty() {
+        Err(syn::Error::new_spanned(lit, "magic must contain at least one byte"))
+    } else {
+        Ok(lit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn parses_container_attributes() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[repr(C)]),
+            parse_quote!(#[abio(tag = "u16", tag_endian = "be", magic = b"MZ")]),
+            parse_quote!(#[abio(trace, assert_size = 8)]),
+            parse_quote!(#[abio(crate = "facade::abio")]),
+        ];
+        let parsed = ContainerAttrs::parse(&attrs).unwrap();
+        let crate_path = parsed.abio_path();
+        assert_eq!(quote::quote!(#crate_path).to_string(), "facade :: abio");
+        assert!(parsed.trace);
+        assert_eq!(parsed.tag.unwrap(), "u16");
+        assert_eq!(parsed.tag_endian, Some(EndianAttr::Big));
+        assert_eq!(parsed.magic.unwrap().value(), b"MZ");
+        assert_eq!(parsed.assert_size, Some(8));
+        assert!(!parsed.const_decode);
+        let crate_path = ContainerAttrs::default().abio_path();
+        assert_eq!(quote::quote!(#crate_path).to_string(), ":: abio");
+
+        let parsed = ContainerAttrs::parse(&[parse_quote!(#[abio(ctx = "Options<'a>")])]).unwrap();
+        let ctx = parsed.ctx.unwrap();
+        assert_eq!(quote::quote!(#ctx).to_string(), "Options < 'a >");
+    }
+
+    #[test]
+    fn rejects_invalid_container_attributes() {
+        let reject = |attr: Attribute| ContainerAttrs::parse(&[attr]).is_err();
+        assert!(reject(parse_quote!(#[abio(tag = "u128")])));
+        assert!(reject(parse_quote!(#[abio(tag_endian = "middle")])));
+        assert!(reject(parse_quote!(#[abio(magic = b"")])));
+        assert!(reject(parse_quote!(#[abio(endianness_param, tag_endian = "le")])));
+        assert!(reject(parse_quote!(#[abio(unknown)])));
+        assert!(reject(parse_quote!(#[abio(crate = "not a path")])));
+        assert!(reject(parse_quote!(#[abio(bit_order = "middle")])));
+        assert!(reject(parse_quote!(#[abio(transparent, magic = b"MZ")])));
+        assert!(reject(parse_quote!(#[abio(align_to = 6)])));
+        assert!(reject(parse_quote!(#[abio(transparent, align_to = 4)])));
+        assert!(reject(parse_quote!(#[abio(ctx = "u8", trace)])));
+        assert!(reject(parse_quote!(#[abio(ctx = "not a type")])));
+    }
+
+    #[test]
+    fn parses_variant_attributes() {
+        assert!(
+            VariantAttrs::parse(&[parse_quote!(#[abio(other)])])
+                .unwrap()
+                .other
+        );
+        assert!(
+            !VariantAttrs::parse(&[parse_quote!(#[repr(C)])])
+                .unwrap()
+                .other
+        );
+        assert!(VariantAttrs::parse(&[parse_quote!(#[abio(skip)])]).is_err());
+    }
+
+    #[test]
+    fn parses_field_attributes() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[abio(pad_before = 2, endian = "le")]),
+            parse_quote!(#[abio(len = "self.count", nested)]),
+        ];
+        let parsed = FieldAttrs::parse(&attrs).unwrap();
+        assert_eq!(parsed.pad_before, 2);
+        assert_eq!(parsed.endian, Some(EndianAttr::Little));
+        assert!(parsed.len.is_some() && parsed.nested);
+
+        let parsed = FieldAttrs::parse(&[parse_quote!(#[abio(default = "7")])]).unwrap();
+        assert!(parsed.skip && parsed.default.is_some());
+
+        let parsed = FieldAttrs::parse(&[parse_quote!(#[abio(since = 2, until = 3)])]).unwrap();
+        assert_eq!((parsed.since, parsed.until), (Some(2), Some(3)));
+        assert!(parsed.is_versioned());
+
+        let parsed = FieldAttrs::parse(&[parse_quote!(#[abio(bits = 3)])]).unwrap();
+        assert_eq!(parsed.bits, Some(3));
+
+        let parsed =
+            FieldAttrs::parse(&[parse_quote!(#[abio(bytes = 16, pad_after = 4)])]).unwrap();
+        assert_eq!(parsed.bytes, Some(16));
+
+        let parsed = FieldAttrs::parse(&[parse_quote!(#[abio(align_to = 8)])]).unwrap();
+        assert_eq!(parsed.align_to, Some(8));
+    }
+
+    #[test]
+    fn rejects_conflicting_field_attributes() {
+        let reject = |attr: Attribute| FieldAttrs::parse(&[attr]).is_err();
+        assert!(reject(parse_quote!(#[abio(skip, if = "true")])));
+        assert!(reject(parse_quote!(#[abio(skip, magic = b"A")])));
+        assert!(reject(parse_quote!(#[abio(skip, len = "4")])));
+        assert!(reject(parse_quote!(#[abio(pad_before = "2")])));
+        assert!(reject(parse_quote!(#[abio(since = 3, until = 2)])));
+        assert!(reject(parse_quote!(#[abio(since = 2, if = "true")])));
+        assert!(reject(parse_quote!(#[abio(until = 2, magic = b"A")])));
+        assert!(reject(parse_quote!(#[abio(tag_field = "kind", len = "4")])));
+        assert!(reject(parse_quote!(#[abio(bits = 0)])));
+        assert!(reject(parse_quote!(#[abio(bits = 65)])));
+        assert!(reject(parse_quote!(#[abio(bits = 3, endian = "be")])));
+        assert!(reject(parse_quote!(#[abio(bytes = 4, endian = "be")])));
+        assert!(reject(parse_quote!(#[abio(bytes = "4")])));
+        assert!(reject(parse_quote!(#[abio(align_to = 0)])));
+        assert!(reject(parse_quote!(#[abio(bits = 3, align_to = 2)])));
+    }
+
+    #[test]
+    fn reports_every_invalid_attribute() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[abio(tag = "u128")]),
+            parse_quote!(#[abio(unknown)]),
+            parse_quote!(#[abio(transparent, magic = b"MZ", ctx = "u8")]),
+        ];
+        let errors = ContainerAttrs::parse(&attrs).unwrap_err();
+        assert_eq!(errors.into_iter().count(), 4);
+
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[abio(skip, len = "4")]),
+            parse_quote!(#[abio(pad_before = "2")]),
+            parse_quote!(#[abio(since = 3, until = 2)]),
+        ];
+        let errors = FieldAttrs::parse(&attrs).unwrap_err();
+        assert_eq!(errors.into_iter().count(), 4);
+    }
+}
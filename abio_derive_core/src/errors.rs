@@ -0,0 +1,83 @@
+//! Accumulation of the errors found while checking the input of a derive macro.
+
+use syn::{Error, Result};
+
+/// Collects the errors found while checking the input of a derive macro, so that
+/// every violation is reported at once instead of only the first one.
+///
+/// Errors are combined with [`Error::combine`], so each of them is still reported
+/// at its own span.
+#[derive(Debug, Default)]
+#[must_use = "errors are only reported once the accumulator is finished"]
+pub struct Errors(Option<Error>);
+
+impl Errors {
+    /// Returns an accumulator holding no errors.
+    pub const fn new() -> Self {
+        Self(None)
+    }
+
+    /// Adds `error` to the errors reported.
+    pub fn push(&mut self, error: Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+
+    /// Returns the value held by `result`, or adds its error to the errors reported
+    /// and returns `None`.
+    pub fn check<T>(&mut self, result: Result<T>) -> Option<T> {
+        result
+            .map_err(|error| self.push(error))
+            .ok()
+    }
+
+    /// Returns `true` if no error was added.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Returns an error combining all errors added, if any.
+    pub fn finish(self) -> Result<()> {
+        self.finish_with(())
+    }
+
+    /// Returns `value` if no error was added, or an error combining all of them.
+    pub fn finish_with<T>(self, value: T) -> Result<T> {
+        match self.0 {
+            Some(errors) => Err(errors),
+            None => Ok(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro2::Span;
+
+    use super::*;
+
+    #[test]
+    fn combines_every_error_added() {
+        let mut errors = Errors::new();
+        assert_eq!(errors.check(Ok::<_, Error>(1)), Some(1));
+        assert!(errors.is_empty());
+
+        errors.push(Error::new(Span::call_site(), "first"));
+        assert_eq!(errors.check(Err::<u8, _>(Error::new(Span::call_site(), "second"))), None);
+        let messages = errors
+            .finish()
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["first", "second"]);
+        assert_eq!(
+            Errors::new()
+                .finish_with(2)
+                .unwrap(),
+            2
+        );
+    }
+}
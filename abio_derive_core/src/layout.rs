@@ -0,0 +1,353 @@
+//! Parsing and analysis of the `#[repr(...)]` attribute.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{parenthesized, Error, Result, Token};
+
+macro_rules! impl_repr_type {
+  (
+    $(#[$attr:meta])*
+    @$enum_ty:ident: $($name:ident => $int:ident),* $(,)?
+  ) => {
+
+        /// Type to represent the possible layout hints, indicated by the `#[repr(...)]`
+        /// attribute.
+        $(#[$attr])*
+        #[derive(Default)]
+        pub enum $enum_ty {
+            #[doc = "Default representation used by the compiler when there is no `repr` attribute."]
+            #[doc = ""]
+            #[doc = "# Warning"]
+            #[doc = ""]
+            #[doc = "Using this layout representation allows the compiler to freely rearrange"]
+            #[doc = "the layout of your type, which may result in the addition of padding bytes"]
+            #[doc = "or other incompatible layouts with the ABI defined by the [`abio`][crate] crate."]
+            #[default]
+            Rust,
+
+            #[doc = "Representation hint that asks compiler to treat the data as if it were structured"]
+            #[doc = "like C. Types that are `#[repr(C)]` should be fully C-compatible."]
+            C,
+
+            /// Layout hint to indicate to the compiler that the type shares an
+            /// identical layout.
+            ///
+            /// This representation is useful for FFI types since it encourages
+            /// creating safer abstractions through newtypes with added or extended
+            /// functionality.
+            Transparent,
+
+            $(
+
+                $name,
+            )*
+        }
+
+        impl Clone for $enum_ty {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+        impl Copy for $enum_ty {}
+        impl ::core::fmt::Debug for $enum_ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Debug::fmt(&self, f)
+            }
+        }
+    };
+    (@$enum_ty:ident: $($outer:ident => $inner:ident),* $(,)?) => {
+        $(
+            stringify!($inner) => $enum_ty::$outer,
+        )*
+    };
+    () => {};
+}
+
+impl_repr_type!(
+    #[derive(Eq, Ord, PartialEq, PartialOrd)]
+    @Repr:
+    U8 => u8,
+    U16 => u16,
+    U32 => u32,
+    U64 => u64,
+    U128 => u128,
+    Usize => usize,
+    I8 => i8,
+    I16 => i16,
+    I32 => i32,
+    I64 => i64,
+    I128 => i128,
+    Isize => isize,
+
+);
+
+#[derive(Debug, PartialEq)]
+pub struct ComptimeLayout {
+    /// The type is decorated with `align`, increasing its alignment requirements.
+    align: Option<u32>,
+    /// The type uses a packed layout, decreasing alignment requirements for the
+    /// type.
+    packed: Option<u32>,
+    /// [`C`][c-layout]-ABI compiler hint regarding type layout.
+    c_layout: bool,
+    /// Whether the `transparent` hint exists on the type.
+    transparent: bool,
+    repr: Repr,
+}
+
+impl Default for ComptimeLayout {
+    fn default() -> Self {
+        Self {
+            packed: None,
+            align: None,
+            repr: Repr::Rust,
+            c_layout: false,
+            transparent: false,
+        }
+    }
+}
+
+impl ComptimeLayout {
+    /// Returns the representation hint of the type, or [`Repr::Rust`] if it has
+    /// none.
+    pub fn repr(&self) -> Repr {
+        self.repr
+    }
+
+    /// Returns `true` if the type is decorated with `packed`, so that its fields
+    /// may not be aligned.
+    pub fn is_packed(&self) -> bool {
+        self.packed.is_some()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.packed == Some(1) || self.repr == Repr::Transparent
+    }
+
+    pub fn peel_packed(self) -> u32 {
+        self.packed.unwrap_or(0)
+    }
+
+    pub fn peel_align(self) -> u32 {
+        self.align.unwrap_or(0)
+    }
+
+    pub fn is_c_layout(&self) -> bool {
+        self.c_layout
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    /// Parses the `repr` attribute from the list of attributes in the source code
+    /// for the target type.
+    ///
+    /// # Accumulator / Fold Method
+    ///    
+    /// By choosing to initialize a default, legal representation, we can ensure we
+    /// start with a valid layout. Using this instance, we can declartively parse
+    /// related attributes and their associated values and obtain all relevant
+    /// compiler hints.
+    ///
+    /// The final instance includes a `ComptimeLayout` instance with its `repr`
+    /// attribute parsed with graceful failures for any unsupported layout type..
+    pub fn parse_repr_attr(attributes: &[syn::Attribute]) -> Result<Self> {
+        attributes
+            .iter()
+            .filter(|attr| attr.path().is_ident("repr"))
+            .try_fold(Self::default(), |layout, attr| {
+                let input = parse_attr_repr(attr, "repr")?;
+                let repr = layout.parse_repr(&input, attr)?;
+                let packed = layout.parse_packed(&input, attr)?;
+                let align = layout.parse_align(&input, attr)?;
+
+                Ok(Self { align, packed, repr, ..Self::default() })
+            })
+    }
+
+    fn parse_repr(&self, input: &Self, attr: &syn::Attribute) -> Result<Repr> {
+        Ok(match (self.repr, input.repr) {
+            (lhs, Repr::Rust) => lhs,
+            (Repr::Rust, rhs) => rhs,
+            _ => {
+                return Err(Error::new_spanned(
+                    attr,
+                    "Compiler hints contain conflicting layout representations.",
+                ))
+            }
+        })
+    }
+
+    fn parse_align(&self, input: &Self, attr: &syn::Attribute) -> Result<Option<u32>> {
+        match (self.align, input.align) {
+            (Some(_), Some(_)) => Err(Error::new_spanned(
+                attr,
+                "Compiler hints contain conflicting layout representations.",
+            )),
+            (curr, input) => Ok(curr.or(input)),
+        }
+    }
+
+    fn parse_packed(&self, input: &Self, attr: &syn::Attribute) -> Result<Option<u32>> {
+        match (self.packed, input.packed) {
+            (Some(_), Some(_)) => Err(Error::new_spanned(
+                attr,
+                "Compiler hints contain conflicting layout representations.",
+            )),
+            (curr, input) => Ok(curr.or(input)),
+        }
+    }
+}
+
+macro_rules! impl_comptime_layout {
+    (@$parent:ident with $enum_ty:ident: $($outer:ident => $inner:ident),* $(,)?) => {
+        impl ::syn::parse::Parse for $parent {
+        fn parse(input: ::syn::parse::ParseStream<'_>) -> Result<$parent> {
+            let mut ret = $parent::default();
+            while !input.is_empty() {
+                let keyword = input.parse::<::syn::Ident>()?;
+                let keyword_str = keyword.to_string();
+                let new_repr = match keyword_str.as_str() {
+                    "Rust" => $enum_ty::Rust,
+                    "C" => $enum_ty::C,
+                    "transparent" => $enum_ty::Transparent,
+                    "packed" => {
+                        ret.packed = Some(if input.peek(syn::token::Paren) {
+                        let contents; parenthesized!(contents in input);
+                        ::syn::LitInt::base10_parse::<u32>(&contents.parse()?)?
+                        } else {
+                            1
+                        });
+                        let _: ::core::option::Option<Token![,]> = input.parse()?;
+                        continue;
+                    },
+                    "align" => {
+                        let contents; parenthesized!(contents in input);
+                        ret.align = Some(syn::LitInt::base10_parse::<u32>(&contents.parse()?)?);
+                        let _: ::core::option::Option<Token![,]> = input.parse()?;
+                        continue;
+                    },
+                    $(
+                        stringify!($inner) => $enum_ty::$outer,
+                    )*
+                    _ => return Err(input.error("unrecognized representation hint"))
+                };
+                ret.repr = match (ret.repr, new_repr) {
+                    ($enum_ty::Rust, repr) | (repr, $enum_ty::Rust) => repr,
+                    // `repr(C, u8)` lays out an enum like a C union tagged by a `u8`,
+                    // whose tag is what the derive macros need to know.
+                    ($enum_ty::C, repr) | (repr, $enum_ty::C)
+                        if !matches!(repr, $enum_ty::C | $enum_ty::Transparent) => repr,
+                    _ => {
+                        return Err(::syn::Error::new_spanned(
+                            &keyword,
+                            "duplicate representation hint",
+                        ))
+                    }
+                };
+                let _: ::core::option::Option<Token![,]> = input.parse()?;
+            }
+          Ok(ret)
+        }
+      }
+
+        impl ::quote::ToTokens for $parent {
+            fn to_tokens(&self, tokens: &mut TokenStream) {
+                let repr = match self.repr {
+                    $enum_ty::Rust => None,
+                    $enum_ty::C => Some(quote!(C)),
+                    $enum_ty::Transparent => Some(quote!(transparent)),
+                    $(
+                        $enum_ty::$outer => Some(quote!($inner)),
+                    )*
+                };
+
+                let packed = self.packed.map(|p| {
+                    let lit = ::syn::LitInt::new(&p.to_string(), Span::call_site());
+                    quote!(packed(#lit))
+                });
+
+                let comma = if packed.is_some() && repr.is_some() {
+                    Some(quote!(,))
+                } else {
+                    None
+                };
+
+                tokens.extend(quote!(
+                    #[repr( #repr #comma #packed )]
+                ));
+        }
+      }
+    }
+}
+
+impl_comptime_layout! {
+    @ComptimeLayout with Repr:
+    U8 => u8,
+    U16 => u16,
+    U32 => u32,
+    U64 => u64,
+    U128 => u128,
+    Usize => usize,
+    I8 => i8,
+    I16 => i16,
+    I32 => i32,
+    I64 => i64,
+    I128 => i128,
+    Isize => isize,
+}
+
+pub fn parse_attr_repr(attr: &syn::Attribute, ident: &str) -> syn::Result<ComptimeLayout> {
+    if attr.path().is_ident(ident) {
+        attr.parse_args::<ComptimeLayout>()
+    } else {
+        Err(Error::new_spanned(attr, "Failed while attempting to parse attribute for layout"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Attribute};
+
+    use super::*;
+
+    #[test]
+    fn parses_repr_hints() {
+        let attrs: Vec<Attribute> =
+            vec![parse_quote!(#[derive(Clone)]), parse_quote!(#[repr(C, packed(2))])];
+        let layout = ComptimeLayout::parse_repr_attr(&attrs).unwrap();
+        assert_eq!(layout.repr(), Repr::C);
+        assert_eq!(layout.peel_packed(), 2);
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[repr(transparent)])];
+        let layout = ComptimeLayout::parse_repr_attr(&attrs).unwrap();
+        assert!(layout.is_valid());
+
+        let attrs: Vec<Attribute> =
+            vec![parse_quote!(#[repr(u16)]), parse_quote!(#[repr(align(8))])];
+        let layout = ComptimeLayout::parse_repr_attr(&attrs).unwrap();
+        assert_eq!(layout.repr(), Repr::U16);
+        assert_eq!(layout.peel_align(), 8);
+
+        let layout = ComptimeLayout::parse_repr_attr(&[parse_quote!(#[repr(C, u8)])]).unwrap();
+        assert_eq!(layout.repr(), Repr::U8);
+
+        let layout = ComptimeLayout::parse_repr_attr(&[]).unwrap();
+        assert_eq!(layout.repr(), Repr::Rust);
+        assert!(!layout.is_valid());
+    }
+
+    #[test]
+    fn rejects_conflicting_repr_hints() {
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[repr(C)]), parse_quote!(#[repr(u8)])];
+        assert!(ComptimeLayout::parse_repr_attr(&attrs).is_err());
+
+        let attrs: Vec<Attribute> =
+            vec![parse_quote!(#[repr(packed)]), parse_quote!(#[repr(packed(2))])];
+        assert!(ComptimeLayout::parse_repr_attr(&attrs).is_err());
+
+        assert!(ComptimeLayout::parse_repr_attr(&[parse_quote!(#[repr(u8, u16)])]).is_err());
+        assert!(ComptimeLayout::parse_repr_attr(&[parse_quote!(#[repr(C, flat)])]).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+//! Attribute parsing and layout analysis shared by the derive macros of
+//! [`abio_derive`](https://docs.rs/abio_derive).
+//!
+//! This crate is not a procedural macro crate, so the parsers it provides can be
+//! unit tested directly. It is an implementation detail of `abio_derive`, and its
+//! API is not covered by semantic versioning.
+
+pub mod attrs;
+pub mod errors;
+pub mod layout;
@@ -0,0 +1,82 @@
+//! Firmware-style test decoding a sensor frame with `abio` on a bare-metal target.
+//!
+//! This crate only links `core`, so building it proves that the default features of
+//! `abio` need neither `std` nor `alloc`. `cargo run` runs it under QEMU, and the
+//! emulator exits with a failure status if any check panics or returns an error.
+
+#![no_std]
+#![no_main]
+
+use core::hint::black_box;
+use core::panic::PanicInfo;
+
+use abio::{Bytes, Decode, Source, BE};
+use cortex_m_rt::entry;
+use cortex_m_semihosting::{debug, hprintln};
+
+/// Frame sent by a sensor node, excluding its trailing checksum.
+#[derive(Decode)]
+#[abio(magic = b"SN")]
+struct SensorFrame<'data> {
+    node: u8,
+    sequence: u16,
+    /// Temperature, in hundredths of a degree Celsius.
+    temperature: i16,
+    /// Relative humidity, in hundredths of a percent.
+    humidity: u16,
+    len: u8,
+    #[abio(len = "len")]
+    payload: Bytes<'data>,
+}
+
+/// Frame received from node 7, reporting -12.50 °C and 45.00 % humidity.
+const FRAME: [u8; 14] =
+    [b'S', b'N', 0x07, 0x01, 0x02, 0xfb, 0x1e, 0x11, 0x94, 0x03, b'o', b'k', b'!', 0x67];
+
+/// Returns the wrapping sum of `bytes`, which is the checksum of a frame.
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0, |sum, byte| sum.wrapping_add(*byte))
+}
+
+fn decode_sensor_frame() -> abio::Result<()> {
+    // Hide the contents of the frame from the optimizer, so that it is decoded at
+    // run time on the target instead of being folded into a constant.
+    let input = black_box(&FRAME);
+    let (body, trailer) = Bytes::new(input).take_back(1)?;
+    assert_eq!(checksum(body.as_slice()), trailer.as_slice()[0]);
+
+    let (frame, len) = SensorFrame::decode::<BE>(body.into_slice())?;
+    assert_eq!(len, body.len());
+    assert_eq!((frame.node, frame.sequence), (7, 0x0102));
+    assert_eq!((frame.temperature, frame.humidity), (-1250, 4500));
+    assert_eq!(usize::from(frame.len), frame.payload.len());
+    assert_eq!(frame.payload.as_slice(), b"ok!");
+
+    let magic = input.read_chunk::<2>(0)?;
+    assert_eq!(magic.into_array(), *b"SN");
+    assert!(SensorFrame::decode::<BE>(&input[..8]).is_err());
+    assert!(SensorFrame::decode::<BE>(b"NS\x07\x01\x02\xfb\x1e\x11\x94\x00").is_err());
+    Ok(())
+}
+
+#[entry]
+fn main() -> ! {
+    let status = match decode_sensor_frame() {
+        Ok(()) => debug::EXIT_SUCCESS,
+        Err(err) => {
+            hprintln!("error: {}", err);
+            debug::EXIT_FAILURE
+        }
+    };
+    debug::exit(status);
+    loop {}
+}
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    hprintln!("{}", info);
+    debug::exit(debug::EXIT_FAILURE);
+    loop {}
+}